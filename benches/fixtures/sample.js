@@ -0,0 +1,127 @@
+'use strict';
+
+// a small event emitter, a cache and some helpers, in the style of the code
+// bundlers put together
+
+var DEFAULT_LIMIT = 100;
+var WORD = /^[a-z][a-z0-9_]*$/i;
+
+class Emitter {
+  #listeners = new Map();
+
+  on(name, listener) {
+    if (!this.#listeners.has(name)) {
+      this.#listeners.set(name, []);
+    }
+    this.#listeners.get(name).push(listener);
+    return () => this.off(name, listener);
+  }
+
+  off(name, listener) {
+    const listeners = this.#listeners.get(name) ?? [];
+    const index = listeners.indexOf(listener);
+    if (index !== -1) {
+      listeners.splice(index, 1);
+    }
+  }
+
+  emit(name, args) {
+    for (const listener of this.#listeners.get(name) ?? []) {
+      try {
+        listener.apply(this, args);
+      } catch (error) {
+        console.error(`listener for ${name} failed: ${error.message}`);
+      }
+    }
+  }
+}
+
+class Cache extends Emitter {
+  static instances = 0;
+
+  constructor(limit) {
+    super();
+    this.limit = limit || DEFAULT_LIMIT;
+    this.entries = new Map();
+    Cache.instances += 1;
+  }
+
+  get size() {
+    return this.entries.size;
+  }
+
+  get(key) {
+    const entry = this.entries.get(key);
+    if (entry === undefined) {
+      this.emit('miss', key);
+      return null;
+    }
+    entry.hits++;
+    return entry.value;
+  }
+
+  set(key, value) {
+    if (this.entries.size >= this.limit) {
+      let oldest = null;
+      for (const candidate of this.entries.keys()) {
+        const hits = this.entries.get(candidate).hits;
+        if (oldest === null || hits < this.entries.get(oldest).hits) {
+          oldest = candidate;
+        }
+      }
+      this.entries.delete(oldest);
+      this.emit('evict', oldest);
+    }
+    this.entries.set(key, { value: value, hits: 0, at: Date.now() });
+  }
+}
+
+function debounce(fn, wait) {
+  let timer = null;
+  return function () {
+    const args = arguments;
+    clearTimeout(timer);
+    timer = setTimeout(() => fn.apply(this, args), wait);
+  };
+}
+
+function format(template, values) {
+  return template.replace(/\{(\w+)\}/g, (match, key) =>
+    key in values ? String(values[key]) : match
+  );
+}
+
+const isWord = (text) => typeof text === 'string' && WORD.test(text);
+
+function tally(words) {
+  const counts = {};
+  for (let i = 0; i < words.length; i++) {
+    const word = words[i].toLowerCase();
+    if (!isWord(word)) continue;
+    counts[word] = (counts[word] || 0) + 1;
+  }
+  return Object.keys(counts)
+    .sort((a, b) => counts[b] - counts[a] || (a < b ? -1 : 1))
+    .slice(0, 10)
+    .map((word) => ({ word, count: counts[word] }));
+}
+
+async function load(url, options) {
+  const response = await fetch(url, { method: 'GET', ...options });
+  if (!response.ok) {
+    throw new Error('request failed with ' + response.status);
+  }
+  return response.json();
+}
+
+function* range(start, end, step) {
+  for (let value = start; value < end; value += step || 1) {
+    yield value;
+  }
+}
+
+const cache = new Cache(1 << 8);
+cache.on('evict', debounce((key) => console.log(format('evicted {key}', { key })), 50));
+for (const n of range(0, 0x40, 2)) {
+  cache.set('key' + n, n ** 2 % 7 === 0 ? null : { n, half: n / 2, odd: (n & 1) === 1 });
+}
@@ -0,0 +1,74 @@
+// Throughput of the lexer and the parser, with `cargo bench`:
+//
+// - tokenize/small and tokenize/bundle lex fixtures/sample.js and a ~1MB
+//   bundle made of copies of it;
+// - tokenize/names lexes mostly identifiers and whitespace, where the cost
+//   of classifying each character dominates, and tokenize/non_ascii text
+//   full of multi-byte characters, where finding characters by position
+//   does;
+// - tokenize/scaling lexes bundles of growing size, whose throughput stays
+//   flat as long as lexing is linear;
+// - parse/small and parse/bundle parse end to end.
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use js_parser::{parse, tokenize};
+
+const SAMPLE: &str = include_str!("fixtures/sample.js");
+
+// copies of the sample, each in a function of its own so that their
+// declarations don't clash, up to at least `size` bytes
+fn bundle(size: usize) -> String {
+    let mut bundle = String::new();
+    while bundle.len() < size {
+        bundle += "(function () {\n";
+        bundle += SAMPLE;
+        bundle += "})();\n";
+    }
+    bundle
+}
+
+fn repeat_to(line: &str, size: usize) -> String {
+    line.repeat(size / line.len() + 1)
+}
+
+fn tokenize_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tokenize");
+    let names = repeat_to("alpha beta gamma delta epsilon zeta eta theta\n", 64 * 1024);
+    let non_ascii = repeat_to(
+        "const s = 'naïve café — ünïcödé ☃ 😀'; // ½ ∑ →\n",
+        64 * 1024,
+    );
+    let large = bundle(1024 * 1024);
+    for (name, src) in [
+        ("small", SAMPLE),
+        ("names", &names),
+        ("non_ascii", &non_ascii),
+        ("bundle", &large),
+    ] {
+        group.throughput(Throughput::Bytes(src.len() as u64));
+        group.bench_function(name, |b| b.iter(|| tokenize(src).unwrap()));
+    }
+
+    group.sample_size(10);
+    for size in [64 * 1024, 256 * 1024, 1024 * 1024] {
+        let src = bundle(size);
+        group.throughput(Throughput::Bytes(src.len() as u64));
+        group.bench_with_input(BenchmarkId::new("scaling", size), &src, |b, src| {
+            b.iter(|| tokenize(src).unwrap())
+        });
+    }
+    group.finish();
+}
+
+fn parse_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    let large = bundle(1024 * 1024);
+    group.throughput(Throughput::Bytes(SAMPLE.len() as u64));
+    group.bench_function("small", |b| b.iter(|| parse(SAMPLE).unwrap()));
+    group.sample_size(10);
+    group.throughput(Throughput::Bytes(large.len() as u64));
+    group.bench_function("bundle", |b| b.iter(|| parse(&large).unwrap()));
+    group.finish();
+}
+
+criterion_group!(benches, tokenize_benches, parse_benches);
+criterion_main!(benches);
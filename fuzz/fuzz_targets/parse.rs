@@ -0,0 +1,19 @@
+// Whatever the input, tokenizing and parsing it ends in a result, never a
+// panic or a hang:
+//
+//   cargo +nightly fuzz run parse -- -dict=fuzz/js.dict
+//
+// Crashes worth keeping go in tests/fuzz_regressions/.
+#![no_main]
+
+use js_parser::{parse_module, parse_script, parse_with_recovery, tokenize, SourceType};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // the parser takes text, so invalid UTF-8 is replaced rather than skipped
+    let src = String::from_utf8_lossy(data);
+    let _ = tokenize(&src);
+    let _ = parse_script(&src);
+    let _ = parse_module(&src);
+    let _ = parse_with_recovery(&src, SourceType::Script);
+});
@@ -0,0 +1,52 @@
+#ifndef JS_PARSER_H
+#define JS_PARSER_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit it by hand. */
+
+#include <stddef.h>
+
+/**
+ * Why parsing failed. `line` counts from 1 and `column` from 0, as in the
+ * JSON, and both are 0 when there is no position to give, as for a panic.
+ * `message` belongs to the error and is released with `js_free_error`.
+ */
+typedef struct JsError {
+  char *message;
+  size_t line;
+  size_t column;
+} JsError;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Parses `len` bytes of UTF-8 at `src` as a script and returns its ESTree
+ * JSON as a NUL-terminated string, to be released with `js_free_string`.
+ * On failure returns NULL and, unless `out_err` is NULL, fills it in; on
+ * success `out_err` is filled in with a NULL message, so it can always be
+ * passed to `js_free_error`.
+ */
+char *js_parse_to_json(const char *src, size_t len, struct JsError *out_err);
+
+/**
+ * As `js_parse_to_json`, parsing the source as an ES module.
+ */
+char *js_parse_module_to_json(const char *src, size_t len, struct JsError *out_err);
+
+/**
+ * Releases a string returned by `js_parse_to_json`; NULL is ignored.
+ */
+void js_free_string(char *json);
+
+/**
+ * Releases the message of an error and sets it to NULL; the `JsError`
+ * itself belongs to the caller.
+ */
+void js_free_error(struct JsError *error);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* JS_PARSER_H */
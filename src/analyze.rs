@@ -0,0 +1,805 @@
+// Scope analysis: the scopes of a program, the bindings declared in each,
+// and every identifier that refers to one of them.
+//
+// A walk over the tree opens a scope for the program, each function, block,
+// catch clause and class, and declares names as it meets them: `var` and
+// parameters in the closest function, `let`, `const`, classes and functions
+// in the scope they're written in. References are only resolved once the
+// walk is over, so a name used before the `var` or `function` declaring it
+// further down still finds it, as hoisting has it. A name that no scope
+// declares is a global.
+use crate::{
+    node::{
+        ArrowFunctionExpression, ArrowFunctionExpressionBody, AssignmentExpression,
+        AssignmentExpressionLeft, AssignmentOperator, BlockStatement, BreakStatement,
+        CallExpression, CatchClause, ClassDeclaration, ClassExpression, ContinueStatement,
+        ExportAllDeclaration, ExportDefaultDeclaration, ExportDefaultDeclarationKind,
+        ExportNamedDeclaration, Expression, ForInStatement, ForInStatementLeft, ForOfStatement,
+        ForStatement, ForStatementInit, FunctionDeclaration, FunctionExpression, Identifier,
+        ImportDeclaration, ImportDeclarationSpecifier, LabeledStatement, MemberExpression,
+        MetaProperty, MethodDefinition, ObjectPatternProperty, Pattern, Program, Property,
+        PropertyDefinition, PropertyKey, SourceLocation, Statement, StaticBlock, SwitchStatement,
+        UpdateExpression, VariableDeclaration,
+    },
+    visit::{self, Visitor},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScopeId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BindingId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReferenceId(pub u32);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScopeKind {
+    Program,
+    Function,
+    // holds only the name of a named function expression, which its body
+    // can see but can also shadow
+    FunctionName,
+    Block,
+    Catch,
+    Class,
+    StaticBlock,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BindingKind {
+    Var,
+    Let,
+    Const,
+    Function,
+    Param,
+    Class,
+    CatchParam,
+    Import,
+    // the `arguments` every non-arrow function declares without being told
+    Arguments,
+}
+
+impl BindingKind {
+    // the bindings in their temporal dead zone until their declaration runs
+    pub fn is_lexical(&self) -> bool {
+        matches!(
+            self,
+            BindingKind::Let | BindingKind::Const | BindingKind::Class
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    pub parent: Option<ScopeId>,
+    pub bindings: Vec<BindingId>,
+    pub loc: SourceLocation,
+    // whether it calls `eval` directly, which can read any name in sight
+    pub calls_eval: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub name: String,
+    pub kind: BindingKind,
+    pub scope: ScopeId,
+    // the identifier declaring it, or the function for `arguments`; a `var`
+    // declared twice keeps the first
+    pub loc: SourceLocation,
+    // the identifiers declaring it again, as in `var a; var a;`
+    pub redeclarations: Vec<SourceLocation>,
+    pub references: Vec<ReferenceId>,
+    // declared by `export const`, `export function` and the like
+    pub is_exported: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub name: String,
+    pub loc: SourceLocation,
+    pub scope: ScopeId,
+    // None for a global
+    pub binding: Option<BindingId>,
+    pub is_read: bool,
+    pub is_write: bool,
+    // written before the declaration of the `let`, `const` or class it
+    // refers to, which throws if it runs before the declaration has
+    pub before_declaration: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+    pub scopes: Vec<Scope>,
+    pub bindings: Vec<Binding>,
+    pub references: Vec<Reference>,
+}
+
+impl Analysis {
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id.0 as usize]
+    }
+
+    pub fn binding(&self, id: BindingId) -> &Binding {
+        &self.bindings[id.0 as usize]
+    }
+
+    pub fn reference(&self, id: ReferenceId) -> &Reference {
+        &self.references[id.0 as usize]
+    }
+
+    // the reference written at a byte offset
+    pub fn reference_at(&self, offset: usize) -> Option<&Reference> {
+        self.references
+            .iter()
+            .find(|reference| contains(&reference.loc, offset))
+    }
+
+    // what the identifier at a byte offset stands for, whether it refers to
+    // a binding or declares it; None for globals and for anything else
+    pub fn resolve_at(&self, offset: usize) -> Option<BindingId> {
+        if let Some(reference) = self.reference_at(offset) {
+            return reference.binding;
+        }
+        self.bindings
+            .iter()
+            .position(|binding| {
+                binding.kind != BindingKind::Arguments && contains(&binding.loc, offset)
+            })
+            .map(|index| BindingId(index as u32))
+    }
+
+    pub fn references_to(&self, binding: BindingId) -> Vec<&Reference> {
+        self.binding(binding)
+            .references
+            .iter()
+            .map(|&id| self.reference(id))
+            .collect()
+    }
+
+    // the references no scope declares, in source order
+    pub fn globals(&self) -> Vec<&Reference> {
+        self.references
+            .iter()
+            .filter(|reference| reference.binding.is_none())
+            .collect()
+    }
+
+    // the binding a name stands for in a scope, looking outwards
+    pub fn lookup(&self, scope: ScopeId, name: &str) -> Option<BindingId> {
+        let mut scope = Some(scope);
+        while let Some(id) = scope {
+            let found = self
+                .scope(id)
+                .bindings
+                .iter()
+                .find(|&&binding| self.binding(binding).name == name);
+            if let Some(&binding) = found {
+                return Some(binding);
+            }
+            scope = self.scope(id).parent;
+        }
+        None
+    }
+}
+
+fn contains(loc: &SourceLocation, offset: usize) -> bool {
+    loc.range.0 <= offset && offset < loc.range.1
+}
+
+pub fn analyze(program: &Program) -> Analysis {
+    let mut analyzer = Analyzer {
+        analysis: Analysis {
+            scopes: vec![],
+            bindings: vec![],
+            references: vec![],
+        },
+        current: ScopeId(0),
+    };
+    analyzer.visit_program(program);
+    analyzer.resolve();
+    analyzer.analysis
+}
+
+struct Analyzer {
+    analysis: Analysis,
+    current: ScopeId,
+}
+
+impl Analyzer {
+    fn open_scope(&mut self, kind: ScopeKind, loc: &SourceLocation) -> ScopeId {
+        let id = ScopeId(self.analysis.scopes.len() as u32);
+        let parent = if self.analysis.scopes.is_empty() {
+            None
+        } else {
+            Some(self.current)
+        };
+        self.analysis.scopes.push(Scope {
+            kind,
+            parent,
+            bindings: vec![],
+            loc: loc.clone(),
+            calls_eval: false,
+        });
+        self.current = id;
+        id
+    }
+
+    fn close_scope(&mut self) {
+        if let Some(parent) = self.analysis.scope(self.current).parent {
+            self.current = parent;
+        }
+    }
+
+    fn in_scope(&mut self, kind: ScopeKind, loc: &SourceLocation, walk: impl FnOnce(&mut Self)) {
+        self.open_scope(kind, loc);
+        walk(self);
+        self.close_scope();
+    }
+
+    // where `var` goes: the closest function, static block or the program
+    fn var_scope(&self) -> ScopeId {
+        let mut id = self.current;
+        loop {
+            let scope = self.analysis.scope(id);
+            match (scope.kind, scope.parent) {
+                (ScopeKind::Program | ScopeKind::Function | ScopeKind::StaticBlock, _) => {
+                    return id
+                }
+                (_, Some(parent)) => id = parent,
+                (_, None) => return id,
+            }
+        }
+    }
+
+    // a name declared twice in one scope, as `var` and parameters may be,
+    // stays a single binding
+    fn declare(&mut self, scope: ScopeId, name: &str, kind: BindingKind, loc: &SourceLocation) {
+        let existing = self.analysis.scopes[scope.0 as usize]
+            .bindings
+            .iter()
+            .find(|&&binding| self.analysis.binding(binding).name == name);
+        if let Some(&binding) = existing {
+            self.analysis.bindings[binding.0 as usize]
+                .redeclarations
+                .push(loc.clone());
+            return;
+        }
+        let id = BindingId(self.analysis.bindings.len() as u32);
+        self.analysis.bindings.push(Binding {
+            name: name.to_string(),
+            kind,
+            scope,
+            loc: loc.clone(),
+            redeclarations: vec![],
+            references: vec![],
+            is_exported: false,
+        });
+        self.analysis.scopes[scope.0 as usize].bindings.push(id);
+    }
+
+    fn refer(&mut self, identifier: &Identifier, is_read: bool, is_write: bool) {
+        self.analysis.references.push(Reference {
+            name: identifier.name.to_string(),
+            loc: identifier.loc.clone(),
+            scope: self.current,
+            binding: None,
+            is_read,
+            is_write,
+            before_declaration: false,
+        });
+    }
+
+    fn resolve(&mut self) {
+        for index in 0..self.analysis.references.len() {
+            let reference = &self.analysis.references[index];
+            let Some(binding) = self.analysis.lookup(reference.scope, &reference.name) else {
+                continue;
+            };
+            let declaration = &self.analysis.binding(binding).loc;
+            let before_declaration = self.analysis.binding(binding).kind.is_lexical()
+                && reference.loc.range.0 < declaration.range.0;
+            let reference = &mut self.analysis.references[index];
+            reference.binding = Some(binding);
+            reference.before_declaration = before_declaration;
+            self.analysis.bindings[binding.0 as usize]
+                .references
+                .push(ReferenceId(index as u32));
+        }
+    }
+
+    // the names a declaration binds, while default values and computed keys
+    // inside the pattern are read as usual
+    fn declare_pattern(&mut self, pattern: &Pattern, scope: ScopeId, kind: BindingKind) {
+        match pattern {
+            Pattern::Identifier(identifier) => {
+                self.declare(scope, &identifier.name, kind, &identifier.loc)
+            }
+            Pattern::Member(member) => self.visit_member_expression(member),
+            Pattern::Object(object) => {
+                for property in &object.properties {
+                    match property {
+                        ObjectPatternProperty::AssignmentProperty(property) => {
+                            self.property_key(&property.key);
+                            self.declare_pattern(&property.value, scope, kind);
+                        }
+                        ObjectPatternProperty::RestElement(rest) => {
+                            self.declare_pattern(&rest.argument, scope, kind)
+                        }
+                    }
+                }
+            }
+            Pattern::Array(array) => {
+                for element in array.elements.iter().flatten() {
+                    self.declare_pattern(element, scope, kind);
+                }
+            }
+            Pattern::Rest(rest) => self.declare_pattern(&rest.argument, scope, kind),
+            Pattern::Assignment(assignment) => {
+                self.declare_pattern(&assignment.left, scope, kind);
+                self.visit_expression(&assignment.right);
+            }
+            Pattern::Error(_) => {}
+        }
+    }
+
+    // the targets of an assignment, which are written and, for compound
+    // operators such as `+=`, read first
+    fn assign_pattern(&mut self, pattern: &Pattern, is_read: bool) {
+        match pattern {
+            Pattern::Identifier(identifier) => self.refer(identifier, is_read, true),
+            Pattern::Member(member) => self.visit_member_expression(member),
+            Pattern::Object(object) => {
+                for property in &object.properties {
+                    match property {
+                        ObjectPatternProperty::AssignmentProperty(property) => {
+                            self.property_key(&property.key);
+                            self.assign_pattern(&property.value, false);
+                        }
+                        ObjectPatternProperty::RestElement(rest) => {
+                            self.assign_pattern(&rest.argument, false)
+                        }
+                    }
+                }
+            }
+            Pattern::Array(array) => {
+                for element in array.elements.iter().flatten() {
+                    self.assign_pattern(element, false);
+                }
+            }
+            Pattern::Rest(rest) => self.assign_pattern(&rest.argument, false),
+            Pattern::Assignment(assignment) => {
+                self.assign_pattern(&assignment.left, false);
+                self.visit_expression(&assignment.right);
+            }
+            Pattern::Error(_) => {}
+        }
+    }
+
+    // only a computed key is an expression to evaluate
+    fn property_key(&mut self, key: &PropertyKey) {
+        if let PropertyKey::Expression(expression) = key {
+            self.visit_expression(expression);
+        }
+    }
+
+    fn function(
+        &mut self,
+        loc: &SourceLocation,
+        params: &[Pattern],
+        body: &[Statement],
+        is_arrow: bool,
+    ) {
+        self.in_scope(ScopeKind::Function, loc, |this| {
+            let scope = this.current;
+            if !is_arrow {
+                this.declare(scope, "arguments", BindingKind::Arguments, loc);
+            }
+            for param in params {
+                this.declare_pattern(param, scope, BindingKind::Param);
+            }
+            for statement in body {
+                this.visit_statement(statement);
+            }
+        });
+    }
+
+    // marks what a declaration binds in the current scope as exported
+    fn export(&mut self, declare: impl FnOnce(&mut Self)) {
+        let scope = self.current;
+        let from = self.analysis.scope(scope).bindings.len();
+        declare(self);
+        for index in from..self.analysis.scope(scope).bindings.len() {
+            let binding = self.analysis.scope(scope).bindings[index];
+            self.analysis.bindings[binding.0 as usize].is_exported = true;
+        }
+    }
+
+    fn for_in_left(&mut self, left: &ForInStatementLeft) {
+        match left {
+            ForInStatementLeft::VariableDeclaration(declaration) => {
+                self.visit_variable_declaration(declaration)
+            }
+            ForInStatementLeft::Pattern(pattern) => self.assign_pattern(pattern, false),
+        }
+    }
+}
+
+fn is_lexical_declaration(declaration: &VariableDeclaration) -> bool {
+    declaration.kind != "var"
+}
+
+impl<'ast> Visitor<'ast> for Analyzer {
+    fn visit_program(&mut self, node: &'ast Program) {
+        self.in_scope(ScopeKind::Program, &node.loc, |this| {
+            visit::walk_program(this, node)
+        });
+    }
+
+    fn visit_identifier(&mut self, node: &'ast Identifier) {
+        self.refer(node, true, false);
+    }
+
+    fn visit_pattern(&mut self, node: &'ast Pattern) {
+        self.assign_pattern(node, false);
+    }
+
+    fn visit_block_statement(&mut self, node: &'ast BlockStatement) {
+        self.in_scope(ScopeKind::Block, &node.loc, |this| {
+            visit::walk_block_statement(this, node)
+        });
+    }
+
+    fn visit_labeled_statement(&mut self, node: &'ast LabeledStatement) {
+        self.visit_statement(&node.body);
+    }
+
+    fn visit_break_statement(&mut self, _node: &'ast BreakStatement) {}
+
+    fn visit_continue_statement(&mut self, _node: &'ast ContinueStatement) {}
+
+    fn visit_switch_statement(&mut self, node: &'ast SwitchStatement) {
+        self.visit_expression(&node.discriminant);
+        self.in_scope(ScopeKind::Block, &node.loc, |this| {
+            for case in &node.cases {
+                this.visit_switch_case(case);
+            }
+        });
+    }
+
+    fn visit_catch_clause(&mut self, node: &'ast CatchClause) {
+        self.in_scope(ScopeKind::Catch, &node.loc, |this| {
+            if let Some(param) = &node.param {
+                let scope = this.current;
+                this.declare_pattern(param, scope, BindingKind::CatchParam);
+            }
+            this.visit_block_statement(&node.body);
+        });
+    }
+
+    // `let` and `const` in the head of a loop are scoped to the loop
+    fn visit_for_statement(&mut self, node: &'ast ForStatement) {
+        match &node.init {
+            Some(ForStatementInit::VariableDeclaration(declaration))
+                if is_lexical_declaration(declaration) =>
+            {
+                self.in_scope(ScopeKind::Block, &node.loc, |this| {
+                    visit::walk_for_statement(this, node)
+                })
+            }
+            _ => visit::walk_for_statement(self, node),
+        }
+    }
+
+    fn visit_for_in_statement(&mut self, node: &'ast ForInStatement) {
+        self.in_scope(ScopeKind::Block, &node.loc, |this| {
+            this.for_in_left(&node.left);
+            this.visit_expression(&node.right);
+            this.visit_statement(&node.body);
+        });
+    }
+
+    fn visit_for_of_statement(&mut self, node: &'ast ForOfStatement) {
+        self.in_scope(ScopeKind::Block, &node.loc, |this| {
+            this.for_in_left(&node.left);
+            this.visit_expression(&node.right);
+            this.visit_statement(&node.body);
+        });
+    }
+
+    fn visit_variable_declaration(&mut self, node: &'ast VariableDeclaration) {
+        let (scope, kind) = match node.kind.as_str() {
+            "let" => (self.current, BindingKind::Let),
+            "const" => (self.current, BindingKind::Const),
+            _ => (self.var_scope(), BindingKind::Var),
+        };
+        for declarator in &node.declarations {
+            self.declare_pattern(&declarator.id, scope, kind);
+            if let Some(init) = &declarator.init {
+                self.visit_expression(init);
+            }
+        }
+    }
+
+    fn visit_function_declaration(&mut self, node: &'ast FunctionDeclaration) {
+        if let Some(id) = &node.id {
+            let scope = self.current;
+            self.declare(scope, &id.name, BindingKind::Function, &id.loc);
+        }
+        self.function(&node.loc, &node.params, &node.body.body, false);
+    }
+
+    fn visit_function_expression(&mut self, node: &'ast FunctionExpression) {
+        match &node.id {
+            Some(id) => self.in_scope(ScopeKind::FunctionName, &node.loc, |this| {
+                let scope = this.current;
+                this.declare(scope, &id.name, BindingKind::Function, &id.loc);
+                this.function(&node.loc, &node.params, &node.body.body, false);
+            }),
+            None => self.function(&node.loc, &node.params, &node.body.body, false),
+        }
+    }
+
+    fn visit_arrow_function_expression(&mut self, node: &'ast ArrowFunctionExpression) {
+        match &node.body {
+            ArrowFunctionExpressionBody::FunctionBody(body) => {
+                self.function(&node.loc, &node.params, &body.body, true)
+            }
+            ArrowFunctionExpressionBody::Expression(expression) => {
+                self.in_scope(ScopeKind::Function, &node.loc, |this| {
+                    let scope = this.current;
+                    for param in &node.params {
+                        this.declare_pattern(param, scope, BindingKind::Param);
+                    }
+                    this.visit_expression(expression);
+                })
+            }
+        }
+    }
+
+    // a class declaration binds its name where it's written, and references
+    // from inside the class go to that binding
+    fn visit_class_declaration(&mut self, node: &'ast ClassDeclaration) {
+        if let Some(id) = &node.id {
+            let scope = self.current;
+            self.declare(scope, &id.name, BindingKind::Class, &id.loc);
+        }
+        self.in_scope(ScopeKind::Class, &node.loc, |this| {
+            if let Some(super_class) = &node.super_class {
+                this.visit_expression(super_class);
+            }
+            this.visit_class_body(&node.body);
+        });
+    }
+
+    fn visit_class_expression(&mut self, node: &'ast ClassExpression) {
+        self.in_scope(ScopeKind::Class, &node.loc, |this| {
+            if let Some(id) = &node.id {
+                let scope = this.current;
+                this.declare(scope, &id.name, BindingKind::Class, &id.loc);
+            }
+            if let Some(super_class) = &node.super_class {
+                this.visit_expression(super_class);
+            }
+            this.visit_class_body(&node.body);
+        });
+    }
+
+    fn visit_method_definition(&mut self, node: &'ast MethodDefinition) {
+        if node.computed {
+            self.visit_expression(&node.key);
+        }
+        self.visit_function_expression(&node.value);
+    }
+
+    fn visit_property_definition(&mut self, node: &'ast PropertyDefinition) {
+        if node.computed {
+            self.visit_expression(&node.key);
+        }
+        if let Some(value) = &node.value {
+            self.visit_expression(value);
+        }
+    }
+
+    fn visit_static_block(&mut self, node: &'ast StaticBlock) {
+        self.in_scope(ScopeKind::StaticBlock, &node.loc, |this| {
+            visit::walk_static_block(this, node)
+        });
+    }
+
+    fn visit_property(&mut self, node: &'ast Property) {
+        self.property_key(&node.key);
+        self.visit_expression(&node.value);
+    }
+
+    fn visit_member_expression(&mut self, node: &'ast MemberExpression) {
+        self.visit_expression(&node.object);
+        if node.computed {
+            self.visit_expression(&node.property);
+        }
+    }
+
+    // only a call of `eval` itself is direct, rather than `window.eval(..)`
+    fn visit_call_expression(&mut self, node: &'ast CallExpression) {
+        if matches!(&node.callee, Expression::Identifier(callee) if callee.name == "eval") {
+            self.analysis.scopes[self.current.0 as usize].calls_eval = true;
+        }
+        visit::walk_call_expression(self, node);
+    }
+
+    fn visit_meta_property(&mut self, _node: &'ast MetaProperty) {}
+
+    fn visit_assignment_expression(&mut self, node: &'ast AssignmentExpression) {
+        let is_read = !matches!(node.operator, AssignmentOperator::Normal);
+        match &node.left {
+            AssignmentExpressionLeft::Pattern(pattern) => self.assign_pattern(pattern, is_read),
+            AssignmentExpressionLeft::Expression(Expression::Identifier(identifier)) => {
+                self.refer(identifier, is_read, true)
+            }
+            AssignmentExpressionLeft::Expression(expression) => self.visit_expression(expression),
+        }
+        self.visit_expression(&node.right);
+    }
+
+    fn visit_update_expression(&mut self, node: &'ast UpdateExpression) {
+        match &node.argument {
+            Expression::Identifier(identifier) => self.refer(identifier, true, true),
+            argument => self.visit_expression(argument),
+        }
+    }
+
+    fn visit_import_declaration(&mut self, node: &'ast ImportDeclaration) {
+        let scope = self.current;
+        for specifier in &node.specifiers {
+            let local = match specifier {
+                ImportDeclarationSpecifier::ImportSpecifier(specifier) => &specifier.local,
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier) => &specifier.local,
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(specifier) => &specifier.local,
+            };
+            self.declare(scope, &local.name, BindingKind::Import, &local.loc);
+        }
+    }
+
+    // `export { a }` reads `a`, but `export { a } from "b"` names what another
+    // module exports
+    fn visit_export_named_declaration(&mut self, node: &'ast ExportNamedDeclaration) {
+        if let Some(declaration) = &node.declaration {
+            self.export(|this| this.visit_statement(declaration));
+        }
+        if node.source.is_none() {
+            for specifier in &node.specifiers {
+                self.refer(&specifier.local, true, false);
+            }
+        }
+    }
+
+    fn visit_export_default_declaration(&mut self, node: &'ast ExportDefaultDeclaration) {
+        match &node.declaration {
+            ExportDefaultDeclarationKind::Expression(expression) => {
+                self.visit_expression(expression)
+            }
+            _ => self.export(|this| visit::walk_export_default_declaration(this, node)),
+        }
+    }
+
+    fn visit_export_all_declaration(&mut self, _node: &'ast ExportAllDeclaration) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analyze::{analyze, Analysis, BindingKind, ScopeKind},
+        parser::parse,
+    };
+
+    // the binding the identifier at the nth occurrence of `needle` resolves to
+    fn resolve_nth(
+        analysis: &Analysis,
+        src: &str,
+        needle: &str,
+        nth: usize,
+    ) -> Option<(String, BindingKind, usize)> {
+        let offset = src.match_indices(needle).nth(nth).unwrap().0;
+        analysis.resolve_at(offset).map(|id| {
+            let binding = analysis.binding(id);
+            (binding.name.clone(), binding.kind, binding.loc.range.0)
+        })
+    }
+
+    #[test]
+    fn analysis_resolves_shadowed_names_to_the_innermost_binding() {
+        let src = "let x = 1; { let x = 2; x; } x; function f(x) { x; } y;";
+        let analysis = analyze(&parse(src).unwrap());
+        let at = |nth| resolve_nth(&analysis, src, "x", nth);
+        assert_eq!(at(2), Some(("x".to_string(), BindingKind::Let, 17)));
+        assert_eq!(at(3), Some(("x".to_string(), BindingKind::Let, 4)));
+        assert_eq!(at(5), Some(("x".to_string(), BindingKind::Param, 43)));
+        let globals: Vec<&str> = analysis
+            .globals()
+            .iter()
+            .map(|reference| reference.name.as_str())
+            .collect();
+        assert_eq!(globals, ["y"]);
+    }
+
+    #[test]
+    fn analysis_marks_references_before_a_lexical_declaration() {
+        let src = "a; b; f(); let a = 1; var b; function f() { a; }";
+        let analysis = analyze(&parse(src).unwrap());
+        let before: Vec<(&str, bool)> = analysis
+            .references
+            .iter()
+            .map(|reference| (reference.name.as_str(), reference.before_declaration))
+            .collect();
+        // `var` and functions are hoisted, and the `a` in `f` comes later
+        assert_eq!(
+            before,
+            [("a", true), ("b", false), ("f", false), ("a", false)]
+        );
+        assert!(analysis.globals().is_empty());
+    }
+
+    #[test]
+    fn analysis_scopes_function_expression_names_to_the_function() {
+        let src = "x = function fact(n) { return fact(n - 1); }; fact; \
+                   y = function g() { var g; g; };";
+        let analysis = analyze(&parse(src).unwrap());
+        let at = |needle, nth| resolve_nth(&analysis, src, needle, nth);
+        assert_eq!(
+            at("fact", 1),
+            Some(("fact".to_string(), BindingKind::Function, 13))
+        );
+        assert_eq!(at("fact", 2), None);
+        // a `var` of the same name in the body shadows it
+        assert_eq!(at("g", 2).map(|binding| binding.1), Some(BindingKind::Var));
+        let fact = analysis.resolve_at(13).unwrap();
+        assert_eq!(
+            analysis.scope(analysis.binding(fact).scope).kind,
+            ScopeKind::FunctionName
+        );
+    }
+
+    #[test]
+    fn analysis_hoists_var_out_of_blocks() {
+        let src = "function f() { if (a) { var v = 1; let l = 2; } for (var i = 0; i < 1; i++) {} \
+                   v; l; i; } v;";
+        let analysis = analyze(&parse(src).unwrap());
+        let at = |needle, nth| resolve_nth(&analysis, src, needle, nth);
+        let v = at("v", 1).unwrap();
+        assert_eq!((v.1, v.2), (BindingKind::Var, 28));
+        let function = analysis
+            .lookup(analysis.references[0].scope, "arguments")
+            .unwrap();
+        assert_eq!(analysis.binding(function).kind, BindingKind::Arguments);
+        let v = analysis.resolve_at(28).unwrap();
+        assert_eq!(analysis.binding(v).scope, analysis.binding(function).scope);
+        assert_eq!(at("l;", 0), None);
+        assert_eq!(at("i", 4).map(|binding| binding.1), Some(BindingKind::Var));
+        // outside the function the `var` isn't visible
+        assert_eq!(at("v", 2), None);
+    }
+
+    #[test]
+    fn analysis_lists_the_references_to_a_binding() {
+        let src = "let n = 0; n += 1; n++; ({ n } = o); f(n, o.n, { n: 1 });";
+        let analysis = analyze(&parse(src).unwrap());
+        let n = analysis.resolve_at(4).unwrap();
+        let uses: Vec<(usize, bool, bool)> = analysis
+            .references_to(n)
+            .iter()
+            .map(|reference| (reference.loc.range.0, reference.is_read, reference.is_write))
+            .collect();
+        assert_eq!(
+            uses,
+            [
+                (11, true, true),
+                (19, true, true),
+                (27, false, true),
+                (39, true, false)
+            ]
+        );
+        assert_eq!(analysis.resolve_at(19), Some(n));
+        assert_eq!(analysis.resolve_at(44), None);
+    }
+}
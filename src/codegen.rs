@@ -0,0 +1,2074 @@
+// Code generation: JavaScript source back from a tree, which parses to the
+// same tree again.
+//
+// Parentheses are written where precedence needs them rather than where the
+// source had them, so `(a + b) * c` keeps its and `a + (b * c)` loses its.
+// By default every statement that takes one ends in a semicolon, so nothing
+// relies on automatic semicolon insertion; without them, a line that would
+// run on from the one before starts with one instead. Literals are written
+// from their `raw` text as long as it still spells their value, and from the
+// value when it doesn't, as for a node built or changed after parsing.
+//
+// The layout is the generator's own but for blank lines between statements,
+// which are kept from the source up to `max_blank_lines`. An object or array
+// is written over several lines when it holds a function or class body,
+// which takes several anyway, and on one otherwise.
+//
+// Minified output leaves out every space and line break the tokens can do
+// without, which `needs_space` decides from the text either side, and the
+// semicolon before a closing brace.
+//
+// Error nodes left by recovery have no source; a statement is left out and
+// an expression or pattern written as a `/* error */` comment, which doesn't
+// parse.
+//
+// Expressions and statements display as the code generated for them alone,
+// which is handy in diagnostics.
+use std::fmt;
+
+use crate::{
+    lexer::cook_string,
+    node::{
+        ArrayExpressionElement, ArrowFunctionExpressionBody, AssignmentExpressionLeft,
+        AssignmentOperator, BinaryOperator, CallExpressionArgument, ClassBody, ClassElement,
+        ExportDefaultDeclarationKind, Expression, ForInStatementLeft, ForStatementInit,
+        FunctionBody, Identifier, ImportDeclarationSpecifier, Literal, LiteralValue,
+        LogicalOperator, MemberExpression, MethodDefinitionKind, Node, ObjectExpressionProperty,
+        ObjectPatternProperty, Pattern, Position, Program, PropertyKey, PropertyKind,
+        SourceLocation, Statement, TemplateLiteral, UnaryOperator, UpdateOperator,
+        VariableDeclaration,
+    },
+    source_map::{Mapping, SourceMap},
+    visit::Visitor,
+};
+
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+// how tightly each kind of expression binds, loosest first
+const SEQUENCE: u8 = 1;
+const ASSIGNMENT: u8 = 2;
+const CONDITIONAL: u8 = 3;
+const UNARY: u8 = 15;
+const UPDATE: u8 = 16;
+const CALL: u8 = 17;
+const MEMBER: u8 = 18;
+const PRIMARY: u8 = 19;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Indent {
+    Spaces(usize),
+    Tabs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Quotes {
+    // each string as it was written
+    Preserve,
+    Single,
+    Double,
+    // whichever needs fewer escapes, double when it's a tie
+    Auto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Semicolons {
+    Always,
+    // only where a statement would otherwise run on into the next
+    Never,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodegenOptions {
+    pub indent: Indent,
+    pub quotes: Quotes,
+    pub semicolons: Semicolons,
+    // a comma after the last item of an object or array written over
+    // several lines
+    pub trailing_commas: bool,
+    // the most blank lines kept between two statements
+    pub max_blank_lines: usize,
+    // no layout at all, which overrides the options above but `quotes`
+    pub minify: bool,
+    // `true` and `false` minified as `!0` and `!1`
+    pub shorten_booleans: bool,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        CodegenOptions {
+            indent: Indent::Spaces(2),
+            quotes: Quotes::Preserve,
+            semicolons: Semicolons::Always,
+            trailing_commas: false,
+            max_blank_lines: 1,
+            minify: false,
+            shorten_booleans: true,
+        }
+    }
+}
+
+impl CodegenOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn indent(self, indent: Indent) -> Self {
+        CodegenOptions { indent, ..self }
+    }
+
+    pub fn quotes(self, quotes: Quotes) -> Self {
+        CodegenOptions { quotes, ..self }
+    }
+
+    pub fn semicolons(self, semicolons: Semicolons) -> Self {
+        CodegenOptions { semicolons, ..self }
+    }
+
+    pub fn trailing_commas(self, trailing_commas: bool) -> Self {
+        CodegenOptions {
+            trailing_commas,
+            ..self
+        }
+    }
+
+    pub fn max_blank_lines(self, max_blank_lines: usize) -> Self {
+        CodegenOptions {
+            max_blank_lines,
+            ..self
+        }
+    }
+
+    pub fn minify(self, minify: bool) -> Self {
+        CodegenOptions { minify, ..self }
+    }
+
+    pub fn shorten_booleans(self, shorten_booleans: bool) -> Self {
+        CodegenOptions {
+            shorten_booleans,
+            ..self
+        }
+    }
+}
+
+pub fn generate(program: &Program) -> String {
+    generate_with_options(program, &CodegenOptions::default())
+}
+
+pub fn generate_minified(program: &Program) -> String {
+    generate_with_options(program, &CodegenOptions::new().minify(true))
+}
+
+pub fn generate_with_options(program: &Program, options: &CodegenOptions) -> String {
+    Generator::new(options, false).program(program).0
+}
+
+// the code along with a map back to `source`, the name of the file the
+// program was parsed from
+pub fn generate_with_source_map(
+    program: &Program,
+    options: &CodegenOptions,
+    source: &str,
+) -> (String, SourceMap) {
+    let (out, mappings) = Generator::new(options, true).program(program);
+    let mut source_map = SourceMap::new(source);
+    source_map.mappings = mappings.unwrap_or_default();
+    (out, source_map)
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut generator = Generator::new(&CodegenOptions::default(), false);
+        generator.expression(self, SEQUENCE);
+        f.write_str(&generator.out)
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut generator = Generator::new(&CodegenOptions::default(), false);
+        generator.statement(self);
+        f.write_str(&generator.out)
+    }
+}
+
+struct Generator {
+    out: String,
+    indent: usize,
+    indent_unit: String,
+    options: CodegenOptions,
+    // in the head of a `for` statement, where a bare `in` would be read as
+    // a `for`-`in`
+    no_in: bool,
+    // in a statement followed by more on the same line, the consequent of
+    // an `if` with an `else` or the body of a `do`-`while`, which has to
+    // end in a semicolon
+    inline_body: bool,
+    // the statement just written left out its semicolon
+    omitted_semicolon: bool,
+    // minified, the statement just written ends in a semicolon unless a
+    // closing brace comes next
+    pending_semicolon: bool,
+    // minified, the last piece of text written
+    previous: String,
+    // only kept when a source map is asked for
+    mappings: Option<Vec<Mapping>>,
+    // where the next text written starts in the source, and the name when
+    // it's an identifier; the innermost of the nodes that start there
+    mark: Option<(Position, Option<String>)>,
+    // the line and column at the end of `out` up to `scanned`
+    generated: Position,
+    scanned: usize,
+}
+
+impl Generator {
+    fn new(options: &CodegenOptions, source_map: bool) -> Self {
+        Generator {
+            out: String::new(),
+            indent: 0,
+            indent_unit: match options.indent {
+                Indent::Spaces(width) => " ".repeat(width),
+                Indent::Tabs => "\t".to_string(),
+            },
+            options: options.clone(),
+            no_in: false,
+            inline_body: false,
+            omitted_semicolon: false,
+            pending_semicolon: false,
+            previous: String::new(),
+            mappings: source_map.then(Vec::new),
+            mark: None,
+            generated: Position::new(1, 0),
+            scanned: 0,
+        }
+    }
+
+    fn program(mut self, program: &Program) -> (String, Option<Vec<Mapping>>) {
+        self.statements(&program.body);
+        if !self.out.is_empty() && !self.options.minify {
+            self.out.push('\n');
+        }
+        (self.out, self.mappings)
+    }
+
+    // maps the next text written to where `loc` starts, unless the node was
+    // made rather than parsed and has no location
+    fn mark(&mut self, loc: &SourceLocation, name: Option<&str>) {
+        if self.mappings.is_some() && loc.start.line > 0 {
+            self.mark = Some((loc.start, name.map(str::to_string)));
+        }
+    }
+
+    fn identifier(&mut self, identifier: &Identifier) {
+        self.mark(&identifier.loc, Some(&identifier.name));
+        self.write(&identifier.name);
+    }
+
+    fn write(&mut self, text: &str) {
+        if self.options.minify {
+            // the spaces are put back where the tokens need them
+            for piece in text.split(' ') {
+                self.verbatim(piece);
+            }
+        } else {
+            self.verbatim(text);
+        }
+    }
+
+    // text that keeps its spaces when minified, as a literal does
+    fn verbatim(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.options.minify {
+            if std::mem::take(&mut self.pending_semicolon) && !text.starts_with('}') {
+                self.out.push(';');
+                self.previous = ";".to_string();
+            }
+            if needs_space(&self.previous, text) || html_comment(&self.out, text) {
+                self.out.push(' ');
+            }
+            self.previous.clear();
+            self.previous.push_str(text);
+        }
+        if self.omitted_semicolon {
+            self.omitted_semicolon = false;
+            // which would continue the statement before
+            if text.starts_with(['(', '[', '`', '+', '-', '/', '*']) {
+                self.out.push(';');
+            }
+        }
+        // mapped after whatever separates it from the text before
+        if let (Some((original, name)), Some(mappings)) = (self.mark.take(), &mut self.mappings) {
+            for char in self.out[self.scanned..].chars() {
+                if char == '\n' {
+                    self.generated = Position::new(self.generated.line + 1, 0);
+                } else {
+                    self.generated.column += 1;
+                }
+            }
+            self.scanned = self.out.len();
+            mappings.push(Mapping {
+                generated: self.generated,
+                original,
+                name,
+            });
+        }
+        self.out.push_str(text);
+    }
+
+    fn newline(&mut self) {
+        if self.options.minify {
+            return;
+        }
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        for _ in 0..self.indent {
+            self.out.push_str(&self.indent_unit);
+        }
+    }
+
+    // the blank lines the source had between two nodes, up to the most kept
+    fn blank_lines(&mut self, previous: Option<&SourceLocation>, next: &SourceLocation) {
+        if let (Some(previous), false) = (previous, self.options.minify) {
+            let lines = next.start.line.saturating_sub(previous.end.line + 1);
+            for _ in 0..lines.min(self.options.max_blank_lines) {
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn semicolon(&mut self) {
+        if self.options.minify {
+            self.pending_semicolon = true;
+        } else if self.options.semicolons == Semicolons::Always || self.inline_body {
+            self.write(";");
+        } else {
+            self.omitted_semicolon = true;
+        }
+    }
+
+    fn statements(&mut self, statements: &[Statement]) {
+        let inline_body = std::mem::replace(&mut self.inline_body, false);
+        let mut previous = None;
+        for statement in statements {
+            if !matches!(statement, Statement::Error(_)) {
+                self.blank_lines(previous, statement.loc());
+                self.newline();
+                self.statement(statement);
+                previous = Some(statement.loc());
+            }
+        }
+        self.inline_body = inline_body;
+    }
+
+    fn block(&mut self, body: &[Statement]) {
+        if body.is_empty() {
+            self.write("{}");
+            return;
+        }
+        self.write("{");
+        self.indent += 1;
+        self.statements(body);
+        self.indent -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    // the body of an `if`, a loop and the like, on the same line
+    fn body(&mut self, body: &Statement) {
+        self.write(" ");
+        self.statement(body);
+    }
+
+    // a body with more of its statement after it on the same line
+    fn inline_body(&mut self, body: &Statement) {
+        let inline_body = std::mem::replace(&mut self.inline_body, true);
+        self.body(body);
+        self.inline_body = inline_body;
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        self.mark(statement.loc(), None);
+        stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match statement {
+            Statement::Expression(statement) => {
+                // what would start a declaration or a directive instead
+                let parenthesize = statement.directive.is_none()
+                    && (starts_statement_ambiguously(&statement.expression)
+                        || matches!(
+                            &statement.expression,
+                            Expression::Literal(literal)
+                                if matches!(literal.value, LiteralValue::String(_))
+                        ));
+                if parenthesize {
+                    self.write("(");
+                    self.expression(&statement.expression, SEQUENCE);
+                    self.write(")");
+                } else {
+                    self.expression(&statement.expression, SEQUENCE);
+                }
+                self.semicolon();
+            }
+            Statement::Directive(directive) => {
+                // a directive is what it was written as, escapes and all, so
+                // only a plain one changes its quotes
+                let raw = &directive.expression.raw;
+                if raw.len() >= 2 && raw[1..raw.len() - 1].contains(['\\', '\'', '"']) {
+                    self.verbatim(raw);
+                } else {
+                    self.literal(&directive.expression);
+                }
+                self.semicolon();
+            }
+            Statement::Block(block) => self.block(&block.body),
+            Statement::Empty(_) => self.write(";"),
+            Statement::Debugger(_) => self.write("debugger;"),
+            Statement::With(statement) => {
+                self.write("with (");
+                self.expression(&statement.object, SEQUENCE);
+                self.write(")");
+                self.body(&statement.body);
+            }
+            Statement::Return(statement) => {
+                self.write("return");
+                if let Some(argument) = &statement.argument {
+                    self.write(" ");
+                    self.expression(argument, SEQUENCE);
+                }
+                self.semicolon();
+            }
+            Statement::Labeled(statement) => {
+                self.identifier(&statement.label);
+                self.write(":");
+                self.body(&statement.body);
+            }
+            Statement::Break(statement) => {
+                self.write("break");
+                self.label(&statement.label);
+            }
+            Statement::Continue(statement) => {
+                self.write("continue");
+                self.label(&statement.label);
+            }
+            Statement::If(statement) => {
+                self.write("if (");
+                self.expression(&statement.test, SEQUENCE);
+                self.write(")");
+                match &statement.alternate {
+                    // an `else` would go to an `if` without one inside
+                    Some(_) if ends_in_if_without_else(&statement.consequent) => {
+                        self.write(" ");
+                        self.block(std::slice::from_ref(&statement.consequent));
+                    }
+                    Some(_) => self.inline_body(&statement.consequent),
+                    None => self.body(&statement.consequent),
+                }
+                if let Some(alternate) = &statement.alternate {
+                    self.write(" else");
+                    self.body(alternate);
+                }
+            }
+            Statement::Switch(statement) => {
+                self.write("switch (");
+                self.expression(&statement.discriminant, SEQUENCE);
+                self.write(") {");
+                self.indent += 1;
+                for case in &statement.cases {
+                    self.newline();
+                    match &case.test {
+                        Some(test) => {
+                            self.write("case ");
+                            self.expression(test, SEQUENCE);
+                            self.write(":");
+                        }
+                        None => self.write("default:"),
+                    }
+                    self.indent += 1;
+                    self.statements(&case.consequent);
+                    self.indent -= 1;
+                }
+                self.indent -= 1;
+                self.newline();
+                self.write("}");
+            }
+            Statement::Throw(statement) => {
+                self.write("throw ");
+                self.expression(&statement.argument, SEQUENCE);
+                self.semicolon();
+            }
+            Statement::Try(statement) => {
+                self.write("try ");
+                self.block(&statement.block.body);
+                if let Some(handler) = &statement.handler {
+                    self.write(" catch ");
+                    if let Some(param) = &handler.param {
+                        self.write("(");
+                        self.pattern(param);
+                        self.write(") ");
+                    }
+                    self.block(&handler.body.body);
+                }
+                if let Some(finalizer) = &statement.finalizer {
+                    self.write(" finally ");
+                    self.block(&finalizer.body);
+                }
+            }
+            Statement::While(statement) => {
+                self.write("while (");
+                self.expression(&statement.test, SEQUENCE);
+                self.write(")");
+                self.body(&statement.body);
+            }
+            Statement::DoWhile(statement) => {
+                self.write("do");
+                self.inline_body(&statement.body);
+                self.write(" while (");
+                self.expression(&statement.test, SEQUENCE);
+                self.write(")");
+                self.semicolon();
+            }
+            Statement::For(statement) => {
+                self.write("for (");
+                self.no_in = true;
+                match &statement.init {
+                    Some(ForStatementInit::VariableDeclaration(declaration)) => {
+                        self.variable_declaration(declaration)
+                    }
+                    Some(ForStatementInit::Expression(init)) => {
+                        if starts_with_let(init) {
+                            self.write("(");
+                            self.expression(init, SEQUENCE);
+                            self.write(")");
+                        } else {
+                            self.expression(init, SEQUENCE);
+                        }
+                    }
+                    None => {}
+                }
+                self.no_in = false;
+                self.write(";");
+                if let Some(test) = &statement.test {
+                    self.write(" ");
+                    self.expression(test, SEQUENCE);
+                }
+                self.write(";");
+                if let Some(update) = &statement.update {
+                    self.write(" ");
+                    self.expression(update, SEQUENCE);
+                }
+                self.write(")");
+                self.body(&statement.body);
+            }
+            Statement::ForIn(statement) => {
+                self.write("for (");
+                self.for_left(&statement.left);
+                self.write(" in ");
+                self.expression(&statement.right, SEQUENCE);
+                self.write(")");
+                self.body(&statement.body);
+            }
+            Statement::ForOf(statement) => {
+                self.write(if statement.is_await {
+                    "for await ("
+                } else {
+                    "for ("
+                });
+                self.for_left(&statement.left);
+                self.write(" of ");
+                self.expression(&statement.right, ASSIGNMENT);
+                self.write(")");
+                self.body(&statement.body);
+            }
+            Statement::FunctionDeclaration(function) => self.function(
+                function.is_async,
+                function.generator,
+                &function.id,
+                &function.params,
+                &function.body,
+            ),
+            Statement::VariableDeclaration(declaration) => {
+                self.variable_declaration(declaration);
+                self.semicolon();
+            }
+            Statement::ClassDeclaration(class) => {
+                self.class(&class.id, &class.super_class, &class.body)
+            }
+            Statement::ImportDeclaration(declaration) => {
+                self.write("import ");
+                let mut named = vec![];
+                let mut clauses = 0;
+                for specifier in &declaration.specifiers {
+                    match specifier {
+                        ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier) => {
+                            self.identifier(&specifier.local);
+                            clauses += 1;
+                        }
+                        ImportDeclarationSpecifier::ImportNamespaceSpecifier(specifier) => {
+                            if clauses > 0 {
+                                self.write(", ");
+                            }
+                            self.write("* as ");
+                            self.identifier(&specifier.local);
+                            clauses += 1;
+                        }
+                        ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
+                            named.push((&specifier.imported, &specifier.local));
+                        }
+                    }
+                }
+                if !named.is_empty() {
+                    if clauses > 0 {
+                        self.write(", ");
+                    }
+                    self.specifiers(&named);
+                    clauses += 1;
+                }
+                if clauses > 0 {
+                    self.write(" from ");
+                }
+                self.literal(&declaration.source);
+                self.semicolon();
+            }
+            Statement::ExportNamedDeclaration(declaration) => {
+                self.write("export ");
+                match &declaration.declaration {
+                    Some(statement) => self.statement(statement),
+                    None => {
+                        let specifiers: Vec<_> = declaration
+                            .specifiers
+                            .iter()
+                            .map(|specifier| (&specifier.local, &specifier.exported))
+                            .collect();
+                        self.specifiers(&specifiers);
+                        if let Some(source) = &declaration.source {
+                            self.write(" from ");
+                            self.literal(source);
+                        }
+                        self.semicolon();
+                    }
+                }
+            }
+            Statement::ExportDefaultDeclaration(declaration) => {
+                self.write("export default ");
+                match &declaration.declaration {
+                    ExportDefaultDeclarationKind::FunctionDeclaration(function) => self.function(
+                        function.is_async,
+                        function.generator,
+                        &function.id,
+                        &function.params,
+                        &function.body,
+                    ),
+                    ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                        self.class(&class.id, &class.super_class, &class.body)
+                    }
+                    ExportDefaultDeclarationKind::Expression(expression) => {
+                        // which would be read as a declaration
+                        if matches!(
+                            leftmost(expression),
+                            Expression::Function(_) | Expression::Class(_)
+                        ) {
+                            self.write("(");
+                            self.expression(expression, ASSIGNMENT);
+                            self.write(")");
+                        } else {
+                            self.expression(expression, ASSIGNMENT);
+                        }
+                        self.semicolon();
+                    }
+                }
+            }
+            Statement::ExportAllDeclaration(declaration) => {
+                self.write("export *");
+                if let Some(exported) = &declaration.exported {
+                    self.write(" as ");
+                    self.identifier(exported);
+                }
+                self.write(" from ");
+                self.literal(&declaration.source);
+                self.semicolon();
+            }
+            // left out of a list of statements, so only written on its own
+            Statement::Error(_) => self.verbatim("/* error */"),
+        })
+    }
+
+    fn label(&mut self, label: &Option<Identifier>) {
+        if let Some(label) = label {
+            self.write(" ");
+            self.identifier(label);
+        }
+        self.semicolon();
+    }
+
+    // `{ a, b as c }`, from the name on the near side to the one on the far
+    fn specifiers(&mut self, specifiers: &[(&Identifier, &Identifier)]) {
+        if specifiers.is_empty() {
+            self.write("{}");
+            return;
+        }
+        self.write("{ ");
+        for (index, (from, to)) in specifiers.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.identifier(from);
+            if from.name != to.name {
+                self.write(" as ");
+                self.identifier(to);
+            }
+        }
+        self.write(" }");
+    }
+
+    fn for_left(&mut self, left: &ForInStatementLeft) {
+        match left {
+            ForInStatementLeft::VariableDeclaration(declaration) => {
+                self.variable_declaration(declaration)
+            }
+            ForInStatementLeft::Pattern(pattern) => self.pattern(pattern),
+        }
+    }
+
+    fn variable_declaration(&mut self, declaration: &VariableDeclaration) {
+        self.write(&declaration.kind);
+        self.write(" ");
+        for (index, declarator) in declaration.declarations.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.pattern(&declarator.id);
+            if let Some(init) = &declarator.init {
+                self.write(" = ");
+                self.expression(init, ASSIGNMENT);
+            }
+        }
+    }
+
+    fn function(
+        &mut self,
+        is_async: bool,
+        generator: bool,
+        id: &Option<Identifier>,
+        params: &[Pattern],
+        body: &FunctionBody,
+    ) {
+        if is_async {
+            self.write("async ");
+        }
+        self.write(if generator { "function* " } else { "function " });
+        if let Some(id) = id {
+            self.identifier(id);
+        }
+        self.params(params);
+        self.write(" ");
+        self.block(&body.body);
+    }
+
+    fn params(&mut self, params: &[Pattern]) {
+        self.write("(");
+        for (index, param) in params.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            self.pattern(param);
+        }
+        self.write(")");
+    }
+
+    fn class(
+        &mut self,
+        id: &Option<Identifier>,
+        super_class: &Option<Expression>,
+        body: &ClassBody,
+    ) {
+        self.write("class ");
+        if let Some(id) = id {
+            self.identifier(id);
+            self.write(" ");
+        }
+        if let Some(super_class) = super_class {
+            self.write("extends ");
+            self.expression(super_class, CALL);
+            self.write(" ");
+        }
+        if body.body.is_empty() {
+            self.write("{}");
+            return;
+        }
+        self.write("{");
+        self.indent += 1;
+        let mut previous = None;
+        for element in &body.body {
+            let loc = match element {
+                ClassElement::MethodDefinition(method) => &method.loc,
+                ClassElement::PropertyDefinition(property) => &property.loc,
+                ClassElement::StaticBlock(block) => &block.loc,
+            };
+            self.blank_lines(previous, loc);
+            previous = Some(loc);
+            self.newline();
+            match element {
+                ClassElement::MethodDefinition(method) => {
+                    if method.is_static {
+                        self.write("static ");
+                    }
+                    let function = &method.value;
+                    match method.kind {
+                        MethodDefinitionKind::Get => self.write("get "),
+                        MethodDefinitionKind::Set => self.write("set "),
+                        MethodDefinitionKind::Constructor | MethodDefinitionKind::Method => {
+                            if function.is_async {
+                                self.write("async ");
+                            }
+                            if function.generator {
+                                self.write("*");
+                            }
+                        }
+                    }
+                    self.computed_key(&method.key, method.computed);
+                    self.params(&function.params);
+                    self.write(" ");
+                    self.block(&function.body.body);
+                }
+                ClassElement::PropertyDefinition(property) => {
+                    if property.is_static {
+                        self.write("static ");
+                    }
+                    self.computed_key(&property.key, property.computed);
+                    match &property.value {
+                        Some(value) => {
+                            self.write(" = ");
+                            self.expression(value, ASSIGNMENT);
+                            self.semicolon();
+                        }
+                        // which would modify the element on the next line
+                        None if matches!(
+                            &property.key,
+                            Expression::Identifier(key)
+                                if !property.computed
+                                    && matches!(key.name.as_str(), "get" | "set" | "static" | "async")
+                        ) =>
+                        {
+                            self.write(";")
+                        }
+                        None => self.semicolon(),
+                    }
+                }
+                ClassElement::StaticBlock(block) => {
+                    self.write("static ");
+                    self.block(&block.body);
+                }
+            }
+        }
+        self.indent -= 1;
+        self.newline();
+        self.write("}");
+    }
+
+    fn computed_key(&mut self, key: &Expression, computed: bool) {
+        if computed {
+            self.write("[");
+            self.expression(key, ASSIGNMENT);
+            self.write("]");
+        } else {
+            self.expression(key, PRIMARY);
+        }
+    }
+
+    fn property_key(&mut self, key: &PropertyKey, computed: bool) {
+        match key {
+            PropertyKey::Literal(literal) if !computed => self.literal(literal),
+            PropertyKey::Identifier(identifier) if !computed => self.identifier(identifier),
+            PropertyKey::Literal(literal) => {
+                self.write("[");
+                self.literal(literal);
+                self.write("]");
+            }
+            PropertyKey::Identifier(identifier) => {
+                self.write("[");
+                self.identifier(identifier);
+                self.write("]");
+            }
+            PropertyKey::Expression(expression) => self.computed_key(expression, true),
+        }
+    }
+
+    // wrapped in parentheses when it binds looser than `precedence`
+    fn expression(&mut self, expression: &Expression, precedence: u8) {
+        match expression {
+            Expression::Identifier(identifier) => {
+                self.mark(&identifier.loc, Some(&identifier.name))
+            }
+            _ => self.mark(expression.loc(), None),
+        }
+        let precedence_of = match expression {
+            Expression::Literal(literal) if self.boolean(literal).is_some() => UNARY,
+            _ => precedence_of(expression),
+        };
+        if precedence_of < precedence {
+            let no_in = std::mem::replace(&mut self.no_in, false);
+            self.write("(");
+            self.expression(expression, SEQUENCE);
+            self.write(")");
+            self.no_in = no_in;
+            return;
+        }
+        stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || self.bare_expression(expression))
+    }
+
+    fn bare_expression(&mut self, expression: &Expression) {
+        match expression {
+            Expression::Identifier(identifier) => self.write(&identifier.name),
+            Expression::Literal(literal) => self.literal(literal),
+            Expression::This(_) => self.write("this"),
+            Expression::Super(_) => self.write("super"),
+            Expression::Array(array) => {
+                let multiline =
+                    takes_lines(
+                        array
+                            .elements
+                            .iter()
+                            .flatten()
+                            .map(|element| match element {
+                                ArrayExpressionElement::Expression(element) => element,
+                                ArrayExpressionElement::SpreadElement(spread) => &spread.argument,
+                            }),
+                    );
+                self.write("[");
+                if multiline {
+                    self.indent += 1;
+                }
+                for (index, element) in array.elements.iter().enumerate() {
+                    self.item(index, multiline);
+                    match element {
+                        Some(ArrayExpressionElement::Expression(element)) => {
+                            self.expression(element, ASSIGNMENT)
+                        }
+                        Some(ArrayExpressionElement::SpreadElement(spread)) => {
+                            self.write("...");
+                            self.expression(&spread.argument, ASSIGNMENT);
+                        }
+                        None => {}
+                    }
+                }
+                // a hole at the end takes a comma of its own
+                let hole = matches!(array.elements.last(), Some(None));
+                self.end_items(multiline, hole);
+                self.write("]");
+            }
+            Expression::Object(object) => {
+                if object.properties.is_empty() {
+                    self.write("{}");
+                    return;
+                }
+                let multiline =
+                    takes_lines(object.properties.iter().map(|property| match property {
+                        ObjectExpressionProperty::Property(property) => &property.value,
+                        ObjectExpressionProperty::SpreadElement(spread) => &spread.argument,
+                    }));
+                if multiline {
+                    self.write("{");
+                    self.indent += 1;
+                } else {
+                    self.write("{ ");
+                }
+                for (index, property) in object.properties.iter().enumerate() {
+                    self.item(index, multiline);
+                    match property {
+                        ObjectExpressionProperty::Property(property) => {
+                            match (&property.kind, &property.value) {
+                                (PropertyKind::Init, _) if property.shorthand => {
+                                    self.expression(&property.value, ASSIGNMENT)
+                                }
+                                (PropertyKind::Init, Expression::Function(function))
+                                    if property.method =>
+                                {
+                                    if function.is_async {
+                                        self.write("async ");
+                                    }
+                                    if function.generator {
+                                        self.write("*");
+                                    }
+                                    self.property_key(&property.key, property.computed);
+                                    self.params(&function.params);
+                                    self.write(" ");
+                                    self.block(&function.body.body);
+                                }
+                                (
+                                    PropertyKind::Get | PropertyKind::Set,
+                                    Expression::Function(function),
+                                ) => {
+                                    self.write(match property.kind {
+                                        PropertyKind::Get => "get ",
+                                        _ => "set ",
+                                    });
+                                    self.property_key(&property.key, property.computed);
+                                    self.params(&function.params);
+                                    self.write(" ");
+                                    self.block(&function.body.body);
+                                }
+                                _ => {
+                                    self.property_key(&property.key, property.computed);
+                                    self.write(": ");
+                                    self.expression(&property.value, ASSIGNMENT);
+                                }
+                            }
+                        }
+                        ObjectExpressionProperty::SpreadElement(spread) => {
+                            self.write("...");
+                            self.expression(&spread.argument, ASSIGNMENT);
+                        }
+                    }
+                }
+                self.end_items(multiline, false);
+                self.write(if multiline { "}" } else { " }" });
+            }
+            Expression::Function(function) => self.function(
+                function.is_async,
+                function.generator,
+                &function.id,
+                &function.params,
+                &function.body,
+            ),
+            Expression::ArrowFunction(arrow) => {
+                if arrow.is_async {
+                    self.write("async ");
+                }
+                self.params(&arrow.params);
+                self.write(" => ");
+                match &arrow.body {
+                    ArrowFunctionExpressionBody::FunctionBody(body) => self.block(&body.body),
+                    // which would be read as a block
+                    ArrowFunctionExpressionBody::Expression(body)
+                        if matches!(leftmost(body), Expression::Object(_)) =>
+                    {
+                        self.write("(");
+                        self.expression(body, ASSIGNMENT);
+                        self.write(")");
+                    }
+                    ArrowFunctionExpressionBody::Expression(body) => {
+                        self.expression(body, ASSIGNMENT)
+                    }
+                }
+            }
+            Expression::Class(class) => self.class(&class.id, &class.super_class, &class.body),
+            Expression::Unary(unary) => {
+                let operator = unary_operator(&unary.operator);
+                self.write(operator);
+                // `typeof a`
+                if operator.ends_with(char::is_alphabetic) {
+                    self.write(" ");
+                }
+                // `- -a` and `+ ++a` rather than `--a` and `+++a`
+                if let Some(sign) = first_sign(&unary.argument) {
+                    if operator.starts_with(sign) {
+                        self.write(" ");
+                    }
+                }
+                self.expression(&unary.argument, UNARY);
+            }
+            Expression::Update(update) => {
+                let operator = match update.operator {
+                    UpdateOperator::Increment => "++",
+                    UpdateOperator::Decrement => "--",
+                };
+                if update.prefix {
+                    self.write(operator);
+                    if let Some(sign) = first_sign(&update.argument) {
+                        if operator.starts_with(sign) {
+                            self.write(" ");
+                        }
+                    }
+                    self.expression(&update.argument, CALL);
+                } else {
+                    self.expression(&update.argument, CALL);
+                    self.write(operator);
+                }
+            }
+            Expression::Binary(binary) => {
+                let precedence = binary_precedence(&binary.operator);
+                // `in` in the head of a `for` statement
+                let parenthesize = self.no_in && binary.operator == BinaryOperator::In;
+                if parenthesize {
+                    self.no_in = false;
+                    self.write("(");
+                }
+                if binary.operator == BinaryOperator::Exponent {
+                    // right-associative, and a unary expression can't be its
+                    // left operand
+                    self.expression(&binary.left, UPDATE);
+                    self.write(" ** ");
+                    self.expression(&binary.right, precedence);
+                } else {
+                    self.expression(&binary.left, precedence);
+                    self.write(" ");
+                    self.write(binary_operator(&binary.operator));
+                    self.write(" ");
+                    self.expression(&binary.right, precedence + 1);
+                }
+                if parenthesize {
+                    self.write(")");
+                    self.no_in = true;
+                }
+            }
+            Expression::Logical(logical) => {
+                let precedence = logical_precedence(&logical.operator);
+                let operator = logical_operator(&logical.operator);
+                self.logical_operand(&logical.operator, &logical.left, precedence);
+                self.write(" ");
+                self.write(operator);
+                self.write(" ");
+                self.logical_operand(&logical.operator, &logical.right, precedence + 1);
+            }
+            Expression::Assignment(assignment) => {
+                match &assignment.left {
+                    AssignmentExpressionLeft::Pattern(pattern) => self.pattern(pattern),
+                    AssignmentExpressionLeft::Expression(left) => self.expression(left, CALL),
+                }
+                self.write(" ");
+                self.write(assignment_operator(&assignment.operator));
+                self.write(" ");
+                self.expression(&assignment.right, ASSIGNMENT);
+            }
+            Expression::Member(member) => self.member(member),
+            Expression::Conditional(conditional) => {
+                self.expression(&conditional.test, CONDITIONAL + 1);
+                self.write(" ? ");
+                let no_in = std::mem::replace(&mut self.no_in, false);
+                self.expression(&conditional.consequent, ASSIGNMENT);
+                self.no_in = no_in;
+                self.write(" : ");
+                self.expression(&conditional.alternate, ASSIGNMENT);
+            }
+            Expression::Call(call) => {
+                self.callee(&call.callee);
+                if call.optional {
+                    self.write("?.");
+                }
+                self.arguments(&call.arguments);
+            }
+            Expression::Chain(chain) => self.expression(&chain.expression, CALL),
+            Expression::Yield(expression) => {
+                self.write(if expression.delegate {
+                    "yield*"
+                } else {
+                    "yield"
+                });
+                if let Some(argument) = &expression.argument {
+                    self.write(" ");
+                    self.expression(argument, ASSIGNMENT);
+                }
+            }
+            Expression::TemplateLiteral(template) => self.template(template),
+            Expression::TaggedTemplate(tagged) => {
+                self.callee(&tagged.tag);
+                self.template(&tagged.quasi);
+            }
+            Expression::New(new) => {
+                self.write("new ");
+                // a call in the callee would take the arguments instead
+                if precedence_of(&new.callee) < MEMBER || contains_call(&new.callee) {
+                    self.write("(");
+                    self.expression(&new.callee, SEQUENCE);
+                    self.write(")");
+                } else {
+                    self.expression(&new.callee, MEMBER);
+                }
+                self.arguments(&new.arguments);
+            }
+            Expression::MetaProperty(meta) => {
+                self.identifier(&meta.meta);
+                self.write(".");
+                self.identifier(&meta.property);
+            }
+            Expression::Import(import) => {
+                self.write("import(");
+                self.expression(&import.source, ASSIGNMENT);
+                self.write(")");
+            }
+            Expression::Sequence(sequence) => {
+                for (index, expression) in sequence.expressions.iter().enumerate() {
+                    if index > 0 {
+                        self.write(", ");
+                    }
+                    self.expression(expression, ASSIGNMENT);
+                }
+            }
+            Expression::Parenthesized(parenthesized) => {
+                let no_in = std::mem::replace(&mut self.no_in, false);
+                self.write("(");
+                self.expression(&parenthesized.expression, SEQUENCE);
+                self.write(")");
+                self.no_in = no_in;
+            }
+            Expression::Await(expression) => {
+                self.write("await ");
+                self.expression(&expression.argument, UNARY);
+            }
+            Expression::PrivateIdentifier(identifier) => {
+                self.write("#");
+                self.write(&identifier.name);
+            }
+            Expression::Error(_) => self.verbatim("/* error */"),
+        }
+    }
+
+    fn member(&mut self, member: &MemberExpression) {
+        self.callee(&member.object);
+        if member.computed {
+            self.write(if member.optional { "?.[" } else { "[" });
+            self.expression(&member.property, SEQUENCE);
+            self.write("]");
+        } else {
+            // `1..a`, as `1.a` would be a malformed number; minified, the
+            // space `needs_space` puts before the dot does instead, and `?.`
+            // can't be read as part of the number either way
+            if let (Expression::Literal(literal), false, false) =
+                (&member.object, self.options.minify, member.optional)
+            {
+                if is_integer(&literal_text(literal)) {
+                    self.write(".");
+                }
+            }
+            self.write(if member.optional { "?." } else { "." });
+            self.expression(&member.property, PRIMARY);
+        }
+    }
+
+    // what comes before the item at `index` of an object or array literal
+    fn item(&mut self, index: usize, multiline: bool) {
+        if multiline {
+            if index > 0 {
+                self.write(",");
+            }
+            self.newline();
+        } else if index > 0 {
+            self.write(", ");
+        }
+    }
+
+    // and what comes after the last one, where a hole needs its comma
+    fn end_items(&mut self, multiline: bool, hole: bool) {
+        if hole || (multiline && self.options.trailing_commas) {
+            self.write(",");
+        }
+        if multiline {
+            self.indent -= 1;
+            self.newline();
+        }
+    }
+
+    // the object of a member expression, or what is called or tagged
+    fn callee(&mut self, callee: &Expression) {
+        // `(a?.b).c` ends the optional chain before `.c`
+        if matches!(callee, Expression::Chain(_)) {
+            self.write("(");
+            self.expression(callee, SEQUENCE);
+            self.write(")");
+        } else {
+            self.expression(callee, CALL);
+        }
+    }
+
+    fn arguments(&mut self, arguments: &[CallExpressionArgument]) {
+        let no_in = std::mem::replace(&mut self.no_in, false);
+        self.write("(");
+        for (index, argument) in arguments.iter().enumerate() {
+            if index > 0 {
+                self.write(", ");
+            }
+            match argument {
+                CallExpressionArgument::Expression(argument) => {
+                    self.expression(argument, ASSIGNMENT)
+                }
+                CallExpressionArgument::SpreadElement(spread) => {
+                    self.write("...");
+                    self.expression(&spread.argument, ASSIGNMENT);
+                }
+            }
+        }
+        self.write(")");
+        self.no_in = no_in;
+    }
+
+    // `??` can't be mixed with `||` and `&&` without parentheses
+    fn logical_operand(
+        &mut self,
+        operator: &LogicalOperator,
+        operand: &Expression,
+        precedence: u8,
+    ) {
+        let mixed = match operand {
+            Expression::Logical(logical) => {
+                (*operator == LogicalOperator::NullishCoalescing)
+                    != (logical.operator == LogicalOperator::NullishCoalescing)
+            }
+            _ => false,
+        };
+        if mixed {
+            self.write("(");
+            self.expression(operand, SEQUENCE);
+            self.write(")");
+        } else {
+            self.expression(operand, precedence);
+        }
+    }
+
+    // written a chunk at a time from one substitution to the next, so that
+    // nothing comes between the delimiters and the text
+    fn template(&mut self, template: &TemplateLiteral) {
+        let mut chunk = "`".to_string();
+        for (index, quasi) in template.quasis.iter().enumerate() {
+            chunk.push_str(&quasi.value.raw);
+            if let Some(expression) = template.expressions.get(index) {
+                chunk.push_str("${");
+                self.verbatim(&chunk);
+                self.expression(expression, SEQUENCE);
+                chunk = "}".to_string();
+            }
+        }
+        chunk.push('`');
+        self.verbatim(&chunk);
+    }
+
+    fn pattern(&mut self, pattern: &Pattern) {
+        match pattern {
+            Pattern::Identifier(identifier) => self.mark(&identifier.loc, Some(&identifier.name)),
+            _ => self.mark(pattern.loc(), None),
+        }
+        match pattern {
+            Pattern::Identifier(identifier) => self.write(&identifier.name),
+            Pattern::Member(member) => self.member(member),
+            Pattern::Object(object) => {
+                if object.properties.is_empty() {
+                    self.write("{}");
+                    return;
+                }
+                self.write("{ ");
+                for (index, property) in object.properties.iter().enumerate() {
+                    if index > 0 {
+                        self.write(", ");
+                    }
+                    match property {
+                        ObjectPatternProperty::AssignmentProperty(property) => {
+                            if !property.shorthand {
+                                self.property_key(&property.key, property.computed);
+                                self.write(": ");
+                            }
+                            self.pattern(&property.value);
+                        }
+                        ObjectPatternProperty::RestElement(rest) => {
+                            self.write("...");
+                            self.pattern(&rest.argument);
+                        }
+                    }
+                }
+                self.write(" }");
+            }
+            Pattern::Array(array) => {
+                self.write("[");
+                for (index, element) in array.elements.iter().enumerate() {
+                    if index > 0 {
+                        self.write(", ");
+                    }
+                    if let Some(element) = element {
+                        self.pattern(element);
+                    }
+                }
+                if let Some(None) = array.elements.last() {
+                    self.write(",");
+                }
+                self.write("]");
+            }
+            Pattern::Rest(rest) => {
+                self.write("...");
+                self.pattern(&rest.argument);
+            }
+            Pattern::Assignment(assignment) => {
+                self.pattern(&assignment.left);
+                self.write(" = ");
+                self.expression(&assignment.right, ASSIGNMENT);
+            }
+            Pattern::Error(_) => self.verbatim("/* error */"),
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) {
+        let text = match &literal.value {
+            LiteralValue::String(value) => self.string(literal, value),
+            _ => match self.boolean(literal) {
+                Some(text) => text.to_string(),
+                None => literal_text(literal),
+            },
+        };
+        // the spaces of a division are a minified one's to drop
+        if is_division(literal) {
+            self.write(&text);
+        } else {
+            self.verbatim(&text);
+        }
+    }
+
+    // a boolean as it's written when minified, which binds as a unary
+    // expression does
+    fn boolean(&self, literal: &Literal) -> Option<&'static str> {
+        match literal.value {
+            LiteralValue::Boolean(value)
+                if self.options.minify && self.options.shorten_booleans =>
+            {
+                Some(if value { "!0" } else { "!1" })
+            }
+            _ => None,
+        }
+    }
+
+    fn string(&self, literal: &Literal, value: &str) -> String {
+        let raw = &literal.raw;
+        // the text between the quotes, escapes and all
+        let (quote, content) = if spells(raw, value) {
+            (
+                raw.chars().next().unwrap(),
+                raw[1..raw.len() - 1].to_string(),
+            )
+        } else {
+            ('"', escape(value))
+        };
+        let quote = match self.options.quotes {
+            Quotes::Preserve => quote,
+            Quotes::Single => '\'',
+            Quotes::Double => '"',
+            Quotes::Auto => {
+                let count = |quote| content.chars().filter(|char| *char == quote).count();
+                if count('"') <= count('\'') {
+                    '"'
+                } else {
+                    '\''
+                }
+            }
+        };
+        if spells(raw, value) && raw.starts_with(quote) {
+            raw.clone()
+        } else {
+            requote(&content, quote)
+        }
+    }
+}
+
+// whether any of the expressions holds a function or class body with
+// something in it, which is written over several lines
+fn takes_lines<'ast>(expressions: impl Iterator<Item = &'ast Expression>) -> bool {
+    struct Bodies(bool);
+
+    impl<'ast> Visitor<'ast> for Bodies {
+        fn visit_function_body(&mut self, node: &'ast FunctionBody) {
+            self.0 |= !node.body.is_empty();
+        }
+
+        fn visit_class_body(&mut self, node: &'ast ClassBody) {
+            self.0 |= !node.body.is_empty();
+        }
+    }
+
+    let mut bodies = Bodies(false);
+    for expression in expressions {
+        bodies.visit_expression(expression);
+        if bodies.0 {
+            return true;
+        }
+    }
+    false
+}
+
+// the last character of one token and the first of the next that would be
+// read as part of a longer punctuator, or as the start of a comment
+const RUN_TOGETHER: [&str; 26] = [
+    "==", "!=", "<=", ">=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "?=", "=>", "++", "--",
+    "**", "<<", ">>", "&&", "||", "??", "?.", "..", "//", "/*",
+];
+
+// whether minified `text` can't follow the `previous` piece written without
+// a space: a name, keyword or number runs on into the next, as does a
+// regular expression into what would be its flags, a dot and digits either
+// side of it read as a number, and punctuators run together as
+// `RUN_TOGETHER` has it
+pub(crate) fn needs_space(previous: &str, text: &str) -> bool {
+    let (Some(last), Some(first)) = (previous.chars().next_back(), text.chars().next()) else {
+        return false;
+    };
+    let word = |char: char| char.is_alphanumeric() || matches!(char, '$' | '_');
+    let regex = previous.len() > 1 && previous.starts_with('/') && last == '/';
+    if word(first) && (word(last) || regex) {
+        return true;
+    }
+    if (is_integer(previous) && first == '.') || (last == '.' && first.is_ascii_digit()) {
+        return true;
+    }
+    let mut pair = String::new();
+    pair.extend([last, first]);
+    RUN_TOGETHER.contains(&pair.as_str())
+}
+
+// whether `text` right after `out` would spell `<!--` or `-->` across the
+// two, which open a comment in a script; `-->` only does at the start of a
+// line but is kept apart everywhere
+fn html_comment(out: &str, text: &str) -> bool {
+    let tail = out.len() - out.chars().rev().take(3).map(char::len_utf8).sum::<usize>();
+    let joined = format!(
+        "{}{}",
+        &out[tail..],
+        text.chars().take(3).collect::<String>()
+    );
+    joined.contains("<!--") || joined.contains("-->")
+}
+
+// a number a dot right after would be read as the fraction of, such as `1`
+// or `1_000`
+fn is_integer(text: &str) -> bool {
+    text.starts_with(|char: char| char.is_ascii_digit())
+        && text
+            .chars()
+            .all(|char| char.is_ascii_digit() || char == '_')
+}
+
+pub(crate) fn literal_text(literal: &Literal) -> String {
+    match &literal.value {
+        LiteralValue::String(value) if spells(&literal.raw, value) => literal.raw.clone(),
+        LiteralValue::String(value) => requote(&escape(value), '"'),
+        LiteralValue::Boolean(value) => value.to_string(),
+        LiteralValue::Null(_) => "null".to_string(),
+        LiteralValue::Number(value) => {
+            // raw text that reads as a decimal number has to read as this
+            // one; hexadecimal and the like are trusted
+            let decimal = literal.raw.replace('_', "").parse::<f64>();
+            if literal.raw.is_empty() || matches!(decimal, Ok(raw) if raw != *value) {
+                number(*value)
+            } else {
+                literal.raw.clone()
+            }
+        }
+        LiteralValue::Bigint(digits) => {
+            if literal.raw.is_empty() {
+                format!("{}n", digits)
+            } else {
+                literal.raw.clone()
+            }
+        }
+        LiteralValue::RegExp(regex) => format!("/{}/{}", regex.pattern, regex.flags),
+    }
+}
+
+// `NaN` and `Infinity` are names a binding can shadow, so the values are
+// written as the divisions that give them
+fn number(value: f64) -> String {
+    if value.is_nan() {
+        "0 / 0".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "1 / 0" } else { "-1 / 0" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+// a number without a literal of its own, which `number` writes as a division
+fn is_division(literal: &Literal) -> bool {
+    matches!(literal.value, LiteralValue::Number(value) if !value.is_finite())
+        && literal_text(literal).contains('/')
+}
+
+// whether the raw text of a string literal still spells its value, escapes
+// and all
+fn spells(raw: &str, value: &str) -> bool {
+    cook_string(raw).as_deref() == Some(value)
+}
+
+// a string in quotes, where the quote is escaped and the other isn't
+fn requote(content: &str, quote: char) -> String {
+    let mut quoted = String::with_capacity(content.len() + 2);
+    quoted.push(quote);
+    let mut chars = content.chars();
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => match chars.next() {
+                Some(next @ ('\'' | '"')) if next != quote => quoted.push(next),
+                Some(next) => {
+                    quoted.push('\\');
+                    quoted.push(next);
+                }
+                None => quoted.push('\\'),
+            },
+            char if char == quote => {
+                quoted.push('\\');
+                quoted.push(char);
+            }
+            char => quoted.push(char),
+        }
+    }
+    quoted.push(quote);
+    quoted
+}
+
+// the escapes a string needs between quotes of either kind
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for char in value.chars() {
+        match char {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            char if (char as u32) < 0x20 || char == '\u{7f}' => {
+                escaped.push_str(&format!("\\x{:02x}", char as u32))
+            }
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+pub(crate) fn binary_operator(operator: &BinaryOperator) -> &'static str {
+    match operator {
+        BinaryOperator::DoubleE => "==",
+        BinaryOperator::DoubleNE => "!=",
+        BinaryOperator::TripleE => "===",
+        BinaryOperator::TripleNE => "!==",
+        BinaryOperator::LT => "<",
+        BinaryOperator::LTE => "<=",
+        BinaryOperator::GT => ">",
+        BinaryOperator::GTE => ">=",
+        BinaryOperator::LeftShift => "<<",
+        BinaryOperator::RightShift => ">>",
+        BinaryOperator::URightShift => ">>>",
+        BinaryOperator::Plus => "+",
+        BinaryOperator::Minus => "-",
+        BinaryOperator::Multiple => "*",
+        BinaryOperator::Divide => "/",
+        BinaryOperator::Modulo => "%",
+        BinaryOperator::Exponent => "**",
+        BinaryOperator::BitwiseOR => "|",
+        BinaryOperator::BitwiseXOR => "^",
+        BinaryOperator::BitwiseAND => "&",
+        BinaryOperator::In => "in",
+        BinaryOperator::Instanceof => "instanceof",
+    }
+}
+
+pub(crate) fn logical_operator(operator: &LogicalOperator) -> &'static str {
+    match operator {
+        LogicalOperator::LogicalOR => "||",
+        LogicalOperator::LogicalAND => "&&",
+        LogicalOperator::NullishCoalescing => "??",
+    }
+}
+
+pub(crate) fn unary_operator(operator: &UnaryOperator) -> &'static str {
+    match operator {
+        UnaryOperator::Positive => "+",
+        UnaryOperator::Negative => "-",
+        UnaryOperator::LogicalInversion => "!",
+        UnaryOperator::BitwiseInversion => "~",
+        UnaryOperator::Typeof => "typeof",
+        UnaryOperator::Void => "void",
+        UnaryOperator::Delete => "delete",
+    }
+}
+
+pub(crate) fn assignment_operator(operator: &AssignmentOperator) -> &'static str {
+    match operator {
+        AssignmentOperator::Normal => "=",
+        AssignmentOperator::Addition => "+=",
+        AssignmentOperator::Subtraction => "-=",
+        AssignmentOperator::Multiplication => "*=",
+        AssignmentOperator::Division => "/=",
+        AssignmentOperator::Remainder => "%=",
+        AssignmentOperator::Exponentiation => "**=",
+        AssignmentOperator::LeftShift => "<<=",
+        AssignmentOperator::RightShift => ">>=",
+        AssignmentOperator::URightShift => ">>>=",
+        AssignmentOperator::BitwiseOR => "|=",
+        AssignmentOperator::BitwiseXOR => "^=",
+        AssignmentOperator::BitwiseAND => "&=",
+        AssignmentOperator::LogicalOR => "||=",
+        AssignmentOperator::LogicalAND => "&&=",
+        AssignmentOperator::NullishCoalescing => "??=",
+    }
+}
+
+fn binary_precedence(operator: &BinaryOperator) -> u8 {
+    match operator {
+        BinaryOperator::BitwiseOR => 6,
+        BinaryOperator::BitwiseXOR => 7,
+        BinaryOperator::BitwiseAND => 8,
+        BinaryOperator::DoubleE
+        | BinaryOperator::DoubleNE
+        | BinaryOperator::TripleE
+        | BinaryOperator::TripleNE => 9,
+        BinaryOperator::LT
+        | BinaryOperator::LTE
+        | BinaryOperator::GT
+        | BinaryOperator::GTE
+        | BinaryOperator::In
+        | BinaryOperator::Instanceof => 10,
+        BinaryOperator::LeftShift | BinaryOperator::RightShift | BinaryOperator::URightShift => 11,
+        BinaryOperator::Plus | BinaryOperator::Minus => 12,
+        BinaryOperator::Multiple | BinaryOperator::Divide | BinaryOperator::Modulo => 13,
+        BinaryOperator::Exponent => 14,
+    }
+}
+
+fn logical_precedence(operator: &LogicalOperator) -> u8 {
+    match operator {
+        LogicalOperator::LogicalOR | LogicalOperator::NullishCoalescing => 4,
+        LogicalOperator::LogicalAND => 5,
+    }
+}
+
+fn precedence_of(expression: &Expression) -> u8 {
+    match expression {
+        Expression::Sequence(_) => SEQUENCE,
+        Expression::Assignment(_) | Expression::ArrowFunction(_) | Expression::Yield(_) => {
+            ASSIGNMENT
+        }
+        Expression::Conditional(_) => CONDITIONAL,
+        Expression::Logical(logical) => logical_precedence(&logical.operator),
+        Expression::Binary(binary) => binary_precedence(&binary.operator),
+        Expression::Unary(_) | Expression::Await(_) => UNARY,
+        Expression::Update(_) => UPDATE,
+        Expression::Call(_)
+        | Expression::Chain(_)
+        | Expression::TaggedTemplate(_)
+        | Expression::Import(_) => CALL,
+        Expression::Member(_) | Expression::New(_) => MEMBER,
+        Expression::Literal(literal) if is_division(literal) => {
+            binary_precedence(&BinaryOperator::Divide)
+        }
+        // written with a minus sign
+        Expression::Literal(literal)
+            if matches!(literal.value, LiteralValue::Number(value) if value.is_sign_negative())
+                && literal_text(literal).starts_with('-') =>
+        {
+            UNARY
+        }
+        _ => PRIMARY,
+    }
+}
+
+// the expression written first, which decides how a statement starting with
+// it would be read; one that ends up in parentheses of its own is looked into
+// all the same, which at worst adds a pair
+fn leftmost(expression: &Expression) -> &Expression {
+    let mut expression = expression;
+    loop {
+        expression = match expression {
+            Expression::Binary(binary) => &binary.left,
+            Expression::Logical(logical) => &logical.left,
+            Expression::Conditional(conditional) => &conditional.test,
+            Expression::Assignment(assignment) => match &assignment.left {
+                AssignmentExpressionLeft::Expression(left) => left,
+                _ => return expression,
+            },
+            Expression::Sequence(sequence) => match sequence.expressions.first() {
+                Some(first) => first,
+                None => return expression,
+            },
+            Expression::Member(member) => &member.object,
+            Expression::Call(call) => &call.callee,
+            Expression::TaggedTemplate(tagged) => &tagged.tag,
+            Expression::Update(update) if !update.prefix => &update.argument,
+            _ => return expression,
+        };
+    }
+}
+
+fn starts_statement_ambiguously(expression: &Expression) -> bool {
+    match leftmost(expression) {
+        Expression::Object(_) | Expression::Function(_) | Expression::Class(_) => true,
+        Expression::Assignment(assignment) => matches!(
+            &assignment.left,
+            AssignmentExpressionLeft::Pattern(Pattern::Object(_))
+        ),
+        _ => starts_with_let(expression),
+    }
+}
+
+// `let [` starts a declaration
+fn starts_with_let(expression: &Expression) -> bool {
+    matches!(leftmost(expression), Expression::Identifier(identifier) if identifier.name == "let")
+}
+
+// the sign an expression is written starting with, which can't follow the
+// same sign
+fn first_sign(expression: &Expression) -> Option<char> {
+    match expression {
+        Expression::Unary(unary) => match unary.operator {
+            UnaryOperator::Positive => Some('+'),
+            UnaryOperator::Negative => Some('-'),
+            _ => None,
+        },
+        Expression::Update(update) if update.prefix => match update.operator {
+            UpdateOperator::Increment => Some('+'),
+            UpdateOperator::Decrement => Some('-'),
+        },
+        Expression::Literal(literal) if literal_text(literal).starts_with('-') => Some('-'),
+        _ => None,
+    }
+}
+
+fn contains_call(expression: &Expression) -> bool {
+    match expression {
+        Expression::Call(_) => true,
+        Expression::Member(member) => contains_call(&member.object),
+        Expression::TaggedTemplate(tagged) => contains_call(&tagged.tag),
+        _ => false,
+    }
+}
+
+fn ends_in_if_without_else(statement: &Statement) -> bool {
+    match statement {
+        Statement::If(statement) => match &statement.alternate {
+            Some(alternate) => ends_in_if_without_else(alternate),
+            None => true,
+        },
+        Statement::While(statement) => ends_in_if_without_else(&statement.body),
+        Statement::For(statement) => ends_in_if_without_else(&statement.body),
+        Statement::ForIn(statement) => ends_in_if_without_else(&statement.body),
+        Statement::ForOf(statement) => ends_in_if_without_else(&statement.body),
+        Statement::With(statement) => ends_in_if_without_else(&statement.body),
+        Statement::Labeled(statement) => ends_in_if_without_else(&statement.body),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        codegen::{
+            generate, generate_minified, generate_with_options, needs_space, CodegenOptions, Quotes,
+        },
+        node::{Expression, LiteralValue, SourceType, Statement},
+        parser::parse,
+        test_support::README_SRC,
+        tokenize,
+    };
+
+    #[test]
+    fn generated_code_parses_back_to_the_same_program() {
+        let program = parse(README_SRC).unwrap();
+        let generated = generate(&program);
+        assert_eq!(generate(&parse(&generated).unwrap()), generated);
+        assert!(generated.starts_with("const a = getNumber() ?? 1;\nconst b = 2;\n"));
+    }
+
+    #[test]
+    fn generated_code_has_the_parentheses_precedence_needs() {
+        let cases = [
+            ("(a + b) * c;", "(a + b) * c;\n"),
+            ("a + (b * c);", "a + b * c;\n"),
+            ("(a - b) - c;", "a - b - c;\n"),
+            ("a - (b - c);", "a - (b - c);\n"),
+            ("(a ** b) ** c;", "(a ** b) ** c;\n"),
+            ("(-a) ** b;", "(-a) ** b;\n"),
+            ("(a ?? b) || c;", "(a ?? b) || c;\n"),
+            ("x = (a, b);", "x = (a, b);\n"),
+            ("(a?.b).c;", "(a?.b).c;\n"),
+            ("new (f())();", "new (f())();\n"),
+            ("- (-a);", "- -a;\n"),
+            ("(1).toString();", "1..toString();\n"),
+            ("x = 1_000 .toFixed();", "x = 1_000..toFixed();\n"),
+            ("1?.x;", "1?.x;\n"),
+            ("1.5.toFixed();", "1.5.toFixed();\n"),
+            (
+                "for (var i = ('a' in b);;);",
+                "for (var i = ('a' in b);;) ;\n",
+            ),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(generate(&parse(src).unwrap()), expected, "{}", src);
+        }
+    }
+
+    // a statement that starts like a declaration, a block or a directive
+    // gets parentheses of its own, and none relies on a line break
+    #[test]
+    fn generated_statements_start_unambiguously() {
+        let cases = [
+            ("(function () {})();", "(function () {}());\n"),
+            ("({}).toString();", "({}.toString());\n"),
+            ("({ a } = b);", "({ a } = b);\n"),
+            ("(class {});", "(class {});\n"),
+            ("a\n(b)", "a(b);\n"),
+            ("a = b\n;[c] = d", "a = b;\n[c] = d;\n"),
+            ("f();\n('not a directive');", "f();\n('not a directive');\n"),
+            ("a = () => ({});", "a = () => ({});\n"),
+            (
+                "if (a) { if (b) c; } else d;",
+                "if (a) {\n  if (b) c;\n} else d;\n",
+            ),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(generate(&parse(src).unwrap()), expected, "{}", src);
+        }
+    }
+
+    // a literal built after parsing is written from its value
+    #[test]
+    fn generated_literals_are_escaped() {
+        let mut program = parse("a = 'b'; c = 1;").unwrap();
+        for (statement, value) in program.body.iter_mut().zip([
+            LiteralValue::String("say \"hi\"\n\\".to_string()),
+            LiteralValue::Number(0.5),
+        ]) {
+            let Statement::Expression(statement) = statement else {
+                panic!("expected an expression statement");
+            };
+            let Expression::Assignment(assignment) = &mut statement.expression else {
+                panic!("expected an assignment");
+            };
+            let Expression::Literal(literal) = &mut assignment.right else {
+                panic!("expected a literal");
+            };
+            literal.value = value;
+        }
+        assert_eq!(
+            generate(&program),
+            "a = \"say \\\"hi\\\"\\n\\\\\";\nc = 0.5;\n"
+        );
+        // escapes that still spell the value are kept as written
+        let src = "a = '\\x41\\\nb';\n";
+        assert_eq!(generate(&parse(src).unwrap()), src);
+    }
+
+    #[test]
+    fn generated_strings_take_the_quotes_asked_for() {
+        let program = parse(r#"a = ['x', "it's", 'say "hi"', 'it\'s'];"#).unwrap();
+        let cases = [
+            (
+                Quotes::Preserve,
+                r#"a = ['x', "it's", 'say "hi"', 'it\'s'];"#,
+            ),
+            (
+                Quotes::Single,
+                r#"a = ['x', 'it\'s', 'say "hi"', 'it\'s'];"#,
+            ),
+            (
+                Quotes::Double,
+                r#"a = ["x", "it's", "say \"hi\"", "it's"];"#,
+            ),
+            (Quotes::Auto, r#"a = ["x", "it's", 'say "hi"', "it's"];"#),
+        ];
+        for (quotes, expected) in cases {
+            let options = CodegenOptions::new().quotes(quotes);
+            assert_eq!(
+                generate_with_options(&program, &options),
+                format!("{}\n", expected)
+            );
+        }
+    }
+
+    #[test]
+    fn minified_tokens_stay_apart() {
+        // a token of every kind, and of every form a number takes
+        let tokens = [
+            "1", "1.5", ".5", "0x1", "1e3", "1_0", "1n", "'s'", "`t`", "a", "$", "_", "#p", "/r/",
+            "var", "in", "typeof", "true", "==", "===", "!=", "!==", "<", "<=", ">", ">=", "+",
+            "-", "*", "/", "%", "**", "=", "+=", "-=", "*=", "/=", "%=", "**=", "<<=", ">>=",
+            ">>>=", "|=", "^=", "&=", "||=", "&&=", "??=", "(", ")", "[", "]", "{", "}", ".", "?.",
+            ";", ",", ":", "?", "??", "!", "~", "||", "|", "&&", "&", "^", "<<", ">>", ">>>", "++",
+            "--", "=>", "...",
+        ];
+        let lex = |src: &str| {
+            tokenize(src).map(|lexemes| {
+                lexemes
+                    .into_iter()
+                    .map(|lexeme| lexeme.token)
+                    .collect::<Vec<_>>()
+            })
+        };
+        for first in tokens {
+            for second in tokens {
+                let spaced = format!("{} {}", first, second);
+                // pairs that don't read as the two tokens even apart never
+                // come up
+                let spans = tokenize(&spaced).map(|lexemes| {
+                    lexemes
+                        .iter()
+                        .map(|lexeme| &spaced[lexeme.offset..lexeme.end_offset])
+                        .collect::<Vec<_>>()
+                });
+                if spans != Ok(vec![first, second]) {
+                    continue;
+                }
+                let expected = lex(&spaced);
+                let minified = if needs_space(first, second) {
+                    spaced.clone()
+                } else {
+                    format!("{}{}", first, second)
+                };
+                assert_eq!(lex(&minified), expected, "{}", spaced);
+            }
+        }
+    }
+
+    #[test]
+    fn minified_code_keeps_only_the_spaces_it_needs() {
+        let cases = [
+            ("a + +b; c - -d; e + ++f;", "a+ +b;c- -d;e+ ++f"),
+            ("function f(a) { return a; }", "function f(a){return a}"),
+            (
+                "x = 1 .toString() + 1.5.toFixed();",
+                "x=1 .toString()+1.5.toFixed()",
+            ),
+            ("if (a) b; else { c; }", "if(a)b;else{c}"),
+            ("a = `x ${ b } y`;", "a=`x ${b} y`"),
+            ("a = b / /c/;", "a=b/ /c/"),
+            (
+                "a = true && false; true.valueOf();",
+                "a=!0&&!1;(!0).valueOf()",
+            ),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(generate_minified(&parse(src).unwrap()), expected);
+        }
+        let program = parse("a = true;").unwrap();
+        let options = CodegenOptions::new().minify(true).shorten_booleans(false);
+        assert_eq!(generate_with_options(&program, &options), "a=true");
+    }
+
+    #[test]
+    fn minified_code_never_spells_an_html_comment() {
+        for (source, expected) in [("x = y < !--z;", "x=y<! --z"), ("a-- > b;", "a-- >b")] {
+            let minified = generate_minified(&parse(source).unwrap());
+            assert_eq!(minified, expected);
+            assert_eq!(
+                generate(&parse(&minified).unwrap()),
+                generate(&parse(source).unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn nodes_display_as_their_source() {
+        let program = parse("function plus(a, b) { return a + b; }\nplus(a, b);").unwrap();
+        let Statement::Expression(statement) = &program.body[1] else {
+            panic!("expected an expression statement");
+        };
+        assert_eq!(statement.expression.to_string(), "plus(a, b)");
+        assert_eq!(program.body[1].to_string(), "plus(a, b);");
+        assert_eq!(
+            program.body[0].to_string(),
+            "function plus(a, b) {\n  return a + b;\n}"
+        );
+
+        let (program, errors) =
+            crate::parser::parse_with_recovery("a = ;\nb = c;", SourceType::Script);
+        assert!(!errors.is_empty());
+        let displayed: Vec<String> = program
+            .body
+            .iter()
+            .map(|statement| statement.to_string())
+            .collect();
+        assert_eq!(displayed, ["a = /* error */;", "b = c;"]);
+    }
+}
@@ -0,0 +1,207 @@
+// Attaches comments to the nodes around them, for formatters and doc tools
+// that have to say which declaration a `/** doc */` describes.
+//
+// A comment goes to the innermost node that contains it, and within that to
+// one of its children: the one before it when they share a line, so that
+// `a(); // why` trails the call, and otherwise the one after it, so that a
+// comment on its own line leads what follows. A comment with nothing after
+// it in its node, such as one just before the `}` of a block or inside an
+// empty block, is left dangling in that node rather than dropped.
+use std::{collections::HashMap, ops::Range};
+
+use crate::{
+    node::{Comment, Node},
+    node_ref::NodeRef,
+    parent_map::{NodeId, ParentMap},
+};
+
+// the comments each node leads, trails or holds, as runs of the comments
+// given, which come in source order
+pub struct CommentMap<'c> {
+    comments: &'c [Comment],
+    leading: HashMap<NodeId, Range<usize>>,
+    trailing: HashMap<NodeId, Range<usize>>,
+    dangling: HashMap<NodeId, Range<usize>>,
+}
+
+enum Attachment {
+    Leading,
+    Trailing,
+    Dangling,
+}
+
+impl<'c> CommentMap<'c> {
+    pub fn new(parents: &ParentMap, comments: &'c [Comment]) -> Self {
+        let mut map = CommentMap {
+            comments,
+            leading: HashMap::new(),
+            trailing: HashMap::new(),
+            dangling: HashMap::new(),
+        };
+        let Some(program) = parents.node(NodeId(0)) else {
+            return map;
+        };
+        for (index, comment) in comments.iter().enumerate() {
+            let (attachment, node) = attach(program, comment);
+            let Some(id) = parents.id_of(node) else {
+                continue;
+            };
+            let table = match attachment {
+                Attachment::Leading => &mut map.leading,
+                Attachment::Trailing => &mut map.trailing,
+                Attachment::Dangling => &mut map.dangling,
+            };
+            table
+                .entry(id)
+                .and_modify(|run| run.end = index + 1)
+                .or_insert(index..index + 1);
+        }
+        map
+    }
+
+    // the comments before a node, closest last
+    pub fn leading(&self, id: NodeId) -> &'c [Comment] {
+        self.run(&self.leading, id)
+    }
+
+    // the comments after a node on the line it ends
+    pub fn trailing(&self, id: NodeId) -> &'c [Comment] {
+        self.run(&self.trailing, id)
+    }
+
+    // the comments inside a node that come after all of its children
+    pub fn dangling(&self, id: NodeId) -> &'c [Comment] {
+        self.run(&self.dangling, id)
+    }
+
+    fn run(&self, table: &HashMap<NodeId, Range<usize>>, id: NodeId) -> &'c [Comment] {
+        match table.get(&id) {
+            Some(run) => &self.comments[run.clone()],
+            None => &[],
+        }
+    }
+}
+
+fn attach<'a>(program: NodeRef<'a>, comment: &Comment) -> (Attachment, NodeRef<'a>) {
+    let (start, end) = comment.loc.range;
+    let mut node = program;
+    loop {
+        let children = node.children();
+        if let Some(&child) = children
+            .iter()
+            .find(|child| child.loc().range.0 <= start && end <= child.loc().range.1)
+        {
+            node = child;
+            continue;
+        }
+        let before = children
+            .iter()
+            .rev()
+            .find(|child| child.loc().range.1 <= start);
+        if let Some(&before) = before {
+            if before.loc().end.line == comment.loc.start.line {
+                return (Attachment::Trailing, before);
+            }
+        }
+        return match children.iter().find(|child| end <= child.loc().range.0) {
+            Some(&after) => (Attachment::Leading, after),
+            None => (Attachment::Dangling, node),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        comments::CommentMap,
+        node::{CommentKind, SourceType, Statement},
+        parent_map::{NodeId, ParentMap},
+    };
+
+    #[test]
+    fn comments_come_out_of_parsing_with_their_spans() {
+        let src = "a; // one\n/* two\n   lines */ b / c; /**/";
+        let (program, comments) =
+            crate::parser::parse_with_comments(src, SourceType::Script).unwrap();
+        assert_eq!(program.body.len(), 2);
+        let comments: Vec<_> = comments
+            .iter()
+            .map(|comment| {
+                let loc = &comment.loc;
+                (
+                    comment.kind,
+                    comment.value.as_str(),
+                    (loc.start.line, loc.start.column),
+                    loc.range,
+                )
+            })
+            .collect();
+        assert_eq!(
+            comments,
+            [
+                (CommentKind::Line, " one", (1, 3), (3, 9)),
+                (CommentKind::Block, " two\n   lines ", (2, 0), (10, 28)),
+                (CommentKind::Block, "", (3, 19), (36, 40)),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_attach_to_the_nodes_around_them() {
+        let src = "/** doc */\n\
+                   function f(a /* inline */) {\n\
+                   \x20 // leads x\n\
+                   \x20 x(); // trails x\n\
+                   \x20 // before the brace\n\
+                   }\n\
+                   if (a) { /* dangling */ }";
+        let (program, comments) =
+            crate::parser::parse_with_comments(src, SourceType::Script).unwrap();
+        let parents = ParentMap::new(&program);
+        let attached = CommentMap::new(&parents, &comments);
+        let values = |comments: &[crate::node::Comment]| {
+            comments
+                .iter()
+                .map(|comment| comment.value.trim().to_string())
+                .collect::<Vec<_>>()
+        };
+        let id = |node| parents.id_of(node).unwrap();
+
+        let Statement::FunctionDeclaration(function) = &program.body[0] else {
+            panic!("expected a function");
+        };
+        assert_eq!(
+            values(attached.leading(id((&program.body[0]).into()))),
+            ["* doc"]
+        );
+        assert_eq!(
+            values(attached.trailing(id((&function.params[0]).into()))),
+            ["inline"]
+        );
+        let call = &function.body.body[0];
+        assert_eq!(values(attached.leading(id(call.into()))), ["leads x"]);
+        assert_eq!(values(attached.trailing(id(call.into()))), ["trails x"]);
+        assert_eq!(
+            values(attached.dangling(id((&function.body).into()))),
+            ["before the brace"]
+        );
+
+        let Statement::If(statement) = &program.body[1] else {
+            panic!("expected an if statement");
+        };
+        assert_eq!(
+            values(attached.dangling(id((&statement.consequent).into()))),
+            ["dangling"]
+        );
+        // every comment ends up somewhere
+        let total: usize = (0..parents.len() as u32)
+            .map(|index| {
+                let id = NodeId(index);
+                attached.leading(id).len()
+                    + attached.trailing(id).len()
+                    + attached.dangling(id).len()
+            })
+            .sum();
+        assert_eq!(total, comments.len());
+    }
+}
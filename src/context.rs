@@ -1,21 +1,350 @@
-use crate::node::{Expression, Statement};
+use std::collections::HashMap;
 
-pub struct Context<'a> {
-    pub is_function_identifier: bool,
+use crate::{
+    name::Name,
+    node::{SourceType, Statement},
+    options::{ParserOptions, DEFAULT_MAX_NESTING, LATEST_ECMA_VERSION},
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DeclarationKind {
+    // `var`, and `var` from a nested block hoisted through this one
+    Var,
+    // `let`, `const`, classes and imports
+    Lexical,
+    Function,
+    // parameters of the function, or of the catch clause
+    Param,
+}
+
+// the names declared in a block, with what declared each and where it was
+// declared first
+#[derive(Default)]
+pub struct Block {
+    pub names: HashMap<String, (DeclarationKind, usize, usize)>,
+}
+
+// a statement `break` or `continue` can refer to
+pub enum TargetKind {
+    Loop,
+    Switch,
+    // whether the labeled statement is a loop decides if `continue` may
+    // name the label, which `break` always may
+    Label { name: Name, is_loop: bool },
+}
+
+pub struct Target {
+    pub kind: TargetKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Target {
+    pub fn describe(&self) -> String {
+        let what = match &self.kind {
+            TargetKind::Loop => "loop".to_string(),
+            TargetKind::Switch => "switch".to_string(),
+            TargetKind::Label { name, .. } => format!("statement labeled '{}'", name),
+        };
+        format!("{} at line:{}, column:{}", what, self.line, self.column)
+    }
+}
+
+// how many scopes, blocks and targets were open at some point, for an error
+// to close what it left open
+pub struct Depth {
+    scopes: usize,
+    blocks: usize,
+    targets: usize,
+    classes: usize,
+}
+
+// a frame for the program, a function body or a class static block, holding
+// the statements parsed so far and the flags that decide what is legal in it
+pub struct Scope {
+    pub statements: Vec<Statement>,
+    pub is_strict: bool,
+    pub is_function: bool,
+    pub is_async: bool,
+    pub is_generator: bool,
+    pub is_static_block: bool,
+    pub is_arrow: bool,
+    // `super.x` is allowed in methods, field initializers and static blocks,
+    // and `super()` only in the constructor of a class that extends another
+    pub allows_super_property: bool,
+    pub allows_super_call: bool,
+    // a field initializer, where `arguments` can't be used as it can in a
+    // method
+    pub is_field_initializer: bool,
+    // the body of the frame first, then the blocks open inside it
+    pub blocks: Vec<Block>,
+    // the loops, switches and labeled statements around the current one,
+    // which can't be seen through a function
+    pub targets: Vec<Target>,
+}
+
+impl Scope {
+    fn new(is_strict: bool) -> Self {
+        Scope {
+            statements: vec![],
+            is_strict,
+            is_function: false,
+            is_async: false,
+            is_generator: false,
+            is_static_block: false,
+            is_arrow: false,
+            allows_super_property: false,
+            allows_super_call: false,
+            is_field_initializer: false,
+            blocks: vec![Block::default()],
+            targets: vec![],
+        }
+    }
+}
+
+// what a private name of a class was declared as; a getter and a setter
+// may share a name, after which it is taken like any other
+#[derive(Clone, Copy, PartialEq)]
+pub enum PrivateKind {
+    Get,
+    Set,
+    Other,
+}
+
+// the private names a class body declares, with whether each is static,
+// and those used in it so far with where, which it or a class around it
+// must declare by the end of its body
+#[derive(Default)]
+pub struct PrivateNames {
+    pub declared: HashMap<Name, (PrivateKind, bool)>,
+    pub used: Vec<(Name, usize, usize)>,
+}
+
+// errors that depend on how the cover grammar is resolved, found while an
+// expression may still turn out to be a pattern; only the first of each
+// kind is kept
+#[derive(Default)]
+pub struct CoverErrors {
+    // only valid in a pattern, like the default in `({ a = 1 })`
+    pub pattern: Option<(usize, usize, &'static str)>,
+    // not valid in a binding, like the parentheses in `((a)) => a`
+    pub binding: Option<(usize, usize, &'static str)>,
+    // not valid in a destructuring assignment either, like the parentheses
+    // in `[([a])] = b`, which only a name or member may have
+    pub assignment: Option<(usize, usize, &'static str)>,
+}
+
+impl CoverErrors {
+    pub fn merge(&mut self, other: CoverErrors) {
+        self.pattern = self.pattern.or(other.pattern);
+        self.binding = self.binding.or(other.binding);
+        self.assignment = self.assignment.or(other.assignment);
+    }
+}
+
+pub struct Context {
+    pub source_type: SourceType,
+    // syntax newer than this edition is an error
+    pub ecma_version: u32,
+    // still in the directive prologue of a program or function body
     pub is_directive: bool,
     pub is_pattern: bool,
-    pub statements: &'a mut Vec<Box<dyn Statement>>,
-    pub expressions: Option<&'a mut Vec<Box<dyn Expression>>>,
+    // set in the head of a `for`, where a top-level `in` starts a for-in loop
+    // rather than being the relational operator
+    pub no_in: bool,
+    // keep `ParenthesizedExpression` nodes instead of dropping the parentheses
+    pub preserve_parens: bool,
+    // allow `return` at the top level, for code that will be wrapped in a
+    // function, as CommonJS modules are
+    pub allow_return_outside_function: bool,
+    // record errors in statements and carry on with the next statement
+    // instead of stopping at the first one
+    pub recover: bool,
+    // how many statements and expressions the parser is inside of, which
+    // may not go past `max_nesting`
+    pub nesting: usize,
+    pub max_nesting: usize,
+    pub cover_errors: CoverErrors,
+    // exported names with the line and column they were first exported at
+    pub exported_names: HashMap<String, (usize, usize)>,
+    // the local names `export { a }` exports, with where, which the module
+    // must declare somewhere at its top level
+    pub exported_locals: Vec<(String, usize, usize)>,
+    // the class bodies the parser is inside of, innermost last
+    pub classes: Vec<PrivateNames>,
+    scopes: Vec<Scope>,
 }
 
-impl<'a> Context<'a> {
-    pub fn new(statements: &'a mut Vec<Box<dyn Statement>>) -> Self {
+impl Context {
+    pub fn new(source_type: SourceType) -> Self {
+        // modules are strict and allow `await` at the top level
+        let is_module = source_type == SourceType::Module;
+        let mut program_scope = Scope::new(is_module);
+        program_scope.is_async = is_module;
         Context {
-            statements,
-            expressions: None,
-            is_function_identifier: false,
+            source_type,
+            ecma_version: LATEST_ECMA_VERSION,
             is_directive: false,
             is_pattern: false,
+            no_in: false,
+            preserve_parens: false,
+            allow_return_outside_function: false,
+            recover: false,
+            nesting: 0,
+            max_nesting: DEFAULT_MAX_NESTING,
+            cover_errors: CoverErrors::default(),
+            exported_names: HashMap::new(),
+            exported_locals: vec![],
+            classes: vec![],
+            scopes: vec![program_scope],
         }
     }
+
+    pub fn with_options(options: &ParserOptions) -> Self {
+        let mut context = Context::new(options.source_type);
+        context.ecma_version = options.ecma_version;
+        context.allow_return_outside_function = options.allow_return_outside_function;
+        context.preserve_parens = options.preserve_parens;
+        context.recover = options.tolerant;
+        context.max_nesting = options.max_nesting;
+        context
+    }
+
+    pub fn is_module(&self) -> bool {
+        self.source_type == SourceType::Module
+    }
+
+    pub fn scope(&self) -> &Scope {
+        self.scopes
+            .last()
+            .expect("the program scope is never popped early")
+    }
+
+    pub fn scope_mut(&mut self) -> &mut Scope {
+        self.scopes
+            .last_mut()
+            .expect("the program scope is never popped early")
+    }
+
+    // arrows have no `new.target`, `super` or `this` of their own, so those
+    // are decided by the closest scope that isn't an arrow
+    pub fn function_scope(&self) -> &Scope {
+        self.scopes
+            .iter()
+            .rev()
+            .find(|scope| !scope.is_arrow)
+            .expect("the program scope is not an arrow")
+    }
+
+    pub fn push_function_scope(&mut self, is_async: bool, is_generator: bool) {
+        let mut scope = Scope::new(self.scope().is_strict);
+        scope.is_function = true;
+        scope.is_async = is_async;
+        scope.is_generator = is_generator;
+        self.scopes.push(scope);
+    }
+
+    pub fn push_method_scope(
+        &mut self,
+        is_async: bool,
+        is_generator: bool,
+        allows_super_call: bool,
+    ) {
+        self.push_function_scope(is_async, is_generator);
+        let scope = self.scope_mut();
+        scope.allows_super_property = true;
+        scope.allows_super_call = allows_super_call;
+    }
+
+    pub fn push_arrow_scope(&mut self, is_async: bool) {
+        self.push_function_scope(is_async, false);
+        self.scope_mut().is_arrow = true;
+    }
+
+    // class bodies are always strict
+    pub fn push_static_block_scope(&mut self) {
+        let mut scope = Scope::new(true);
+        scope.is_static_block = true;
+        scope.allows_super_property = true;
+        self.scopes.push(scope);
+    }
+
+    pub fn push_block(&mut self) {
+        self.scope_mut().blocks.push(Block::default());
+    }
+
+    pub fn pop_block(&mut self) {
+        self.scope_mut().blocks.pop();
+    }
+
+    pub fn depth(&self) -> Depth {
+        Depth {
+            scopes: self.scopes.len(),
+            blocks: self.scope().blocks.len(),
+            targets: self.scope().targets.len(),
+            classes: self.classes.len(),
+        }
+    }
+
+    // drops the scopes, blocks, targets and classes an error left open
+    pub fn truncate(&mut self, depth: Depth) {
+        self.scopes.truncate(depth.scopes);
+        self.classes.truncate(depth.classes);
+        let scope = self.scope_mut();
+        scope.blocks.truncate(depth.blocks);
+        scope.targets.truncate(depth.targets);
+    }
+
+    // adds a name to the innermost block, or for `var` to every block up to
+    // the body of the function, returning where the name was declared first
+    // when the two declarations conflict; `var` may repeat `var` and
+    // parameters, and in sloppy code a function in a block may repeat
+    // another, but lexical declarations may repeat nothing
+    pub fn declare(
+        &mut self,
+        name: &str,
+        kind: DeclarationKind,
+        line: usize,
+        column: usize,
+    ) -> Result<(), (usize, usize)> {
+        let is_module = self.is_module() && self.scopes.len() == 1;
+        let scope = self.scope_mut();
+        let is_strict = scope.is_strict;
+        let count = scope.blocks.len();
+        let first_block = if kind == DeclarationKind::Var {
+            0
+        } else {
+            count - 1
+        };
+        for index in (first_block..count).rev() {
+            // functions in the body of a function or script act like `var`
+            let is_body = index == 0;
+            let block = &mut scope.blocks[index];
+            if let Some(&(first, first_line, first_column)) = block.names.get(name) {
+                let allowed = match (first, kind) {
+                    (DeclarationKind::Lexical, _) | (_, DeclarationKind::Lexical) => false,
+                    (DeclarationKind::Param, DeclarationKind::Function) => is_body,
+                    (DeclarationKind::Param, _) | (_, DeclarationKind::Param) => true,
+                    (DeclarationKind::Var, DeclarationKind::Var) => true,
+                    (DeclarationKind::Function, DeclarationKind::Function) => {
+                        !is_module && (is_body || !is_strict)
+                    }
+                    (DeclarationKind::Var, DeclarationKind::Function)
+                    | (DeclarationKind::Function, DeclarationKind::Var) => is_body && !is_module,
+                };
+                if !allowed {
+                    return Err((first_line, first_column));
+                }
+            } else {
+                block.names.insert(name.to_string(), (kind, line, column));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn pop_scope(&mut self) -> Scope {
+        self.scopes
+            .pop()
+            .expect("scopes are pushed and popped in pairs")
+    }
 }
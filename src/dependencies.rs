@@ -0,0 +1,127 @@
+// The modules a program refers to, for building an import graph without
+// walking the tree again.
+//
+// Import declarations, exports with a `from` and `import()` are found
+// wherever the language allows them. `require` is only recognised as a call
+// of that name with a single string literal, since any other argument might
+// be anything, and only where no scope declares a `require` of its own.
+use std::collections::HashSet;
+
+use crate::{
+    analyze::analyze,
+    node::{
+        CallExpression, CallExpressionArgument, ExportAllDeclaration, ExportNamedDeclaration,
+        Expression, ImportDeclaration, ImportExpression, Literal, LiteralValue, Node, Program,
+        SourceLocation,
+    },
+    visit::{walk_call_expression, walk_export_named_declaration, walk_import_expression, Visitor},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DependencyKind {
+    Import,
+    // `export * from` and `export { a } from`
+    ReExport,
+    DynamicImport,
+    Require,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependency {
+    // None for an `import()` of something other than a string, which can't
+    // be known without running it
+    pub specifier: Option<String>,
+    pub kind: DependencyKind,
+    // of the specifier, or of the whole argument of `import()`
+    pub loc: SourceLocation,
+}
+
+// in source order
+pub fn extract_dependencies(program: &Program) -> Vec<Dependency> {
+    let globals = analyze(program)
+        .globals()
+        .iter()
+        .map(|reference| reference.loc.range.0)
+        .collect();
+    let mut extractor = DependencyExtractor {
+        dependencies: vec![],
+        globals,
+    };
+    extractor.visit_program(program);
+    extractor.dependencies
+}
+
+struct DependencyExtractor {
+    dependencies: Vec<Dependency>,
+    // where the references to globals start, as a `require` has to be one
+    globals: HashSet<usize>,
+}
+
+impl DependencyExtractor {
+    fn push(&mut self, specifier: Option<String>, kind: DependencyKind, loc: &SourceLocation) {
+        self.dependencies.push(Dependency {
+            specifier,
+            kind,
+            loc: loc.clone(),
+        });
+    }
+}
+
+impl<'ast> Visitor<'ast> for DependencyExtractor {
+    fn visit_import_declaration(&mut self, node: &'ast ImportDeclaration) {
+        self.push(
+            string(&node.source),
+            DependencyKind::Import,
+            &node.source.loc,
+        );
+    }
+
+    fn visit_export_named_declaration(&mut self, node: &'ast ExportNamedDeclaration) {
+        if let Some(source) = &node.source {
+            self.push(string(source), DependencyKind::ReExport, &source.loc);
+        }
+        walk_export_named_declaration(self, node);
+    }
+
+    fn visit_export_all_declaration(&mut self, node: &'ast ExportAllDeclaration) {
+        self.push(
+            string(&node.source),
+            DependencyKind::ReExport,
+            &node.source.loc,
+        );
+    }
+
+    // a template without substitutions is as good as a string
+    fn visit_import_expression(&mut self, node: &'ast ImportExpression) {
+        let specifier = match &node.source {
+            Expression::Literal(literal) => string(literal),
+            Expression::TemplateLiteral(template) if template.expressions.is_empty() => {
+                template.quasis[0].value.cooked.clone()
+            }
+            _ => None,
+        };
+        self.push(specifier, DependencyKind::DynamicImport, node.source.loc());
+        walk_import_expression(self, node);
+    }
+
+    fn visit_call_expression(&mut self, node: &'ast CallExpression) {
+        if let (
+            Expression::Identifier(callee),
+            [CallExpressionArgument::Expression(Expression::Literal(literal))],
+        ) = (&node.callee, node.arguments.as_slice())
+        {
+            let is_global = self.globals.contains(&callee.loc.range.0);
+            if callee.name == "require" && is_global && string(literal).is_some() {
+                self.push(string(literal), DependencyKind::Require, &literal.loc);
+            }
+        }
+        walk_call_expression(self, node);
+    }
+}
+
+fn string(literal: &Literal) -> Option<String> {
+    match &literal.value {
+        LiteralValue::String(value) => Some(value.clone()),
+        _ => None,
+    }
+}
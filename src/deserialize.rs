@@ -0,0 +1,511 @@
+// Reading ESTree JSON back into the tree. Nodes with a fixed shape derive
+// `Deserialize`; the enums standing for a choice of nodes are resolved here
+// from the `type` of each node, since JSON alone can't tell an `Identifier`
+// expression from an `Identifier` pattern.
+use serde::{
+    de::{DeserializeOwned, Error},
+    Deserialize, Deserializer,
+};
+use serde_json::Value;
+
+use crate::{
+    node::{
+        ArrayExpressionElement, ArrowFunctionExpressionBody, AssignmentExpressionLeft,
+        AssignmentProperty, CallExpressionArgument, ClassElement, ExportDefaultDeclarationKind,
+        Expression, ForInStatementLeft, ForStatementInit, ImportDeclarationSpecifier, Literal,
+        LiteralValue, Null, ObjectExpressionProperty, ObjectPatternProperty, Pattern, Position,
+        Program, Property, PropertyKey, RegExpValue, SourceLocation, Statement,
+    },
+    token::Token,
+};
+
+// the whole program, with errors naming the node they were found in, as in
+// "unknown node type FooExpression at body[3]"
+pub fn from_json(json: &str) -> Result<Program, String> {
+    let value: Value = serde_json::from_str(json).map_err(|error| error.to_string())?;
+    from_value(value)
+}
+
+fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, String> {
+    serde_path_to_error::deserialize(value).map_err(|error| {
+        let path = error.path().to_string();
+        locate(&path, error.into_inner().to_string())
+    })
+}
+
+// an error from a nested node already ends with its path inside that node,
+// which is joined onto the path leading to the node
+fn locate(path: &str, message: String) -> String {
+    let path = if path == "." { "" } else { path };
+    let (message, inner) = match message.rsplit_once(" at ") {
+        Some((message, inner)) if is_path(inner) => (message.to_string(), inner),
+        _ => (message, ""),
+    };
+    match (path, inner) {
+        ("", "") => message,
+        (path, "") | ("", path) => format!("{message} at {path}"),
+        (path, inner) if inner.starts_with('[') => format!("{message} at {path}{inner}"),
+        (path, inner) => format!("{message} at {path}.{inner}"),
+    }
+}
+
+fn is_path(text: &str) -> bool {
+    !text.is_empty()
+        && text
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || matches!(char, '_' | '.' | '[' | ']'))
+}
+
+fn node<'de, D: Deserializer<'de>, T: DeserializeOwned>(value: Value) -> Result<T, D::Error> {
+    from_value(value).map_err(D::Error::custom)
+}
+
+fn node_type<'de, D: Deserializer<'de>>(value: &Value) -> Result<String, D::Error> {
+    match value.get("type") {
+        Some(Value::String(node_type)) => Ok(node_type.clone()),
+        Some(_) => Err(D::Error::custom("node type must be a string")),
+        None => Err(D::Error::missing_field("type")),
+    }
+}
+
+fn unknown<'de, D: Deserializer<'de>>(node_type: &str) -> D::Error {
+    D::Error::custom(format!("unknown node type {node_type}"))
+}
+
+// what acorn gives depends on its `locations` and `ranges` options, so every
+// part of a location may be missing, and `loc` may be null
+#[derive(Deserialize)]
+struct RawLocation {
+    start: Option<usize>,
+    end: Option<usize>,
+    loc: Option<LocObject>,
+    range: Option<(usize, usize)>,
+}
+
+#[derive(Deserialize)]
+struct LocObject {
+    start: Position,
+    end: Position,
+    source: Option<String>,
+}
+
+impl<'de> Deserialize<'de> for SourceLocation {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawLocation::deserialize(deserializer)?;
+        let mut loc = SourceLocation::new(0, 0, 0);
+        if let Some(object) = raw.loc {
+            loc.start = object.start;
+            loc.end = object.end;
+            loc.source = object.source;
+        }
+        loc.range = match raw.range {
+            Some(range) => range,
+            None => (raw.start.unwrap_or(0), raw.end.unwrap_or(0)),
+        };
+        Ok(loc)
+    }
+}
+
+#[derive(Deserialize)]
+struct RawLiteral {
+    #[serde(flatten)]
+    loc: SourceLocation,
+    value: Value,
+    raw: String,
+    regex: Option<RegExpValue>,
+    bigint: Option<String>,
+}
+
+// the `regex` and `bigint` fields win over `value`, which is null for them;
+// it is also null for a number JSON can't hold, whose value is read back
+// from `raw` instead
+impl<'de> Deserialize<'de> for Literal {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = RawLiteral::deserialize(deserializer)?;
+        let value = match (raw.regex, raw.bigint, raw.value) {
+            (Some(regex), _, _) => LiteralValue::RegExp(regex),
+            (_, Some(digits), _) => LiteralValue::Bigint(digits),
+            (_, _, Value::String(value)) => LiteralValue::String(value),
+            (_, _, Value::Bool(value)) => LiteralValue::Boolean(value),
+            (_, _, Value::Null) if raw.raw == "null" => LiteralValue::Null(Null),
+            (_, _, Value::Null) => match non_finite(&raw.raw) {
+                Some(value) => LiteralValue::Number(value),
+                None => return Err(D::Error::custom("invalid literal value")),
+            },
+            (_, _, Value::Number(value)) => match value.as_f64() {
+                Some(value) => LiteralValue::Number(value),
+                None => return Err(D::Error::custom("invalid number literal")),
+            },
+            _ => return Err(D::Error::custom("invalid literal value")),
+        };
+        Ok(Literal::new(value, raw.raw, raw.loc))
+    }
+}
+
+// `1e400`, or the division the code generator writes a folded `NaN` or
+// `Infinity` as
+fn non_finite(raw: &str) -> Option<f64> {
+    match raw {
+        "0 / 0" => return Some(f64::NAN),
+        "1 / 0" => return Some(f64::INFINITY),
+        "-1 / 0" => return Some(f64::NEG_INFINITY),
+        _ => {}
+    }
+    match crate::tokenize(raw).ok()?.as_slice() {
+        [lexeme] => match lexeme.token {
+            Token::Number(value) if !value.is_finite() => Some(value),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// a directive is an `ExpressionStatement` with a `directive` field
+impl<'de> Deserialize<'de> for Statement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let statement = match node_type::<D>(&value)?.as_str() {
+            "ExpressionStatement" if value.get("directive").is_some() => {
+                Statement::Directive(node::<D, _>(value)?)
+            }
+            "ExpressionStatement" => Statement::Expression(node::<D, _>(value)?),
+            "BlockStatement" => Statement::Block(node::<D, _>(value)?),
+            "EmptyStatement" => Statement::Empty(node::<D, _>(value)?),
+            "DebuggerStatement" => Statement::Debugger(node::<D, _>(value)?),
+            "WithStatement" => Statement::With(node::<D, _>(value)?),
+            "ReturnStatement" => Statement::Return(node::<D, _>(value)?),
+            "LabeledStatement" => Statement::Labeled(node::<D, _>(value)?),
+            "BreakStatement" => Statement::Break(node::<D, _>(value)?),
+            "ContinueStatement" => Statement::Continue(node::<D, _>(value)?),
+            "IfStatement" => Statement::If(node::<D, _>(value)?),
+            "SwitchStatement" => Statement::Switch(node::<D, _>(value)?),
+            "ThrowStatement" => Statement::Throw(node::<D, _>(value)?),
+            "TryStatement" => Statement::Try(node::<D, _>(value)?),
+            "WhileStatement" => Statement::While(node::<D, _>(value)?),
+            "DoWhileStatement" => Statement::DoWhile(node::<D, _>(value)?),
+            "ForStatement" => Statement::For(node::<D, _>(value)?),
+            "ForInStatement" => Statement::ForIn(node::<D, _>(value)?),
+            "ForOfStatement" => Statement::ForOf(node::<D, _>(value)?),
+            "FunctionDeclaration" => Statement::FunctionDeclaration(node::<D, _>(value)?),
+            "VariableDeclaration" => Statement::VariableDeclaration(node::<D, _>(value)?),
+            "ClassDeclaration" => Statement::ClassDeclaration(node::<D, _>(value)?),
+            "ImportDeclaration" => Statement::ImportDeclaration(node::<D, _>(value)?),
+            "ExportNamedDeclaration" => Statement::ExportNamedDeclaration(node::<D, _>(value)?),
+            "ExportDefaultDeclaration" => Statement::ExportDefaultDeclaration(node::<D, _>(value)?),
+            "ExportAllDeclaration" => Statement::ExportAllDeclaration(node::<D, _>(value)?),
+            "ErrorNode" => Statement::Error(node::<D, _>(value)?),
+            node_type => return Err(unknown::<D>(node_type)),
+        };
+        Ok(statement)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let expression = match node_type::<D>(&value)?.as_str() {
+            "Identifier" => Expression::Identifier(node::<D, _>(value)?),
+            "Literal" => Expression::Literal(node::<D, _>(value)?),
+            "ThisExpression" => Expression::This(node::<D, _>(value)?),
+            "Super" => Expression::Super(node::<D, _>(value)?),
+            "ArrayExpression" => Expression::Array(node::<D, _>(value)?),
+            "ObjectExpression" => Expression::Object(node::<D, _>(value)?),
+            "FunctionExpression" => Expression::Function(node::<D, _>(value)?),
+            "ArrowFunctionExpression" => Expression::ArrowFunction(node::<D, _>(value)?),
+            "ClassExpression" => Expression::Class(node::<D, _>(value)?),
+            "UnaryExpression" => Expression::Unary(node::<D, _>(value)?),
+            "UpdateExpression" => Expression::Update(node::<D, _>(value)?),
+            "BinaryExpression" => Expression::Binary(node::<D, _>(value)?),
+            "AssignmentExpression" => Expression::Assignment(node::<D, _>(value)?),
+            "LogicalExpression" => Expression::Logical(node::<D, _>(value)?),
+            "MemberExpression" => Expression::Member(node::<D, _>(value)?),
+            "ConditionalExpression" => Expression::Conditional(node::<D, _>(value)?),
+            "CallExpression" => Expression::Call(node::<D, _>(value)?),
+            "ChainExpression" => Expression::Chain(node::<D, _>(value)?),
+            "YieldExpression" => Expression::Yield(node::<D, _>(value)?),
+            "TemplateLiteral" => Expression::TemplateLiteral(node::<D, _>(value)?),
+            "TaggedTemplateExpression" => Expression::TaggedTemplate(node::<D, _>(value)?),
+            "NewExpression" => Expression::New(node::<D, _>(value)?),
+            "MetaProperty" => Expression::MetaProperty(node::<D, _>(value)?),
+            "ImportExpression" => Expression::Import(node::<D, _>(value)?),
+            "SequenceExpression" => Expression::Sequence(node::<D, _>(value)?),
+            "ParenthesizedExpression" => Expression::Parenthesized(node::<D, _>(value)?),
+            "AwaitExpression" => Expression::Await(node::<D, _>(value)?),
+            "PrivateIdentifier" => Expression::PrivateIdentifier(node::<D, _>(value)?),
+            "ErrorNode" => Expression::Error(node::<D, _>(value)?),
+            node_type => return Err(unknown::<D>(node_type)),
+        };
+        Ok(expression)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let pattern = match node_type::<D>(&value)?.as_str() {
+            "Identifier" => Pattern::Identifier(node::<D, _>(value)?),
+            "MemberExpression" => Pattern::Member(node::<D, _>(value)?),
+            "ObjectPattern" => Pattern::Object(node::<D, _>(value)?),
+            "ArrayPattern" => Pattern::Array(node::<D, _>(value)?),
+            "RestElement" => Pattern::Rest(node::<D, _>(value)?),
+            "AssignmentPattern" => Pattern::Assignment(node::<D, _>(value)?),
+            "ErrorNode" => Pattern::Error(node::<D, _>(value)?),
+            node_type => return Err(unknown::<D>(node_type)),
+        };
+        Ok(pattern)
+    }
+}
+
+impl<'de> Deserialize<'de> for ForStatementInit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "VariableDeclaration" => ForStatementInit::VariableDeclaration(node::<D, _>(value)?),
+            _ => ForStatementInit::Expression(node::<D, _>(value)?),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ForInStatementLeft {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "VariableDeclaration" => ForInStatementLeft::VariableDeclaration(node::<D, _>(value)?),
+            _ => ForInStatementLeft::Pattern(node::<D, _>(value)?),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ArrayExpressionElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "SpreadElement" => ArrayExpressionElement::SpreadElement(node::<D, _>(value)?),
+            _ => ArrayExpressionElement::Expression(node::<D, _>(value)?),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectExpressionProperty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "Property" => {
+                let mut property: Property = node::<D, _>(value)?;
+                property.key = computed_key(property.key, property.computed);
+                ObjectExpressionProperty::Property(property)
+            }
+            "SpreadElement" => ObjectExpressionProperty::SpreadElement(node::<D, _>(value)?),
+            node_type => return Err(unknown::<D>(node_type)),
+        })
+    }
+}
+
+// a key is read by its type alone, which can't tell `a` from `[a]`, so the
+// node holding it turns a computed key back into an expression
+impl<'de> Deserialize<'de> for PropertyKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "Literal" => PropertyKey::Literal(node::<D, _>(value)?),
+            "Identifier" => PropertyKey::Identifier(node::<D, _>(value)?),
+            _ => PropertyKey::Expression(node::<D, _>(value)?),
+        })
+    }
+}
+
+fn computed_key(key: PropertyKey, computed: bool) -> PropertyKey {
+    match key {
+        PropertyKey::Literal(literal) if computed => {
+            PropertyKey::Expression(Expression::Literal(Box::new(literal)))
+        }
+        PropertyKey::Identifier(identifier) if computed => {
+            PropertyKey::Expression(Expression::Identifier(Box::new(identifier)))
+        }
+        key => key,
+    }
+}
+
+impl<'de> Deserialize<'de> for ArrowFunctionExpressionBody {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "BlockStatement" => ArrowFunctionExpressionBody::FunctionBody(node::<D, _>(value)?),
+            _ => ArrowFunctionExpressionBody::Expression(node::<D, _>(value)?),
+        })
+    }
+}
+
+// the parser keeps a plain identifier or member expression on the left of
+// `=` as an expression, and only destructuring as a pattern
+impl<'de> Deserialize<'de> for AssignmentExpressionLeft {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "ObjectPattern" | "ArrayPattern" => {
+                AssignmentExpressionLeft::Pattern(node::<D, _>(value)?)
+            }
+            _ => AssignmentExpressionLeft::Expression(node::<D, _>(value)?),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for CallExpressionArgument {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "SpreadElement" => CallExpressionArgument::SpreadElement(node::<D, _>(value)?),
+            _ => CallExpressionArgument::Expression(node::<D, _>(value)?),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ObjectPatternProperty {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "Property" => {
+                let mut property: AssignmentProperty = node::<D, _>(value)?;
+                property.key = computed_key(property.key, property.computed);
+                ObjectPatternProperty::AssignmentProperty(property)
+            }
+            "RestElement" => ObjectPatternProperty::RestElement(node::<D, _>(value)?),
+            node_type => return Err(unknown::<D>(node_type)),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ClassElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "MethodDefinition" => ClassElement::MethodDefinition(node::<D, _>(value)?),
+            "PropertyDefinition" => ClassElement::PropertyDefinition(node::<D, _>(value)?),
+            "StaticBlock" => ClassElement::StaticBlock(node::<D, _>(value)?),
+            node_type => return Err(unknown::<D>(node_type)),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ImportDeclarationSpecifier {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "ImportSpecifier" => ImportDeclarationSpecifier::ImportSpecifier(node::<D, _>(value)?),
+            "ImportDefaultSpecifier" => {
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(node::<D, _>(value)?)
+            }
+            "ImportNamespaceSpecifier" => {
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(node::<D, _>(value)?)
+            }
+            node_type => return Err(unknown::<D>(node_type)),
+        })
+    }
+}
+
+// an anonymous `export default function` or `class` is still a declaration
+impl<'de> Deserialize<'de> for ExportDefaultDeclarationKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        Ok(match node_type::<D>(&value)?.as_str() {
+            "FunctionDeclaration" => {
+                ExportDefaultDeclarationKind::FunctionDeclaration(node::<D, _>(value)?)
+            }
+            "ClassDeclaration" => {
+                ExportDefaultDeclarationKind::ClassDeclaration(node::<D, _>(value)?)
+            }
+            _ => ExportDefaultDeclarationKind::Expression(node::<D, _>(value)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{node::SourceType, parser::parse, test_support::README_SRC};
+
+    #[test]
+    fn deserializing_serialized_json_gives_the_same_tree() {
+        let snippets = [
+            README_SRC,
+            "'use strict'; a.b?.[c](...d, e);",
+            "for (let i = 0, j; i < 10n; i++) { continue; }",
+            "for (const a of d) label: while (e) break label;",
+            "for (x in y); do ; while (z)",
+            "if (a) b = 1.5; else { [c, d] = [, d, ...e]; }",
+            "switch (a) { case 1: throw new Error(`x${a}y`); default: }",
+            "try { f(async (a, ...b) => await a); } catch (error) {} finally {}",
+            "var o = { a, [b]: 1, get c() { return 2; }, set c(v) {}, *d() { yield; }, ...e };",
+            "class A extends B { static #x = 1; static { this.y = tag`t`; } constructor() { super(); } }",
+            "x = a ? b || c : d ?? e, typeof f, void 0, !g, -h;",
+            "function* gen(a) { yield* a; return function () { new.target; }; }",
+            "x = /ab+c/gi.test(null) === true;",
+            "f = ({ a = 1, ...b }, [c, , ...d]) => a; ({ e, f: [g] } = h);",
+        ];
+        for src in snippets {
+            let program = parse(src).unwrap();
+            let json = serde_json::to_string(&program).unwrap();
+            assert_eq!(
+                crate::deserialize::from_json(&json).unwrap(),
+                program,
+                "{src}"
+            );
+        }
+        let others = [
+            crate::parser::parse_module(
+                "import a, { b as c } from 'd'; import * as e from 'f'; export default class {} export { a }; export * from 'g';",
+            ).unwrap(),
+            crate::parser::parse_preserving_parens(
+                "(a, (b)) + c;",
+                SourceType::Script,
+            ).unwrap(),
+        ];
+        for program in others {
+            let json = serde_json::to_string(&program).unwrap();
+            assert_eq!(crate::deserialize::from_json(&json).unwrap(), program);
+        }
+    }
+
+    #[test]
+    fn numbers_json_cant_hold_are_read_back_from_their_raw_text() {
+        let program = parse("x = [1e400, null];").unwrap();
+        let json = serde_json::to_string(&program).unwrap();
+        assert_eq!(crate::deserialize::from_json(&json).unwrap(), program);
+
+        let mut program = parse("x = [1 / 0, -1 / 0, 0 / 0];").unwrap();
+        crate::transform::fold_constants(&mut program);
+        let json = serde_json::to_string(&program).unwrap();
+        let read = crate::deserialize::from_json(&json).unwrap();
+        assert_eq!(
+            crate::codegen::generate(&read),
+            "x = [1 / 0, -1 / 0, 0 / 0];\n"
+        );
+    }
+
+    #[test]
+    fn deserializing_reports_where_an_unknown_node_is() {
+        let json = r#"{
+            "type": "Program",
+            "sourceType": "script",
+            "extra": true,
+            "body": [
+                { "type": "EmptyStatement", "loc": null },
+                {
+                    "type": "ExpressionStatement",
+                    "expression": {
+                        "type": "BinaryExpression",
+                        "operator": "+",
+                        "left": { "type": "Identifier", "name": "a" },
+                        "right": { "type": "FooExpression" }
+                    }
+                }
+            ]
+        }"#;
+        assert_eq!(
+            crate::deserialize::from_json(json).unwrap_err(),
+            "unknown node type FooExpression at body[1].expression.right"
+        );
+        let json = json.replace(r#""FooExpression""#, r#""Identifier", "name": "b""#);
+        let program = crate::deserialize::from_json(&json).unwrap();
+        assert_eq!(program.body.len(), 2);
+    }
+}
@@ -0,0 +1,334 @@
+// Where two programs differ in their syntax, leaving aside where anything
+// is written, so that reformatting changes nothing.
+//
+// The trees are walked side by side. Two nodes are the same when they are
+// of the same type with the same operators, flags, names and values, and
+// their children are the same in turn; a node that differs in itself is
+// reported as changed, without going into its children. Lists of children,
+// such as the statements of a block, are lined up on the longest run of
+// nodes the same in both. Of the nodes left between, one with an exact copy
+// left on the other side has moved; the others are paired up in order to be
+// compared in turn, and any left over have been removed or inserted.
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    mem::Discriminant,
+};
+
+use crate::{
+    node::{Node, Program, SourceLocation},
+    node_ref::NodeRef,
+    sexpr::attributes,
+};
+
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffKind {
+    Inserted,
+    Removed,
+    Changed,
+    Moved,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffNode {
+    pub node_type: &'static str,
+    pub loc: SourceLocation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffEntry {
+    pub kind: DiffKind,
+    // None for an inserted node
+    pub old: Option<DiffNode>,
+    // None for a removed node
+    pub new: Option<DiffNode>,
+}
+
+// empty when the programs are the same
+pub fn compare(old: &Program, new: &Program) -> Vec<DiffEntry> {
+    let (old, new) = (NodeRef::Program(old), NodeRef::Program(new));
+    let mut differ = Differ {
+        fingerprints: HashMap::new(),
+        entries: vec![],
+    };
+    differ.fingerprint(old);
+    differ.fingerprint(new);
+    differ.compare(old, new);
+    differ.entries
+}
+
+type Key<'a> = (usize, Discriminant<NodeRef<'a>>);
+
+struct Differ<'a> {
+    // a hash of each node of both trees, equal for nodes that are the same
+    fingerprints: HashMap<Key<'a>, u64>,
+    entries: Vec<DiffEntry>,
+}
+
+impl<'a> Differ<'a> {
+    fn fingerprint(&mut self, node: NodeRef<'a>) -> u64 {
+        stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || {
+            let mut hasher = DefaultHasher::new();
+            label(node).hash(&mut hasher);
+            for child in node.children() {
+                self.fingerprint(child).hash(&mut hasher);
+            }
+            let fingerprint = hasher.finish();
+            self.fingerprints.insert(key(node), fingerprint);
+            fingerprint
+        })
+    }
+
+    fn fingerprint_of(&self, node: NodeRef<'a>) -> u64 {
+        self.fingerprints[&key(node)]
+    }
+
+    fn push(&mut self, kind: DiffKind, old: Option<NodeRef>, new: Option<NodeRef>) {
+        let node = |node: NodeRef| DiffNode {
+            node_type: node.node_type(),
+            loc: node.loc().clone(),
+        };
+        self.entries.push(DiffEntry {
+            kind,
+            old: old.map(node),
+            new: new.map(node),
+        });
+    }
+
+    fn compare(&mut self, old: NodeRef<'a>, new: NodeRef<'a>) {
+        if self.fingerprint_of(old) == self.fingerprint_of(new) {
+            return;
+        }
+        if label(old) != label(new) {
+            self.push(DiffKind::Changed, Some(old), Some(new));
+            return;
+        }
+        stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || {
+            self.compare_lists(&old.children(), &new.children())
+        });
+    }
+
+    fn compare_lists(&mut self, old: &[NodeRef<'a>], new: &[NodeRef<'a>]) {
+        let old_prints: Vec<u64> = old.iter().map(|&node| self.fingerprint_of(node)).collect();
+        let new_prints: Vec<u64> = new.iter().map(|&node| self.fingerprint_of(node)).collect();
+
+        // the stretches between the nodes that are the same in both
+        let mut gaps = vec![];
+        let (mut old_start, mut new_start) = (0, 0);
+        for (old_end, new_end) in common_subsequence(&old_prints, &new_prints)
+            .into_iter()
+            .chain([(old.len(), new.len())])
+        {
+            gaps.push((old_start..old_end, new_start..new_end));
+            (old_start, new_start) = (old_end + 1, new_end + 1);
+        }
+
+        let left_old: Vec<u64> = gaps
+            .iter()
+            .flat_map(|(range, _)| old_prints[range.clone()].iter().copied())
+            .collect();
+        let left_new: Vec<u64> = gaps
+            .iter()
+            .flat_map(|(_, range)| new_prints[range.clone()].iter().copied())
+            .collect();
+        let mut moved_old = vec![];
+        let mut moved_new = vec![];
+        let mut removed = vec![];
+        let mut inserted = vec![];
+        for (old_range, new_range) in gaps {
+            let mut paired_old = vec![];
+            for index in old_range {
+                if left_new.contains(&old_prints[index]) {
+                    moved_old.push(index);
+                } else {
+                    paired_old.push(old[index]);
+                }
+            }
+            let mut paired_new = vec![];
+            for index in new_range {
+                if left_old.contains(&new_prints[index]) {
+                    moved_new.push(index);
+                } else {
+                    paired_new.push(new[index]);
+                }
+            }
+            for (&old, &new) in paired_old.iter().zip(&paired_new) {
+                if old.node_type() == new.node_type() {
+                    self.compare(old, new);
+                } else {
+                    self.push(DiffKind::Changed, Some(old), Some(new));
+                }
+            }
+            removed.extend(paired_old.iter().skip(paired_new.len()).copied());
+            inserted.extend(paired_new.iter().skip(paired_old.len()).copied());
+        }
+
+        // a node may have more copies on one side than the other
+        for old_index in moved_old {
+            let position = moved_new
+                .iter()
+                .position(|&new_index| new_prints[new_index] == old_prints[old_index]);
+            match position {
+                Some(position) => {
+                    let new_index = moved_new.remove(position);
+                    self.push(DiffKind::Moved, Some(old[old_index]), Some(new[new_index]));
+                }
+                None => removed.push(old[old_index]),
+            }
+        }
+        inserted.extend(moved_new.into_iter().map(|index| new[index]));
+        for node in removed {
+            self.push(DiffKind::Removed, Some(node), None);
+        }
+        for node in inserted {
+            self.push(DiffKind::Inserted, None, Some(node));
+        }
+    }
+}
+
+fn key(node: NodeRef) -> Key {
+    (node.as_ptr() as usize, std::mem::discriminant(&node))
+}
+
+// what a node is apart from its children and where it's written
+fn label(node: NodeRef) -> String {
+    let own = match node {
+        NodeRef::Identifier(identifier) => identifier.name.to_string(),
+        NodeRef::PrivateIdentifier(identifier) => identifier.name.to_string(),
+        NodeRef::Literal(literal) => format!("{:?}", literal.value),
+        NodeRef::TemplateElement(element) => format!("{:?}", element.value),
+        _ => attributes(node).join(" "),
+    };
+    format!("{} {}", node.node_type(), own)
+}
+
+// the pairs of indices of a longest run of equal items in both, in order;
+// the ends the two have in common are matched before the rest is searched
+fn common_subsequence(old: &[u64], new: &[u64]) -> Vec<(usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (old_middle, new_middle) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    // lengths[i][j] is that of the longest run in old_middle[i..] and new_middle[j..]
+    let width = new_middle.len() + 1;
+    let mut lengths = vec![0usize; (old_middle.len() + 1) * width];
+    for i in (0..old_middle.len()).rev() {
+        for j in (0..new_middle.len()).rev() {
+            lengths[i * width + j] = if old_middle[i] == new_middle[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|index| (index, index)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < old_middle.len() && j < new_middle.len() {
+        if old_middle[i] == new_middle[j] {
+            pairs.push((prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs.extend((0..suffix).map(|index| (old.len() - suffix + index, new.len() - suffix + index)));
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        diff::{compare, DiffKind, DiffNode},
+        parser::parse,
+    };
+
+    // each difference with the text of the nodes in the old and new source
+    fn differences<'a>(
+        old: &'a str,
+        new: &'a str,
+    ) -> Vec<(DiffKind, Option<&'a str>, Option<&'a str>)> {
+        let text = |src: &'a str, node: Option<DiffNode>| {
+            node.map(|node| &src[node.loc.range.0..node.loc.range.1])
+        };
+        compare(&parse(old).unwrap(), &parse(new).unwrap())
+            .into_iter()
+            .map(|entry| (entry.kind, text(old, entry.old), text(new, entry.new)))
+            .collect()
+    }
+
+    #[test]
+    fn programs_differing_only_in_formatting_are_the_same() {
+        assert!(differences(
+            "function f(a,b){return a+b*1}\nx = 'y'",
+            "// adds\nfunction f(a, b) {\n  return a + b * 1.0;\n}\nx = \"y\";\n"
+        )
+        .is_empty());
+        assert!(!differences("a + b * c", "(a + b) * c").is_empty());
+    }
+
+    #[test]
+    fn differences_are_found_where_they_are() {
+        use DiffKind::*;
+        assert_eq!(
+            differences("a + b; f(x);", "a - b; f(y);"),
+            [
+                (Changed, Some("a + b"), Some("a - b")),
+                (Changed, Some("x"), Some("y"))
+            ]
+        );
+        assert_eq!(
+            differences("a(); b(); c();", "a(); c(); d();"),
+            [
+                (Removed, Some("b();"), None),
+                (Inserted, None, Some("d();"))
+            ]
+        );
+        assert_eq!(
+            differences("if (a) b();", "if (a) b(); else { c(); }"),
+            [(Inserted, None, Some("{ c(); }"))]
+        );
+        assert_eq!(
+            differences("x = 1;", "x = y;"),
+            [(Changed, Some("1"), Some("y"))]
+        );
+    }
+
+    #[test]
+    fn statements_that_only_change_places_have_moved() {
+        use DiffKind::*;
+        assert_eq!(
+            differences(
+                "function f() { return 1; } a(); b();",
+                "a(); b(); function f() { return 1; }"
+            ),
+            [(
+                Moved,
+                Some("function f() { return 1; }"),
+                Some("function f() { return 1; }")
+            )]
+        );
+        assert_eq!(
+            differences("a(); b(); c(); d();", "d(); a(); c(); x();"),
+            [
+                (Moved, Some("d();"), Some("d();")),
+                (Removed, Some("b();"), None),
+                (Inserted, None, Some("x();"))
+            ]
+        );
+    }
+}
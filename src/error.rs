@@ -0,0 +1,606 @@
+// Errors come back from parsing as values rather than panics, so that a
+// program embedding the parser can report them and carry on.
+use std::fmt;
+
+use crate::{node::Position, token::TokenKind};
+
+// each kind has a stable code, which `code` matches on exhaustively so that
+// a new kind can't be added without one; tooling outside the crate has to
+// allow for kinds added later
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseErrorKind {
+    // a character no token starts with
+    UnexpectedCharacter,
+    UnterminatedString,
+    UnterminatedTemplate,
+    UnterminatedComment,
+    UnterminatedRegExp,
+    InvalidRegExpFlags,
+    InvalidNumericSeparator,
+    // a number missing the digits it needs, as in `0x` or `1e`
+    InvalidNumber,
+    InvalidEscape,
+    UnexpectedToken,
+    UnexpectedEnd,
+    // something assigned to, updated or bound that can't be, such as the
+    // `a + b` of `a + b = c`
+    InvalidTarget,
+    // a name used where it's reserved, such as `yield` in a generator
+    ReservedWord,
+    // what strict mode forbids, such as `with`
+    StrictMode,
+    // something outside of where it's allowed, such as `return` outside of
+    // functions or `super` outside of methods
+    Misplaced,
+    InvalidParameters,
+    InvalidClassElement,
+    // a name declared twice in a way that isn't allowed, as in `let a; var a`
+    Redeclaration,
+    // `return` outside of functions
+    MisplacedReturn,
+    // `new.target` outside of functions and static blocks
+    MisplacedNewTarget,
+    // `super.x` outside of methods, field initializers and static blocks
+    MisplacedSuperProperty,
+    // `super()` outside of the constructor of a class that extends another
+    MisplacedSuperCall,
+    // syntax from a newer edition of the language than the options allow
+    UnsupportedSyntax,
+    // brackets, blocks or operators nested deeper than the options allow
+    NestingTooDeep,
+    // anything else the grammar rules out
+    InvalidSyntax,
+}
+
+impl ParseErrorKind {
+    // `JS1xxx` for errors within a token, `JS2xxx` for errors in how tokens
+    // are put together; codes are never reused for another kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseErrorKind::UnterminatedString => "JS1001",
+            ParseErrorKind::InvalidNumericSeparator => "JS1002",
+            ParseErrorKind::UnexpectedCharacter => "JS1003",
+            ParseErrorKind::UnterminatedTemplate => "JS1004",
+            ParseErrorKind::UnterminatedComment => "JS1005",
+            ParseErrorKind::UnterminatedRegExp => "JS1006",
+            ParseErrorKind::InvalidRegExpFlags => "JS1007",
+            ParseErrorKind::InvalidNumber => "JS1008",
+            ParseErrorKind::InvalidEscape => "JS1009",
+            ParseErrorKind::UnexpectedToken => "JS2001",
+            ParseErrorKind::UnexpectedEnd => "JS2002",
+            ParseErrorKind::InvalidTarget => "JS2003",
+            ParseErrorKind::ReservedWord => "JS2004",
+            ParseErrorKind::StrictMode => "JS2005",
+            ParseErrorKind::Misplaced => "JS2006",
+            ParseErrorKind::InvalidParameters => "JS2007",
+            ParseErrorKind::InvalidClassElement => "JS2008",
+            ParseErrorKind::InvalidSyntax => "JS2009",
+            ParseErrorKind::Redeclaration => "JS2010",
+            ParseErrorKind::MisplacedReturn => "JS2011",
+            ParseErrorKind::MisplacedNewTarget => "JS2012",
+            ParseErrorKind::MisplacedSuperProperty => "JS2013",
+            ParseErrorKind::MisplacedSuperCall => "JS2014",
+            ParseErrorKind::UnsupportedSyntax => "JS2015",
+            ParseErrorKind::NestingTooDeep => "JS2016",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub message: String,
+    // where what's wrong starts and ends, the same position for errors
+    // found at a single point
+    pub start: Position,
+    pub end: Position,
+    // for an unexpected token, the tokens that would have been valid there,
+    // if the parser knows them, and the token found, `None` at the end of
+    // input
+    pub expected: Vec<TokenKind>,
+    pub found: Option<TokenKind>,
+}
+
+impl ParseError {
+    pub fn new(kind: ParseErrorKind, message: &str, start: Position, end: Position) -> Self {
+        ParseError {
+            kind,
+            message: message.to_string(),
+            start,
+            end,
+            expected: vec![],
+            found: None,
+        }
+    }
+
+    pub fn expected(
+        expected: Vec<TokenKind>,
+        found: Option<TokenKind>,
+        (start, end): (Position, Position),
+    ) -> Self {
+        let (kind, found_text) = match &found {
+            Some(found) => (ParseErrorKind::UnexpectedToken, describe(found)),
+            None => (ParseErrorKind::UnexpectedEnd, "end of input".to_string()),
+        };
+        let message = format!(
+            "Expected {} but found {}",
+            describe_all(&expected),
+            found_text
+        );
+        ParseError {
+            expected,
+            found,
+            ..ParseError::new(kind, &message, start, end)
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    // the message along with the line the error starts on, with the span
+    // underlined, in the style of rustc:
+    //
+    // error[JS2001]: Expected `)` but found `{`
+    //  --> line:1, column:6
+    //   |
+    // 1 | if (a { }
+    //   |       ^
+    //
+    // a span over several lines is underlined to the end of its first line,
+    // followed by a note of where it ends
+    pub fn render(&self, src: &str) -> String {
+        let line: Vec<char> = source_line(src, self.start.line.saturating_sub(1))
+            .chars()
+            .collect();
+        let start = self.start.column.min(line.len());
+        let end = if self.end.line == self.start.line {
+            self.end.column.clamp(start, line.len())
+        } else {
+            line.len()
+        };
+        let gutter = self.start.line.to_string();
+        let pad = " ".repeat(gutter.len());
+        let mut rendered = format!(
+            "error[{}]: {}\n{pad}--> line:{}, column:{}\n{pad} |\n{gutter} | {}\n{pad} | {}{}",
+            self.code(),
+            self.message,
+            self.start.line,
+            self.start.column,
+            expand_tabs(&line),
+            " ".repeat(width(&line[..start])),
+            "^".repeat(width(&line[start..end]).max(1)),
+        );
+        if self.end.line != self.start.line {
+            rendered.push_str(&format!(
+                "\n{pad} = ends at line:{}, column:{}",
+                self.end.line, self.end.column
+            ));
+        }
+        rendered
+    }
+
+    pub fn at(kind: ParseErrorKind, message: &str, line: usize, column: usize) -> Self {
+        let position = Position::new(line, column);
+        ParseError::new(kind, message, position, position)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} at line:{}, column:{}.",
+            self.code(),
+            self.message,
+            self.start.line,
+            self.start.column
+        )
+    }
+}
+
+// the source's nth line, counted from 0, where lines end at the same breaks
+// the lexer counts: `\r\n`, `\r`, `\n`, U+2028 and U+2029
+fn source_line(src: &str, n: usize) -> &str {
+    let mut start = 0;
+    let mut line = 0;
+    let mut chars = src.char_indices().peekable();
+    while let Some((index, char)) = chars.next() {
+        if !matches!(char, '\r' | '\n' | '\u{2028}' | '\u{2029}') {
+            continue;
+        }
+        if line == n {
+            return &src[start..index];
+        }
+        if char == '\r' && matches!(chars.peek(), Some((_, '\n'))) {
+            chars.next();
+        }
+        start = chars.peek().map_or(src.len(), |&(index, _)| index);
+        line += 1;
+    }
+    if line == n {
+        &src[start..]
+    } else {
+        ""
+    }
+}
+
+// tabs are shown as four spaces, so that an underline made of spaces and
+// carets lines up with the text above it whatever the terminal's tab stops
+fn expand_tabs(chars: &[char]) -> String {
+    chars
+        .iter()
+        .map(|&char| match char {
+            '\t' => "    ".to_string(),
+            char => char.to_string(),
+        })
+        .collect()
+}
+
+fn width(chars: &[char]) -> usize {
+    chars
+        .iter()
+        .map(|&char| if char == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+fn describe(kind: &TokenKind) -> String {
+    if kind.has_value() {
+        kind.to_string()
+    } else {
+        format!("`{}`", kind)
+    }
+}
+
+// `)`, or one of `,`, `)`
+fn describe_all(kinds: &[TokenKind]) -> String {
+    let described: Vec<String> = kinds.iter().map(describe).collect();
+    match described.as_slice() {
+        [only] => only.clone(),
+        _ => format!("one of {}", described.join(", ")),
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type ParseResult<T> = Result<T, ParseError>;
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ParseErrorKind,
+        node::{Expression, Pattern, SourceType, Statement},
+        node_ref::NodeRef,
+        parser::parse,
+        token::TokenKind,
+    };
+
+    #[test]
+    fn an_unexpected_token_spans_the_token() {
+        let error = parse("a = 1 in in b").unwrap_err();
+        assert_eq!(error.message, "Unexpected token 'in'");
+        assert_eq!((error.start.column, error.end.column), (9, 11));
+        assert_eq!(
+            error.to_string(),
+            "JS2001: Unexpected token 'in' at line:1, column:9."
+        );
+    }
+
+    #[test]
+    fn errors_name_the_tokens_expected_and_found() {
+        let error = parse("f(a b)").unwrap_err();
+        assert_eq!(error.expected, [TokenKind::Comma, TokenKind::ParenR]);
+        assert_eq!(error.found, Some(TokenKind::Name));
+        assert_eq!(
+            error.to_string(),
+            "JS2001: Expected one of `,`, `)` but found identifier at line:1, column:4."
+        );
+
+        let error = parse("if (a { }").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.message, "Expected `)` but found `{`");
+        assert_eq!((error.start.column, error.end.column), (6, 7));
+
+        let error = parse("a ? b").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+        assert_eq!(error.message, "Expected `:` but found end of input");
+        assert_eq!(error.found, None);
+
+        // without a specific token to expect, only what was found is known
+        let error = parse("a = ;").unwrap_err();
+        assert!(error.expected.is_empty());
+        assert_eq!(error.found, Some(TokenKind::Semi));
+    }
+
+    #[test]
+    fn errors_have_stable_codes() {
+        assert_eq!(parse("a = 'abc").unwrap_err().code(), "JS1001");
+        assert_eq!(parse("a = 1__0").unwrap_err().code(), "JS1002");
+        assert_eq!(parse("a = ;").unwrap_err().code(), "JS2001");
+        assert_eq!(parse("return").unwrap_err().code(), "JS2011");
+
+        use ParseErrorKind::*;
+        let kinds = [
+            UnexpectedCharacter,
+            UnterminatedString,
+            UnterminatedTemplate,
+            UnterminatedComment,
+            UnterminatedRegExp,
+            InvalidRegExpFlags,
+            InvalidNumericSeparator,
+            InvalidNumber,
+            InvalidEscape,
+            UnexpectedToken,
+            UnexpectedEnd,
+            InvalidTarget,
+            ReservedWord,
+            StrictMode,
+            Misplaced,
+            InvalidParameters,
+            InvalidClassElement,
+            InvalidSyntax,
+            Redeclaration,
+            MisplacedReturn,
+            MisplacedNewTarget,
+            MisplacedSuperProperty,
+            MisplacedSuperCall,
+            UnsupportedSyntax,
+            NestingTooDeep,
+        ];
+        let codes: std::collections::HashSet<_> = kinds.iter().map(|kind| kind.code()).collect();
+        assert_eq!(codes.len(), kinds.len());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn errors_serialize_with_their_code() {
+        let error = parse("f(a b)").unwrap_err();
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "JS2001",
+                "message": "Expected one of `,`, `)` but found identifier",
+                "start": { "line": 1, "column": 4 },
+                "end": { "line": 1, "column": 5 },
+                "expected": [",", ")"],
+                "found": "identifier"
+            })
+        );
+    }
+
+    #[test]
+    fn errors_render_with_the_line_and_an_underline() {
+        let render = |src| parse(src).unwrap_err().render(src);
+        assert_eq!(
+            render("a = 1 in in b"),
+            "error[JS2001]: Unexpected token 'in'
+ --> line:1, column:9
+  |
+1 | a = 1 in in b
+  |          ^^"
+        );
+        // tabs are expanded the same way in the line and the underline
+        assert_eq!(
+            render("\tif (a { }"),
+            "error[JS2001]: Expected `)` but found `{`
+ --> line:1, column:7
+  |
+1 |     if (a { }
+  |           ^"
+        );
+        assert_eq!(
+            render("a = (1"),
+            "error[JS2002]: Expected ')' to close '(' from line:1, column:4
+ --> line:1, column:6
+  |
+1 | a = (1
+  |       ^"
+        );
+        let src = "a;\n".repeat(9) + "b c";
+        assert_eq!(
+            render(&src),
+            "error[JS2001]: Expected `;` but found identifier
+  --> line:10, column:2
+   |
+10 | b c
+   |   ^"
+        );
+        // lines break wherever the lexer counts a new line
+        for src in ["a;\rb c", "a;\r\nb c", "a;\u{2028}b c", "a;\u{2029}b c"] {
+            assert_eq!(
+                render(src),
+                "error[JS2001]: Expected `;` but found identifier
+ --> line:2, column:2
+  |
+2 | b c
+  |   ^",
+                "{:?}",
+                src
+            );
+        }
+        assert_eq!(
+            render("a;\r\r(1"),
+            "error[JS2002]: Expected ')' to close '(' from line:3, column:0
+ --> line:3, column:2
+  |
+3 | (1
+  |   ^"
+        );
+    }
+
+    #[test]
+    fn a_span_over_several_lines_notes_where_it_ends() {
+        let error = crate::error::ParseError::new(
+            ParseErrorKind::UnterminatedTemplate,
+            "Unterminated template",
+            crate::node::Position::new(1, 4),
+            crate::node::Position::new(3, 2),
+        );
+        assert_eq!(
+            error.render("a = `one\ntwo\nth"),
+            "error[JS1004]: Unterminated template
+ --> line:1, column:4
+  |
+1 | a = `one
+  |     ^^^^
+  = ends at line:3, column:2"
+        );
+    }
+
+    #[test]
+    fn recovery_reports_every_error_and_keeps_the_rest() {
+        let (program, errors) = crate::parser::parse_with_recovery(
+            include_str!("../tests/fixtures/recovery.js"),
+            SourceType::Module,
+        );
+        let positions: Vec<_> = errors
+            .iter()
+            .map(|error| (error.start.line, error.start.column))
+            .collect();
+        assert_eq!(positions, [(2, 15), (5, 34), (10, 25)]);
+        assert_eq!(errors[1].expected, [TokenKind::Comma, TokenKind::ParenR]);
+
+        // what couldn't be parsed is left as error nodes
+        assert_eq!(program.body.len(), 5);
+        let Statement::VariableDeclaration(declaration) = &program.body[1] else {
+            panic!("expected a variable declaration");
+        };
+        let Some(Expression::Error(init)) = &declaration.declarations[0].init else {
+            panic!("expected an error node");
+        };
+        assert_eq!(init.diagnostic, 0);
+        let Statement::FunctionDeclaration(function) = &program.body[2] else {
+            panic!("expected a function declaration");
+        };
+        let [Statement::Error(statement), Statement::Return(_)] = &function.body.body[..] else {
+            panic!("expected an error node and a return statement");
+        };
+        assert_eq!(statement.diagnostic, 1);
+        assert_eq!(statement.loc.start.line, 5);
+        let Statement::If(statement) = &program.body[3] else {
+            panic!("expected an if statement");
+        };
+        let Statement::Block(block) = &statement.consequent else {
+            panic!("expected a block");
+        };
+        assert!(matches!(&block.body[..], [Statement::Error(node)] if node.diagnostic == 2));
+    }
+
+    #[test]
+    fn recovery_always_moves_past_a_bad_token() {
+        let src = "}\nvar a = 1;\n)\nlet b;";
+        let (program, errors) = crate::parser::parse_with_recovery(src, SourceType::Script);
+        let lines: Vec<_> = errors.iter().map(|error| error.start.line).collect();
+        assert_eq!(lines, [1, 3]);
+        let kinds: Vec<_> = program
+            .body
+            .iter()
+            .map(|node| NodeRef::from(node).node_type())
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                "ErrorNode",
+                "VariableDeclaration",
+                "ErrorNode",
+                "VariableDeclaration"
+            ]
+        );
+
+        let (program, errors) = crate::parser::parse_with_recovery("a; b;", SourceType::Script);
+        assert!(errors.is_empty());
+        assert_eq!(program.body.len(), 2);
+    }
+
+    #[test]
+    fn lexer_errors_are_recovered_from() {
+        use ParseErrorKind::*;
+        let src = "a = 1; @ b = 2;\nc = 'x\nd = '\\x' + e;\nf(g) h 'i";
+        let (program, errors) = crate::parser::parse_with_recovery(src, SourceType::Script);
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|error| {
+                let start = (error.start.line, error.start.column);
+                (error.kind, start, error.message.as_str())
+            })
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (UnexpectedCharacter, (1, 7), "Unexpected character '@'"),
+                (UnterminatedString, (2, 6), "Unterminated string constant"),
+                (InvalidEscape, (3, 5), "Invalid escape sequence"),
+                (UnexpectedToken, (4, 5), "Expected `;` but found identifier"),
+                (UnterminatedString, (4, 9), "Unterminated string constant"),
+            ]
+        );
+        let kinds: Vec<_> = program
+            .body
+            .iter()
+            .map(|node| NodeRef::from(node).node_type())
+            .collect();
+        assert_eq!(
+            kinds,
+            ["ExpressionStatement", "ErrorNode", "ErrorNode", "ErrorNode"]
+        );
+
+        // the error is only returned when not tolerant
+        let error = crate::parser::parse("a = 'b").unwrap_err();
+        assert_eq!(error.message, "Unterminated string constant");
+    }
+
+    #[test]
+    fn a_function_with_broken_params_keeps_its_body() {
+        let src = "function f( { return 1 }";
+        let (program, errors) = crate::parser::parse_with_recovery(src, SourceType::Script);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Expected `)` but found `{`");
+        assert_eq!((errors[0].start.line, errors[0].start.column), (1, 12));
+        let [Statement::FunctionDeclaration(function)] = &program.body[..] else {
+            panic!("expected a function declaration");
+        };
+        let [Pattern::Error(param)] = &function.params[..] else {
+            panic!("expected an error node");
+        };
+        assert_eq!((param.diagnostic, param.loc.range), (0, (12, 12)));
+        assert!(matches!(function.body.body[..], [Statement::Return(_)]));
+
+        let src = "function f(1 { return 1 }";
+        let (program, errors) = crate::parser::parse_with_recovery(src, SourceType::Script);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Expected identifier but found number");
+        let Statement::FunctionDeclaration(function) = &program.body[0] else {
+            panic!("expected a function declaration");
+        };
+        let [Pattern::Error(param)] = &function.params[..] else {
+            panic!("expected an error node");
+        };
+        assert_eq!(param.loc.range, (11, 12));
+        assert!(matches!(function.body.body[..], [Statement::Return(_)]));
+
+        // a broken pattern that does have its closing paren is skipped whole
+        let (program, errors) =
+            crate::parser::parse_with_recovery("function f({ a b }) { c }", SourceType::Script);
+        assert_eq!(errors.len(), 1);
+        let Statement::FunctionDeclaration(function) = &program.body[0] else {
+            panic!("expected a function declaration");
+        };
+        let [Pattern::Error(param)] = &function.params[..] else {
+            panic!("expected an error node");
+        };
+        assert_eq!(param.loc.range, (11, 18));
+        assert!(matches!(function.body.body[..], [Statement::Expression(_)]));
+
+        // the rest of the list is skipped up to the closing paren
+        let (program, _) =
+            crate::parser::parse_with_recovery("function f(a, 1 b) {}", SourceType::Script);
+        let Statement::FunctionDeclaration(function) = &program.body[0] else {
+            panic!("expected a function declaration");
+        };
+        let [Pattern::Identifier(_), Pattern::Error(param)] = &function.params[..] else {
+            panic!("expected an identifier and an error node");
+        };
+        assert_eq!(param.loc.range, (14, 17));
+    }
+}
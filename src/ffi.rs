@@ -0,0 +1,162 @@
+// A C interface for hosts that can't link Rust, built with `--features ffi`.
+// `include/js_parser.h` declares it and is generated from this file with
+//
+//   cbindgen --config cbindgen.toml --output include/js_parser.h
+//
+// and `tests/ffi/parse.c` shows it in use. A panic never unwinds into the
+// caller; it is caught and reported as an error like any other.
+use std::{
+    any::Any,
+    ffi::{c_char, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice, str,
+};
+
+use crate::{node::SourceType, parser::parse_with_source_type};
+
+/// Why parsing failed. `line` counts from 1 and `column` from 0, as in the
+/// JSON, and both are 0 when there is no position to give, as for a panic.
+/// `message` belongs to the error and is released with `js_free_error`.
+#[repr(C)]
+pub struct JsError {
+    pub message: *mut c_char,
+    pub line: usize,
+    pub column: usize,
+}
+
+struct Failure {
+    message: String,
+    line: usize,
+    column: usize,
+}
+
+impl Failure {
+    fn new(message: String) -> Self {
+        Failure {
+            message,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    fn from_panic(payload: Box<dyn Any + Send>) -> Self {
+        let reason = match payload.downcast::<String>() {
+            Ok(reason) => *reason,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(reason) => reason.to_string(),
+                Err(_) => "unknown reason".to_string(),
+            },
+        };
+        Failure::new(format!("The parser panicked: {}", reason))
+    }
+}
+
+// the source, which has to be UTF-8; the position of the first bad byte is
+// worked out as a parse error's would be
+unsafe fn read_source<'a>(src: *const c_char, len: usize) -> Result<&'a str, Failure> {
+    if src.is_null() {
+        return match len {
+            0 => Ok(""),
+            _ => Err(Failure::new("The source is a null pointer".to_string())),
+        };
+    }
+    let bytes = slice::from_raw_parts(src as *const u8, len);
+    str::from_utf8(bytes).map_err(|error| {
+        // the prefix is known to be valid
+        let valid = str::from_utf8(&bytes[..error.valid_up_to()]).unwrap_or_default();
+        let line_start = valid.rfind('\n').map_or(0, |index| index + 1);
+        Failure {
+            message: "The source is not valid UTF-8".to_string(),
+            line: valid.matches('\n').count() + 1,
+            column: valid[line_start..].chars().count(),
+        }
+    })
+}
+
+fn to_json(src: &str, source_type: SourceType) -> Result<CString, Failure> {
+    let program = parse_with_source_type(src, source_type).map_err(|error| Failure {
+        message: format!("{}: {}", error.code(), error.message),
+        line: error.start.line,
+        column: error.start.column,
+    })?;
+    let json = serde_json::to_string(&program).map_err(|error| Failure::new(error.to_string()))?;
+    // JSON escapes any NUL in the source
+    CString::new(json).map_err(|error| Failure::new(error.to_string()))
+}
+
+unsafe fn parse_to_json(
+    src: *const c_char,
+    len: usize,
+    source_type: SourceType,
+    out_err: *mut JsError,
+) -> *mut c_char {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        to_json(read_source(src, len)?, source_type)
+    }))
+    .unwrap_or_else(|payload| Err(Failure::from_panic(payload)));
+    let (json, error) = match result {
+        Ok(json) => (json.into_raw(), None),
+        Err(failure) => (ptr::null_mut(), Some(failure)),
+    };
+    if !out_err.is_null() {
+        out_err.write(match error {
+            Some(failure) => JsError {
+                message: CString::new(failure.message.replace('\0', ""))
+                    .unwrap_or_default()
+                    .into_raw(),
+                line: failure.line,
+                column: failure.column,
+            },
+            None => JsError {
+                message: ptr::null_mut(),
+                line: 0,
+                column: 0,
+            },
+        });
+    }
+    json
+}
+
+/// Parses `len` bytes of UTF-8 at `src` as a script and returns its ESTree
+/// JSON as a NUL-terminated string, to be released with `js_free_string`.
+/// On failure returns NULL and, unless `out_err` is NULL, fills it in; on
+/// success `out_err` is filled in with a NULL message, so it can always be
+/// passed to `js_free_error`.
+#[no_mangle]
+pub unsafe extern "C" fn js_parse_to_json(
+    src: *const c_char,
+    len: usize,
+    out_err: *mut JsError,
+) -> *mut c_char {
+    parse_to_json(src, len, SourceType::Script, out_err)
+}
+
+/// As `js_parse_to_json`, parsing the source as an ES module.
+#[no_mangle]
+pub unsafe extern "C" fn js_parse_module_to_json(
+    src: *const c_char,
+    len: usize,
+    out_err: *mut JsError,
+) -> *mut c_char {
+    parse_to_json(src, len, SourceType::Module, out_err)
+}
+
+/// Releases a string returned by `js_parse_to_json`; NULL is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn js_free_string(json: *mut c_char) {
+    if !json.is_null() {
+        drop(CString::from_raw(json));
+    }
+}
+
+/// Releases the message of an error and sets it to NULL; the `JsError`
+/// itself belongs to the caller.
+#[no_mangle]
+pub unsafe extern "C" fn js_free_error(error: *mut JsError) {
+    if let Some(error) = error.as_mut() {
+        if !error.message.is_null() {
+            drop(CString::from_raw(error.message));
+            error.message = ptr::null_mut();
+        }
+    }
+}
@@ -0,0 +1,414 @@
+// What each piece of a source is for highlighting, from its tokens alone
+// without building the tree, and counts of those tokens.
+//
+// Most tokens are what their kind says. Words that are keywords only in some
+// places are told apart by the tokens around them: `async` before `function`,
+// an arrow's parameters or a method name, `get`, `set` and `static` starting
+// a member, `of` in the head of a `for`, `let` before what it declares,
+// `yield` and `await` unless used as a variable, and `from` and `as` in an
+// import or export. Any word after `.` or `?.` is a property name. These are
+// guesses where the tokens alone can't tell, such as `yield` outside a
+// generator, but right for code anyone would write.
+use std::collections::HashMap;
+
+use crate::{
+    error::ParseResult,
+    lexer::Lexer,
+    node::{Comment, Position, SourceLocation},
+    token::{Arithmetic, Lexeme, Token, TokenKind},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Category {
+    Keyword,
+    Identifier,
+    String,
+    Number,
+    RegExp,
+    Comment,
+    Operator,
+    Punctuation,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TokenStats {
+    // comments aren't tokens, so aren't counted
+    pub per_kind: HashMap<TokenKind, usize>,
+    pub lines: usize,
+    pub bytes: usize,
+}
+
+// the tokens and comments of the source in order, with what each is
+pub fn categorize(src: &str) -> ParseResult<Vec<(SourceLocation, Category)>> {
+    let (lexemes, comments, _) = lex(src)?;
+    let mut categorizer = Categorizer {
+        src,
+        lexemes: &lexemes,
+        categories: vec![],
+        parens: vec![],
+        in_module_clause: false,
+    };
+    for index in 0..lexemes.len() {
+        let category = categorizer.category(index);
+        categorizer.categories.push(category);
+        categorizer.track(index);
+    }
+
+    let mut spans: Vec<(SourceLocation, Category)> = lexemes
+        .iter()
+        .map(span)
+        .zip(categorizer.categories)
+        .chain(
+            comments
+                .into_iter()
+                .map(|comment| (comment.loc, Category::Comment)),
+        )
+        .collect();
+    spans.sort_by_key(|(loc, _)| loc.range.0);
+    Ok(spans)
+}
+
+pub fn stats(src: &str) -> ParseResult<TokenStats> {
+    let (lexemes, _, lines) = lex(src)?;
+    let mut per_kind = HashMap::new();
+    for lexeme in &lexemes {
+        *per_kind.entry(lexeme.token.kind()).or_insert(0) += 1;
+    }
+    Ok(TokenStats {
+        per_kind,
+        lines,
+        bytes: src.len(),
+    })
+}
+
+fn lex(src: &str) -> ParseResult<(Vec<Lexeme>, Vec<Comment>, usize)> {
+    let mut lexer = Lexer::new(src);
+    let lexemes = lexer.by_ref().collect::<ParseResult<Vec<Lexeme>>>()?;
+    let (lines, _) = lexer.end();
+    Ok((lexemes, lexer.take_comments(), lines))
+}
+
+fn span(lexeme: &Lexeme) -> SourceLocation {
+    SourceLocation {
+        source: None,
+        start: Position::new(lexeme.line, lexeme.column),
+        end: Position::new(lexeme.end_line, lexeme.end_column),
+        range: (lexeme.offset, lexeme.end_offset),
+    }
+}
+
+struct Categorizer<'a> {
+    src: &'a str,
+    lexemes: &'a [Lexeme],
+    // of the tokens before the one being looked at
+    categories: Vec<Category>,
+    // for each open parenthesis, None unless it's the head of a `for`, and
+    // then whether its `of` has been seen
+    parens: Vec<Option<bool>>,
+    // between `import` or `export` and the end of what it names
+    in_module_clause: bool,
+}
+
+impl Categorizer<'_> {
+    fn token(&self, index: usize) -> Option<&Token> {
+        self.lexemes.get(index).map(|lexeme| &lexeme.token)
+    }
+
+    fn category(&self, index: usize) -> Category {
+        let lexeme = &self.lexemes[index];
+        let is_word = matches!(lexeme.token, Token::Name(_)) || lexeme.token.is_keyword();
+        if is_word && index > 0 {
+            if let Some(Token::Dot | Token::QuestionDot) = self.token(index - 1) {
+                return Category::Identifier;
+            }
+        }
+        match &lexeme.token {
+            Token::Number(_) | Token::Bigint(_) => Category::Number,
+            Token::String(_) | Token::Template(_, _) => Category::String,
+            Token::RegExp(_) => Category::RegExp,
+            Token::PrivateName(_) => Category::Identifier,
+            Token::Name(_) if self.is_contextual_keyword(index) => Category::Keyword,
+            Token::Name(_) => Category::Identifier,
+            Token::Let => match self.token(index + 1) {
+                Some(
+                    Token::Name(_)
+                    | Token::Let
+                    | Token::Of
+                    | Token::Undefined
+                    | Token::BracketL
+                    | Token::BraceL,
+                ) => Category::Keyword,
+                _ => Category::Identifier,
+            },
+            Token::Of => match self.parens.last() {
+                Some(Some(false)) if ends_binding(self.token(index.wrapping_sub(1))) => {
+                    Category::Keyword
+                }
+                _ => Category::Identifier,
+            },
+            token if token.is_keyword() => Category::Keyword,
+            Token::ParenL
+            | Token::ParenR
+            | Token::BracketL
+            | Token::BracketR
+            | Token::BraceL
+            | Token::BraceR
+            | Token::Dot
+            | Token::QuestionDot
+            | Token::Semi
+            | Token::Comma
+            | Token::Colon => Category::Punctuation,
+            _ => Category::Operator,
+        }
+    }
+
+    fn is_contextual_keyword(&self, index: usize) -> bool {
+        let lexeme = &self.lexemes[index];
+        let next = self.token(index + 1);
+        // a line break before the next token may end the statement instead
+        let same_line = self
+            .lexemes
+            .get(index + 1)
+            .is_some_and(|next| !next.newline_before);
+        match lexeme.text(self.src) {
+            "async" => {
+                same_line
+                    && match next {
+                        Some(Token::Function) => true,
+                        Some(Token::ParenL) => self.arrow_after_parens(index + 1),
+                        Some(Token::Arithmetic(Arithmetic::Multiple)) => self.starts_member(index),
+                        Some(token) if is_property_name(token) => {
+                            matches!(self.token(index + 2), Some(Token::Arrow | Token::ParenL))
+                        }
+                        _ => false,
+                    }
+            }
+            "get" | "set" => {
+                same_line && self.starts_member(index) && next.is_some_and(is_property_name)
+            }
+            "static" => {
+                same_line
+                    && self.starts_member(index)
+                    && next.is_some_and(|token| {
+                        is_property_name(token)
+                            || matches!(
+                                token,
+                                Token::BracketL
+                                    | Token::BraceL
+                                    | Token::Arithmetic(Arithmetic::Multiple)
+                            )
+                    })
+            }
+            "yield" | "await" => !matches!(
+                next,
+                Some(
+                    Token::Assign(_)
+                        | Token::Arrow
+                        | Token::Dot
+                        | Token::QuestionDot
+                        | Token::Colon
+                )
+            ),
+            "from" => self.in_module_clause && matches!(next, Some(Token::String(_))),
+            "as" => {
+                let after_name = match index.checked_sub(1) {
+                    Some(previous) => {
+                        !(matches!(self.token(previous), Some(Token::BraceL | Token::Comma))
+                            || self.is_keyword(previous, "as"))
+                    }
+                    None => false,
+                };
+                self.in_module_clause
+                    && after_name
+                    && next.is_some_and(|token| {
+                        matches!(token, Token::Name(_) | Token::String(_)) || token.is_keyword()
+                    })
+            }
+            _ => false,
+        }
+    }
+
+    // whether an earlier token is the given word, taken as a keyword
+    fn is_keyword(&self, index: usize, word: &str) -> bool {
+        self.categories[index] == Category::Keyword && self.lexemes[index].text(self.src) == word
+    }
+
+    // whether the token is where a class member or object property begins
+    fn starts_member(&self, index: usize) -> bool {
+        if index == 0 || self.lexemes[index].newline_before {
+            return true;
+        }
+        let previous = &self.lexemes[index - 1];
+        matches!(
+            previous.token,
+            Token::BraceL | Token::BraceR | Token::Semi | Token::Comma
+        ) || previous.text(self.src) == "static"
+    }
+
+    fn arrow_after_parens(&self, open: usize) -> bool {
+        let mut depth = 0;
+        for (index, lexeme) in self.lexemes.iter().enumerate().skip(open) {
+            match lexeme.token {
+                Token::ParenL => depth += 1,
+                Token::ParenR => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return matches!(self.token(index + 1), Some(Token::Arrow));
+                    }
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    // keeps up with the context for the tokens after this one
+    fn track(&mut self, index: usize) {
+        let token = &self.lexemes[index].token;
+        let previous = index
+            .checked_sub(1)
+            .and_then(|previous| self.token(previous));
+        match token {
+            Token::ParenL => {
+                let for_head = match previous {
+                    Some(Token::For) => true,
+                    Some(Token::Name(_)) => {
+                        index >= 2
+                            && matches!(self.token(index - 2), Some(Token::For))
+                            && self.lexemes[index - 1].text(self.src) == "await"
+                    }
+                    _ => false,
+                };
+                self.parens.push(for_head.then_some(false));
+            }
+            Token::ParenR => {
+                self.parens.pop();
+            }
+            Token::Of if self.categories[index] == Category::Keyword => {
+                if let Some(head) = self.parens.last_mut() {
+                    *head = Some(true);
+                }
+            }
+            Token::Import => {
+                self.in_module_clause =
+                    !matches!(self.token(index + 1), Some(Token::ParenL | Token::Dot))
+            }
+            Token::Export => self.in_module_clause = true,
+            // the specifier ends the clause, as does a declaration instead
+            Token::String(_) if index > 0 && self.is_keyword(index - 1, "from") => {
+                self.in_module_clause = false
+            }
+            Token::Semi
+            | Token::Var
+            | Token::Let
+            | Token::Const
+            | Token::Function
+            | Token::Class
+            | Token::Assign(_) => self.in_module_clause = false,
+            _ => {}
+        }
+    }
+}
+
+// tokens a member may be named by
+fn is_property_name(token: &Token) -> bool {
+    match token {
+        Token::Name(_) | Token::String(_) | Token::Number(_) | Token::PrivateName(_) => true,
+        // after a word these are operators instead
+        Token::In | Token::Instanceof | Token::Of => false,
+        token => token.is_keyword(),
+    }
+}
+
+// whether a token may end the binding or target before `of`
+fn ends_binding(token: Option<&Token>) -> bool {
+    matches!(
+        token,
+        Some(Token::Name(_) | Token::Of | Token::BracketR | Token::BraceR)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        highlight::{categorize, stats, Category},
+        token::TokenKind,
+    };
+
+    // the source text of each token and comment with its category
+    fn categories(src: &str) -> Vec<(&str, Category)> {
+        categorize(src)
+            .unwrap()
+            .into_iter()
+            .map(|(loc, category)| (&src[loc.range.0..loc.range.1], category))
+            .collect()
+    }
+
+    #[test]
+    fn tokens_and_comments_are_categorized() {
+        use Category::*;
+        assert_eq!(
+            categories("if (a.b >= 1n) /* c */ x = [`d${e}`, 'f', /g/i];"),
+            [
+                ("if", Keyword),
+                ("(", Punctuation),
+                ("a", Identifier),
+                (".", Punctuation),
+                ("b", Identifier),
+                (">=", Operator),
+                ("1n", Number),
+                (")", Punctuation),
+                ("/* c */", Comment),
+                ("x", Identifier),
+                ("=", Operator),
+                ("[", Punctuation),
+                ("`d${", String),
+                ("e", Identifier),
+                ("}`", String),
+                (",", Punctuation),
+                ("'f'", String),
+                (",", Punctuation),
+                ("/g/i", RegExp),
+                ("]", Punctuation),
+                (";", Punctuation),
+            ]
+        );
+    }
+
+    #[test]
+    fn contextual_keywords_are_keywords_only_where_they_act_as_them() {
+        let keywords = |src| -> Vec<&str> {
+            categories(src)
+                .into_iter()
+                .filter(|&(_, category)| category == Category::Keyword)
+                .map(|(text, _)| text)
+                .collect()
+        };
+        assert_eq!(
+            keywords("async function f() { await g(async); } async (a) => a; async(b);"),
+            ["async", "function", "await", "async"]
+        );
+        assert_eq!(
+            keywords("class A { static get x() {} set; } o.get(x); ({ get: 1 });"),
+            ["class", "static", "get"]
+        );
+        assert_eq!(
+            keywords("for (const of of of); let = 1; let x;"),
+            ["for", "const", "of", "let"]
+        );
+        assert_eq!(
+            keywords("import { as as as } from 'm'; var from = a.default;"),
+            ["import", "as", "from", "var"]
+        );
+    }
+
+    #[test]
+    fn token_stats_count_each_kind() {
+        let stats = stats("let a = 1;\n// b\na = a + 2;\n").unwrap();
+        assert_eq!(stats.per_kind[&TokenKind::Name], 3);
+        assert_eq!(stats.per_kind[&TokenKind::Number], 2);
+        assert_eq!(stats.per_kind[&TokenKind::Semi], 2);
+        assert_eq!(stats.per_kind.values().sum::<usize>(), 11);
+        assert_eq!((stats.lines, stats.bytes), (4, 27));
+    }
+}
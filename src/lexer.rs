@@ -0,0 +1,1270 @@
+use std::borrow::Cow;
+
+use crate::{
+    error::{ParseError, ParseErrorKind, ParseResult},
+    name::Interner,
+    node::{Comment, CommentKind, Position, RegExpValue, SourceLocation, TemplateElementValue},
+    token::*,
+};
+
+// classes of bytes, so that the lexer dispatches on ASCII without decoding
+// characters; every byte of a character outside ASCII is 0x80 or above and
+// has only `NON_ASCII`, and such a character is decoded where it comes up
+const IDENTIFIER: u8 = 1 << 0;
+const DIGIT: u8 = 1 << 1;
+const QUOTE: u8 = 1 << 2;
+const OPERATOR: u8 = 1 << 3;
+const WHITESPACE: u8 = 1 << 4;
+const LINE_BREAK: u8 = 1 << 5;
+const NON_ASCII: u8 = 1 << 6;
+
+const CLASSES: [u8; 256] = classes();
+
+const fn classes() -> [u8; 256] {
+    let mut classes = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        classes[byte] = match byte as u8 {
+            b'0'..=b'9' => IDENTIFIER | DIGIT,
+            b'a'..=b'z' | b'A'..=b'Z' | b'$' | b'_' => IDENTIFIER,
+            b'\'' | b'"' => QUOTE,
+            b'!' | b'%' | b'&' | b'(' | b')' | b'*' | b'+' | b',' | b'-' | b'.' | b'/' | b':'
+            | b';' | b'<' | b'=' | b'>' | b'?' | b'[' | b']' | b'^' | b'{' | b'|' | b'}' | b'~' => {
+                OPERATOR
+            }
+            b'\n' | b'\r' => WHITESPACE | LINE_BREAK,
+            // as `char::is_whitespace` has them
+            b'\t' | 0x0b | 0x0c | b' ' => WHITESPACE,
+            0x80..=0xff => NON_ASCII,
+            _ => 0,
+        };
+        byte += 1;
+    }
+    classes
+}
+
+fn is(byte: u8, class: u8) -> bool {
+    CLASSES[byte as usize] & class != 0
+}
+
+// where the lexer is in the source, as a byte offset, and the line it's on
+struct Cursor<'a> {
+    src: &'a str,
+    position: usize,
+    line: usize,
+    // where the line would start if every character on it so far took a
+    // byte, so that the column, which counts characters, is the position
+    // less this
+    line_start: usize,
+}
+
+impl<'a> Cursor<'a> {
+    // the byte `offset` bytes on, 0 past the end
+    fn byte(&self, offset: usize) -> u8 {
+        self.src
+            .as_bytes()
+            .get(self.position + offset)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn at_end(&self) -> bool {
+        self.position >= self.src.len()
+    }
+
+    fn column(&self) -> usize {
+        self.position - self.line_start
+    }
+
+    // the character at the position, which is only worth decoding when the
+    // byte there isn't ASCII
+    fn char(&self) -> Option<char> {
+        self.src[self.position..].chars().next()
+    }
+
+    fn slice(&self, start: usize, end: usize) -> &'a str {
+        &self.src[start..end]
+    }
+
+    // moves past a character of however many bytes
+    fn bump(&mut self) {
+        match self.byte(0) {
+            0..=0x7f => self.position += 1,
+            _ => {
+                let length = self.char().map_or(1, char::len_utf8);
+                self.position += length;
+                self.line_start += length - 1;
+            }
+        }
+    }
+
+    // moves past bytes up to `end`, which don't break the line
+    fn skip_to(&mut self, end: usize) {
+        let continuations = self.src.as_bytes()[self.position..end]
+            .iter()
+            .filter(|byte| **byte & 0xc0 == 0x80)
+            .count();
+        self.position = end;
+        self.line_start += continuations;
+    }
+
+    // whether a line break is at the position, which besides "\r" and "\n"
+    // may be U+2028 or U+2029, three bytes each
+    fn at_line_break(&self) -> bool {
+        is(self.byte(0), LINE_BREAK)
+            || (self.byte(0) == 0xe2 && self.byte(1) == 0x80 && matches!(self.byte(2), 0xa8 | 0xa9))
+    }
+
+    // moves past a line break, "\r\n" being a single one
+    fn new_line(&mut self) {
+        self.position += match self.byte(0) {
+            b'\r' if self.byte(1) == b'\n' => 2,
+            b'\r' | b'\n' => 1,
+            _ => 3,
+        };
+        self.line += 1;
+        self.line_start = self.position;
+    }
+
+    fn error(&self, kind: ParseErrorKind, message: &str, column: usize) -> ParseError {
+        ParseError::at(kind, message, self.line, column)
+    }
+}
+
+fn get_operator_by_chars(chars: &str) -> Option<Token> {
+    match chars {
+        "==" => Some(Token::Comparation(Comparation::DoubleE)),
+        "===" => Some(Token::Comparation(Comparation::TripleE)),
+        "!=" => Some(Token::Comparation(Comparation::DoubleNE)),
+        "!==" => Some(Token::Comparation(Comparation::TripleNE)),
+        "<" => Some(Token::Comparation(Comparation::LT)),
+        "<=" => Some(Token::Comparation(Comparation::LTE)),
+        ">" => Some(Token::Comparation(Comparation::GT)),
+        ">=" => Some(Token::Comparation(Comparation::GTE)),
+
+        "+" => Some(Token::Arithmetic(Arithmetic::Plus)),
+        "-" => Some(Token::Arithmetic(Arithmetic::Minus)),
+        "*" => Some(Token::Arithmetic(Arithmetic::Multiple)),
+        "/" => Some(Token::Arithmetic(Arithmetic::Divide)),
+        "%" => Some(Token::Arithmetic(Arithmetic::Modulo)),
+        "**" => Some(Token::Arithmetic(Arithmetic::Exponent)),
+
+        "=" => Some(Token::Assign(Assign::Normal)),
+        "+=" => Some(Token::Assign(Assign::Addition)),
+        "-=" => Some(Token::Assign(Assign::Subtraction)),
+        "*=" => Some(Token::Assign(Assign::Multiplication)),
+        "/=" => Some(Token::Assign(Assign::Division)),
+        "%=" => Some(Token::Assign(Assign::Remainder)),
+        "**=" => Some(Token::Assign(Assign::Exponentiation)),
+        "<<=" => Some(Token::Assign(Assign::LeftShift)),
+        ">>=" => Some(Token::Assign(Assign::RightShift)),
+        ">>>=" => Some(Token::Assign(Assign::URightShift)),
+        "|=" => Some(Token::Assign(Assign::BitwiseOR)),
+        "^=" => Some(Token::Assign(Assign::BitwiseXOR)),
+        "&=" => Some(Token::Assign(Assign::BitwiseAND)),
+        "||=" => Some(Token::Assign(Assign::LogicalOR)),
+        "&&=" => Some(Token::Assign(Assign::LogicalAND)),
+        "??=" => Some(Token::Assign(Assign::NullishCoalescing)),
+
+        "(" => Some(Token::ParenL),
+        ")" => Some(Token::ParenR),
+        "[" => Some(Token::BracketL),
+        "]" => Some(Token::BracketR),
+        "{" => Some(Token::BraceL),
+        "}" => Some(Token::BraceR),
+
+        "." => Some(Token::Dot),
+        "?." => Some(Token::QuestionDot),
+        ";" => Some(Token::Semi),
+        "," => Some(Token::Comma),
+        ":" => Some(Token::Colon),
+        "?" => Some(Token::Question),
+        "??" => Some(Token::NullishCoalesce),
+        "!" => Some(Token::LogicalInversion),
+        "~" => Some(Token::BitwiseInversion),
+        "||" => Some(Token::LogicalOR),
+        "|" => Some(Token::BitwiseOR),
+        "&&" => Some(Token::LogicalAND),
+        "&" => Some(Token::BitwiseAND),
+        "^" => Some(Token::BitwiseXOR),
+        "<<" => Some(Token::LeftShift),
+        ">>" => Some(Token::RightShift),
+        ">>>" => Some(Token::URightShift),
+        "++" => Some(Token::Increment),
+        "--" => Some(Token::Decrement),
+        "=>" => Some(Token::Arrow),
+        "..." => Some(Token::Ellipsis),
+
+        _ => None,
+    }
+}
+
+// the token holds the value of the string, with its escapes cooked; the
+// text as written is the lexeme's. A bad escape is an error once the string
+// is read to its end, where a tolerant lexer goes on from
+fn read_string(cursor: &mut Cursor, names: &mut Interner) -> ParseResult<Token> {
+    let boundary = cursor.byte(0); // ' or "
+
+    // read content
+    cursor.position += 1;
+    let mut value = String::new();
+    let mut unescaped_start = cursor.position;
+    let mut invalid = None;
+
+    // read until boundary or line break, which can only be escaped; U+2028
+    // and U+2029 may be in a string but still start a line
+    while !cursor.at_end() && cursor.byte(0) != boundary && !is(cursor.byte(0), LINE_BREAK) {
+        if cursor.at_line_break() {
+            cursor.new_line();
+            continue;
+        }
+        if cursor.byte(0) != b'\\' {
+            cursor.bump();
+            continue;
+        }
+        value.push_str(cursor.slice(unescaped_start, cursor.position));
+        let column = cursor.column();
+        cursor.position += 1;
+        match read_string_escape(cursor) {
+            Some(escaped) => value.push_str(&escaped),
+            None => {
+                invalid.get_or_insert(cursor.error(
+                    ParseErrorKind::InvalidEscape,
+                    "Invalid escape sequence",
+                    column,
+                ));
+            }
+        }
+        unescaped_start = cursor.position;
+    }
+
+    // unexpected boundary such as line break or ending of code
+    if cursor.byte(0) != boundary || cursor.at_end() {
+        return Err(invalid.unwrap_or_else(|| {
+            cursor.error(
+                ParseErrorKind::UnterminatedString,
+                "Unterminated string constant",
+                cursor.column(),
+            )
+        }));
+    }
+
+    value.push_str(cursor.slice(unescaped_start, cursor.position));
+
+    // ready to read next token
+    cursor.position += 1;
+
+    match invalid {
+        Some(error) => Err(error),
+        None => Ok(Token::String(names.intern(&value))),
+    }
+}
+
+// the value of a string literal written as `raw`, quotes and all, or None
+// if it isn't one
+pub(crate) fn cook_string(raw: &str) -> Option<String> {
+    if !raw.starts_with(['\'', '"']) {
+        return None;
+    }
+    let mut cursor = Cursor {
+        src: raw,
+        position: 0,
+        line: 1,
+        line_start: 0,
+    };
+    match read_string(&mut cursor, &mut Interner::default()) {
+        Ok(Token::String(value)) if cursor.at_end() => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+// strings, unlike templates, have the legacy octal escapes, as in `\101`
+// for "A", whose digits make a code unit up to 255; `\8` and `\9` stand for
+// the digit
+fn read_string_escape(cursor: &mut Cursor) -> Option<String> {
+    match cursor.byte(0) {
+        first @ b'0'..=b'7' => {
+            let length = if first <= b'3' { 3 } else { 2 };
+            let mut code = 0;
+            for _ in 0..length {
+                if !matches!(cursor.byte(0), b'0'..=b'7') {
+                    break;
+                }
+                code = code * 8 + (cursor.byte(0) - b'0') as u32;
+                cursor.position += 1;
+            }
+            char::from_u32(code).map(String::from)
+        }
+        digit @ (b'8' | b'9') => {
+            cursor.position += 1;
+            Some((digit as char).to_string())
+        }
+        _ => read_escape(cursor),
+    }
+}
+
+fn read_hex_digits(cursor: &mut Cursor, count: usize) -> Option<u32> {
+    let digits = cursor.src.get(cursor.position..cursor.position + count)?;
+    if !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    cursor.position += count;
+    u32::from_str_radix(digits, 16).ok()
+}
+
+// `\uXXXX` or `\u{X...}`, with the position right after the `u`
+fn read_unicode_escape(cursor: &mut Cursor) -> Option<u32> {
+    if cursor.byte(0) != b'{' {
+        return read_hex_digits(cursor, 4);
+    }
+    let start = cursor.position + 1;
+    let end = start + cursor.src[start..].find('}')?;
+    let digits = cursor.slice(start, end);
+    if digits.is_empty() || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(digits, 16)
+        .ok()
+        .filter(|&value| value <= 0x10FFFF)?;
+    cursor.position = end + 1;
+    Some(value)
+}
+
+// the value of the escape sequence after a backslash, or `None` when it is
+// not a valid one in a template; a line continuation stands for nothing
+fn read_escape(cursor: &mut Cursor) -> Option<String> {
+    let byte = cursor.byte(0);
+    if cursor.at_end() {
+        return Some(String::new());
+    }
+    if cursor.at_line_break() {
+        cursor.new_line();
+        return Some(String::new());
+    }
+    if !byte.is_ascii() {
+        let char = cursor.char()?;
+        cursor.bump();
+        return Some(char.to_string());
+    }
+    cursor.position += 1;
+    let value = match byte {
+        b'n' => '\n',
+        b't' => '\t',
+        b'r' => '\r',
+        b'b' => '\u{8}',
+        b'f' => '\u{c}',
+        b'v' => '\u{b}',
+        b'0' if !is(cursor.byte(0), DIGIT) => '\0',
+        b'0'..=b'9' => return None,
+        b'x' => char::from_u32(read_hex_digits(cursor, 2)?)?,
+        b'u' => {
+            let mut code = read_unicode_escape(cursor)?;
+            // a surrogate pair written as two escapes is a single character
+            if (0xD800..0xDC00).contains(&code) && cursor.byte(0) == b'\\' && cursor.byte(1) == b'u'
+            {
+                let position = cursor.position;
+                cursor.position += 2;
+                match read_unicode_escape(cursor) {
+                    Some(low) if (0xDC00..0xE000).contains(&low) => {
+                        code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                    }
+                    _ => cursor.position = position,
+                }
+            }
+            char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)
+        }
+        _ => byte as char,
+    };
+    Some(value.to_string())
+}
+
+// reads from the opening "`", or the "}" that ends a substitution, up to and
+// including the next "`" or "${"; line breaks are normalized to "\n" in both
+// the raw and the cooked text
+fn read_template(cursor: &mut Cursor) -> ParseResult<Token> {
+    let start_column = cursor.column();
+    let start_line = cursor.line;
+    cursor.position += 1;
+
+    let mut raw = String::new();
+    let mut cooked = Some(String::new());
+    let tail = loop {
+        if cursor.at_end() {
+            return Err(ParseError::at(
+                ParseErrorKind::UnterminatedTemplate,
+                "Unterminated template",
+                start_line,
+                start_column,
+            ));
+        }
+        match cursor.byte(0) {
+            b'`' => {
+                cursor.position += 1;
+                break true;
+            }
+            b'$' if cursor.byte(1) == b'{' => {
+                cursor.position += 2;
+                break false;
+            }
+            b'\\' => {
+                let escape_start = cursor.position;
+                cursor.position += 1;
+                let value = read_escape(cursor);
+                let text = cursor.slice(escape_start, cursor.position);
+                raw.push_str(&text.replace("\r\n", "\n").replace('\r', "\n"));
+                cooked = cooked.zip(value).map(|(cooked, value)| cooked + &value);
+            }
+            b'\r' | b'\n' => {
+                cursor.new_line();
+                raw.push('\n');
+                if let Some(cooked) = cooked.as_mut() {
+                    cooked.push('\n');
+                }
+            }
+            _ if cursor.at_line_break() => {
+                let text = cursor.slice(cursor.position, cursor.position + 3);
+                cursor.new_line();
+                raw.push_str(text);
+                if let Some(cooked) = cooked.as_mut() {
+                    cooked.push_str(text);
+                }
+            }
+            _ => {
+                // the text up to whatever ends it or needs a closer look
+                let rest = &cursor.src.as_bytes()[cursor.position..];
+                let length = rest
+                    .iter()
+                    .position(|byte| matches!(byte, b'`' | b'$' | b'\\' | b'\r' | b'\n' | 0xe2))
+                    .unwrap_or(rest.len())
+                    .max(cursor.char().map_or(1, char::len_utf8));
+                let text = cursor.slice(cursor.position, cursor.position + length);
+                cursor.skip_to(cursor.position + length);
+                raw.push_str(text);
+                if let Some(cooked) = cooked.as_mut() {
+                    cooked.push_str(text);
+                }
+            }
+        }
+    };
+
+    Ok(Token::Template(
+        Box::new(TemplateElementValue { cooked, raw }),
+        tail,
+    ))
+}
+
+fn is_digit_of(byte: u8, system: &NumberSystem) -> bool {
+    match system {
+        NumberSystem::Binary => matches!(byte, b'0' | b'1'),
+        NumberSystem::Octal => matches!(byte, b'0'..=b'7'),
+        NumberSystem::Decimal => byte.is_ascii_digit(),
+        NumberSystem::Hex => byte.is_ascii_hexdigit(),
+    }
+}
+
+fn separator_error(cursor: &Cursor, message: &str, column: usize) -> ParseError {
+    cursor.error(ParseErrorKind::InvalidNumericSeparator, message, column)
+}
+
+// read digits of the given system, allowing single underscores between them
+fn read_digits(cursor: &mut Cursor, system: &NumberSystem) -> ParseResult<()> {
+    // cannot use separator at the begining of numeric content
+    if cursor.byte(0) == b'_' {
+        return Err(separator_error(
+            cursor,
+            "Numeric separators are not allowed at the first of numeric literals",
+            cursor.column(),
+        ));
+    }
+
+    // separator context flag
+    let mut separate = false;
+
+    // read until non-numeric except numeric separator
+    while is_digit_of(cursor.byte(0), system) || cursor.byte(0) == b'_' {
+        // cannot use separator constantly
+        if cursor.byte(0) == b'_' {
+            if separate {
+                return Err(separator_error(
+                    cursor,
+                    "Only one underscore is allowed as numeric separator",
+                    cursor.column(),
+                ));
+            } else {
+                separate = true;
+            }
+        } else {
+            separate = false;
+        }
+
+        cursor.position += 1;
+    }
+
+    if separate {
+        return Err(separator_error(
+            cursor,
+            "Numeric separators are not allowed at the end of numeric literals",
+            cursor.column() - 1,
+        ));
+    }
+    Ok(())
+}
+
+fn read_numberic(cursor: &mut Cursor) -> ParseResult<Token> {
+    let start = cursor.position;
+    let column = cursor.column();
+    // whether it is `0` followed by digits, a legacy octal or the decimal
+    // that one with an 8 or 9 is
+    let legacy = cursor.byte(0) == b'0' && cursor.byte(1).is_ascii_digit();
+
+    // find number system
+    let system = match (cursor.byte(0), cursor.byte(1)) {
+        (b'0', b'b' | b'B') => {
+            cursor.position += 2;
+            NumberSystem::Binary
+        }
+        (b'0', b'o' | b'O') => {
+            cursor.position += 2;
+            NumberSystem::Octal
+        }
+        (b'0', b'x' | b'X') => {
+            cursor.position += 2;
+            NumberSystem::Hex
+        }
+        // legacy octal such as `017`, though `019` is decimal
+        _ if legacy => {
+            let mut digits = cursor.src.as_bytes()[start..]
+                .iter()
+                .take_while(|byte| byte.is_ascii_digit());
+            if digits.any(|byte| *byte >= b'8') {
+                NumberSystem::Decimal
+            } else {
+                cursor.position += 1;
+                NumberSystem::Octal
+            }
+        }
+        _ => NumberSystem::Decimal,
+    };
+
+    let content_start = cursor.position;
+    read_digits(cursor, &system)?;
+
+    // as `0_1` would be a legacy octal too
+    let integer = cursor.slice(start, cursor.position);
+    if legacy || integer.starts_with("0_") {
+        if let Some(index) = integer.find('_') {
+            return Err(separator_error(
+                cursor,
+                "Numeric separators are not allowed after a leading 0",
+                column + index,
+            ));
+        }
+    }
+
+    if let NumberSystem::Decimal = system {
+        if cursor.byte(0) == b'.' {
+            cursor.position += 1;
+            if cursor.byte(0).is_ascii_digit() {
+                read_digits(cursor, &system)?;
+            }
+        }
+        if matches!(cursor.byte(0), b'e' | b'E') {
+            cursor.position += 1;
+            if matches!(cursor.byte(0), b'+' | b'-') {
+                cursor.position += 1;
+            }
+            read_digits(cursor, &system)?;
+        }
+    }
+
+    let content = cursor
+        .slice(content_start, cursor.position)
+        .replace('_', "");
+    let radix = match system {
+        NumberSystem::Binary => 2,
+        NumberSystem::Octal => 8,
+        NumberSystem::Decimal => 10,
+        NumberSystem::Hex => 16,
+    };
+
+    let bigint = cursor.byte(0) == b'n';
+    if bigint {
+        // only an integer that doesn't start with 0 can be one
+        let fraction = matches!(system, NumberSystem::Decimal) && content.contains(['.', 'e', 'E']);
+        if legacy || fraction {
+            return Err(cursor.error(ParseErrorKind::InvalidNumber, "Invalid BigInt", column));
+        }
+        cursor.position += 1;
+    }
+    // nor can a name or another digit run on from it, as in `3in x`
+    let byte = cursor.byte(0);
+    if is(byte, IDENTIFIER)
+        || byte == b'\\'
+        || (!byte.is_ascii() && cursor.char().is_some_and(starts_name))
+    {
+        return Err(cursor.error(
+            ParseErrorKind::InvalidNumber,
+            "Identifier directly after number",
+            cursor.column(),
+        ));
+    }
+
+    if bigint {
+        // parse bigint, keeping its digits as text since they may not fit any
+        // integer type
+        let digits = cursor.slice(start, cursor.position - 1).replace('_', "");
+        Ok(Token::Bigint(digits.into()))
+    } else {
+        // parse number, folding the digits of other systems into a float as
+        // they may not fit any integer type either
+        let value = match system {
+            NumberSystem::Decimal => content.parse::<f64>().ok(),
+            _ if content.is_empty() => None,
+            _ => Some(content.chars().fold(0.0, |value, digit| {
+                value * radix as f64 + digit.to_digit(radix).unwrap_or(0) as f64
+            })),
+        };
+        match value {
+            Some(value) => Ok(Token::Number(value)),
+            None => Err(cursor.error(ParseErrorKind::InvalidNumber, "Invalid number", column)),
+        }
+    }
+}
+
+// a character class may contain an unescaped `/`, as in `/[/]/`
+fn read_reg_exp(cursor: &mut Cursor) -> ParseResult<Token> {
+    let start = cursor.position;
+    let column = cursor.column();
+
+    // read pattern
+    cursor.position += 1;
+
+    let mut esc = false;
+    let mut in_class = false;
+    loop {
+        let byte = cursor.byte(0);
+        if cursor.at_end() || cursor.at_line_break() {
+            return Err(cursor.error(
+                ParseErrorKind::UnterminatedRegExp,
+                "Unterminated regular expression",
+                column,
+            ));
+        }
+        if esc {
+            esc = false;
+        } else if byte == b'\\' {
+            esc = true;
+        } else if byte == b'[' {
+            in_class = true;
+        } else if byte == b']' {
+            in_class = false;
+        } else if byte == b'/' && !in_class {
+            break;
+        }
+        cursor.bump();
+    }
+    let pattern = cursor.slice(start + 1, cursor.position);
+    cursor.position += 1;
+
+    // read flags, each of which may appear once
+    let flags_start = cursor.position;
+    while is(cursor.byte(0), IDENTIFIER) {
+        cursor.position += 1;
+    }
+    let flags = cursor.slice(flags_start, cursor.position);
+    for (index, flag) in flags.char_indices() {
+        if !"dgimsuyv".contains(flag) || flags[..index].contains(flag) {
+            return Err(cursor.error(
+                ParseErrorKind::InvalidRegExpFlags,
+                "Invalid regular expression flags",
+                column,
+            ));
+        }
+    }
+
+    Ok(Token::RegExp(Box::new(RegExpValue::new(
+        pattern.to_string(),
+        flags.to_string(),
+    ))))
+}
+
+fn read_private_name(cursor: &mut Cursor, names: &mut Interner) -> ParseResult<Token> {
+    // read name
+    cursor.position += 1;
+    let name = read_name(cursor)?;
+    Ok(Token::PrivateName(names.intern(&name)))
+}
+
+// whether a name can start with the character, or go on with it
+fn starts_name(char: char) -> bool {
+    matches!(char, '$' | '_') || unicode_ident::is_xid_start(char)
+}
+
+fn continues_name(char: char) -> bool {
+    matches!(char, '$' | '\u{200c}' | '\u{200d}') || unicode_ident::is_xid_continue(char)
+}
+
+// a name, which besides ASCII may have letters of any script and `\u`
+// escapes of them; the escapes are decoded, so that the name is only
+// borrowed from the source if it has none
+fn read_name<'a>(cursor: &mut Cursor<'a>) -> ParseResult<Cow<'a, str>> {
+    let start = cursor.position;
+    let mut escaped = String::new();
+    let mut unescaped_start = start;
+    loop {
+        let byte = cursor.byte(0);
+        let first = cursor.position == start;
+        let fits = |char| match first {
+            true => starts_name(char),
+            false => continues_name(char),
+        };
+        if is(byte, IDENTIFIER) {
+            cursor.position += 1;
+        } else if !byte.is_ascii() && cursor.char().is_some_and(fits) {
+            cursor.bump();
+        } else if byte == b'\\' {
+            let column = cursor.column();
+            let char = match cursor.byte(1) {
+                b'u' => {
+                    escaped.push_str(cursor.slice(unescaped_start, cursor.position));
+                    cursor.position += 2;
+                    read_unicode_escape(cursor).and_then(char::from_u32)
+                }
+                _ => None,
+            };
+            match char {
+                Some(char) if fits(char) => escaped.push(char),
+                _ => {
+                    return Err(cursor.error(
+                        ParseErrorKind::InvalidEscape,
+                        "Invalid Unicode escape in a name",
+                        column,
+                    ))
+                }
+            }
+            unescaped_start = cursor.position;
+        } else {
+            break;
+        }
+    }
+    if unescaped_start == start {
+        return Ok(Cow::Borrowed(cursor.slice(start, cursor.position)));
+    }
+    escaped.push_str(cursor.slice(unescaped_start, cursor.position));
+    Ok(Cow::Owned(escaped))
+}
+
+fn read_keyword_or_name(cursor: &mut Cursor, names: &mut Interner) -> ParseResult<Token> {
+    let column = cursor.column();
+    let identifier = read_name(cursor)?;
+    let token = match identifier.as_ref() {
+        "var" => Token::Var,
+        "let" => Token::Let,
+        "const" => Token::Const,
+
+        "function" => Token::Function,
+        "return" => Token::Return,
+
+        "for" => Token::For,
+        "of" => Token::Of,
+        "do" => Token::Do,
+        "while" => Token::While,
+        "break" => Token::Break,
+        "continue" => Token::Continue,
+
+        "switch" => Token::Switch,
+        "case" => Token::Case,
+
+        "throw" => Token::Throw,
+        "try" => Token::Try,
+        "catch" => Token::Catch,
+        "finally" => Token::Finally,
+
+        "if" => Token::If,
+        "else" => Token::Else,
+        "with" => Token::With,
+
+        "new" => Token::New,
+        "this" => Token::This,
+        "super" => Token::Super,
+        "delete" => Token::Delete,
+        "class" => Token::Class,
+        "extends" => Token::Extends,
+        "instanceof" => Token::Instanceof,
+        "typeof" => Token::Typeof,
+
+        "import" => Token::Import,
+        "export" => Token::Export,
+        "default" => Token::Default,
+
+        "null" => Token::Null,
+        "undefined" => Token::Undefined,
+        "true" => Token::True,
+        "false" => Token::False,
+        "void" => Token::Void,
+
+        "in" => Token::In,
+
+        _ => return Ok(Token::Name(names.intern(&identifier))),
+    };
+    // a keyword can't be spelled with escapes, though a word that is only
+    // a keyword in places is a name when it is
+    match (identifier, token) {
+        (Cow::Borrowed(_), token) => Ok(token),
+        (identifier, Token::Of | Token::Let | Token::Undefined) => {
+            Ok(Token::Name(names.intern(&identifier)))
+        }
+        _ => Err(cursor.error(
+            ParseErrorKind::InvalidEscape,
+            "Keyword must not contain escaped characters",
+            column,
+        )),
+    }
+}
+
+// the longest operator wins, e.g. `>>>=` over `>>>`, `>>` and `>`
+fn read_operator(cursor: &mut Cursor) -> ParseResult<Token> {
+    for length in (1..=4).rev() {
+        let Some(chars) = cursor.src.get(cursor.position..cursor.position + length) else {
+            continue;
+        };
+        // `a?.5:1` is a conditional with the number `.5`, not optional chaining
+        if chars == "?." && cursor.byte(2).is_ascii_digit() {
+            continue;
+        }
+        if let Some(token) = get_operator_by_chars(chars) {
+            cursor.position += length;
+            return Ok(token);
+        }
+    }
+    let message = format!(
+        "Unexpected character '{}'",
+        cursor.char().map(String::from).unwrap_or_default()
+    );
+    Err(cursor.error(
+        ParseErrorKind::UnexpectedCharacter,
+        &message,
+        cursor.column(),
+    ))
+}
+
+// a `//` comment up to the end of its line, or a `/* */` one, which may span
+// lines; the value is the text between the delimiters
+fn read_comment(cursor: &mut Cursor) -> ParseResult<(CommentKind, String)> {
+    let start = cursor.position;
+    let (start_line, start_column) = (cursor.line, cursor.column());
+    cursor.position += 2;
+
+    if cursor.slice(start, start + 2) == "//" {
+        while !cursor.at_end() && !cursor.at_line_break() {
+            cursor.bump();
+        }
+        return Ok((
+            CommentKind::Line,
+            cursor.slice(start + 2, cursor.position).to_string(),
+        ));
+    }
+
+    loop {
+        if cursor.at_end() {
+            return Err(ParseError::at(
+                ParseErrorKind::UnterminatedComment,
+                "Unterminated comment",
+                start_line,
+                start_column,
+            ));
+        }
+        match cursor.byte(0) {
+            b'*' if cursor.byte(1) == b'/' => {
+                cursor.position += 2;
+                break;
+            }
+            _ if cursor.at_line_break() => cursor.new_line(),
+            _ => cursor.bump(),
+        }
+    }
+    Ok((
+        CommentKind::Block,
+        cursor.slice(start + 2, cursor.position - 2).to_string(),
+    ))
+}
+
+// whether a `/` after the token divides what it ends rather than starting a
+// regular expression: after a name, a literal, `this` and the like or the
+// end of a group, but not after a keyword such as `return`, an operator or
+// a `}` that may end a block
+fn ends_operand(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Number(..)
+            | Token::Bigint(..)
+            | Token::RegExp(..)
+            | Token::String(..)
+            | Token::Template(_, true)
+            | Token::Name(_)
+            | Token::PrivateName(..)
+            | Token::Of
+            | Token::Let
+            | Token::This
+            | Token::Super
+            | Token::Null
+            | Token::Undefined
+            | Token::True
+            | Token::False
+            | Token::ParenR
+            | Token::BracketR
+    )
+}
+
+// lexes a source one lexeme at a time, collecting the comments between them
+// as it goes, and stops after the first error unless tolerant, when what
+// an error was found in becomes an invalid token
+pub(crate) struct Lexer<'a> {
+    cursor: Cursor<'a>,
+    names: Interner,
+    newline_before: bool,
+    comments: Vec<Comment>,
+    // for each open brace, whether it is the `${` of a template substitution,
+    // whose closing `}` resumes the template instead
+    braces: Vec<bool>,
+    // whether the last token ends an operand, so that a `/` divides it
+    divides: bool,
+    tolerant: bool,
+    done: bool,
+}
+
+impl<'a> Lexer<'a> {
+    pub(crate) fn new(src: &'a str) -> Self {
+        Lexer {
+            cursor: Cursor {
+                src,
+                position: 0,
+                line: 1,
+                line_start: 0,
+            },
+            names: Interner::default(),
+            newline_before: false,
+            comments: vec![],
+            braces: vec![],
+            divides: false,
+            tolerant: false,
+            done: false,
+        }
+    }
+
+    pub(crate) fn tolerant(mut self, tolerant: bool) -> Self {
+        self.tolerant = tolerant;
+        self
+    }
+
+    // the line and column lexed up to, which once done is right after the
+    // last character
+    pub(crate) fn end(&self) -> (usize, usize) {
+        (self.cursor.line, self.cursor.column())
+    }
+
+    pub(crate) fn src(&self) -> &'a str {
+        self.cursor.src
+    }
+
+    // the comments lexed so far
+    pub(crate) fn take_comments(&mut self) -> Vec<Comment> {
+        std::mem::take(&mut self.comments)
+    }
+
+    fn next_lexeme(&mut self) -> ParseResult<Option<Lexeme>> {
+        let cursor = &mut self.cursor;
+        while !cursor.at_end() {
+            let byte = cursor.byte(0);
+            let column = cursor.column();
+            // templates and comments may span lines, so where they start is
+            // kept before reading them
+            let start = (cursor.line, column, cursor.position);
+
+            if cursor.at_line_break() {
+                cursor.new_line();
+                self.newline_before = true;
+                continue;
+            }
+            if is(byte, WHITESPACE) {
+                cursor.position += 1;
+                continue;
+            }
+            // which includes the byte order mark
+            let space = |char: char| char.is_whitespace() || char == '\u{feff}';
+            if !byte.is_ascii() && cursor.char().is_some_and(space) {
+                cursor.bump();
+                continue;
+            }
+
+            // neither a regular expression nor a division starts with `//` or `/*`
+            if byte == b'/' && matches!(cursor.byte(1), b'/' | b'*') {
+                let (start_line, _, start_position) = start;
+                let (kind, value) = match read_comment(cursor) {
+                    Ok(comment) => comment,
+                    Err(error) => return self.invalid(error, start).map(Some),
+                };
+                // a comment spanning lines separates tokens as a line break does
+                if cursor.line != start_line {
+                    self.newline_before = true;
+                }
+                self.comments.push(Comment::new(
+                    kind,
+                    value,
+                    SourceLocation {
+                        source: None,
+                        start: Position::new(start_line, column),
+                        end: Position::new(cursor.line, cursor.column()),
+                        range: (start_position, cursor.position),
+                    },
+                ));
+                continue;
+            }
+
+            return match self.read_token(byte) {
+                Ok(token) => Ok(Some(self.lexeme(token, start))),
+                Err(error) => self.invalid(error, start).map(Some),
+            };
+        }
+        Ok(None)
+    }
+
+    fn read_token(&mut self, byte: u8) -> ParseResult<Token> {
+        let cursor = &mut self.cursor;
+        if byte == b'`' || (byte == b'}' && self.braces.last() == Some(&true)) {
+            if byte == b'}' {
+                self.braces.pop();
+            }
+            let token = read_template(cursor)?;
+            if let Token::Template(_, false) = token {
+                self.braces.push(true);
+            }
+            Ok(token)
+        } else if byte == b'/' {
+            if self.divides {
+                read_operator(cursor)
+            } else {
+                read_reg_exp(cursor)
+            }
+        } else if is(byte, QUOTE) {
+            read_string(cursor, &mut self.names)
+        } else if byte == b'#' {
+            read_private_name(cursor, &mut self.names)
+        } else if is(byte, DIGIT) || (byte == b'.' && is(cursor.byte(1), DIGIT)) {
+            // `.5` being a number without integer part
+            read_numberic(cursor)
+        } else if is(byte, IDENTIFIER)
+            || byte == b'\\'
+            || (!byte.is_ascii() && cursor.char().is_some_and(starts_name))
+        {
+            read_keyword_or_name(cursor, &mut self.names)
+        } else {
+            let token = read_operator(cursor)?;
+            match token {
+                Token::BraceL => self.braces.push(false),
+                Token::BraceR => {
+                    self.braces.pop();
+                }
+                _ => {}
+            }
+            Ok(token)
+        }
+    }
+
+    // the token read from `start`, its line, column and offset
+    fn lexeme(&mut self, token: Token, (line, column, start): (usize, usize, usize)) -> Lexeme {
+        self.divides = ends_operand(&token);
+        let lexeme = Lexeme {
+            token,
+            line,
+            column,
+            end_line: self.cursor.line,
+            end_column: self.cursor.column(),
+            offset: start,
+            end_offset: self.cursor.position,
+            newline_before: self.newline_before,
+        };
+        self.newline_before = false;
+        lexeme
+    }
+
+    // once tolerant, the text the error was found in is an invalid token,
+    // of at least a character so that lexing moves on
+    fn invalid(&mut self, error: ParseError, start: (usize, usize, usize)) -> ParseResult<Lexeme> {
+        if !self.tolerant {
+            return Err(error);
+        }
+        if self.cursor.position == start.2 {
+            self.cursor.bump();
+        }
+        Ok(self.lexeme(Token::Invalid(Box::new(error)), start))
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = ParseResult<Lexeme>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_lexeme().transpose();
+        self.done = !matches!(next, Some(Ok(_)));
+        next
+    }
+}
+
+/// Splits the source into lexemes, each a token with where it starts and
+/// ends, leaving out whitespace and comments.
+pub fn tokenize(src: &str) -> ParseResult<Vec<Lexeme>> {
+    Lexer::new(src).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ParseErrorKind,
+        node::{Expression, Statement},
+        parser::parse,
+        test_support::error_at,
+        token::Token,
+        tokenize,
+    };
+
+    #[test]
+    fn a_slash_after_a_string_divides() {
+        let program = parse("x = 'a' / 2;\nx = \"b\" / 2;").unwrap();
+        for statement in &program.body {
+            let right = match statement {
+                Statement::Expression(statement) => match &statement.expression {
+                    Expression::Assignment(assignment) => &assignment.right,
+                    _ => panic!("expected an assignment"),
+                },
+                _ => panic!("expected an expression statement"),
+            };
+            assert!(matches!(right, Expression::Binary(_)));
+        }
+    }
+
+    #[test]
+    fn a_slash_is_told_apart_by_the_token_before_it() {
+        let regexps = |src: &str| {
+            tokenize(src)
+                .unwrap()
+                .into_iter()
+                .filter(|lexeme| matches!(lexeme.token, Token::RegExp(..)))
+                .count()
+        };
+        assert_eq!(regexps("a /* b */ / c / d"), 0);
+        assert_eq!(regexps("this / a; x[0] / 2; f() / 3"), 0);
+        assert_eq!(regexps("return /a/g; typeof /b/; x = /c/ / /d/"), 4);
+        assert_eq!(regexps("if (a) {} /e/.test(b)"), 1);
+    }
+
+    #[test]
+    fn strings_hold_their_cooked_value() {
+        let value = |src: &str| match &tokenize(src).unwrap()[0].token {
+            Token::String(value) => value.to_string(),
+            token => panic!("expected a string, found {:?}", token),
+        };
+        assert_eq!(value(r#""\u0041\n\x42""#), "A\nB");
+        assert_eq!(value(r"'\u{1F600}\uD83D\uDE00'"), "\u{1F600}\u{1F600}");
+        assert_eq!(value("'a\\\nb\\\r\nc'"), "abc");
+        assert_eq!(value(r"'\101\0\08\477\8'"), "A\0\08'78");
+        assert_eq!(value(r"'\'\q'"), "'q");
+        assert_eq!(
+            error_at(r"a = '\x4'"),
+            (ParseErrorKind::InvalidEscape, 1, 5)
+        );
+        assert_eq!(
+            error_at(r"a = '\u{110000}'"),
+            (ParseErrorKind::InvalidEscape, 1, 5)
+        );
+    }
+
+    #[test]
+    fn lexer_errors_have_a_kind_and_position() {
+        use ParseErrorKind::*;
+        assert_eq!(error_at("a = 'abc"), (UnterminatedString, 1, 8));
+        assert_eq!(error_at("a = `abc"), (UnterminatedTemplate, 1, 4));
+        assert_eq!(error_at("a;\n/* abc"), (UnterminatedComment, 2, 0));
+        assert_eq!(error_at("a = /abc"), (UnterminatedRegExp, 1, 4));
+        assert_eq!(error_at("a = /abc/gg"), (InvalidRegExpFlags, 1, 4));
+        assert_eq!(error_at("a = 1__0"), (InvalidNumericSeparator, 1, 6));
+        assert_eq!(error_at("a = 10_"), (InvalidNumericSeparator, 1, 6));
+        assert_eq!(error_at("a = 0x_1"), (InvalidNumericSeparator, 1, 6));
+        assert_eq!(error_at("a = 0x;"), (InvalidNumber, 1, 4));
+        assert_eq!(error_at("a = @"), (UnexpectedCharacter, 1, 4));
+    }
+
+    #[test]
+    fn numbers_starting_with_0_are_legacy_octal_or_decimal() {
+        let numbers = |src: &str| {
+            tokenize(src)
+                .unwrap()
+                .into_iter()
+                .map(|lexeme| match lexeme.token {
+                    Token::Number(value) => value,
+                    token => panic!("expected a number, found {:?}", token),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            numbers("017 08 09 019 08.5 09e1"),
+            [15.0, 8.0, 9.0, 19.0, 8.5, 90.0]
+        );
+        use ParseErrorKind::*;
+        assert_eq!(error_at("a = 07n"), (InvalidNumber, 1, 4));
+        assert_eq!(error_at("a = 08n"), (InvalidNumber, 1, 4));
+        assert_eq!(error_at("a = 1.5n"), (InvalidNumber, 1, 4));
+        assert_eq!(error_at("a = 0_1"), (InvalidNumericSeparator, 1, 5));
+        assert_eq!(error_at("a = 07_1"), (InvalidNumericSeparator, 1, 6));
+        assert_eq!(error_at("a = 08_1"), (InvalidNumericSeparator, 1, 6));
+        assert_eq!(error_at("3in x"), (InvalidNumber, 1, 1));
+        assert_eq!(error_at("a = 0b12"), (InvalidNumber, 1, 7));
+        assert_eq!(error_at("a = 1n_"), (InvalidNumber, 1, 6));
+    }
+
+    #[test]
+    fn names_take_letters_of_any_script_and_escapes() {
+        let names = |src: &str| {
+            tokenize(src)
+                .unwrap()
+                .into_iter()
+                .map(|lexeme| match lexeme.token {
+                    Token::Name(name) | Token::PrivateName(name) => name.to_string(),
+                    token => panic!("expected a name, found {:?}", token),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names("café π \\u0061bc x\\u{62}"),
+            ["café", "π", "abc", "xb"]
+        );
+        assert_eq!(names("\u{feff}a #é l\\u0065t"), ["a", "é", "let"]);
+        use ParseErrorKind::*;
+        assert_eq!(error_at("a = \\u0031"), (InvalidEscape, 1, 4));
+        assert_eq!(error_at("a = b\\u002e"), (InvalidEscape, 1, 5));
+        assert_eq!(error_at("v\\u0061r a"), (InvalidEscape, 1, 0));
+        assert_eq!(error_at("a = €"), (UnexpectedCharacter, 1, 4));
+    }
+
+    #[test]
+    fn u2028_and_u2029_break_lines() {
+        let lexemes = tokenize("a\u{2028}b\u{2029}// c\u{2028}d /* \u{2029} */ e").unwrap();
+        let lines = lexemes
+            .iter()
+            .map(|lexeme| (lexeme.line, lexeme.column, lexeme.newline_before))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            lines,
+            [(1, 0, false), (2, 0, true), (4, 0, true), (5, 4, true)]
+        );
+        // so that a statement ends at one as at a "\n"
+        assert!(parse("a = 1\u{2028}b = 2").is_ok());
+        assert_eq!(
+            error_at("a = /b\u{2029}/"),
+            (ParseErrorKind::UnterminatedRegExp, 1, 4)
+        );
+    }
+}
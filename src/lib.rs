@@ -0,0 +1,57 @@
+#![allow(
+    clippy::upper_case_acronyms,
+    clippy::large_enum_variant,
+    clippy::enum_variant_names
+)]
+
+pub mod analyze;
+pub mod codegen;
+pub mod comments;
+mod context;
+pub mod dependencies;
+#[cfg(feature = "serde")]
+pub mod deserialize;
+pub mod diff;
+pub mod error;
+#[cfg(feature = "ffi")]
+mod ffi;
+pub mod highlight;
+mod lexer;
+pub mod metrics;
+pub mod name;
+pub mod node;
+pub mod node_ref;
+pub mod options;
+pub mod parent_map;
+mod parser;
+pub mod query;
+pub mod rename;
+#[cfg(feature = "serde")]
+mod serialize;
+pub mod sexpr;
+pub mod source_map;
+pub mod token;
+pub mod transform;
+pub mod unreachable;
+pub mod unused;
+pub mod visit;
+pub mod visit_mut;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use error::{ParseError, ParseErrorKind, ParseResult};
+pub use lexer::tokenize;
+pub use node::{
+    Comment, CommentKind, Expression, Identifier, Literal, LiteralValue, Node, Pattern, Position,
+    Program, SourceLocation, SourceType, Statement,
+};
+pub use options::ParserOptions;
+pub use parser::{
+    parse, parse_allowing_return_outside_function, parse_expression, parse_module,
+    parse_preserving_parens, parse_script, parse_with_comments, parse_with_options,
+    parse_with_recovery, parse_with_source_type, ParseOutput,
+};
+pub use token::{Lexeme, Token, TokenKind};
+
+#[cfg(test)]
+mod test_support;
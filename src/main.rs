@@ -1,41 +1,308 @@
-use crate::parser::parse;
+// js_parser [--module] [--tokens] <file | ->
+// js_parser [--module] --check <file>...
+// js_parser [--module] --diff <old> <new>
+//
+// Parses a file, or stdin for `-`, and prints its ESTree JSON to stdout, or
+// with `--tokens` its tokens. Syntax errors are rendered to stderr and exit
+// with 1; bad arguments and unreadable input exit with 2.
+//
+// `--check` only reports the syntax errors of any number of files, one line
+// each, and exits with 1 if there were any.
+//
+// `--diff` prints where the syntax of two files differs, one line for each
+// node inserted, removed, changed or moved, and exits with 1 if it does.
+use std::{
+    fs,
+    io::{self, BufWriter, Read, Write},
+    process::ExitCode,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
 
-mod context;
-mod node;
-mod parser;
-mod string;
-mod token;
+use js_parser::{
+    diff::{compare, DiffKind, DiffNode},
+    parse_with_recovery, parse_with_source_type, tokenize, Lexeme, ParseError, Position,
+    SourceType,
+};
 
-// const SRC: &str = "
-// const a = getNumber() ?? 1;
-// const b = 2;
-// const str = \"Hello, World!\";
-// const reg = /?!(maybe)/i;
+const USAGE: &str = "usage: js_parser [--module] [--tokens] <file | ->
+       js_parser [--module] --check <file>...
+       js_parser [--module] --diff <old> <new>";
 
-// class Parent {
-//   #name = 'parent';
-// }
+struct Args {
+    paths: Vec<String>,
+    source_type: SourceType,
+    tokens: bool,
+    check: bool,
+    diff: bool,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut paths = vec![];
+    let mut source_type = SourceType::Script;
+    let mut tokens = false;
+    let mut check = false;
+    let mut diff = false;
+    for arg in args {
+        match arg.as_str() {
+            "--module" => source_type = SourceType::Module,
+            "--tokens" => tokens = true,
+            "--check" => check = true,
+            "--diff" => diff = true,
+            "-h" | "--help" => return Err(String::new()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option '{}'", flag)),
+            _ => paths.push(arg),
+        }
+    }
+    if [check, tokens, diff].iter().filter(|&&mode| mode).count() > 1 {
+        return Err("only one of --check, --tokens and --diff can be used".to_string());
+    }
+    match paths.len() {
+        0 => return Err("missing the file to parse".to_string()),
+        1 if diff => return Err("missing the new file to compare with".to_string()),
+        1 => {}
+        2 if diff => {}
+        _ if diff => return Err(format!("unexpected argument '{}'", paths[2])),
+        _ if !check => return Err(format!("unexpected argument '{}'", paths[1])),
+        _ => {}
+    }
+    Ok(Args {
+        paths,
+        source_type,
+        tokens,
+        check,
+        diff,
+    })
+}
 
-// class child extends Parent {
-//   #name = 'child';
-// }
+fn read_source(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut src = String::new();
+        io::stdin().read_to_string(&mut src)?;
+        Ok(src)
+    } else {
+        fs::read_to_string(path)
+    }
+}
 
-// function plus(a, b) {
-//   return a + b;
-// }
+// acorn's token objects, with the kind of token as a string rather than the
+// `type` object acorn keeps for its parser
+#[derive(serde::Serialize)]
+struct TokenJson {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+    start: usize,
+    end: usize,
+    loc: LocJson,
+}
 
-// const minus = (a, b) => a - b;
+#[derive(serde::Serialize)]
+struct LocJson {
+    start: Position,
+    end: Position,
+}
 
-// plus(a, b);
-// minus(a, b);
-// ";
+fn write_tokens(out: &mut impl Write, src: &str, lexemes: &[Lexeme]) -> io::Result<()> {
+    write!(out, "[")?;
+    for (index, lexeme) in lexemes.iter().enumerate() {
+        if index > 0 {
+            write!(out, ",")?;
+        }
+        let token = TokenJson {
+            kind: lexeme.token.kind().to_string(),
+            value: lexeme.text(src).to_string(),
+            start: lexeme.offset,
+            end: lexeme.end_offset,
+            loc: LocJson {
+                start: Position::new(lexeme.line, lexeme.column),
+                end: Position::new(lexeme.end_line, lexeme.end_column),
+            },
+        };
+        serde_json::to_writer(&mut *out, &token)?;
+    }
+    writeln!(out, "]")
+}
 
-const SRC: &str = "
-function plus(a, b) {
-  return a + b
+fn report(path: &str, src: &str, error: &ParseError) -> ExitCode {
+    eprintln!("{}: {}", path, error.render(src));
+    ExitCode::from(1)
 }
-";
 
-fn main() {
-    parse(SRC);
+// the lines to print for a file, `path:line:column: message` for each of its
+// errors with columns counted from 1 as compilers print them, and whether
+// it parsed
+fn check_file(path: &str, source_type: SourceType) -> (String, bool) {
+    let src = match read_source(path) {
+        Ok(src) => src,
+        Err(error) => return (format!("{}: can't read the file: {}\n", path, error), false),
+    };
+    let (_, errors) = parse_with_recovery(&src, source_type);
+    let mut lines = String::new();
+    for error in &errors {
+        lines += &format!(
+            "{}:{}:{}: {}: {}\n",
+            path,
+            error.start.line,
+            error.start.column + 1,
+            error.code(),
+            error.message
+        );
+    }
+    (lines, errors.is_empty())
+}
+
+// the files are shared out between threads as they become free, and their
+// results printed in the order the files were given
+fn check(paths: &[String], source_type: SourceType) -> ExitCode {
+    let next = AtomicUsize::new(0);
+    let threads = thread::available_parallelism().map_or(1, |count| count.get());
+    let mut results: Vec<(usize, String, bool)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads.min(paths.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(path) = paths.get(index) else {
+                            return results;
+                        };
+                        let (lines, ok) = check_file(path, source_type);
+                        results.push((index, lines, ok));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("checking a file doesn't panic"))
+            .collect()
+    });
+    results.sort_by_key(|(index, _, _)| *index);
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    let mut all_ok = true;
+    for (_, lines, ok) in &results {
+        all_ok &= ok;
+        if out.write_all(lines.as_bytes()).is_err() {
+            break;
+        }
+    }
+    let _ = out.flush();
+    if all_ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
+
+// `path:line:column: what` for each node, columns counted from 1 as for
+// `--check`, with where a changed or moved node is in the new file
+fn diff(old_path: &str, new_path: &str, source_type: SourceType) -> ExitCode {
+    let mut programs = vec![];
+    for path in [old_path, new_path] {
+        let src = match read_source(path) {
+            Ok(src) => src,
+            Err(error) => {
+                eprintln!("error: can't read {}: {}", path, error);
+                return ExitCode::from(2);
+            }
+        };
+        match parse_with_source_type(&src, source_type) {
+            Ok(program) => programs.push(program),
+            Err(error) => return report(path, &src, &error),
+        }
+    }
+    let at = |path: &str, node: &DiffNode| {
+        let start = node.loc.start;
+        format!("{}:{}:{}", path, start.line, start.column + 1)
+    };
+    let entries = compare(&programs[0], &programs[1]);
+    let mut out = BufWriter::new(io::stdout().lock());
+    for entry in &entries {
+        let line = match (entry.kind, &entry.old, &entry.new) {
+            (DiffKind::Inserted, _, Some(new)) => {
+                format!("{}: inserted {}", at(new_path, new), new.node_type)
+            }
+            (DiffKind::Removed, Some(old), _) => {
+                format!("{}: removed {}", at(old_path, old), old.node_type)
+            }
+            (DiffKind::Changed, Some(old), Some(new)) => format!(
+                "{}: changed {} to {} at {}",
+                at(old_path, old),
+                old.node_type,
+                new.node_type,
+                at(new_path, new)
+            ),
+            (DiffKind::Moved, Some(old), Some(new)) => format!(
+                "{}: moved {} to {}",
+                at(old_path, old),
+                old.node_type,
+                at(new_path, new)
+            ),
+            _ => unreachable!("entries have the nodes their kind needs"),
+        };
+        if writeln!(out, "{}", line).is_err() {
+            break;
+        }
+    }
+    let _ = out.flush();
+    if entries.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) if message.is_empty() => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+    if args.check {
+        return check(&args.paths, args.source_type);
+    }
+    if args.diff {
+        return diff(&args.paths[0], &args.paths[1], args.source_type);
+    }
+    let path = &args.paths[0];
+    let src = match read_source(path) {
+        Ok(src) => src,
+        Err(error) => {
+            eprintln!("error: can't read {}: {}", path, error);
+            return ExitCode::from(2);
+        }
+    };
+
+    // the JSON goes straight to stdout rather than through a String
+    let mut out = BufWriter::new(io::stdout().lock());
+    let written = if args.tokens {
+        match tokenize(&src) {
+            Ok(lexemes) => write_tokens(&mut out, &src, &lexemes),
+            Err(error) => return report(path, &src, &error),
+        }
+    } else {
+        match parse_with_source_type(&src, args.source_type) {
+            Ok(program) => serde_json::to_writer(&mut out, &program)
+                .map_err(io::Error::from)
+                .and_then(|_| writeln!(out)),
+            Err(error) => return report(path, &src, &error),
+        }
+    };
+    match written.and_then(|_| out.flush()) {
+        Ok(()) => ExitCode::SUCCESS,
+        // a closed pipe, as with `| head`, is not worth a complaint
+        Err(error) if error.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: can't write the output: {}", error);
+            ExitCode::from(2)
+        }
+    }
 }
@@ -0,0 +1,302 @@
+// Sizes and complexity of the functions in a program, for reports and for
+// lints with thresholds.
+//
+// Each function counts only its own code, not that of the functions inside
+// it, which have entries of their own. Its complexity is one plus the
+// places control can branch: `if`, loops, each `case` with a test, `&&`,
+// `||`, `??` and `?:`. Its nesting is how deep the `if`s, loops, `switch`es,
+// `try`s and `with`s inside it go, an `else if` staying at the depth of its
+// `if`. Blocks and empty statements aren't counted as statements. Code
+// outside any function counts towards the totals for the file, as if it were
+// a function of its own.
+use crate::{
+    node::{
+        ArrowFunctionExpression, ConditionalExpression, DoWhileStatement, Expression,
+        ForInStatement, ForOfStatement, ForStatement, FunctionDeclaration, FunctionExpression,
+        IfStatement, LiteralValue, LogicalExpression, MethodDefinition, Pattern, Program, Property,
+        PropertyDefinition, PropertyKey, SourceLocation, Statement, SwitchCase, SwitchStatement,
+        TryStatement, VariableDeclarator, WhileStatement, WithStatement,
+    },
+    visit::{
+        walk_arrow_function_expression, walk_conditional_expression, walk_do_while_statement,
+        walk_for_in_statement, walk_for_of_statement, walk_for_statement,
+        walk_function_declaration, walk_function_expression, walk_logical_expression,
+        walk_property, walk_property_definition, walk_statement, walk_switch_case,
+        walk_switch_statement, walk_try_statement, walk_variable_declarator, walk_while_statement,
+        walk_with_statement, Visitor,
+    },
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionMetrics {
+    // its own name, or the one of the variable, property or method it's
+    // the value of, or "<anonymous>"
+    pub name: String,
+    pub loc: SourceLocation,
+    pub params: usize,
+    pub statements: usize,
+    pub max_nesting: usize,
+    pub complexity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileMetrics {
+    // in the order they start in the source
+    pub functions: Vec<FunctionMetrics>,
+    pub statements: usize,
+    pub max_nesting: usize,
+    // of the code outside functions and of every function, added up
+    pub complexity: usize,
+}
+
+pub fn analyze(program: &Program) -> FileMetrics {
+    let mut counter = MetricsCounter {
+        functions: vec![],
+        frames: vec![Frame::default()],
+        name: None,
+    };
+    counter.visit_program(program);
+    let top = counter.frames.pop().unwrap();
+    let functions = counter.functions;
+    FileMetrics {
+        statements: top.statements + functions.iter().map(|f| f.statements).sum::<usize>(),
+        max_nesting: functions
+            .iter()
+            .map(|function| function.max_nesting)
+            .fold(top.max_nesting, usize::max),
+        complexity: top.complexity + functions.iter().map(|f| f.complexity).sum::<usize>(),
+        functions,
+    }
+}
+
+// the counts so far for the function being looked at
+struct Frame {
+    statements: usize,
+    complexity: usize,
+    nesting: usize,
+    max_nesting: usize,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Frame {
+            statements: 0,
+            complexity: 1,
+            nesting: 0,
+            max_nesting: 0,
+        }
+    }
+}
+
+struct MetricsCounter {
+    functions: Vec<FunctionMetrics>,
+    frames: Vec<Frame>,
+    // the name given by what the function about to be visited is the value of
+    name: Option<String>,
+}
+
+impl MetricsCounter {
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().unwrap()
+    }
+
+    fn branch(&mut self) {
+        self.frame().complexity += 1;
+    }
+
+    fn nested(&mut self, visit: impl FnOnce(&mut Self)) {
+        let frame = self.frame();
+        frame.nesting += 1;
+        frame.max_nesting = frame.max_nesting.max(frame.nesting);
+        visit(self);
+        self.frame().nesting -= 1;
+    }
+
+    fn function(
+        &mut self,
+        id: Option<&str>,
+        params: &[Pattern],
+        loc: &SourceLocation,
+        visit: impl FnOnce(&mut Self),
+    ) {
+        let hint = self.name.take();
+        let name = id
+            .map(str::to_string)
+            .or(hint)
+            .unwrap_or_else(|| "<anonymous>".to_string());
+        // the entry is made before those of the functions inside it
+        let index = self.functions.len();
+        self.functions.push(FunctionMetrics {
+            name,
+            loc: loc.clone(),
+            params: params.len(),
+            statements: 0,
+            max_nesting: 0,
+            complexity: 0,
+        });
+        self.frames.push(Frame::default());
+        visit(self);
+        let frame = self.frames.pop().unwrap();
+        let function = &mut self.functions[index];
+        function.statements = frame.statements;
+        function.max_nesting = frame.max_nesting;
+        function.complexity = frame.complexity;
+    }
+
+    // names the function `value` is, if it is one
+    fn name_value(&mut self, value: &Expression, name: Option<String>) {
+        if matches!(
+            value,
+            Expression::Function(_) | Expression::ArrowFunction(_)
+        ) {
+            self.name = name;
+        }
+    }
+}
+
+impl<'ast> Visitor<'ast> for MetricsCounter {
+    fn visit_statement(&mut self, node: &'ast Statement) {
+        if !matches!(node, Statement::Block(_) | Statement::Empty(_)) {
+            self.frame().statements += 1;
+        }
+        walk_statement(self, node);
+    }
+
+    fn visit_function_declaration(&mut self, node: &'ast FunctionDeclaration) {
+        let id = node.id.as_ref().map(|id| id.name.as_str());
+        self.function(id, &node.params, &node.loc, |counter| {
+            walk_function_declaration(counter, node)
+        });
+    }
+
+    fn visit_function_expression(&mut self, node: &'ast FunctionExpression) {
+        let id = node.id.as_ref().map(|id| id.name.as_str());
+        self.function(id, &node.params, &node.loc, |counter| {
+            walk_function_expression(counter, node)
+        });
+    }
+
+    fn visit_arrow_function_expression(&mut self, node: &'ast ArrowFunctionExpression) {
+        self.function(None, &node.params, &node.loc, |counter| {
+            walk_arrow_function_expression(counter, node)
+        });
+    }
+
+    fn visit_variable_declarator(&mut self, node: &'ast VariableDeclarator) {
+        if let (Pattern::Identifier(id), Some(init)) = (&node.id, &node.init) {
+            self.name_value(init, Some(id.name.to_string()));
+        }
+        walk_variable_declarator(self, node);
+    }
+
+    fn visit_property(&mut self, node: &'ast Property) {
+        let name = match &node.key {
+            PropertyKey::Identifier(key) => Some(key.name.to_string()),
+            PropertyKey::Literal(key) => Some(literal_name(&key.value, &key.raw)),
+            PropertyKey::Expression(_) => None,
+        };
+        self.name_value(&node.value, name);
+        walk_property(self, node);
+    }
+
+    fn visit_method_definition(&mut self, node: &'ast MethodDefinition) {
+        self.visit_expression(&node.key);
+        self.name = key_name(&node.key, node.computed);
+        self.visit_function_expression(&node.value);
+    }
+
+    fn visit_property_definition(&mut self, node: &'ast PropertyDefinition) {
+        if let Some(value) = &node.value {
+            self.name_value(value, key_name(&node.key, node.computed));
+        }
+        walk_property_definition(self, node);
+    }
+
+    // an `else if` is nested no deeper than its `if`
+    fn visit_if_statement(&mut self, node: &'ast IfStatement) {
+        self.branch();
+        self.nested(|counter| {
+            counter.visit_expression(&node.test);
+            counter.visit_statement(&node.consequent);
+            match &node.alternate {
+                Some(Statement::If(_)) | None => {}
+                Some(alternate) => counter.visit_statement(alternate),
+            }
+        });
+        if let Some(alternate @ Statement::If(_)) = &node.alternate {
+            self.visit_statement(alternate);
+        }
+    }
+
+    fn visit_switch_statement(&mut self, node: &'ast SwitchStatement) {
+        self.nested(|counter| walk_switch_statement(counter, node));
+    }
+
+    fn visit_switch_case(&mut self, node: &'ast SwitchCase) {
+        if node.test.is_some() {
+            self.branch();
+        }
+        walk_switch_case(self, node);
+    }
+
+    fn visit_try_statement(&mut self, node: &'ast TryStatement) {
+        self.nested(|counter| walk_try_statement(counter, node));
+    }
+
+    fn visit_with_statement(&mut self, node: &'ast WithStatement) {
+        self.nested(|counter| walk_with_statement(counter, node));
+    }
+
+    fn visit_while_statement(&mut self, node: &'ast WhileStatement) {
+        self.branch();
+        self.nested(|counter| walk_while_statement(counter, node));
+    }
+
+    fn visit_do_while_statement(&mut self, node: &'ast DoWhileStatement) {
+        self.branch();
+        self.nested(|counter| walk_do_while_statement(counter, node));
+    }
+
+    fn visit_for_statement(&mut self, node: &'ast ForStatement) {
+        self.branch();
+        self.nested(|counter| walk_for_statement(counter, node));
+    }
+
+    fn visit_for_in_statement(&mut self, node: &'ast ForInStatement) {
+        self.branch();
+        self.nested(|counter| walk_for_in_statement(counter, node));
+    }
+
+    fn visit_for_of_statement(&mut self, node: &'ast ForOfStatement) {
+        self.branch();
+        self.nested(|counter| walk_for_of_statement(counter, node));
+    }
+
+    fn visit_logical_expression(&mut self, node: &'ast LogicalExpression) {
+        self.branch();
+        walk_logical_expression(self, node);
+    }
+
+    fn visit_conditional_expression(&mut self, node: &'ast ConditionalExpression) {
+        self.branch();
+        walk_conditional_expression(self, node);
+    }
+}
+
+// the name a key gives the function that is its value, unless computed
+fn key_name(key: &Expression, computed: bool) -> Option<String> {
+    match key {
+        _ if computed => None,
+        Expression::Identifier(key) => Some(key.name.to_string()),
+        Expression::PrivateIdentifier(key) => Some(format!("#{}", key.name)),
+        Expression::Literal(key) => Some(literal_name(&key.value, &key.raw)),
+        _ => None,
+    }
+}
+
+fn literal_name(value: &LiteralValue, raw: &str) -> String {
+    match value {
+        LiteralValue::String(value) => value.clone(),
+        _ => raw.to_string(),
+    }
+}
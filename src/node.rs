@@ -1,28 +1,79 @@
-use regex::Regex;
+use crate::name::Name;
+
+// `Debug`, `Clone` and `PartialEq` as they would be derived, but growing the
+// stack as they go; every way nodes nest passes through `Statement`,
+// `Expression` or `Pattern`, so a tree of any depth can be compared, cloned
+// or printed as long as these enums are defined with it
+macro_rules! grows_stack {
+    ($(#[$attribute:meta])* pub enum $name:ident { $($variant:ident($node:ty),)* }) => {
+        $(#[$attribute])*
+        pub enum $name {
+            $($variant($node),)*
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match self {
+                    $($name::$variant(node) => {
+                        f.debug_tuple(stringify!($variant)).field(node).finish()
+                    })*
+                })
+            }
+        }
+
+        impl Clone for $name {
+            fn clone(&self) -> Self {
+                stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match self {
+                    $($name::$variant(node) => $name::$variant(node.clone()),)*
+                })
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match (self, other) {
+                    $(($name::$variant(node), $name::$variant(other)) => node == other,)*
+                    _ => false,
+                })
+            }
+        }
+    };
+}
+
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
 
 // interface Node {
 //   type: string;
 //   loc: SourceLocation | null;
 // }
-pub trait Node {}
+pub trait Node {
+    fn loc(&self) -> &SourceLocation;
+}
 
 // interface SourceLocation {
 //   source: string | null;
 //   start: Position;
 //   end: Position;
 // }
+//
+// `range` isn't part of ESTree but is what acorn adds with `ranges: true`:
+// the byte offsets of the start and end of the node in the source text
+#[derive(Debug, Clone, PartialEq)]
 pub struct SourceLocation {
-    source: Option<String>,
-    start: Position,
-    end: Position,
+    pub source: Option<String>,
+    pub start: Position,
+    pub end: Position,
+    pub range: (usize, usize),
 }
 
 impl SourceLocation {
-    pub fn new(line: usize, column: usize) -> Self {
+    pub fn new(line: usize, column: usize, offset: usize) -> Self {
         SourceLocation {
             source: None,
             start: Position::new(line, column),
             end: Position::new(0, 0),
+            range: (offset, offset),
         }
     }
 }
@@ -31,9 +82,11 @@ impl SourceLocation {
 //   line: number; // >= 1
 //   column: number; // >= 0
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Position {
-    line: usize,
-    column: usize,
+    pub line: usize,
+    pub column: usize,
 }
 
 impl Position {
@@ -46,223 +99,609 @@ impl Position {
 //   type: "Identifier";
 //   name: string;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Identifier {
+    #[cfg_attr(feature = "serde", serde(flatten))]
     pub loc: SourceLocation,
-    pub name: String,
+    pub name: Name,
 }
 
 impl Identifier {
-    pub fn new(name: String, start: Position, end: Position) -> Self {
+    pub fn new(name: impl Into<Name>, loc: SourceLocation) -> Self {
         Identifier {
-            loc: SourceLocation {
-                source: Some(name.clone()),
-                start,
-                end,
-            },
-            name,
+            loc,
+            name: name.into(),
         }
     }
 }
 
-impl Node for Identifier {}
-
-impl Expression for Identifier {}
-
-impl Pattern for Identifier {}
+impl Node for Identifier {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface Literal <: Expression {
 //   type: "Literal";
-//   value: string | boolean | null | number | RegExp;
+//   value: string | boolean | null | number | RegExp | bigint;
+//   raw: string;
 // }
+//
+// interface RegExpLiteral <: Literal {
+//   regex: {
+//     pattern: string;
+//     flags: string;
+//   };
+// }
+//
+// interface BigIntLiteral <: Literal {
+//   bigint: string;
+// }
+#[derive(Debug, Clone, PartialEq)]
 pub enum LiteralValue {
     String(String),
     Boolean(bool),
     Null(Null),
     Number(f64),
-    Bigint(i128),
-    RegExp(Regex),
+    // the `bigint` text, as the digits may not fit any integer type
+    Bigint(String),
+    RegExp(RegExpValue),
+}
+
+// kept as text rather than compiled, since JavaScript patterns aren't all
+// valid for any Rust regex engine
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegExpValue {
+    pub pattern: String,
+    pub flags: String,
 }
 
+impl RegExpValue {
+    pub fn new(pattern: String, flags: String) -> Self {
+        RegExpValue { pattern, flags }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Literal {
-    loc: SourceLocation,
-    value: LiteralValue,
+    pub loc: SourceLocation,
+    pub value: LiteralValue,
+    pub raw: String,
 }
 
-impl Node for Literal {}
+impl Literal {
+    pub fn new(value: LiteralValue, raw: String, loc: SourceLocation) -> Self {
+        Literal { loc, value, raw }
+    }
+}
 
-impl Expression for Literal {}
+impl Node for Literal {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface Program <: Node {
 //   type: "Program";
-//   body: [ Directive | Statement ];
+//   sourceType: "script" | "module";
+//   body: [ Directive | Statement | ImportOrExportDeclaration ];
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Program {
+    #[cfg_attr(feature = "serde", serde(flatten))]
     pub loc: SourceLocation,
-    pub body: Vec<Box<dyn Statement>>,
+    #[cfg_attr(feature = "serde", serde(rename = "sourceType"))]
+    pub source_type: SourceType,
+    pub body: Vec<Statement>,
 }
 
-impl Node for Program {}
+impl Node for Program {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 impl Program {
-    pub fn new(line: usize, column: usize) -> Self {
+    // a program starts at the very beginning of the source, whitespace and
+    // comments included
+    pub fn new(source_type: SourceType) -> Self {
         Program {
-            loc: SourceLocation::new(line, column),
+            loc: SourceLocation::new(1, 0, 0),
+            source_type,
             body: vec![],
         }
     }
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SourceType {
+    Script,
+    Module,
+}
+
+// comments aren't part of the tree, but come out of parsing alongside it in
+// the shape acorn's `onComment` gives them:
+//
+// interface Comment {
+//   type: "Line" | "Block";
+//   value: string;
+// }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CommentKind {
+    // `// ...`
+    Line,
+    // `/* ... */`
+    Block,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    #[cfg_attr(feature = "serde", serde(rename = "type"))]
+    pub kind: CommentKind,
+    // the text between the delimiters
+    pub value: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+}
+
+impl Comment {
+    pub fn new(kind: CommentKind, value: String, loc: SourceLocation) -> Self {
+        Comment { kind, value, loc }
+    }
+}
+
+impl Node for Comment {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// not ESTree: what a recovering parse leaves where it skipped text it
+// couldn't parse, with the index of the error it reported for it
+//
+// interface ErrorNode <: Statement, Expression, Pattern {
+//   type: "ErrorNode";
+//   diagnostic: number;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorNode {
+    pub diagnostic: usize,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+}
+
+impl ErrorNode {
+    pub fn new(diagnostic: usize, loc: SourceLocation) -> Self {
+        ErrorNode { diagnostic, loc }
+    }
+}
+
+impl Node for ErrorNode {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
 // interface Function <: Node {
 //   id: Identifier | null;
 //   params: [ Pattern ];
 //   body: FunctionBody;
+//   generator: boolean;
+//   async: boolean;
 // }
 pub trait Function {}
 
 // interface Statement <: Node { }
-pub trait Statement {}
+grows_stack! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+    pub enum Statement {
+        Expression(Box<ExpressionStatement>),
+        Directive(Box<Directive>),
+        Block(Box<BlockStatement>),
+        Empty(Box<EmptyStatement>),
+        Debugger(Box<DebuggerStatement>),
+        With(Box<WithStatement>),
+        Return(Box<ReturnStatement>),
+        Labeled(Box<LabeledStatement>),
+        Break(Box<BreakStatement>),
+        Continue(Box<ContinueStatement>),
+        If(Box<IfStatement>),
+        Switch(Box<SwitchStatement>),
+        Throw(Box<ThrowStatement>),
+        Try(Box<TryStatement>),
+        While(Box<WhileStatement>),
+        DoWhile(Box<DoWhileStatement>),
+        For(Box<ForStatement>),
+        ForIn(Box<ForInStatement>),
+        ForOf(Box<ForOfStatement>),
+        FunctionDeclaration(Box<FunctionDeclaration>),
+        VariableDeclaration(Box<VariableDeclaration>),
+        ClassDeclaration(Box<ClassDeclaration>),
+        ImportDeclaration(Box<ImportDeclaration>),
+        ExportNamedDeclaration(Box<ExportNamedDeclaration>),
+        ExportDefaultDeclaration(Box<ExportDefaultDeclaration>),
+        ExportAllDeclaration(Box<ExportAllDeclaration>),
+        Error(Box<ErrorNode>),
+    }
+}
+
+impl Node for Statement {
+    fn loc(&self) -> &SourceLocation {
+        match self {
+            Statement::Expression(node) => node.loc(),
+            Statement::Directive(node) => node.loc(),
+            Statement::Block(node) => node.loc(),
+            Statement::Empty(node) => node.loc(),
+            Statement::Debugger(node) => node.loc(),
+            Statement::With(node) => node.loc(),
+            Statement::Return(node) => node.loc(),
+            Statement::Labeled(node) => node.loc(),
+            Statement::Break(node) => node.loc(),
+            Statement::Continue(node) => node.loc(),
+            Statement::If(node) => node.loc(),
+            Statement::Switch(node) => node.loc(),
+            Statement::Throw(node) => node.loc(),
+            Statement::Try(node) => node.loc(),
+            Statement::While(node) => node.loc(),
+            Statement::DoWhile(node) => node.loc(),
+            Statement::For(node) => node.loc(),
+            Statement::ForIn(node) => node.loc(),
+            Statement::ForOf(node) => node.loc(),
+            Statement::FunctionDeclaration(node) => node.loc(),
+            Statement::VariableDeclaration(node) => node.loc(),
+            Statement::ClassDeclaration(node) => node.loc(),
+            Statement::ImportDeclaration(node) => node.loc(),
+            Statement::ExportNamedDeclaration(node) => node.loc(),
+            Statement::ExportDefaultDeclaration(node) => node.loc(),
+            Statement::ExportAllDeclaration(node) => node.loc(),
+            Statement::Error(node) => node.loc(),
+        }
+    }
+}
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Null;
 
 // interface ExpressionStatement <: Statement {
 //   type: "ExpressionStatement";
 //   expression: Expression;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ExpressionStatement {
-    loc: SourceLocation,
-    expression: Box<dyn Expression>,
-    directive: Option<String>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub expression: Expression,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub directive: Option<String>,
 }
 
-impl Node for ExpressionStatement {}
+impl ExpressionStatement {
+    pub fn new(expression: Expression, loc: SourceLocation) -> Self {
+        ExpressionStatement {
+            loc,
+            expression,
+            directive: None,
+        }
+    }
+}
 
-impl Statement for ExpressionStatement {}
+impl Node for ExpressionStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface Directive <: ExpressionStatement {
 //   expression: Literal;
 //   directive: string;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename = "ExpressionStatement")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Directive {
-    loc: SourceLocation,
-    expression: Literal,
-    directive: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub expression: Literal,
+    pub directive: String,
 }
 
-impl Node for Directive {}
+impl Directive {
+    pub fn new(expression: Literal, directive: String, loc: SourceLocation) -> Self {
+        Directive {
+            loc,
+            expression,
+            directive,
+        }
+    }
+}
 
-impl Statement for Directive {}
+impl Node for Directive {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface BlockStatement <: Statement {
 //   type: "BlockStatement";
 //   body: [ Statement ];
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct BlockStatement {
-    loc: SourceLocation,
-    body: Vec<Box<dyn Statement>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub body: Vec<Statement>,
 }
 
-impl Node for BlockStatement {}
+impl BlockStatement {
+    pub fn new(body: Vec<Statement>, loc: SourceLocation) -> Self {
+        BlockStatement { loc, body }
+    }
+}
 
-impl Statement for BlockStatement {}
+impl Node for BlockStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface FunctionBody <: BlockStatement {
 //   body: [ Directive | Statement ];
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type", rename = "BlockStatement")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunctionBody {
-    body: Vec<Box<dyn Statement>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub body: Vec<Statement>,
 }
 
 impl FunctionBody {
-    pub fn new() -> Self {
-        FunctionBody { body: vec![] }
+    pub fn new(body: Vec<Statement>, loc: SourceLocation) -> Self {
+        FunctionBody { loc, body }
     }
 }
 
-impl Node for FunctionBody {}
-
-impl Statement for FunctionBody {}
+impl Node for FunctionBody {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface EmptyStatement <: Statement {
 //   type: "EmptyStatement";
 // }
-pub struct EmptyStatement;
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmptyStatement {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+}
 
-impl Node for EmptyStatement {}
+impl EmptyStatement {
+    pub fn new(loc: SourceLocation) -> Self {
+        EmptyStatement { loc }
+    }
+}
 
-impl Statement for EmptyStatement {}
+impl Node for EmptyStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface DebuggerStatement <: Statement {
 //   type: "DebuggerStatement";
 // }
-pub struct DebuggerStatement;
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebuggerStatement {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+}
 
-impl Node for DebuggerStatement {}
+impl DebuggerStatement {
+    pub fn new(loc: SourceLocation) -> Self {
+        DebuggerStatement { loc }
+    }
+}
 
-impl Statement for DebuggerStatement {}
+impl Node for DebuggerStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface WithStatement <: Statement {
 //   type: "WithStatement";
 //   object: Expression;
 //   body: Statement;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WithStatement {
-    object: Box<dyn Expression>,
-    body: Box<dyn Statement>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub object: Expression,
+    pub body: Statement,
 }
 
-impl Node for WithStatement {}
+impl WithStatement {
+    pub fn new(object: Expression, body: Statement, loc: SourceLocation) -> Self {
+        WithStatement { loc, object, body }
+    }
+}
 
-impl Statement for WithStatement {}
+impl Node for WithStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface ReturnStatement <: Statement {
 //   type: "ReturnStatement";
 //   argument: Expression | null;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ReturnStatement {
-    argument: Option<Box<dyn Expression>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub argument: Option<Expression>,
 }
 
-impl Node for ReturnStatement {}
+impl ReturnStatement {
+    pub fn new(argument: Option<Expression>, loc: SourceLocation) -> Self {
+        ReturnStatement { loc, argument }
+    }
+}
 
-impl Statement for ReturnStatement {}
+impl Node for ReturnStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface LabeledStatement <: Statement {
 //   type: "LabeledStatement";
 //   label: Identifier;
 //   body: Statement;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LabeledStatement {
-    label: Identifier,
-    body: Box<dyn Statement>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub label: Identifier,
+    pub body: Statement,
 }
 
-impl Node for LabeledStatement {}
+impl LabeledStatement {
+    pub fn new(label: Identifier, body: Statement, loc: SourceLocation) -> Self {
+        LabeledStatement { loc, label, body }
+    }
+}
 
-impl Statement for LabeledStatement {}
+impl Node for LabeledStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface BreakStatement <: Statement {
 //   type: "BreakStatement";
 //   label: Identifier | null;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct BreakStatement {
-    label: Option<Identifier>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub label: Option<Identifier>,
 }
 
-impl Node for BreakStatement {}
+impl BreakStatement {
+    pub fn new(label: Option<Identifier>, loc: SourceLocation) -> Self {
+        BreakStatement { loc, label }
+    }
+}
 
-impl Statement for BreakStatement {}
+impl Node for BreakStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface ContinueStatement <: Statement {
 //   type: "ContinueStatement";
 //   label: Identifier | null;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ContinueStatement {
-    label: Option<Identifier>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub label: Option<Identifier>,
 }
 
-impl Node for ContinueStatement {}
+impl ContinueStatement {
+    pub fn new(label: Option<Identifier>, loc: SourceLocation) -> Self {
+        ContinueStatement { loc, label }
+    }
+}
 
-impl Statement for ContinueStatement {}
+impl Node for ContinueStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface IfStatement <: Statement {
 //   type: "IfStatement";
@@ -270,53 +709,137 @@ impl Statement for ContinueStatement {}
 //   consequent: Statement;
 //   alternate: Statement | null;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct IfStatement {
-    test: Box<dyn Expression>,
-    consequent: Box<dyn Statement>,
-    alternate: Option<Box<dyn Statement>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub test: Expression,
+    pub consequent: Statement,
+    pub alternate: Option<Statement>,
 }
 
-impl Node for IfStatement {}
+impl IfStatement {
+    pub fn new(
+        test: Expression,
+        consequent: Statement,
+        alternate: Option<Statement>,
+        loc: SourceLocation,
+    ) -> Self {
+        IfStatement {
+            loc,
+            test,
+            consequent,
+            alternate,
+        }
+    }
+}
 
-impl Statement for IfStatement {}
+impl Node for IfStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface SwitchStatement <: Statement {
 //   type: "SwitchStatement";
 //   discriminant: Expression;
 //   cases: [ SwitchCase ];
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SwitchStatement {
-    discriminant: Box<dyn Expression>,
-    cases: Vec<SwitchCase>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub discriminant: Expression,
+    pub cases: Vec<SwitchCase>,
 }
 
-impl Node for SwitchStatement {}
+impl SwitchStatement {
+    pub fn new(discriminant: Expression, cases: Vec<SwitchCase>, loc: SourceLocation) -> Self {
+        SwitchStatement {
+            loc,
+            discriminant,
+            cases,
+        }
+    }
+}
 
-impl Statement for SwitchStatement {}
+impl Node for SwitchStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface SwitchCase <: Node {
 //   type: "SwitchCase";
 //   test: Expression | null;
 //   consequent: [ Statement ];
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SwitchCase {
-    test: Option<Box<dyn Expression>>,
-    consequent: Vec<Box<dyn Statement>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub test: Option<Expression>,
+    pub consequent: Vec<Statement>,
+}
+
+impl SwitchCase {
+    pub fn new(test: Option<Expression>, consequent: Vec<Statement>, loc: SourceLocation) -> Self {
+        SwitchCase {
+            loc,
+            test,
+            consequent,
+        }
+    }
 }
 
-impl Node for SwitchCase {}
+impl Node for SwitchCase {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface ThrowStatement <: Statement {
 //   type: "ThrowStatement";
 //   argument: Expression;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ThrowStatement {
-    argument: Box<dyn Expression>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub argument: Expression,
 }
 
-impl Node for ThrowStatement {}
+impl ThrowStatement {
+    pub fn new(argument: Expression, loc: SourceLocation) -> Self {
+        ThrowStatement { loc, argument }
+    }
+}
 
-impl Statement for ThrowStatement {}
+impl Node for ThrowStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface TryStatement <: Statement {
 //   type: "TryStatement";
@@ -324,55 +847,131 @@ impl Statement for ThrowStatement {}
 //   handler: CatchClause | null;
 //   finalizer: BlockStatement | null;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TryStatement {
-    block: BlockStatement,
-    handler: Option<CatchClause>,
-    finalizer: Option<BlockStatement>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub block: BlockStatement,
+    pub handler: Option<CatchClause>,
+    pub finalizer: Option<BlockStatement>,
 }
 
-impl Node for TryStatement {}
+impl TryStatement {
+    pub fn new(
+        block: BlockStatement,
+        handler: Option<CatchClause>,
+        finalizer: Option<BlockStatement>,
+        loc: SourceLocation,
+    ) -> Self {
+        TryStatement {
+            loc,
+            block,
+            handler,
+            finalizer,
+        }
+    }
+}
 
-impl Statement for TryStatement {}
+impl Node for TryStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface CatchClause <: Node {
 //   type: "CatchClause";
-//   param: Pattern;
+//   param: Pattern | null;
 //   body: BlockStatement;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CatchClause {
-    param: Box<dyn Pattern>,
-    body: BlockStatement,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub param: Option<Pattern>,
+    pub body: BlockStatement,
+}
+
+impl CatchClause {
+    pub fn new(param: Option<Pattern>, body: BlockStatement, loc: SourceLocation) -> Self {
+        CatchClause { loc, param, body }
+    }
 }
 
-impl Node for CatchClause {}
+impl Node for CatchClause {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface WhileStatement <: Statement {
 //   type: "WhileStatement";
 //   test: Expression;
 //   body: Statement;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WhileStatement {
-    test: Box<dyn Expression>,
-    body: Box<dyn Statement>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub test: Expression,
+    pub body: Statement,
 }
 
-impl Node for WhileStatement {}
+impl WhileStatement {
+    pub fn new(test: Expression, body: Statement, loc: SourceLocation) -> Self {
+        WhileStatement { loc, test, body }
+    }
+}
 
-impl Statement for WhileStatement {}
+impl Node for WhileStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface DoWhileStatement <: Statement {
 //   type: "DoWhileStatement";
 //   body: Statement;
 //   test: Expression;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DoWhileStatement {
-    test: Box<dyn Expression>,
-    body: Box<dyn Statement>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub test: Expression,
+    pub body: Statement,
 }
 
-impl Node for DoWhileStatement {}
+impl DoWhileStatement {
+    pub fn new(test: Expression, body: Statement, loc: SourceLocation) -> Self {
+        DoWhileStatement { loc, test, body }
+    }
+}
 
-impl Statement for DoWhileStatement {}
+impl Node for DoWhileStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface ForStatement <: Statement {
 //   type: "ForStatement";
@@ -381,21 +980,51 @@ impl Statement for DoWhileStatement {}
 //   update: Expression | null;
 //   body: Statement;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ForStatementInit {
     VariableDeclaration(VariableDeclaration),
-    Expression(Box<dyn Expression>),
+    Expression(Expression),
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ForStatement {
-    init: Option<ForStatementInit>,
-    test: Option<Box<dyn Expression>>,
-    update: Option<Box<dyn Expression>>,
-    body: Box<dyn Statement>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub init: Option<ForStatementInit>,
+    pub test: Option<Expression>,
+    pub update: Option<Expression>,
+    pub body: Statement,
 }
 
-impl Node for ForStatement {}
+impl ForStatement {
+    pub fn new(
+        init: Option<ForStatementInit>,
+        test: Option<Expression>,
+        update: Option<Expression>,
+        body: Statement,
+        loc: SourceLocation,
+    ) -> Self {
+        ForStatement {
+            loc,
+            init,
+            test,
+            update,
+            body,
+        }
+    }
+}
 
-impl Statement for ForStatement {}
+impl Node for ForStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface ForInStatement <: Statement {
 //   type: "ForInStatement";
@@ -403,65 +1032,183 @@ impl Statement for ForStatement {}
 //   right: Expression;
 //   body: Statement;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ForInStatementLeft {
     VariableDeclaration(VariableDeclaration),
-    Expression(Box<dyn Expression>),
+    Pattern(Pattern),
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ForInStatement {
-    left: ForInStatementLeft,
-    right: Box<dyn Expression>,
-    body: Box<dyn Statement>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub left: ForInStatementLeft,
+    pub right: Expression,
+    pub body: Statement,
+}
+
+impl ForInStatement {
+    pub fn new(
+        left: ForInStatementLeft,
+        right: Expression,
+        body: Statement,
+        loc: SourceLocation,
+    ) -> Self {
+        ForInStatement {
+            loc,
+            left,
+            right,
+            body,
+        }
+    }
+}
+
+impl Node for ForInStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface ForOfStatement <: ForInStatement {
+//   type: "ForOfStatement";
+//   await: boolean;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForOfStatement {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub left: ForInStatementLeft,
+    pub right: Expression,
+    pub body: Statement,
+    #[cfg_attr(feature = "serde", serde(rename = "await"))]
+    pub is_await: bool,
 }
 
-impl Node for ForInStatement {}
+impl ForOfStatement {
+    pub fn new(
+        left: ForInStatementLeft,
+        right: Expression,
+        body: Statement,
+        is_await: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        ForOfStatement {
+            loc,
+            left,
+            right,
+            body,
+            is_await,
+        }
+    }
+}
 
-impl Statement for ForInStatement {}
+impl Node for ForOfStatement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface Declaration <: Statement { }
-trait Declaration {}
+pub trait Declaration {}
 
 // interface FunctionDeclaration <: Function, Declaration {
 //   type: "FunctionDeclaration";
-//   id: Identifier;
+//   id: Identifier | null; // null only under `export default`
 // }
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunctionDeclaration {
-    id: Identifier,
-    params: Vec<Box<dyn Pattern>>,
-    body: FunctionBody,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub id: Option<Identifier>,
+    pub params: Vec<Pattern>,
+    pub body: FunctionBody,
+    #[cfg_attr(feature = "serde", serde(rename = "async"))]
+    pub is_async: bool,
+    pub generator: bool,
 }
 
 impl FunctionDeclaration {
-    pub fn new(id: Identifier) -> Self {
+    pub fn new(
+        id: Option<Identifier>,
+        params: Vec<Pattern>,
+        body: FunctionBody,
+        is_async: bool,
+        generator: bool,
+        loc: SourceLocation,
+    ) -> Self {
         FunctionDeclaration {
+            loc,
             id,
-            params: vec![],
-            body: FunctionBody::new(),
+            params,
+            body,
+            is_async,
+            generator,
         }
     }
 }
 
-impl Node for FunctionDeclaration {}
+impl Node for FunctionDeclaration {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 impl Function for FunctionDeclaration {}
 
-impl Statement for FunctionDeclaration {}
-
 impl Declaration for FunctionDeclaration {}
 
 // interface VariableDeclaration <: Declaration {
 //   type: "VariableDeclaration";
 //   declarations: [ VariableDeclarator ];
-//   kind: "var";
+//   kind: "var" | "let" | "const";
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct VariableDeclaration {
-    declarations: Vec<VariableDeclarator>,
-    kind: String,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub declarations: Vec<VariableDeclarator>,
+    pub kind: String,
 }
 
-impl Node for VariableDeclaration {}
+impl VariableDeclaration {
+    pub fn new(declarations: Vec<VariableDeclarator>, kind: &str, loc: SourceLocation) -> Self {
+        VariableDeclaration {
+            loc,
+            declarations,
+            kind: kind.to_string(),
+        }
+    }
+
+    pub fn bound_names(&self) -> Vec<&Identifier> {
+        self.declarations
+            .iter()
+            .flat_map(|declarator| declarator.id.bound_names())
+            .collect()
+    }
+}
 
-impl Statement for VariableDeclaration {}
+impl Node for VariableDeclaration {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 impl Declaration for VariableDeclaration {}
 
@@ -470,96 +1217,512 @@ impl Declaration for VariableDeclaration {}
 //   id: Pattern;
 //   init: Expression | null;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct VariableDeclarator {
-    id: Box<dyn Pattern>,
-    init: Option<Box<dyn Expression>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub id: Pattern,
+    pub init: Option<Expression>,
+}
+
+impl VariableDeclarator {
+    pub fn new(id: Pattern, init: Option<Expression>, loc: SourceLocation) -> Self {
+        VariableDeclarator { loc, id, init }
+    }
 }
 
-impl Node for VariableDeclarator {}
+impl Node for VariableDeclarator {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface Expression <: Node { }
-pub trait Expression {}
+grows_stack! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+    pub enum Expression {
+        Identifier(Box<Identifier>),
+        Literal(Box<Literal>),
+        This(Box<ThisExpression>),
+        Super(Box<Super>),
+        Array(Box<ArrayExpression>),
+        Object(Box<ObjectExpression>),
+        Function(Box<FunctionExpression>),
+        ArrowFunction(Box<ArrowFunctionExpression>),
+        Class(Box<ClassExpression>),
+        Unary(Box<UnaryExpression>),
+        Update(Box<UpdateExpression>),
+        Binary(Box<BinaryExpression>),
+        Assignment(Box<AssignmentExpression>),
+        Logical(Box<LogicalExpression>),
+        Member(Box<MemberExpression>),
+        Conditional(Box<ConditionalExpression>),
+        Call(Box<CallExpression>),
+        Chain(Box<ChainExpression>),
+        Yield(Box<YieldExpression>),
+        TemplateLiteral(Box<TemplateLiteral>),
+        TaggedTemplate(Box<TaggedTemplateExpression>),
+        New(Box<NewExpression>),
+        MetaProperty(Box<MetaProperty>),
+        Import(Box<ImportExpression>),
+        Sequence(Box<SequenceExpression>),
+        Parenthesized(Box<ParenthesizedExpression>),
+        Await(Box<AwaitExpression>),
+        PrivateIdentifier(Box<PrivateIdentifier>),
+        Error(Box<ErrorNode>),
+    }
+}
+
+impl Node for Expression {
+    fn loc(&self) -> &SourceLocation {
+        match self {
+            Expression::Identifier(node) => node.loc(),
+            Expression::Literal(node) => node.loc(),
+            Expression::This(node) => node.loc(),
+            Expression::Super(node) => node.loc(),
+            Expression::Array(node) => node.loc(),
+            Expression::Object(node) => node.loc(),
+            Expression::Function(node) => node.loc(),
+            Expression::ArrowFunction(node) => node.loc(),
+            Expression::Class(node) => node.loc(),
+            Expression::Unary(node) => node.loc(),
+            Expression::Update(node) => node.loc(),
+            Expression::Binary(node) => node.loc(),
+            Expression::Assignment(node) => node.loc(),
+            Expression::Logical(node) => node.loc(),
+            Expression::Member(node) => node.loc(),
+            Expression::Conditional(node) => node.loc(),
+            Expression::Call(node) => node.loc(),
+            Expression::Chain(node) => node.loc(),
+            Expression::Yield(node) => node.loc(),
+            Expression::TemplateLiteral(node) => node.loc(),
+            Expression::TaggedTemplate(node) => node.loc(),
+            Expression::New(node) => node.loc(),
+            Expression::MetaProperty(node) => node.loc(),
+            Expression::Import(node) => node.loc(),
+            Expression::Sequence(node) => node.loc(),
+            Expression::Parenthesized(node) => node.loc(),
+            Expression::Await(node) => node.loc(),
+            Expression::PrivateIdentifier(node) => node.loc(),
+            Expression::Error(node) => node.loc(),
+        }
+    }
+}
+
+impl Expression {
+    // identifiers and member expressions, the only targets compound
+    // assignment operators such as `+=` accept
+    pub fn is_simple_assignment_target(&self) -> bool {
+        match self {
+            // an error node has already been reported
+            Expression::Identifier(_) | Expression::Member(_) | Expression::Error(_) => true,
+            // `(a) = 1` is fine, but parentheses can't wrap a destructuring pattern
+            Expression::Parenthesized(node) => node.expression.is_simple_assignment_target(),
+            _ => false,
+        }
+    }
+
+    // reinterprets an expression parsed ahead of `=` as the pattern it
+    // covers, as in `({ a: b } = c)`, or gives the location of the part
+    // that isn't a valid target, such as `a + b` in `[a + b] = c`
+    pub fn into_pattern(self) -> Result<Pattern, SourceLocation> {
+        match self {
+            Expression::Identifier(node) => Ok(Pattern::Identifier(node)),
+            Expression::Member(node) => Ok(Pattern::Member(node)),
+            Expression::Error(node) => Ok(Pattern::Error(node)),
+            Expression::Array(node) => node.into_pattern(),
+            Expression::Object(node) => node.into_pattern(),
+            Expression::Assignment(node) => node.into_pattern(),
+            Expression::Parenthesized(node) => {
+                if node.expression.is_simple_assignment_target() {
+                    node.expression.into_pattern()
+                } else {
+                    Err(node.loc)
+                }
+            }
+            expression => Err(expression.loc().clone()),
+        }
+    }
+
+    // the identifier itself, seeing through parentheses
+    pub fn as_identifier(&self) -> Option<&Identifier> {
+        match self {
+            Expression::Identifier(node) => Some(node),
+            Expression::Parenthesized(node) => node.expression.as_identifier(),
+            _ => None,
+        }
+    }
+
+    // nothing can be applied to an arrow function without parentheses around
+    // it, so `() => {}` ends the expression it starts
+    pub fn is_arrow_function(&self) -> bool {
+        matches!(self, Expression::ArrowFunction(_))
+    }
+}
 
 // interface ThisExpression <: Expression {
 //   type: "ThisExpression";
 // }
-pub struct ThisExpression;
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThisExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+}
+
+impl ThisExpression {
+    pub fn new(loc: SourceLocation) -> Self {
+        ThisExpression { loc }
+    }
+}
+
+impl Node for ThisExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// Only the callee of a call or the object of a member expression.
+// interface Super <: Node {
+//   type: "Super";
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Super {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+}
 
-impl Node for ThisExpression {}
+impl Super {
+    pub fn new(loc: SourceLocation) -> Self {
+        Super { loc }
+    }
+}
 
-impl Expression for ThisExpression {}
+impl Node for Super {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface ArrayExpression <: Expression {
 //   type: "ArrayExpression";
-//   elements: [ Expression | null ];
+//   elements: [ Expression | SpreadElement | null ];
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrayExpressionElement {
+    Expression(Expression),
+    SpreadElement(SpreadElement),
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ArrayExpression {
-    elements: Vec<Option<Box<dyn Expression>>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub elements: Vec<Option<ArrayExpressionElement>>,
 }
 
-impl Node for ArrayExpression {}
+impl ArrayExpression {
+    pub fn new(elements: Vec<Option<ArrayExpressionElement>>, loc: SourceLocation) -> Self {
+        ArrayExpression { loc, elements }
+    }
+}
+
+impl Node for ArrayExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
-impl Expression for ArrayExpression {}
+// `[a, , ...rest] = b`, where a spread becomes the rest element and may
+// only come last
+impl ArrayExpression {
+    fn into_pattern(self) -> Result<Pattern, SourceLocation> {
+        let count = self.elements.len();
+        let mut elements = vec![];
+        for (index, element) in self.elements.into_iter().enumerate() {
+            let element: Option<Pattern> = match element {
+                None => None,
+                Some(ArrayExpressionElement::Expression(expression)) => {
+                    Some(expression.into_pattern()?)
+                }
+                Some(ArrayExpressionElement::SpreadElement(spread)) => {
+                    if index + 1 != count {
+                        return Err(spread.loc);
+                    }
+                    Some(Pattern::Rest(Box::new(spread.into_rest_element()?)))
+                }
+            };
+            elements.push(element);
+        }
+        Ok(Pattern::Array(Box::new(ArrayPattern::new(
+            elements, self.loc,
+        ))))
+    }
+}
 
 // interface ObjectExpression <: Expression {
 //   type: "ObjectExpression";
-//   properties: [ Property ];
+//   properties: [ Property | SpreadElement ];
 // }
-pub struct ObjectExpression {
-    properties: Vec<Property>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectExpressionProperty {
+    Property(Property),
+    SpreadElement(SpreadElement),
 }
 
-impl Node for ObjectExpression {}
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub properties: Vec<ObjectExpressionProperty>,
+}
 
-impl Expression for ObjectExpression {}
+impl ObjectExpression {
+    pub fn new(properties: Vec<ObjectExpressionProperty>, loc: SourceLocation) -> Self {
+        ObjectExpression { loc, properties }
+    }
+}
+
+impl Node for ObjectExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// methods and accessors can't be assigned to, and a spread becomes the rest
+// element, which may only come last and only be a name or member
+impl ObjectExpression {
+    fn into_pattern(self) -> Result<Pattern, SourceLocation> {
+        let count = self.properties.len();
+        let mut properties = vec![];
+        for (index, property) in self.properties.into_iter().enumerate() {
+            let property = match property {
+                ObjectExpressionProperty::Property(property) => {
+                    if property.method || !matches!(property.kind, PropertyKind::Init) {
+                        return Err(property.loc);
+                    }
+                    let value = property.value.into_pattern()?;
+                    ObjectPatternProperty::AssignmentProperty(AssignmentProperty::new(
+                        property.key,
+                        value,
+                        property.shorthand,
+                        property.computed,
+                        property.loc,
+                    ))
+                }
+                ObjectExpressionProperty::SpreadElement(spread) => {
+                    if index + 1 != count {
+                        return Err(spread.loc);
+                    }
+                    let rest = spread.into_rest_element()?;
+                    if !matches!(rest.argument, Pattern::Identifier(_) | Pattern::Member(_)) {
+                        return Err(rest.argument.loc().clone());
+                    }
+                    ObjectPatternProperty::RestElement(rest)
+                }
+            };
+            properties.push(property);
+        }
+        Ok(Pattern::Object(Box::new(ObjectPattern::new(
+            properties, self.loc,
+        ))))
+    }
+}
 
 // interface Property <: Node {
 //   type: "Property";
-//   key: Literal | Identifier;
+//   key: Expression;
 //   value: Expression;
 //   kind: "init" | "get" | "set";
+//   method: boolean;
+//   shorthand: boolean;
+//   computed: boolean;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PropertyKey {
     Literal(Literal),
     Identifier(Identifier),
+    Expression(Expression),
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum PropertyKind {
     Init,
     Get,
     Set,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Property {
-    key: PropertyKey,
-    value: Box<dyn Expression>,
-    kind: PropertyKind,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub key: PropertyKey,
+    pub value: Expression,
+    pub kind: PropertyKind,
+    pub method: bool,
+    pub shorthand: bool,
+    pub computed: bool,
+}
+
+impl Property {
+    pub fn new(
+        key: PropertyKey,
+        value: Expression,
+        kind: PropertyKind,
+        method: bool,
+        shorthand: bool,
+        computed: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        Property {
+            loc,
+            key,
+            value,
+            kind,
+            method,
+            shorthand,
+            computed,
+        }
+    }
 }
 
-impl Node for Property {}
+impl Node for Property {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface FunctionExpression <: Function, Expression {
 //   type: "FunctionExpression";
 // }
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FunctionExpression {
-    params: Vec<Box<dyn Pattern>>,
-    body: FunctionBody,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub id: Option<Identifier>,
+    pub params: Vec<Pattern>,
+    pub body: FunctionBody,
+    #[cfg_attr(feature = "serde", serde(rename = "async"))]
+    pub is_async: bool,
+    pub generator: bool,
 }
 
 impl FunctionExpression {
-    pub fn new() -> Self {
+    pub fn new(
+        id: Option<Identifier>,
+        params: Vec<Pattern>,
+        body: FunctionBody,
+        is_async: bool,
+        generator: bool,
+        loc: SourceLocation,
+    ) -> Self {
         FunctionExpression {
-            params: vec![],
-            body: FunctionBody::new(),
+            loc,
+            id,
+            params,
+            body,
+            is_async,
+            generator,
         }
     }
 }
 
-impl Node for FunctionExpression {}
+impl Node for FunctionExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 impl Function for FunctionExpression {}
 
-impl Expression for FunctionExpression {}
+// interface ArrowFunctionExpression <: Function, Expression {
+//   type: "ArrowFunctionExpression";
+//   body: FunctionBody | Expression;
+//   expression: boolean;
+// }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArrowFunctionExpressionBody {
+    FunctionBody(FunctionBody),
+    Expression(Expression),
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrowFunctionExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub params: Vec<Pattern>,
+    pub body: ArrowFunctionExpressionBody,
+    #[cfg_attr(feature = "serde", serde(rename = "async"))]
+    pub is_async: bool,
+    // whether the body is a bare expression rather than a block
+    pub expression: bool,
+}
+
+impl ArrowFunctionExpression {
+    pub fn new(
+        params: Vec<Pattern>,
+        body: ArrowFunctionExpressionBody,
+        is_async: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        let expression = matches!(body, ArrowFunctionExpressionBody::Expression(_));
+        ArrowFunctionExpression {
+            loc,
+            params,
+            body,
+            is_async,
+            expression,
+        }
+    }
+}
+
+impl Node for ArrowFunctionExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl Function for ArrowFunctionExpression {}
 
 // interface UnaryExpression <: Expression {
 //   type: "UnaryExpression";
@@ -567,25 +1730,55 @@ impl Expression for FunctionExpression {}
 //   prefix: boolean;
 //   argument: Expression;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum UnaryOperator {
+    #[cfg_attr(feature = "serde", serde(rename = "+"))]
     Positive,
+    #[cfg_attr(feature = "serde", serde(rename = "-"))]
     Negative,
+    #[cfg_attr(feature = "serde", serde(rename = "!"))]
     LogicalInversion,
+    #[cfg_attr(feature = "serde", serde(rename = "~"))]
     BitwiseInversion,
+    #[cfg_attr(feature = "serde", serde(rename = "typeof"))]
     Typeof,
+    #[cfg_attr(feature = "serde", serde(rename = "void"))]
     Void,
+    #[cfg_attr(feature = "serde", serde(rename = "delete"))]
     Delete,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UnaryExpression {
-    operator: UnaryOperator,
-    prefix: bool,
-    argument: Box<dyn Expression>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub operator: UnaryOperator,
+    pub prefix: bool,
+    pub argument: Expression,
 }
 
-impl Node for UnaryExpression {}
+impl UnaryExpression {
+    pub fn new(operator: UnaryOperator, argument: Expression, loc: SourceLocation) -> Self {
+        UnaryExpression {
+            loc,
+            operator,
+            prefix: true,
+            argument,
+        }
+    }
+}
 
-impl Expression for UnaryExpression {}
+impl Node for UnaryExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface UpdateExpression <: Expression {
 //   type: "UpdateExpression";
@@ -593,20 +1786,50 @@ impl Expression for UnaryExpression {}
 //   argument: Expression;
 //   prefix: boolean;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum UpdateOperator {
+    #[cfg_attr(feature = "serde", serde(rename = "++"))]
     Increment,
+    #[cfg_attr(feature = "serde", serde(rename = "--"))]
     Decrement,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct UpdateExpression {
-    operator: UpdateOperator,
-    prefix: bool,
-    argument: Box<dyn Expression>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub operator: UpdateOperator,
+    pub prefix: bool,
+    pub argument: Expression,
 }
 
-impl Node for UpdateExpression {}
+impl UpdateExpression {
+    pub fn new(
+        operator: UpdateOperator,
+        prefix: bool,
+        argument: Expression,
+        loc: SourceLocation,
+    ) -> Self {
+        UpdateExpression {
+            loc,
+            operator,
+            prefix,
+            argument,
+        }
+    }
+}
 
-impl Expression for UpdateExpression {}
+impl Node for UpdateExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface BinaryExpression <: Expression {
 //   type: "BinaryExpression";
@@ -614,39 +1837,90 @@ impl Expression for UpdateExpression {}
 //   left: Expression;
 //   right: Expression;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum BinaryOperator {
+    #[cfg_attr(feature = "serde", serde(rename = "=="))]
     DoubleE,
+    #[cfg_attr(feature = "serde", serde(rename = "!="))]
     DoubleNE,
+    #[cfg_attr(feature = "serde", serde(rename = "==="))]
     TripleE,
+    #[cfg_attr(feature = "serde", serde(rename = "!=="))]
     TripleNE,
+    #[cfg_attr(feature = "serde", serde(rename = "<"))]
     LT,
+    #[cfg_attr(feature = "serde", serde(rename = "<="))]
     LTE,
+    #[cfg_attr(feature = "serde", serde(rename = ">"))]
     GT,
+    #[cfg_attr(feature = "serde", serde(rename = ">="))]
     GTE,
+    #[cfg_attr(feature = "serde", serde(rename = "<<"))]
     LeftShift,
+    #[cfg_attr(feature = "serde", serde(rename = ">>"))]
     RightShift,
+    #[cfg_attr(feature = "serde", serde(rename = ">>>"))]
     URightShift,
+    #[cfg_attr(feature = "serde", serde(rename = "+"))]
     Plus,
+    #[cfg_attr(feature = "serde", serde(rename = "-"))]
     Minus,
+    #[cfg_attr(feature = "serde", serde(rename = "*"))]
     Multiple,
+    #[cfg_attr(feature = "serde", serde(rename = "/"))]
     Divide,
+    #[cfg_attr(feature = "serde", serde(rename = "%"))]
     Modulo,
+    #[cfg_attr(feature = "serde", serde(rename = "**"))]
+    Exponent,
+    #[cfg_attr(feature = "serde", serde(rename = "|"))]
     BitwiseOR,
+    #[cfg_attr(feature = "serde", serde(rename = "^"))]
     BitwiseXOR,
+    #[cfg_attr(feature = "serde", serde(rename = "&"))]
     BitwiseAND,
+    #[cfg_attr(feature = "serde", serde(rename = "in"))]
     In,
+    #[cfg_attr(feature = "serde", serde(rename = "instanceof"))]
     Instanceof,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct BinaryExpression {
-    operator: BinaryOperator,
-    left: Box<dyn Expression>,
-    right: Box<dyn Expression>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub operator: BinaryOperator,
+    pub left: Expression,
+    pub right: Expression,
 }
 
-impl Node for BinaryExpression {}
+impl BinaryExpression {
+    pub fn new(
+        operator: BinaryOperator,
+        left: Expression,
+        right: Expression,
+        loc: SourceLocation,
+    ) -> Self {
+        BinaryExpression {
+            loc,
+            operator,
+            left,
+            right,
+        }
+    }
+}
 
-impl Expression for BinaryExpression {}
+impl Node for BinaryExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface AssignmentExpression <: Expression {
 //   type: "AssignmentExpression";
@@ -654,29 +1928,101 @@ impl Expression for BinaryExpression {}
 //   left: Pattern | Expression;
 //   right: Expression;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AssignmentOperator {
+    #[cfg_attr(feature = "serde", serde(rename = "="))]
     Normal,
+    #[cfg_attr(feature = "serde", serde(rename = "+="))]
     Addition,
+    #[cfg_attr(feature = "serde", serde(rename = "-="))]
     Subtraction,
+    #[cfg_attr(feature = "serde", serde(rename = "*="))]
     Multiplication,
+    #[cfg_attr(feature = "serde", serde(rename = "/="))]
     Division,
+    #[cfg_attr(feature = "serde", serde(rename = "%="))]
+    Remainder,
+    #[cfg_attr(feature = "serde", serde(rename = "**="))]
+    Exponentiation,
+    #[cfg_attr(feature = "serde", serde(rename = "<<="))]
+    LeftShift,
+    #[cfg_attr(feature = "serde", serde(rename = ">>="))]
+    RightShift,
+    #[cfg_attr(feature = "serde", serde(rename = ">>>="))]
+    URightShift,
+    #[cfg_attr(feature = "serde", serde(rename = "|="))]
+    BitwiseOR,
+    #[cfg_attr(feature = "serde", serde(rename = "^="))]
+    BitwiseXOR,
+    #[cfg_attr(feature = "serde", serde(rename = "&="))]
+    BitwiseAND,
+    #[cfg_attr(feature = "serde", serde(rename = "||="))]
+    LogicalOR,
+    #[cfg_attr(feature = "serde", serde(rename = "&&="))]
+    LogicalAND,
+    #[cfg_attr(feature = "serde", serde(rename = "??="))]
     NullishCoalescing,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AssignmentExpressionLeft {
-    Pattern(Box<dyn Pattern>),
-    Expression(Box<dyn Expression>),
+    Pattern(Pattern),
+    Expression(Expression),
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct AssignmentExpression {
-    operator: AssignmentOperator,
-    left: AssignmentExpressionLeft,
-    right: Box<dyn Expression>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub operator: AssignmentOperator,
+    pub left: AssignmentExpressionLeft,
+    pub right: Expression,
 }
 
-impl Node for AssignmentExpression {}
+impl AssignmentExpression {
+    pub fn new(
+        operator: AssignmentOperator,
+        left: AssignmentExpressionLeft,
+        right: Expression,
+        loc: SourceLocation,
+    ) -> Self {
+        AssignmentExpression {
+            loc,
+            operator,
+            left,
+            right,
+        }
+    }
+}
 
-impl Expression for AssignmentExpression {}
+impl Node for AssignmentExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// a default value inside a destructuring target, as in `({ a = 1 } = b)`
+impl AssignmentExpression {
+    fn into_pattern(self) -> Result<Pattern, SourceLocation> {
+        if !matches!(self.operator, AssignmentOperator::Normal) {
+            return Err(self.loc);
+        }
+        let left = match self.left {
+            AssignmentExpressionLeft::Pattern(pattern) => pattern,
+            AssignmentExpressionLeft::Expression(expression) => expression.into_pattern()?,
+        };
+        Ok(Pattern::Assignment(Box::new(AssignmentPattern::new(
+            left, self.right, self.loc,
+        ))))
+    }
+}
 
 // interface LogicalExpression <: Expression {
 //   type: "LogicalExpression";
@@ -684,39 +2030,98 @@ impl Expression for AssignmentExpression {}
 //   left: Expression;
 //   right: Expression;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
 pub enum LogicalOperator {
+    #[cfg_attr(feature = "serde", serde(rename = "||"))]
     LogicalOR,
+    #[cfg_attr(feature = "serde", serde(rename = "&&"))]
     LogicalAND,
+    #[cfg_attr(feature = "serde", serde(rename = "??"))]
+    NullishCoalescing,
 }
 
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct LogicalExpression {
-    operator: AssignmentOperator,
-    left: Box<dyn Expression>,
-    right: Box<dyn Expression>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub operator: LogicalOperator,
+    pub left: Expression,
+    pub right: Expression,
 }
 
-impl Node for LogicalExpression {}
+impl LogicalExpression {
+    pub fn new(
+        operator: LogicalOperator,
+        left: Expression,
+        right: Expression,
+        loc: SourceLocation,
+    ) -> Self {
+        LogicalExpression {
+            loc,
+            operator,
+            left,
+            right,
+        }
+    }
+}
 
-impl Expression for LogicalExpression {}
+impl Node for LogicalExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface MemberExpression <: Expression, Pattern {
 //   type: "MemberExpression";
 //   object: Expression;
-//   property: Expression;
+//   property: Expression | PrivateIdentifier;
 //   computed: boolean;
+//   optional: boolean;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct MemberExpression {
-    operator: AssignmentOperator,
-    object: Box<dyn Expression>,
-    property: Box<dyn Expression>,
-    computed: bool,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub object: Expression,
+    pub property: Expression,
+    pub computed: bool,
+    pub optional: bool,
 }
 
-impl Node for MemberExpression {}
-
-impl Pattern for MemberExpression {}
+impl MemberExpression {
+    pub fn new(
+        object: Expression,
+        property: Expression,
+        computed: bool,
+        optional: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        MemberExpression {
+            loc,
+            object,
+            property,
+            computed,
+            optional,
+        }
+    }
+}
 
-impl Expression for MemberExpression {}
+impl Node for MemberExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface ConditionalExpression <: Expression {
 //   type: "ConditionalExpression";
@@ -724,55 +2129,1375 @@ impl Expression for MemberExpression {}
 //   alternate: Expression;
 //   consequent: Expression;
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ConditionalExpression {
-    test: Box<dyn Expression>,
-    alternate: Box<dyn Expression>,
-    consequent: Box<dyn Expression>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub test: Expression,
+    pub alternate: Expression,
+    pub consequent: Expression,
 }
 
-impl Node for ConditionalExpression {}
+impl ConditionalExpression {
+    pub fn new(
+        test: Expression,
+        consequent: Expression,
+        alternate: Expression,
+        loc: SourceLocation,
+    ) -> Self {
+        ConditionalExpression {
+            loc,
+            test,
+            alternate,
+            consequent,
+        }
+    }
+}
 
-impl Expression for ConditionalExpression {}
+impl Node for ConditionalExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface CallExpression <: Expression {
 //   type: "CallExpression";
 //   callee: Expression;
-//   arguments: [ Expression ];
+//   arguments: [ Expression | SpreadElement ];
+//   optional: boolean;
 // }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallExpressionArgument {
+    Expression(Expression),
+    SpreadElement(SpreadElement),
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct CallExpression {
-    callee: Box<dyn Expression>,
-    arguments: Vec<Box<dyn Expression>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub callee: Expression,
+    pub arguments: Vec<CallExpressionArgument>,
+    pub optional: bool,
 }
 
-impl Node for CallExpression {}
+impl CallExpression {
+    pub fn new(
+        callee: Expression,
+        arguments: Vec<CallExpressionArgument>,
+        optional: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        CallExpression {
+            loc,
+            callee,
+            arguments,
+            optional,
+        }
+    }
+}
 
-impl Expression for CallExpression {}
+impl Node for CallExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface ChainExpression <: Expression {
+//   type: "ChainExpression";
+//   expression: ChainElement;
+// }
+//
+// type ChainElement = CallExpression | MemberExpression;
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub expression: Expression,
+}
+
+impl ChainExpression {
+    pub fn new(expression: Expression, loc: SourceLocation) -> Self {
+        ChainExpression { loc, expression }
+    }
+}
+
+impl Node for ChainExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface YieldExpression <: Expression {
+//   type: "YieldExpression";
+//   argument: Expression | null;
+//   delegate: boolean;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct YieldExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub argument: Option<Expression>,
+    pub delegate: bool,
+}
+
+impl YieldExpression {
+    pub fn new(argument: Option<Expression>, delegate: bool, loc: SourceLocation) -> Self {
+        YieldExpression {
+            loc,
+            argument,
+            delegate,
+        }
+    }
+}
+
+impl Node for YieldExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface TemplateLiteral <: Expression {
+//   type: "TemplateLiteral";
+//   quasis: [ TemplateElement ];
+//   expressions: [ Expression ];
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateLiteral {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub quasis: Vec<TemplateElement>,
+    pub expressions: Vec<Expression>,
+}
+
+impl TemplateLiteral {
+    pub fn new(
+        quasis: Vec<TemplateElement>,
+        expressions: Vec<Expression>,
+        loc: SourceLocation,
+    ) -> Self {
+        TemplateLiteral {
+            loc,
+            quasis,
+            expressions,
+        }
+    }
+}
+
+impl Node for TemplateLiteral {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface TaggedTemplateExpression <: Expression {
+//   type: "TaggedTemplateExpression";
+//   tag: Expression;
+//   quasi: TemplateLiteral;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaggedTemplateExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub tag: Expression,
+    pub quasi: TemplateLiteral,
+}
+
+impl TaggedTemplateExpression {
+    pub fn new(tag: Expression, quasi: TemplateLiteral, loc: SourceLocation) -> Self {
+        TaggedTemplateExpression { loc, tag, quasi }
+    }
+}
+
+impl Node for TaggedTemplateExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface TemplateElement <: Node {
+//   type: "TemplateElement";
+//   tail: boolean;
+//   value: {
+//     cooked: string | null;
+//     raw: string;
+//   };
+// }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateElementValue {
+    pub cooked: Option<String>,
+    pub raw: String,
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateElement {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub tail: bool,
+    pub value: TemplateElementValue,
+}
+
+impl TemplateElement {
+    pub fn new(raw: String, cooked: Option<String>, tail: bool, loc: SourceLocation) -> Self {
+        TemplateElement {
+            loc,
+            tail,
+            value: TemplateElementValue { cooked, raw },
+        }
+    }
+}
+
+impl Node for TemplateElement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface SpreadElement <: Node {
+//   type: "SpreadElement";
+//   argument: Expression;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpreadElement {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub argument: Expression,
+}
+
+impl SpreadElement {
+    pub fn new(argument: Expression, loc: SourceLocation) -> Self {
+        SpreadElement { loc, argument }
+    }
+
+    // `...a` in a pattern, where it becomes the rest element, which can't
+    // have a default as in `[...a = []] = b`
+    pub fn into_rest_element(self) -> Result<RestElement, SourceLocation> {
+        let argument = self.argument.into_pattern()?;
+        if argument.has_default() {
+            return Err(argument.loc().clone());
+        }
+        Ok(RestElement::new(argument, self.loc))
+    }
+}
+
+impl Node for SpreadElement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface NewExpression <: Expression {
 //   type: "NewExpression";
 //   callee: Expression;
-//   arguments: [ Expression ];
+//   arguments: [ Expression | SpreadElement ];
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct NewExpression {
-    callee: Box<dyn Expression>,
-    arguments: Vec<Box<dyn Expression>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub callee: Expression,
+    pub arguments: Vec<CallExpressionArgument>,
+}
+
+impl NewExpression {
+    pub fn new(
+        callee: Expression,
+        arguments: Vec<CallExpressionArgument>,
+        loc: SourceLocation,
+    ) -> Self {
+        NewExpression {
+            loc,
+            callee,
+            arguments,
+        }
+    }
+}
+
+impl Node for NewExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface MetaProperty <: Expression {
+//   type: "MetaProperty";
+//   meta: Identifier;
+//   property: Identifier;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetaProperty {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub meta: Identifier,
+    pub property: Identifier,
+}
+
+impl MetaProperty {
+    pub fn new(meta: Identifier, property: Identifier, loc: SourceLocation) -> Self {
+        MetaProperty {
+            loc,
+            meta,
+            property,
+        }
+    }
+}
+
+impl Node for MetaProperty {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface ImportExpression <: Expression {
+//   type: "ImportExpression";
+//   source: Expression;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub source: Expression,
 }
 
-impl Node for NewExpression {}
+impl ImportExpression {
+    pub fn new(source: Expression, loc: SourceLocation) -> Self {
+        ImportExpression { loc, source }
+    }
+}
 
-impl Expression for NewExpression {}
+impl Node for ImportExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface SequenceExpression <: Expression {
 //   type: "SequenceExpression";
 //   expressions: [ Expression ];
 // }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct SequenceExpression {
-    expressions: Vec<Box<dyn Expression>>,
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub expressions: Vec<Expression>,
+}
+
+impl SequenceExpression {
+    pub fn new(expressions: Vec<Expression>, loc: SourceLocation) -> Self {
+        SequenceExpression { loc, expressions }
+    }
+}
+
+impl Node for SequenceExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// Only produced when parentheses are preserved, as acorn's `preserveParens`
+// does; otherwise the inner expression is used directly.
+// interface ParenthesizedExpression <: Expression {
+//   type: "ParenthesizedExpression";
+//   expression: Expression;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParenthesizedExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub expression: Expression,
+}
+
+impl ParenthesizedExpression {
+    pub fn new(expression: Expression, loc: SourceLocation) -> Self {
+        ParenthesizedExpression { loc, expression }
+    }
+}
+
+impl Node for ParenthesizedExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
 }
 
-impl Node for SequenceExpression {}
+// interface AwaitExpression <: Expression {
+//   type: "AwaitExpression";
+//   argument: Expression;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AwaitExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub argument: Expression,
+}
 
-impl Expression for SequenceExpression {}
+impl AwaitExpression {
+    pub fn new(argument: Expression, loc: SourceLocation) -> Self {
+        AwaitExpression { loc, argument }
+    }
+}
+
+impl Node for AwaitExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
 
 // interface Pattern <: Node { }
-pub trait Pattern {}
+grows_stack! {
+    #[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+    pub enum Pattern {
+        Identifier(Box<Identifier>),
+        Member(Box<MemberExpression>),
+        Object(Box<ObjectPattern>),
+        Array(Box<ArrayPattern>),
+        Rest(Box<RestElement>),
+        Assignment(Box<AssignmentPattern>),
+        Error(Box<ErrorNode>),
+    }
+}
+
+impl Node for Pattern {
+    fn loc(&self) -> &SourceLocation {
+        match self {
+            Pattern::Identifier(node) => node.loc(),
+            Pattern::Member(node) => node.loc(),
+            Pattern::Object(node) => node.loc(),
+            Pattern::Array(node) => node.loc(),
+            Pattern::Rest(node) => node.loc(),
+            Pattern::Assignment(node) => node.loc(),
+            Pattern::Error(node) => node.loc(),
+        }
+    }
+}
+
+impl Pattern {
+    // the identifiers a pattern declares, e.g. for duplicate export checks
+    pub fn bound_names(&self) -> Vec<&Identifier> {
+        match self {
+            Pattern::Identifier(node) => vec![node],
+            Pattern::Member(_) | Pattern::Error(_) => vec![],
+            Pattern::Object(node) => node.bound_names(),
+            Pattern::Array(node) => node.bound_names(),
+            Pattern::Rest(node) => node.bound_names(),
+            Pattern::Assignment(node) => node.left.bound_names(),
+        }
+    }
+
+    // whether the pattern only declares names, as parameters and variable
+    // declarations require, rather than assigning to members
+    pub fn is_binding(&self) -> bool {
+        match self {
+            Pattern::Identifier(_) => true,
+            // members can be assigned to but never declared
+            Pattern::Member(_) => false,
+            Pattern::Object(node) => node.is_binding(),
+            Pattern::Array(node) => node.is_binding(),
+            Pattern::Rest(node) => node.is_binding(),
+            Pattern::Assignment(node) => node.left.is_binding(),
+            // already reported, so not reported again as an invalid binding
+            Pattern::Error(_) => true,
+        }
+    }
+
+    // a default value is only allowed inside a pattern or for a parameter,
+    // so `for (a = 1 in b)` has no valid target
+    pub fn has_default(&self) -> bool {
+        matches!(self, Pattern::Assignment(_))
+    }
+}
+
+// interface ObjectPattern <: Pattern {
+//   type: "ObjectPattern";
+//   properties: [ AssignmentProperty | RestElement ];
+// }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObjectPatternProperty {
+    AssignmentProperty(AssignmentProperty),
+    RestElement(RestElement),
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectPattern {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub properties: Vec<ObjectPatternProperty>,
+}
+
+impl ObjectPattern {
+    pub fn new(properties: Vec<ObjectPatternProperty>, loc: SourceLocation) -> Self {
+        ObjectPattern { loc, properties }
+    }
+}
+
+impl Node for ObjectPattern {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ObjectPattern {
+    fn bound_names(&self) -> Vec<&Identifier> {
+        self.properties
+            .iter()
+            .flat_map(|property| match property {
+                ObjectPatternProperty::AssignmentProperty(property) => property.value.bound_names(),
+                ObjectPatternProperty::RestElement(rest) => rest.bound_names(),
+            })
+            .collect()
+    }
+
+    fn is_binding(&self) -> bool {
+        self.properties.iter().all(|property| match property {
+            ObjectPatternProperty::AssignmentProperty(property) => property.value.is_binding(),
+            ObjectPatternProperty::RestElement(rest) => rest.is_binding(),
+        })
+    }
+}
+
+// interface ArrayPattern <: Pattern {
+//   type: "ArrayPattern";
+//   elements: [ Pattern | null ];
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayPattern {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub elements: Vec<Option<Pattern>>,
+}
+
+impl ArrayPattern {
+    pub fn new(elements: Vec<Option<Pattern>>, loc: SourceLocation) -> Self {
+        ArrayPattern { loc, elements }
+    }
+}
+
+impl Node for ArrayPattern {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ArrayPattern {
+    fn bound_names(&self) -> Vec<&Identifier> {
+        self.elements
+            .iter()
+            .flatten()
+            .flat_map(|element| element.bound_names())
+            .collect()
+    }
+
+    fn is_binding(&self) -> bool {
+        self.elements
+            .iter()
+            .flatten()
+            .all(|element| element.is_binding())
+    }
+}
+
+// interface RestElement <: Pattern {
+//   type: "RestElement";
+//   argument: Pattern;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestElement {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub argument: Pattern,
+}
+
+impl RestElement {
+    pub fn new(argument: Pattern, loc: SourceLocation) -> Self {
+        RestElement { loc, argument }
+    }
+}
+
+impl Node for RestElement {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl RestElement {
+    fn bound_names(&self) -> Vec<&Identifier> {
+        self.argument.bound_names()
+    }
+
+    fn is_binding(&self) -> bool {
+        self.argument.is_binding()
+    }
+}
+
+// interface AssignmentProperty <: Property {
+//   type: "Property"; // inherited
+//   value: Pattern;
+//   kind: "init";
+//   method: false;
+// }
+#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignmentProperty {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub key: PropertyKey,
+    pub value: Pattern,
+    pub shorthand: bool,
+    pub computed: bool,
+}
+
+impl AssignmentProperty {
+    pub fn new(
+        key: PropertyKey,
+        value: Pattern,
+        shorthand: bool,
+        computed: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        AssignmentProperty {
+            loc,
+            key,
+            value,
+            shorthand,
+            computed,
+        }
+    }
+}
+
+impl Node for AssignmentProperty {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface AssignmentPattern <: Pattern {
+//   type: "AssignmentPattern";
+//   left: Pattern;
+//   right: Expression;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignmentPattern {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub left: Pattern,
+    pub right: Expression,
+}
+
+impl AssignmentPattern {
+    pub fn new(left: Pattern, right: Expression, loc: SourceLocation) -> Self {
+        AssignmentPattern { loc, left, right }
+    }
+}
+
+impl Node for AssignmentPattern {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface PrivateIdentifier <: Node {
+//   type: "PrivateIdentifier";
+//   name: string;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrivateIdentifier {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub name: String,
+}
+
+impl PrivateIdentifier {
+    pub fn new(name: String, loc: SourceLocation) -> Self {
+        PrivateIdentifier { loc, name }
+    }
+}
+
+impl Node for PrivateIdentifier {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface Class <: Node {
+//   id: Identifier | null;
+//   superClass: Expression | null;
+//   body: ClassBody;
+// }
+pub trait Class {}
+
+// interface ClassBody <: Node {
+//   type: "ClassBody";
+//   body: [ MethodDefinition | PropertyDefinition | StaticBlock ];
+// }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClassElement {
+    MethodDefinition(MethodDefinition),
+    PropertyDefinition(PropertyDefinition),
+    StaticBlock(StaticBlock),
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassBody {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub body: Vec<ClassElement>,
+}
+
+impl ClassBody {
+    pub fn new(body: Vec<ClassElement>, loc: SourceLocation) -> Self {
+        ClassBody { loc, body }
+    }
+}
+
+impl Node for ClassBody {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface MethodDefinition <: Node {
+//   type: "MethodDefinition";
+//   key: Expression | PrivateIdentifier;
+//   value: FunctionExpression;
+//   kind: "constructor" | "method" | "get" | "set";
+//   computed: boolean;
+//   static: boolean;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum MethodDefinitionKind {
+    Constructor,
+    Method,
+    Get,
+    Set,
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodDefinition {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub key: Expression,
+    pub value: FunctionExpression,
+    pub kind: MethodDefinitionKind,
+    pub computed: bool,
+    #[cfg_attr(feature = "serde", serde(rename = "static"))]
+    pub is_static: bool,
+}
+
+impl MethodDefinition {
+    pub fn new(
+        key: Expression,
+        value: FunctionExpression,
+        kind: MethodDefinitionKind,
+        computed: bool,
+        is_static: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        MethodDefinition {
+            loc,
+            key,
+            value,
+            kind,
+            computed,
+            is_static,
+        }
+    }
+
+    pub fn is_constructor(&self) -> bool {
+        matches!(self.kind, MethodDefinitionKind::Constructor)
+    }
+}
+
+impl Node for MethodDefinition {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface PropertyDefinition <: Node {
+//   type: "PropertyDefinition";
+//   key: Expression | PrivateIdentifier;
+//   value: Expression | null;
+//   computed: boolean;
+//   static: boolean;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyDefinition {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub key: Expression,
+    pub value: Option<Expression>,
+    pub computed: bool,
+    #[cfg_attr(feature = "serde", serde(rename = "static"))]
+    pub is_static: bool,
+}
+
+impl PropertyDefinition {
+    pub fn new(
+        key: Expression,
+        value: Option<Expression>,
+        computed: bool,
+        is_static: bool,
+        loc: SourceLocation,
+    ) -> Self {
+        PropertyDefinition {
+            loc,
+            key,
+            value,
+            computed,
+            is_static,
+        }
+    }
+}
+
+impl Node for PropertyDefinition {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface StaticBlock <: BlockStatement {
+//   type: "StaticBlock";
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaticBlock {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub body: Vec<Statement>,
+}
+
+impl StaticBlock {
+    pub fn new(body: Vec<Statement>, loc: SourceLocation) -> Self {
+        StaticBlock { loc, body }
+    }
+}
+
+impl Node for StaticBlock {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+// interface ClassDeclaration <: Class, Declaration {
+//   type: "ClassDeclaration";
+//   id: Identifier | null; // null only under `export default`
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassDeclaration {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub id: Option<Identifier>,
+    #[cfg_attr(feature = "serde", serde(rename = "superClass"))]
+    pub super_class: Option<Expression>,
+    pub body: ClassBody,
+}
+
+impl ClassDeclaration {
+    pub fn new(
+        id: Option<Identifier>,
+        super_class: Option<Expression>,
+        body: ClassBody,
+        loc: SourceLocation,
+    ) -> Self {
+        ClassDeclaration {
+            loc,
+            id,
+            super_class,
+            body,
+        }
+    }
+}
+
+impl Node for ClassDeclaration {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl Class for ClassDeclaration {}
+
+impl Declaration for ClassDeclaration {}
+
+// interface ClassExpression <: Class, Expression {
+//   type: "ClassExpression";
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassExpression {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub id: Option<Identifier>,
+    #[cfg_attr(feature = "serde", serde(rename = "superClass"))]
+    pub super_class: Option<Expression>,
+    pub body: ClassBody,
+}
+
+impl ClassExpression {
+    pub fn new(
+        id: Option<Identifier>,
+        super_class: Option<Expression>,
+        body: ClassBody,
+        loc: SourceLocation,
+    ) -> Self {
+        ClassExpression {
+            loc,
+            id,
+            super_class,
+            body,
+        }
+    }
+}
+
+impl Node for ClassExpression {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl Class for ClassExpression {}
+
+// interface ModuleDeclaration <: Node { }
+pub trait ModuleDeclaration {}
+
+// interface ModuleSpecifier <: Node {
+//   local: Identifier;
+// }
+pub trait ModuleSpecifier {}
+
+// interface ImportDeclaration <: ModuleDeclaration {
+//   type: "ImportDeclaration";
+//   specifiers: [ ImportSpecifier | ImportDefaultSpecifier | ImportNamespaceSpecifier ];
+//   source: Literal;
+// }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportDeclarationSpecifier {
+    ImportSpecifier(ImportSpecifier),
+    ImportDefaultSpecifier(ImportDefaultSpecifier),
+    ImportNamespaceSpecifier(ImportNamespaceSpecifier),
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportDeclaration {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub specifiers: Vec<ImportDeclarationSpecifier>,
+    pub source: Literal,
+}
+
+impl ImportDeclaration {
+    pub fn new(
+        specifiers: Vec<ImportDeclarationSpecifier>,
+        source: Literal,
+        loc: SourceLocation,
+    ) -> Self {
+        ImportDeclaration {
+            loc,
+            specifiers,
+            source,
+        }
+    }
+}
+
+impl Node for ImportDeclaration {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleDeclaration for ImportDeclaration {}
+
+// interface ImportSpecifier <: ModuleSpecifier {
+//   type: "ImportSpecifier";
+//   imported: Identifier;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSpecifier {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub imported: Identifier,
+    pub local: Identifier,
+}
+
+impl ImportSpecifier {
+    pub fn new(imported: Identifier, local: Identifier, loc: SourceLocation) -> Self {
+        ImportSpecifier {
+            loc,
+            imported,
+            local,
+        }
+    }
+}
+
+impl Node for ImportSpecifier {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleSpecifier for ImportSpecifier {}
+
+// interface ImportDefaultSpecifier <: ModuleSpecifier {
+//   type: "ImportDefaultSpecifier";
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportDefaultSpecifier {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub local: Identifier,
+}
+
+impl ImportDefaultSpecifier {
+    pub fn new(local: Identifier, loc: SourceLocation) -> Self {
+        ImportDefaultSpecifier { loc, local }
+    }
+}
+
+impl Node for ImportDefaultSpecifier {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleSpecifier for ImportDefaultSpecifier {}
+
+// interface ImportNamespaceSpecifier <: ModuleSpecifier {
+//   type: "ImportNamespaceSpecifier";
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportNamespaceSpecifier {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub local: Identifier,
+}
+
+impl ImportNamespaceSpecifier {
+    pub fn new(local: Identifier, loc: SourceLocation) -> Self {
+        ImportNamespaceSpecifier { loc, local }
+    }
+}
+
+impl Node for ImportNamespaceSpecifier {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleSpecifier for ImportNamespaceSpecifier {}
+
+// interface ExportNamedDeclaration <: ModuleDeclaration {
+//   type: "ExportNamedDeclaration";
+//   declaration: Declaration | null;
+//   specifiers: [ ExportSpecifier ];
+//   source: Literal | null;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportNamedDeclaration {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub declaration: Option<Statement>,
+    pub specifiers: Vec<ExportSpecifier>,
+    pub source: Option<Literal>,
+}
+
+impl ExportNamedDeclaration {
+    pub fn new(
+        declaration: Option<Statement>,
+        specifiers: Vec<ExportSpecifier>,
+        source: Option<Literal>,
+        loc: SourceLocation,
+    ) -> Self {
+        ExportNamedDeclaration {
+            loc,
+            declaration,
+            specifiers,
+            source,
+        }
+    }
+}
+
+impl Node for ExportNamedDeclaration {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleDeclaration for ExportNamedDeclaration {}
+
+// interface ExportSpecifier <: ModuleSpecifier {
+//   type: "ExportSpecifier";
+//   exported: Identifier;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportSpecifier {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub local: Identifier,
+    pub exported: Identifier,
+}
+
+impl ExportSpecifier {
+    pub fn new(local: Identifier, exported: Identifier, loc: SourceLocation) -> Self {
+        ExportSpecifier {
+            loc,
+            local,
+            exported,
+        }
+    }
+}
+
+impl Node for ExportSpecifier {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleSpecifier for ExportSpecifier {}
+
+// interface ExportDefaultDeclaration <: ModuleDeclaration {
+//   type: "ExportDefaultDeclaration";
+//   declaration: AnonymousDefaultExportedFunctionDeclaration | FunctionDeclaration | AnonymousDefaultExportedClassDeclaration | ClassDeclaration | Expression;
+// }
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportDefaultDeclarationKind {
+    FunctionDeclaration(FunctionDeclaration),
+    ClassDeclaration(ClassDeclaration),
+    Expression(Expression),
+}
+
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportDefaultDeclaration {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub declaration: ExportDefaultDeclarationKind,
+}
+
+impl ExportDefaultDeclaration {
+    pub fn new(declaration: ExportDefaultDeclarationKind, loc: SourceLocation) -> Self {
+        ExportDefaultDeclaration { loc, declaration }
+    }
+}
+
+impl Node for ExportDefaultDeclaration {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleDeclaration for ExportDefaultDeclaration {}
+
+// interface ExportAllDeclaration <: ModuleDeclaration {
+//   type: "ExportAllDeclaration";
+//   exported: Identifier | null;
+//   source: Literal;
+// }
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "type")
+)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportAllDeclaration {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub loc: SourceLocation,
+    pub exported: Option<Identifier>,
+    pub source: Literal,
+}
+
+impl ExportAllDeclaration {
+    pub fn new(exported: Option<Identifier>, source: Literal, loc: SourceLocation) -> Self {
+        ExportAllDeclaration {
+            loc,
+            exported,
+            source,
+        }
+    }
+}
+
+impl Node for ExportAllDeclaration {
+    fn loc(&self) -> &SourceLocation {
+        &self.loc
+    }
+}
+
+impl ModuleDeclaration for ExportAllDeclaration {}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    #[test]
+    fn deep_trees_compare_clone_and_print_without_overflowing() {
+        let src = format!("x = a{};", " + a".repeat(10_000));
+        let program = parse(&src).unwrap();
+        let copy = program.clone();
+        assert_eq!(copy, program);
+        assert!(format!("{:?}", copy).contains("Binary(BinaryExpression"));
+    }
+}
@@ -0,0 +1,2119 @@
+// Borrowed references to nodes of any kind, for traversals too small to
+// deserve a `Visitor`. The children of a node are found by walking it with a
+// visitor that stops at each child, so they come in the same order, and
+// cover the same fields, as a `Visitor` would visit them.
+use crate::{
+    node::{
+        ArrayExpression, ArrayPattern, ArrowFunctionExpression, AssignmentExpression,
+        AssignmentPattern, AssignmentProperty, AwaitExpression, BinaryExpression, BlockStatement,
+        BreakStatement, CallExpression, CatchClause, ChainExpression, ClassBody, ClassDeclaration,
+        ClassExpression, ConditionalExpression, ContinueStatement, DebuggerStatement, Directive,
+        DoWhileStatement, EmptyStatement, ErrorNode, ExportAllDeclaration,
+        ExportDefaultDeclaration, ExportNamedDeclaration, ExportSpecifier, Expression,
+        ExpressionStatement, ForInStatement, ForOfStatement, ForStatement, FunctionBody,
+        FunctionDeclaration, FunctionExpression, Identifier, IfStatement, ImportDeclaration,
+        ImportDefaultSpecifier, ImportExpression, ImportNamespaceSpecifier, ImportSpecifier,
+        LabeledStatement, Literal, LogicalExpression, MemberExpression, MetaProperty,
+        MethodDefinition, NewExpression, Node, ObjectExpression, ObjectPattern,
+        ParenthesizedExpression, Pattern, PrivateIdentifier, Program, Property, PropertyDefinition,
+        RestElement, ReturnStatement, SequenceExpression, SourceLocation, SpreadElement, Statement,
+        StaticBlock, Super, SwitchCase, SwitchStatement, TaggedTemplateExpression, TemplateElement,
+        TemplateLiteral, ThisExpression, ThrowStatement, TryStatement, UnaryExpression,
+        UpdateExpression, VariableDeclaration, VariableDeclarator, WhileStatement, WithStatement,
+        YieldExpression,
+    },
+    visit::{self, Visitor},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodeRef<'a> {
+    Program(&'a Program),
+    Identifier(&'a Identifier),
+    Literal(&'a Literal),
+    ExpressionStatement(&'a ExpressionStatement),
+    Directive(&'a Directive),
+    BlockStatement(&'a BlockStatement),
+    FunctionBody(&'a FunctionBody),
+    EmptyStatement(&'a EmptyStatement),
+    DebuggerStatement(&'a DebuggerStatement),
+    WithStatement(&'a WithStatement),
+    ReturnStatement(&'a ReturnStatement),
+    LabeledStatement(&'a LabeledStatement),
+    BreakStatement(&'a BreakStatement),
+    ContinueStatement(&'a ContinueStatement),
+    IfStatement(&'a IfStatement),
+    SwitchStatement(&'a SwitchStatement),
+    SwitchCase(&'a SwitchCase),
+    ThrowStatement(&'a ThrowStatement),
+    TryStatement(&'a TryStatement),
+    CatchClause(&'a CatchClause),
+    WhileStatement(&'a WhileStatement),
+    DoWhileStatement(&'a DoWhileStatement),
+    ForStatement(&'a ForStatement),
+    ForInStatement(&'a ForInStatement),
+    ForOfStatement(&'a ForOfStatement),
+    FunctionDeclaration(&'a FunctionDeclaration),
+    VariableDeclaration(&'a VariableDeclaration),
+    VariableDeclarator(&'a VariableDeclarator),
+    ThisExpression(&'a ThisExpression),
+    Super(&'a Super),
+    ArrayExpression(&'a ArrayExpression),
+    ObjectExpression(&'a ObjectExpression),
+    Property(&'a Property),
+    FunctionExpression(&'a FunctionExpression),
+    ArrowFunctionExpression(&'a ArrowFunctionExpression),
+    ClassExpression(&'a ClassExpression),
+    UnaryExpression(&'a UnaryExpression),
+    UpdateExpression(&'a UpdateExpression),
+    BinaryExpression(&'a BinaryExpression),
+    AssignmentExpression(&'a AssignmentExpression),
+    LogicalExpression(&'a LogicalExpression),
+    MemberExpression(&'a MemberExpression),
+    ConditionalExpression(&'a ConditionalExpression),
+    CallExpression(&'a CallExpression),
+    ChainExpression(&'a ChainExpression),
+    YieldExpression(&'a YieldExpression),
+    TemplateLiteral(&'a TemplateLiteral),
+    TaggedTemplateExpression(&'a TaggedTemplateExpression),
+    TemplateElement(&'a TemplateElement),
+    SpreadElement(&'a SpreadElement),
+    NewExpression(&'a NewExpression),
+    MetaProperty(&'a MetaProperty),
+    ImportExpression(&'a ImportExpression),
+    SequenceExpression(&'a SequenceExpression),
+    ParenthesizedExpression(&'a ParenthesizedExpression),
+    AwaitExpression(&'a AwaitExpression),
+    PrivateIdentifier(&'a PrivateIdentifier),
+    ObjectPattern(&'a ObjectPattern),
+    ArrayPattern(&'a ArrayPattern),
+    RestElement(&'a RestElement),
+    AssignmentProperty(&'a AssignmentProperty),
+    AssignmentPattern(&'a AssignmentPattern),
+    ClassBody(&'a ClassBody),
+    MethodDefinition(&'a MethodDefinition),
+    PropertyDefinition(&'a PropertyDefinition),
+    StaticBlock(&'a StaticBlock),
+    ClassDeclaration(&'a ClassDeclaration),
+    ImportDeclaration(&'a ImportDeclaration),
+    ImportSpecifier(&'a ImportSpecifier),
+    ImportDefaultSpecifier(&'a ImportDefaultSpecifier),
+    ImportNamespaceSpecifier(&'a ImportNamespaceSpecifier),
+    ExportNamedDeclaration(&'a ExportNamedDeclaration),
+    ExportSpecifier(&'a ExportSpecifier),
+    ExportDefaultDeclaration(&'a ExportDefaultDeclaration),
+    ExportAllDeclaration(&'a ExportAllDeclaration),
+    ErrorNode(&'a ErrorNode),
+}
+
+impl<'a> NodeRef<'a> {
+    // the `type` ESTree gives the node
+    pub fn node_type(&self) -> &'static str {
+        match self {
+            NodeRef::Program(_) => "Program",
+            NodeRef::Identifier(_) => "Identifier",
+            NodeRef::Literal(_) => "Literal",
+            NodeRef::ExpressionStatement(_) => "ExpressionStatement",
+            NodeRef::Directive(_) => "ExpressionStatement",
+            NodeRef::BlockStatement(_) => "BlockStatement",
+            NodeRef::FunctionBody(_) => "BlockStatement",
+            NodeRef::EmptyStatement(_) => "EmptyStatement",
+            NodeRef::DebuggerStatement(_) => "DebuggerStatement",
+            NodeRef::WithStatement(_) => "WithStatement",
+            NodeRef::ReturnStatement(_) => "ReturnStatement",
+            NodeRef::LabeledStatement(_) => "LabeledStatement",
+            NodeRef::BreakStatement(_) => "BreakStatement",
+            NodeRef::ContinueStatement(_) => "ContinueStatement",
+            NodeRef::IfStatement(_) => "IfStatement",
+            NodeRef::SwitchStatement(_) => "SwitchStatement",
+            NodeRef::SwitchCase(_) => "SwitchCase",
+            NodeRef::ThrowStatement(_) => "ThrowStatement",
+            NodeRef::TryStatement(_) => "TryStatement",
+            NodeRef::CatchClause(_) => "CatchClause",
+            NodeRef::WhileStatement(_) => "WhileStatement",
+            NodeRef::DoWhileStatement(_) => "DoWhileStatement",
+            NodeRef::ForStatement(_) => "ForStatement",
+            NodeRef::ForInStatement(_) => "ForInStatement",
+            NodeRef::ForOfStatement(_) => "ForOfStatement",
+            NodeRef::FunctionDeclaration(_) => "FunctionDeclaration",
+            NodeRef::VariableDeclaration(_) => "VariableDeclaration",
+            NodeRef::VariableDeclarator(_) => "VariableDeclarator",
+            NodeRef::ThisExpression(_) => "ThisExpression",
+            NodeRef::Super(_) => "Super",
+            NodeRef::ArrayExpression(_) => "ArrayExpression",
+            NodeRef::ObjectExpression(_) => "ObjectExpression",
+            NodeRef::Property(_) => "Property",
+            NodeRef::FunctionExpression(_) => "FunctionExpression",
+            NodeRef::ArrowFunctionExpression(_) => "ArrowFunctionExpression",
+            NodeRef::ClassExpression(_) => "ClassExpression",
+            NodeRef::UnaryExpression(_) => "UnaryExpression",
+            NodeRef::UpdateExpression(_) => "UpdateExpression",
+            NodeRef::BinaryExpression(_) => "BinaryExpression",
+            NodeRef::AssignmentExpression(_) => "AssignmentExpression",
+            NodeRef::LogicalExpression(_) => "LogicalExpression",
+            NodeRef::MemberExpression(_) => "MemberExpression",
+            NodeRef::ConditionalExpression(_) => "ConditionalExpression",
+            NodeRef::CallExpression(_) => "CallExpression",
+            NodeRef::ChainExpression(_) => "ChainExpression",
+            NodeRef::YieldExpression(_) => "YieldExpression",
+            NodeRef::TemplateLiteral(_) => "TemplateLiteral",
+            NodeRef::TaggedTemplateExpression(_) => "TaggedTemplateExpression",
+            NodeRef::TemplateElement(_) => "TemplateElement",
+            NodeRef::SpreadElement(_) => "SpreadElement",
+            NodeRef::NewExpression(_) => "NewExpression",
+            NodeRef::MetaProperty(_) => "MetaProperty",
+            NodeRef::ImportExpression(_) => "ImportExpression",
+            NodeRef::SequenceExpression(_) => "SequenceExpression",
+            NodeRef::ParenthesizedExpression(_) => "ParenthesizedExpression",
+            NodeRef::AwaitExpression(_) => "AwaitExpression",
+            NodeRef::PrivateIdentifier(_) => "PrivateIdentifier",
+            NodeRef::ObjectPattern(_) => "ObjectPattern",
+            NodeRef::ArrayPattern(_) => "ArrayPattern",
+            NodeRef::RestElement(_) => "RestElement",
+            NodeRef::AssignmentProperty(_) => "Property",
+            NodeRef::AssignmentPattern(_) => "AssignmentPattern",
+            NodeRef::ClassBody(_) => "ClassBody",
+            NodeRef::MethodDefinition(_) => "MethodDefinition",
+            NodeRef::PropertyDefinition(_) => "PropertyDefinition",
+            NodeRef::StaticBlock(_) => "StaticBlock",
+            NodeRef::ClassDeclaration(_) => "ClassDeclaration",
+            NodeRef::ImportDeclaration(_) => "ImportDeclaration",
+            NodeRef::ImportSpecifier(_) => "ImportSpecifier",
+            NodeRef::ImportDefaultSpecifier(_) => "ImportDefaultSpecifier",
+            NodeRef::ImportNamespaceSpecifier(_) => "ImportNamespaceSpecifier",
+            NodeRef::ExportNamedDeclaration(_) => "ExportNamedDeclaration",
+            NodeRef::ExportSpecifier(_) => "ExportSpecifier",
+            NodeRef::ExportDefaultDeclaration(_) => "ExportDefaultDeclaration",
+            NodeRef::ExportAllDeclaration(_) => "ExportAllDeclaration",
+            NodeRef::ErrorNode(_) => "ErrorNode",
+        }
+    }
+
+    // the address of the node, which tells apart equal nodes in different
+    // places of the tree
+    pub fn as_ptr(&self) -> *const () {
+        match self {
+            NodeRef::Program(node) => *node as *const Program as *const (),
+            NodeRef::Identifier(node) => *node as *const Identifier as *const (),
+            NodeRef::Literal(node) => *node as *const Literal as *const (),
+            NodeRef::ExpressionStatement(node) => *node as *const ExpressionStatement as *const (),
+            NodeRef::Directive(node) => *node as *const Directive as *const (),
+            NodeRef::BlockStatement(node) => *node as *const BlockStatement as *const (),
+            NodeRef::FunctionBody(node) => *node as *const FunctionBody as *const (),
+            NodeRef::EmptyStatement(node) => *node as *const EmptyStatement as *const (),
+            NodeRef::DebuggerStatement(node) => *node as *const DebuggerStatement as *const (),
+            NodeRef::WithStatement(node) => *node as *const WithStatement as *const (),
+            NodeRef::ReturnStatement(node) => *node as *const ReturnStatement as *const (),
+            NodeRef::LabeledStatement(node) => *node as *const LabeledStatement as *const (),
+            NodeRef::BreakStatement(node) => *node as *const BreakStatement as *const (),
+            NodeRef::ContinueStatement(node) => *node as *const ContinueStatement as *const (),
+            NodeRef::IfStatement(node) => *node as *const IfStatement as *const (),
+            NodeRef::SwitchStatement(node) => *node as *const SwitchStatement as *const (),
+            NodeRef::SwitchCase(node) => *node as *const SwitchCase as *const (),
+            NodeRef::ThrowStatement(node) => *node as *const ThrowStatement as *const (),
+            NodeRef::TryStatement(node) => *node as *const TryStatement as *const (),
+            NodeRef::CatchClause(node) => *node as *const CatchClause as *const (),
+            NodeRef::WhileStatement(node) => *node as *const WhileStatement as *const (),
+            NodeRef::DoWhileStatement(node) => *node as *const DoWhileStatement as *const (),
+            NodeRef::ForStatement(node) => *node as *const ForStatement as *const (),
+            NodeRef::ForInStatement(node) => *node as *const ForInStatement as *const (),
+            NodeRef::ForOfStatement(node) => *node as *const ForOfStatement as *const (),
+            NodeRef::FunctionDeclaration(node) => *node as *const FunctionDeclaration as *const (),
+            NodeRef::VariableDeclaration(node) => *node as *const VariableDeclaration as *const (),
+            NodeRef::VariableDeclarator(node) => *node as *const VariableDeclarator as *const (),
+            NodeRef::ThisExpression(node) => *node as *const ThisExpression as *const (),
+            NodeRef::Super(node) => *node as *const Super as *const (),
+            NodeRef::ArrayExpression(node) => *node as *const ArrayExpression as *const (),
+            NodeRef::ObjectExpression(node) => *node as *const ObjectExpression as *const (),
+            NodeRef::Property(node) => *node as *const Property as *const (),
+            NodeRef::FunctionExpression(node) => *node as *const FunctionExpression as *const (),
+            NodeRef::ArrowFunctionExpression(node) => {
+                *node as *const ArrowFunctionExpression as *const ()
+            }
+            NodeRef::ClassExpression(node) => *node as *const ClassExpression as *const (),
+            NodeRef::UnaryExpression(node) => *node as *const UnaryExpression as *const (),
+            NodeRef::UpdateExpression(node) => *node as *const UpdateExpression as *const (),
+            NodeRef::BinaryExpression(node) => *node as *const BinaryExpression as *const (),
+            NodeRef::AssignmentExpression(node) => {
+                *node as *const AssignmentExpression as *const ()
+            }
+            NodeRef::LogicalExpression(node) => *node as *const LogicalExpression as *const (),
+            NodeRef::MemberExpression(node) => *node as *const MemberExpression as *const (),
+            NodeRef::ConditionalExpression(node) => {
+                *node as *const ConditionalExpression as *const ()
+            }
+            NodeRef::CallExpression(node) => *node as *const CallExpression as *const (),
+            NodeRef::ChainExpression(node) => *node as *const ChainExpression as *const (),
+            NodeRef::YieldExpression(node) => *node as *const YieldExpression as *const (),
+            NodeRef::TemplateLiteral(node) => *node as *const TemplateLiteral as *const (),
+            NodeRef::TaggedTemplateExpression(node) => {
+                *node as *const TaggedTemplateExpression as *const ()
+            }
+            NodeRef::TemplateElement(node) => *node as *const TemplateElement as *const (),
+            NodeRef::SpreadElement(node) => *node as *const SpreadElement as *const (),
+            NodeRef::NewExpression(node) => *node as *const NewExpression as *const (),
+            NodeRef::MetaProperty(node) => *node as *const MetaProperty as *const (),
+            NodeRef::ImportExpression(node) => *node as *const ImportExpression as *const (),
+            NodeRef::SequenceExpression(node) => *node as *const SequenceExpression as *const (),
+            NodeRef::ParenthesizedExpression(node) => {
+                *node as *const ParenthesizedExpression as *const ()
+            }
+            NodeRef::AwaitExpression(node) => *node as *const AwaitExpression as *const (),
+            NodeRef::PrivateIdentifier(node) => *node as *const PrivateIdentifier as *const (),
+            NodeRef::ObjectPattern(node) => *node as *const ObjectPattern as *const (),
+            NodeRef::ArrayPattern(node) => *node as *const ArrayPattern as *const (),
+            NodeRef::RestElement(node) => *node as *const RestElement as *const (),
+            NodeRef::AssignmentProperty(node) => *node as *const AssignmentProperty as *const (),
+            NodeRef::AssignmentPattern(node) => *node as *const AssignmentPattern as *const (),
+            NodeRef::ClassBody(node) => *node as *const ClassBody as *const (),
+            NodeRef::MethodDefinition(node) => *node as *const MethodDefinition as *const (),
+            NodeRef::PropertyDefinition(node) => *node as *const PropertyDefinition as *const (),
+            NodeRef::StaticBlock(node) => *node as *const StaticBlock as *const (),
+            NodeRef::ClassDeclaration(node) => *node as *const ClassDeclaration as *const (),
+            NodeRef::ImportDeclaration(node) => *node as *const ImportDeclaration as *const (),
+            NodeRef::ImportSpecifier(node) => *node as *const ImportSpecifier as *const (),
+            NodeRef::ImportDefaultSpecifier(node) => {
+                *node as *const ImportDefaultSpecifier as *const ()
+            }
+            NodeRef::ImportNamespaceSpecifier(node) => {
+                *node as *const ImportNamespaceSpecifier as *const ()
+            }
+            NodeRef::ExportNamedDeclaration(node) => {
+                *node as *const ExportNamedDeclaration as *const ()
+            }
+            NodeRef::ExportSpecifier(node) => *node as *const ExportSpecifier as *const (),
+            NodeRef::ExportDefaultDeclaration(node) => {
+                *node as *const ExportDefaultDeclaration as *const ()
+            }
+            NodeRef::ExportAllDeclaration(node) => {
+                *node as *const ExportAllDeclaration as *const ()
+            }
+            NodeRef::ErrorNode(node) => *node as *const ErrorNode as *const (),
+        }
+    }
+
+    pub fn downcast<T: FromNodeRef>(self) -> Option<&'a T> {
+        T::from_node_ref(self)
+    }
+
+    pub fn children(&self) -> Vec<NodeRef<'a>> {
+        let mut children = Children(vec![]);
+        match *self {
+            NodeRef::Program(node) => visit::walk_program(&mut children, node),
+            NodeRef::ExpressionStatement(node) => {
+                visit::walk_expression_statement(&mut children, node)
+            }
+            NodeRef::Directive(node) => visit::walk_directive(&mut children, node),
+            NodeRef::BlockStatement(node) => visit::walk_block_statement(&mut children, node),
+            NodeRef::FunctionBody(node) => visit::walk_function_body(&mut children, node),
+            NodeRef::WithStatement(node) => visit::walk_with_statement(&mut children, node),
+            NodeRef::ReturnStatement(node) => visit::walk_return_statement(&mut children, node),
+            NodeRef::LabeledStatement(node) => visit::walk_labeled_statement(&mut children, node),
+            NodeRef::BreakStatement(node) => visit::walk_break_statement(&mut children, node),
+            NodeRef::ContinueStatement(node) => visit::walk_continue_statement(&mut children, node),
+            NodeRef::IfStatement(node) => visit::walk_if_statement(&mut children, node),
+            NodeRef::SwitchStatement(node) => visit::walk_switch_statement(&mut children, node),
+            NodeRef::SwitchCase(node) => visit::walk_switch_case(&mut children, node),
+            NodeRef::ThrowStatement(node) => visit::walk_throw_statement(&mut children, node),
+            NodeRef::TryStatement(node) => visit::walk_try_statement(&mut children, node),
+            NodeRef::CatchClause(node) => visit::walk_catch_clause(&mut children, node),
+            NodeRef::WhileStatement(node) => visit::walk_while_statement(&mut children, node),
+            NodeRef::DoWhileStatement(node) => visit::walk_do_while_statement(&mut children, node),
+            NodeRef::ForStatement(node) => visit::walk_for_statement(&mut children, node),
+            NodeRef::ForInStatement(node) => visit::walk_for_in_statement(&mut children, node),
+            NodeRef::ForOfStatement(node) => visit::walk_for_of_statement(&mut children, node),
+            NodeRef::FunctionDeclaration(node) => {
+                visit::walk_function_declaration(&mut children, node)
+            }
+            NodeRef::VariableDeclaration(node) => {
+                visit::walk_variable_declaration(&mut children, node)
+            }
+            NodeRef::VariableDeclarator(node) => {
+                visit::walk_variable_declarator(&mut children, node)
+            }
+            NodeRef::ArrayExpression(node) => visit::walk_array_expression(&mut children, node),
+            NodeRef::ObjectExpression(node) => visit::walk_object_expression(&mut children, node),
+            NodeRef::Property(node) => visit::walk_property(&mut children, node),
+            NodeRef::FunctionExpression(node) => {
+                visit::walk_function_expression(&mut children, node)
+            }
+            NodeRef::ArrowFunctionExpression(node) => {
+                visit::walk_arrow_function_expression(&mut children, node)
+            }
+            NodeRef::ClassExpression(node) => visit::walk_class_expression(&mut children, node),
+            NodeRef::UnaryExpression(node) => visit::walk_unary_expression(&mut children, node),
+            NodeRef::UpdateExpression(node) => visit::walk_update_expression(&mut children, node),
+            NodeRef::BinaryExpression(node) => visit::walk_binary_expression(&mut children, node),
+            NodeRef::AssignmentExpression(node) => {
+                visit::walk_assignment_expression(&mut children, node)
+            }
+            NodeRef::LogicalExpression(node) => visit::walk_logical_expression(&mut children, node),
+            NodeRef::MemberExpression(node) => visit::walk_member_expression(&mut children, node),
+            NodeRef::ConditionalExpression(node) => {
+                visit::walk_conditional_expression(&mut children, node)
+            }
+            NodeRef::CallExpression(node) => visit::walk_call_expression(&mut children, node),
+            NodeRef::ChainExpression(node) => visit::walk_chain_expression(&mut children, node),
+            NodeRef::YieldExpression(node) => visit::walk_yield_expression(&mut children, node),
+            NodeRef::TemplateLiteral(node) => visit::walk_template_literal(&mut children, node),
+            NodeRef::TaggedTemplateExpression(node) => {
+                visit::walk_tagged_template_expression(&mut children, node)
+            }
+            NodeRef::SpreadElement(node) => visit::walk_spread_element(&mut children, node),
+            NodeRef::NewExpression(node) => visit::walk_new_expression(&mut children, node),
+            NodeRef::MetaProperty(node) => visit::walk_meta_property(&mut children, node),
+            NodeRef::ImportExpression(node) => visit::walk_import_expression(&mut children, node),
+            NodeRef::SequenceExpression(node) => {
+                visit::walk_sequence_expression(&mut children, node)
+            }
+            NodeRef::ParenthesizedExpression(node) => {
+                visit::walk_parenthesized_expression(&mut children, node)
+            }
+            NodeRef::AwaitExpression(node) => visit::walk_await_expression(&mut children, node),
+            NodeRef::ObjectPattern(node) => visit::walk_object_pattern(&mut children, node),
+            NodeRef::ArrayPattern(node) => visit::walk_array_pattern(&mut children, node),
+            NodeRef::RestElement(node) => visit::walk_rest_element(&mut children, node),
+            NodeRef::AssignmentProperty(node) => {
+                visit::walk_assignment_property(&mut children, node)
+            }
+            NodeRef::AssignmentPattern(node) => visit::walk_assignment_pattern(&mut children, node),
+            NodeRef::ClassBody(node) => visit::walk_class_body(&mut children, node),
+            NodeRef::MethodDefinition(node) => visit::walk_method_definition(&mut children, node),
+            NodeRef::PropertyDefinition(node) => {
+                visit::walk_property_definition(&mut children, node)
+            }
+            NodeRef::StaticBlock(node) => visit::walk_static_block(&mut children, node),
+            NodeRef::ClassDeclaration(node) => visit::walk_class_declaration(&mut children, node),
+            NodeRef::ImportDeclaration(node) => visit::walk_import_declaration(&mut children, node),
+            NodeRef::ImportSpecifier(node) => visit::walk_import_specifier(&mut children, node),
+            NodeRef::ImportDefaultSpecifier(node) => {
+                visit::walk_import_default_specifier(&mut children, node)
+            }
+            NodeRef::ImportNamespaceSpecifier(node) => {
+                visit::walk_import_namespace_specifier(&mut children, node)
+            }
+            NodeRef::ExportNamedDeclaration(node) => {
+                visit::walk_export_named_declaration(&mut children, node)
+            }
+            NodeRef::ExportSpecifier(node) => visit::walk_export_specifier(&mut children, node),
+            NodeRef::ExportDefaultDeclaration(node) => {
+                visit::walk_export_default_declaration(&mut children, node)
+            }
+            NodeRef::ExportAllDeclaration(node) => {
+                visit::walk_export_all_declaration(&mut children, node)
+            }
+            // identifiers, literals and the like have no children
+            _ => {}
+        }
+        children.0
+    }
+}
+
+impl Node for NodeRef<'_> {
+    fn loc(&self) -> &SourceLocation {
+        match self {
+            NodeRef::Program(node) => node.loc(),
+            NodeRef::Identifier(node) => node.loc(),
+            NodeRef::Literal(node) => node.loc(),
+            NodeRef::ExpressionStatement(node) => node.loc(),
+            NodeRef::Directive(node) => node.loc(),
+            NodeRef::BlockStatement(node) => node.loc(),
+            NodeRef::FunctionBody(node) => node.loc(),
+            NodeRef::EmptyStatement(node) => node.loc(),
+            NodeRef::DebuggerStatement(node) => node.loc(),
+            NodeRef::WithStatement(node) => node.loc(),
+            NodeRef::ReturnStatement(node) => node.loc(),
+            NodeRef::LabeledStatement(node) => node.loc(),
+            NodeRef::BreakStatement(node) => node.loc(),
+            NodeRef::ContinueStatement(node) => node.loc(),
+            NodeRef::IfStatement(node) => node.loc(),
+            NodeRef::SwitchStatement(node) => node.loc(),
+            NodeRef::SwitchCase(node) => node.loc(),
+            NodeRef::ThrowStatement(node) => node.loc(),
+            NodeRef::TryStatement(node) => node.loc(),
+            NodeRef::CatchClause(node) => node.loc(),
+            NodeRef::WhileStatement(node) => node.loc(),
+            NodeRef::DoWhileStatement(node) => node.loc(),
+            NodeRef::ForStatement(node) => node.loc(),
+            NodeRef::ForInStatement(node) => node.loc(),
+            NodeRef::ForOfStatement(node) => node.loc(),
+            NodeRef::FunctionDeclaration(node) => node.loc(),
+            NodeRef::VariableDeclaration(node) => node.loc(),
+            NodeRef::VariableDeclarator(node) => node.loc(),
+            NodeRef::ThisExpression(node) => node.loc(),
+            NodeRef::Super(node) => node.loc(),
+            NodeRef::ArrayExpression(node) => node.loc(),
+            NodeRef::ObjectExpression(node) => node.loc(),
+            NodeRef::Property(node) => node.loc(),
+            NodeRef::FunctionExpression(node) => node.loc(),
+            NodeRef::ArrowFunctionExpression(node) => node.loc(),
+            NodeRef::ClassExpression(node) => node.loc(),
+            NodeRef::UnaryExpression(node) => node.loc(),
+            NodeRef::UpdateExpression(node) => node.loc(),
+            NodeRef::BinaryExpression(node) => node.loc(),
+            NodeRef::AssignmentExpression(node) => node.loc(),
+            NodeRef::LogicalExpression(node) => node.loc(),
+            NodeRef::MemberExpression(node) => node.loc(),
+            NodeRef::ConditionalExpression(node) => node.loc(),
+            NodeRef::CallExpression(node) => node.loc(),
+            NodeRef::ChainExpression(node) => node.loc(),
+            NodeRef::YieldExpression(node) => node.loc(),
+            NodeRef::TemplateLiteral(node) => node.loc(),
+            NodeRef::TaggedTemplateExpression(node) => node.loc(),
+            NodeRef::TemplateElement(node) => node.loc(),
+            NodeRef::SpreadElement(node) => node.loc(),
+            NodeRef::NewExpression(node) => node.loc(),
+            NodeRef::MetaProperty(node) => node.loc(),
+            NodeRef::ImportExpression(node) => node.loc(),
+            NodeRef::SequenceExpression(node) => node.loc(),
+            NodeRef::ParenthesizedExpression(node) => node.loc(),
+            NodeRef::AwaitExpression(node) => node.loc(),
+            NodeRef::PrivateIdentifier(node) => node.loc(),
+            NodeRef::ObjectPattern(node) => node.loc(),
+            NodeRef::ArrayPattern(node) => node.loc(),
+            NodeRef::RestElement(node) => node.loc(),
+            NodeRef::AssignmentProperty(node) => node.loc(),
+            NodeRef::AssignmentPattern(node) => node.loc(),
+            NodeRef::ClassBody(node) => node.loc(),
+            NodeRef::MethodDefinition(node) => node.loc(),
+            NodeRef::PropertyDefinition(node) => node.loc(),
+            NodeRef::StaticBlock(node) => node.loc(),
+            NodeRef::ClassDeclaration(node) => node.loc(),
+            NodeRef::ImportDeclaration(node) => node.loc(),
+            NodeRef::ImportSpecifier(node) => node.loc(),
+            NodeRef::ImportDefaultSpecifier(node) => node.loc(),
+            NodeRef::ImportNamespaceSpecifier(node) => node.loc(),
+            NodeRef::ExportNamedDeclaration(node) => node.loc(),
+            NodeRef::ExportSpecifier(node) => node.loc(),
+            NodeRef::ExportDefaultDeclaration(node) => node.loc(),
+            NodeRef::ExportAllDeclaration(node) => node.loc(),
+            NodeRef::ErrorNode(node) => node.loc(),
+        }
+    }
+}
+
+impl<'a> From<&'a Program> for NodeRef<'a> {
+    fn from(node: &'a Program) -> Self {
+        NodeRef::Program(node)
+    }
+}
+
+impl<'a> From<&'a Identifier> for NodeRef<'a> {
+    fn from(node: &'a Identifier) -> Self {
+        NodeRef::Identifier(node)
+    }
+}
+
+impl<'a> From<&'a Literal> for NodeRef<'a> {
+    fn from(node: &'a Literal) -> Self {
+        NodeRef::Literal(node)
+    }
+}
+
+impl<'a> From<&'a ExpressionStatement> for NodeRef<'a> {
+    fn from(node: &'a ExpressionStatement) -> Self {
+        NodeRef::ExpressionStatement(node)
+    }
+}
+
+impl<'a> From<&'a Directive> for NodeRef<'a> {
+    fn from(node: &'a Directive) -> Self {
+        NodeRef::Directive(node)
+    }
+}
+
+impl<'a> From<&'a BlockStatement> for NodeRef<'a> {
+    fn from(node: &'a BlockStatement) -> Self {
+        NodeRef::BlockStatement(node)
+    }
+}
+
+impl<'a> From<&'a FunctionBody> for NodeRef<'a> {
+    fn from(node: &'a FunctionBody) -> Self {
+        NodeRef::FunctionBody(node)
+    }
+}
+
+impl<'a> From<&'a EmptyStatement> for NodeRef<'a> {
+    fn from(node: &'a EmptyStatement) -> Self {
+        NodeRef::EmptyStatement(node)
+    }
+}
+
+impl<'a> From<&'a DebuggerStatement> for NodeRef<'a> {
+    fn from(node: &'a DebuggerStatement) -> Self {
+        NodeRef::DebuggerStatement(node)
+    }
+}
+
+impl<'a> From<&'a WithStatement> for NodeRef<'a> {
+    fn from(node: &'a WithStatement) -> Self {
+        NodeRef::WithStatement(node)
+    }
+}
+
+impl<'a> From<&'a ReturnStatement> for NodeRef<'a> {
+    fn from(node: &'a ReturnStatement) -> Self {
+        NodeRef::ReturnStatement(node)
+    }
+}
+
+impl<'a> From<&'a LabeledStatement> for NodeRef<'a> {
+    fn from(node: &'a LabeledStatement) -> Self {
+        NodeRef::LabeledStatement(node)
+    }
+}
+
+impl<'a> From<&'a BreakStatement> for NodeRef<'a> {
+    fn from(node: &'a BreakStatement) -> Self {
+        NodeRef::BreakStatement(node)
+    }
+}
+
+impl<'a> From<&'a ContinueStatement> for NodeRef<'a> {
+    fn from(node: &'a ContinueStatement) -> Self {
+        NodeRef::ContinueStatement(node)
+    }
+}
+
+impl<'a> From<&'a IfStatement> for NodeRef<'a> {
+    fn from(node: &'a IfStatement) -> Self {
+        NodeRef::IfStatement(node)
+    }
+}
+
+impl<'a> From<&'a SwitchStatement> for NodeRef<'a> {
+    fn from(node: &'a SwitchStatement) -> Self {
+        NodeRef::SwitchStatement(node)
+    }
+}
+
+impl<'a> From<&'a SwitchCase> for NodeRef<'a> {
+    fn from(node: &'a SwitchCase) -> Self {
+        NodeRef::SwitchCase(node)
+    }
+}
+
+impl<'a> From<&'a ThrowStatement> for NodeRef<'a> {
+    fn from(node: &'a ThrowStatement) -> Self {
+        NodeRef::ThrowStatement(node)
+    }
+}
+
+impl<'a> From<&'a TryStatement> for NodeRef<'a> {
+    fn from(node: &'a TryStatement) -> Self {
+        NodeRef::TryStatement(node)
+    }
+}
+
+impl<'a> From<&'a CatchClause> for NodeRef<'a> {
+    fn from(node: &'a CatchClause) -> Self {
+        NodeRef::CatchClause(node)
+    }
+}
+
+impl<'a> From<&'a WhileStatement> for NodeRef<'a> {
+    fn from(node: &'a WhileStatement) -> Self {
+        NodeRef::WhileStatement(node)
+    }
+}
+
+impl<'a> From<&'a DoWhileStatement> for NodeRef<'a> {
+    fn from(node: &'a DoWhileStatement) -> Self {
+        NodeRef::DoWhileStatement(node)
+    }
+}
+
+impl<'a> From<&'a ForStatement> for NodeRef<'a> {
+    fn from(node: &'a ForStatement) -> Self {
+        NodeRef::ForStatement(node)
+    }
+}
+
+impl<'a> From<&'a ForInStatement> for NodeRef<'a> {
+    fn from(node: &'a ForInStatement) -> Self {
+        NodeRef::ForInStatement(node)
+    }
+}
+
+impl<'a> From<&'a ForOfStatement> for NodeRef<'a> {
+    fn from(node: &'a ForOfStatement) -> Self {
+        NodeRef::ForOfStatement(node)
+    }
+}
+
+impl<'a> From<&'a FunctionDeclaration> for NodeRef<'a> {
+    fn from(node: &'a FunctionDeclaration) -> Self {
+        NodeRef::FunctionDeclaration(node)
+    }
+}
+
+impl<'a> From<&'a VariableDeclaration> for NodeRef<'a> {
+    fn from(node: &'a VariableDeclaration) -> Self {
+        NodeRef::VariableDeclaration(node)
+    }
+}
+
+impl<'a> From<&'a VariableDeclarator> for NodeRef<'a> {
+    fn from(node: &'a VariableDeclarator) -> Self {
+        NodeRef::VariableDeclarator(node)
+    }
+}
+
+impl<'a> From<&'a ThisExpression> for NodeRef<'a> {
+    fn from(node: &'a ThisExpression) -> Self {
+        NodeRef::ThisExpression(node)
+    }
+}
+
+impl<'a> From<&'a Super> for NodeRef<'a> {
+    fn from(node: &'a Super) -> Self {
+        NodeRef::Super(node)
+    }
+}
+
+impl<'a> From<&'a ArrayExpression> for NodeRef<'a> {
+    fn from(node: &'a ArrayExpression) -> Self {
+        NodeRef::ArrayExpression(node)
+    }
+}
+
+impl<'a> From<&'a ObjectExpression> for NodeRef<'a> {
+    fn from(node: &'a ObjectExpression) -> Self {
+        NodeRef::ObjectExpression(node)
+    }
+}
+
+impl<'a> From<&'a Property> for NodeRef<'a> {
+    fn from(node: &'a Property) -> Self {
+        NodeRef::Property(node)
+    }
+}
+
+impl<'a> From<&'a FunctionExpression> for NodeRef<'a> {
+    fn from(node: &'a FunctionExpression) -> Self {
+        NodeRef::FunctionExpression(node)
+    }
+}
+
+impl<'a> From<&'a ArrowFunctionExpression> for NodeRef<'a> {
+    fn from(node: &'a ArrowFunctionExpression) -> Self {
+        NodeRef::ArrowFunctionExpression(node)
+    }
+}
+
+impl<'a> From<&'a ClassExpression> for NodeRef<'a> {
+    fn from(node: &'a ClassExpression) -> Self {
+        NodeRef::ClassExpression(node)
+    }
+}
+
+impl<'a> From<&'a UnaryExpression> for NodeRef<'a> {
+    fn from(node: &'a UnaryExpression) -> Self {
+        NodeRef::UnaryExpression(node)
+    }
+}
+
+impl<'a> From<&'a UpdateExpression> for NodeRef<'a> {
+    fn from(node: &'a UpdateExpression) -> Self {
+        NodeRef::UpdateExpression(node)
+    }
+}
+
+impl<'a> From<&'a BinaryExpression> for NodeRef<'a> {
+    fn from(node: &'a BinaryExpression) -> Self {
+        NodeRef::BinaryExpression(node)
+    }
+}
+
+impl<'a> From<&'a AssignmentExpression> for NodeRef<'a> {
+    fn from(node: &'a AssignmentExpression) -> Self {
+        NodeRef::AssignmentExpression(node)
+    }
+}
+
+impl<'a> From<&'a LogicalExpression> for NodeRef<'a> {
+    fn from(node: &'a LogicalExpression) -> Self {
+        NodeRef::LogicalExpression(node)
+    }
+}
+
+impl<'a> From<&'a MemberExpression> for NodeRef<'a> {
+    fn from(node: &'a MemberExpression) -> Self {
+        NodeRef::MemberExpression(node)
+    }
+}
+
+impl<'a> From<&'a ConditionalExpression> for NodeRef<'a> {
+    fn from(node: &'a ConditionalExpression) -> Self {
+        NodeRef::ConditionalExpression(node)
+    }
+}
+
+impl<'a> From<&'a CallExpression> for NodeRef<'a> {
+    fn from(node: &'a CallExpression) -> Self {
+        NodeRef::CallExpression(node)
+    }
+}
+
+impl<'a> From<&'a ChainExpression> for NodeRef<'a> {
+    fn from(node: &'a ChainExpression) -> Self {
+        NodeRef::ChainExpression(node)
+    }
+}
+
+impl<'a> From<&'a YieldExpression> for NodeRef<'a> {
+    fn from(node: &'a YieldExpression) -> Self {
+        NodeRef::YieldExpression(node)
+    }
+}
+
+impl<'a> From<&'a TemplateLiteral> for NodeRef<'a> {
+    fn from(node: &'a TemplateLiteral) -> Self {
+        NodeRef::TemplateLiteral(node)
+    }
+}
+
+impl<'a> From<&'a TaggedTemplateExpression> for NodeRef<'a> {
+    fn from(node: &'a TaggedTemplateExpression) -> Self {
+        NodeRef::TaggedTemplateExpression(node)
+    }
+}
+
+impl<'a> From<&'a TemplateElement> for NodeRef<'a> {
+    fn from(node: &'a TemplateElement) -> Self {
+        NodeRef::TemplateElement(node)
+    }
+}
+
+impl<'a> From<&'a SpreadElement> for NodeRef<'a> {
+    fn from(node: &'a SpreadElement) -> Self {
+        NodeRef::SpreadElement(node)
+    }
+}
+
+impl<'a> From<&'a NewExpression> for NodeRef<'a> {
+    fn from(node: &'a NewExpression) -> Self {
+        NodeRef::NewExpression(node)
+    }
+}
+
+impl<'a> From<&'a MetaProperty> for NodeRef<'a> {
+    fn from(node: &'a MetaProperty) -> Self {
+        NodeRef::MetaProperty(node)
+    }
+}
+
+impl<'a> From<&'a ImportExpression> for NodeRef<'a> {
+    fn from(node: &'a ImportExpression) -> Self {
+        NodeRef::ImportExpression(node)
+    }
+}
+
+impl<'a> From<&'a SequenceExpression> for NodeRef<'a> {
+    fn from(node: &'a SequenceExpression) -> Self {
+        NodeRef::SequenceExpression(node)
+    }
+}
+
+impl<'a> From<&'a ParenthesizedExpression> for NodeRef<'a> {
+    fn from(node: &'a ParenthesizedExpression) -> Self {
+        NodeRef::ParenthesizedExpression(node)
+    }
+}
+
+impl<'a> From<&'a AwaitExpression> for NodeRef<'a> {
+    fn from(node: &'a AwaitExpression) -> Self {
+        NodeRef::AwaitExpression(node)
+    }
+}
+
+impl<'a> From<&'a PrivateIdentifier> for NodeRef<'a> {
+    fn from(node: &'a PrivateIdentifier) -> Self {
+        NodeRef::PrivateIdentifier(node)
+    }
+}
+
+impl<'a> From<&'a ObjectPattern> for NodeRef<'a> {
+    fn from(node: &'a ObjectPattern) -> Self {
+        NodeRef::ObjectPattern(node)
+    }
+}
+
+impl<'a> From<&'a ArrayPattern> for NodeRef<'a> {
+    fn from(node: &'a ArrayPattern) -> Self {
+        NodeRef::ArrayPattern(node)
+    }
+}
+
+impl<'a> From<&'a RestElement> for NodeRef<'a> {
+    fn from(node: &'a RestElement) -> Self {
+        NodeRef::RestElement(node)
+    }
+}
+
+impl<'a> From<&'a AssignmentProperty> for NodeRef<'a> {
+    fn from(node: &'a AssignmentProperty) -> Self {
+        NodeRef::AssignmentProperty(node)
+    }
+}
+
+impl<'a> From<&'a AssignmentPattern> for NodeRef<'a> {
+    fn from(node: &'a AssignmentPattern) -> Self {
+        NodeRef::AssignmentPattern(node)
+    }
+}
+
+impl<'a> From<&'a ClassBody> for NodeRef<'a> {
+    fn from(node: &'a ClassBody) -> Self {
+        NodeRef::ClassBody(node)
+    }
+}
+
+impl<'a> From<&'a MethodDefinition> for NodeRef<'a> {
+    fn from(node: &'a MethodDefinition) -> Self {
+        NodeRef::MethodDefinition(node)
+    }
+}
+
+impl<'a> From<&'a PropertyDefinition> for NodeRef<'a> {
+    fn from(node: &'a PropertyDefinition) -> Self {
+        NodeRef::PropertyDefinition(node)
+    }
+}
+
+impl<'a> From<&'a StaticBlock> for NodeRef<'a> {
+    fn from(node: &'a StaticBlock) -> Self {
+        NodeRef::StaticBlock(node)
+    }
+}
+
+impl<'a> From<&'a ClassDeclaration> for NodeRef<'a> {
+    fn from(node: &'a ClassDeclaration) -> Self {
+        NodeRef::ClassDeclaration(node)
+    }
+}
+
+impl<'a> From<&'a ImportDeclaration> for NodeRef<'a> {
+    fn from(node: &'a ImportDeclaration) -> Self {
+        NodeRef::ImportDeclaration(node)
+    }
+}
+
+impl<'a> From<&'a ImportSpecifier> for NodeRef<'a> {
+    fn from(node: &'a ImportSpecifier) -> Self {
+        NodeRef::ImportSpecifier(node)
+    }
+}
+
+impl<'a> From<&'a ImportDefaultSpecifier> for NodeRef<'a> {
+    fn from(node: &'a ImportDefaultSpecifier) -> Self {
+        NodeRef::ImportDefaultSpecifier(node)
+    }
+}
+
+impl<'a> From<&'a ImportNamespaceSpecifier> for NodeRef<'a> {
+    fn from(node: &'a ImportNamespaceSpecifier) -> Self {
+        NodeRef::ImportNamespaceSpecifier(node)
+    }
+}
+
+impl<'a> From<&'a ExportNamedDeclaration> for NodeRef<'a> {
+    fn from(node: &'a ExportNamedDeclaration) -> Self {
+        NodeRef::ExportNamedDeclaration(node)
+    }
+}
+
+impl<'a> From<&'a ExportSpecifier> for NodeRef<'a> {
+    fn from(node: &'a ExportSpecifier) -> Self {
+        NodeRef::ExportSpecifier(node)
+    }
+}
+
+impl<'a> From<&'a ExportDefaultDeclaration> for NodeRef<'a> {
+    fn from(node: &'a ExportDefaultDeclaration) -> Self {
+        NodeRef::ExportDefaultDeclaration(node)
+    }
+}
+
+impl<'a> From<&'a ExportAllDeclaration> for NodeRef<'a> {
+    fn from(node: &'a ExportAllDeclaration) -> Self {
+        NodeRef::ExportAllDeclaration(node)
+    }
+}
+
+impl<'a> From<&'a ErrorNode> for NodeRef<'a> {
+    fn from(node: &'a ErrorNode) -> Self {
+        NodeRef::ErrorNode(node)
+    }
+}
+
+// the node inside the enum rather than the enum itself
+impl<'a> From<&'a Statement> for NodeRef<'a> {
+    fn from(node: &'a Statement) -> Self {
+        match node {
+            Statement::Expression(node) => NodeRef::ExpressionStatement(node),
+            Statement::Directive(node) => NodeRef::Directive(node),
+            Statement::Block(node) => NodeRef::BlockStatement(node),
+            Statement::Empty(node) => NodeRef::EmptyStatement(node),
+            Statement::Debugger(node) => NodeRef::DebuggerStatement(node),
+            Statement::With(node) => NodeRef::WithStatement(node),
+            Statement::Return(node) => NodeRef::ReturnStatement(node),
+            Statement::Labeled(node) => NodeRef::LabeledStatement(node),
+            Statement::Break(node) => NodeRef::BreakStatement(node),
+            Statement::Continue(node) => NodeRef::ContinueStatement(node),
+            Statement::If(node) => NodeRef::IfStatement(node),
+            Statement::Switch(node) => NodeRef::SwitchStatement(node),
+            Statement::Throw(node) => NodeRef::ThrowStatement(node),
+            Statement::Try(node) => NodeRef::TryStatement(node),
+            Statement::While(node) => NodeRef::WhileStatement(node),
+            Statement::DoWhile(node) => NodeRef::DoWhileStatement(node),
+            Statement::For(node) => NodeRef::ForStatement(node),
+            Statement::ForIn(node) => NodeRef::ForInStatement(node),
+            Statement::ForOf(node) => NodeRef::ForOfStatement(node),
+            Statement::FunctionDeclaration(node) => NodeRef::FunctionDeclaration(node),
+            Statement::VariableDeclaration(node) => NodeRef::VariableDeclaration(node),
+            Statement::ClassDeclaration(node) => NodeRef::ClassDeclaration(node),
+            Statement::ImportDeclaration(node) => NodeRef::ImportDeclaration(node),
+            Statement::ExportNamedDeclaration(node) => NodeRef::ExportNamedDeclaration(node),
+            Statement::ExportDefaultDeclaration(node) => NodeRef::ExportDefaultDeclaration(node),
+            Statement::ExportAllDeclaration(node) => NodeRef::ExportAllDeclaration(node),
+            Statement::Error(node) => NodeRef::ErrorNode(node),
+        }
+    }
+}
+
+impl Program {
+    pub fn children(&self) -> Vec<NodeRef<'_>> {
+        NodeRef::Program(self).children()
+    }
+}
+
+impl Statement {
+    pub fn children(&self) -> Vec<NodeRef<'_>> {
+        NodeRef::from(self).children()
+    }
+}
+
+impl<'a> From<&'a Expression> for NodeRef<'a> {
+    fn from(node: &'a Expression) -> Self {
+        match node {
+            Expression::Identifier(node) => NodeRef::Identifier(node),
+            Expression::Literal(node) => NodeRef::Literal(node),
+            Expression::This(node) => NodeRef::ThisExpression(node),
+            Expression::Super(node) => NodeRef::Super(node),
+            Expression::Array(node) => NodeRef::ArrayExpression(node),
+            Expression::Object(node) => NodeRef::ObjectExpression(node),
+            Expression::Function(node) => NodeRef::FunctionExpression(node),
+            Expression::ArrowFunction(node) => NodeRef::ArrowFunctionExpression(node),
+            Expression::Class(node) => NodeRef::ClassExpression(node),
+            Expression::Unary(node) => NodeRef::UnaryExpression(node),
+            Expression::Update(node) => NodeRef::UpdateExpression(node),
+            Expression::Binary(node) => NodeRef::BinaryExpression(node),
+            Expression::Assignment(node) => NodeRef::AssignmentExpression(node),
+            Expression::Logical(node) => NodeRef::LogicalExpression(node),
+            Expression::Member(node) => NodeRef::MemberExpression(node),
+            Expression::Conditional(node) => NodeRef::ConditionalExpression(node),
+            Expression::Call(node) => NodeRef::CallExpression(node),
+            Expression::Chain(node) => NodeRef::ChainExpression(node),
+            Expression::Yield(node) => NodeRef::YieldExpression(node),
+            Expression::TemplateLiteral(node) => NodeRef::TemplateLiteral(node),
+            Expression::TaggedTemplate(node) => NodeRef::TaggedTemplateExpression(node),
+            Expression::New(node) => NodeRef::NewExpression(node),
+            Expression::MetaProperty(node) => NodeRef::MetaProperty(node),
+            Expression::Import(node) => NodeRef::ImportExpression(node),
+            Expression::Sequence(node) => NodeRef::SequenceExpression(node),
+            Expression::Parenthesized(node) => NodeRef::ParenthesizedExpression(node),
+            Expression::Await(node) => NodeRef::AwaitExpression(node),
+            Expression::PrivateIdentifier(node) => NodeRef::PrivateIdentifier(node),
+            Expression::Error(node) => NodeRef::ErrorNode(node),
+        }
+    }
+}
+
+impl Expression {
+    pub fn children(&self) -> Vec<NodeRef<'_>> {
+        NodeRef::from(self).children()
+    }
+}
+
+impl<'a> From<&'a Pattern> for NodeRef<'a> {
+    fn from(node: &'a Pattern) -> Self {
+        match node {
+            Pattern::Identifier(node) => NodeRef::Identifier(node),
+            Pattern::Member(node) => NodeRef::MemberExpression(node),
+            Pattern::Object(node) => NodeRef::ObjectPattern(node),
+            Pattern::Array(node) => NodeRef::ArrayPattern(node),
+            Pattern::Rest(node) => NodeRef::RestElement(node),
+            Pattern::Assignment(node) => NodeRef::AssignmentPattern(node),
+            Pattern::Error(node) => NodeRef::ErrorNode(node),
+        }
+    }
+}
+
+impl Pattern {
+    pub fn children(&self) -> Vec<NodeRef<'_>> {
+        NodeRef::from(self).children()
+    }
+}
+
+// the node types a `NodeRef` can be narrowed down to, one for each variant
+pub trait FromNodeRef {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&Self>;
+}
+
+impl FromNodeRef for Program {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&Program> {
+        match node {
+            NodeRef::Program(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for Identifier {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&Identifier> {
+        match node {
+            NodeRef::Identifier(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for Literal {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&Literal> {
+        match node {
+            NodeRef::Literal(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ExpressionStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ExpressionStatement> {
+        match node {
+            NodeRef::ExpressionStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for Directive {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&Directive> {
+        match node {
+            NodeRef::Directive(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for BlockStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&BlockStatement> {
+        match node {
+            NodeRef::BlockStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for FunctionBody {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&FunctionBody> {
+        match node {
+            NodeRef::FunctionBody(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for EmptyStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&EmptyStatement> {
+        match node {
+            NodeRef::EmptyStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for DebuggerStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&DebuggerStatement> {
+        match node {
+            NodeRef::DebuggerStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for WithStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&WithStatement> {
+        match node {
+            NodeRef::WithStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ReturnStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ReturnStatement> {
+        match node {
+            NodeRef::ReturnStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for LabeledStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&LabeledStatement> {
+        match node {
+            NodeRef::LabeledStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for BreakStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&BreakStatement> {
+        match node {
+            NodeRef::BreakStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ContinueStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ContinueStatement> {
+        match node {
+            NodeRef::ContinueStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for IfStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&IfStatement> {
+        match node {
+            NodeRef::IfStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for SwitchStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&SwitchStatement> {
+        match node {
+            NodeRef::SwitchStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for SwitchCase {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&SwitchCase> {
+        match node {
+            NodeRef::SwitchCase(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ThrowStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ThrowStatement> {
+        match node {
+            NodeRef::ThrowStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for TryStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&TryStatement> {
+        match node {
+            NodeRef::TryStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for CatchClause {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&CatchClause> {
+        match node {
+            NodeRef::CatchClause(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for WhileStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&WhileStatement> {
+        match node {
+            NodeRef::WhileStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for DoWhileStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&DoWhileStatement> {
+        match node {
+            NodeRef::DoWhileStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ForStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ForStatement> {
+        match node {
+            NodeRef::ForStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ForInStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ForInStatement> {
+        match node {
+            NodeRef::ForInStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ForOfStatement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ForOfStatement> {
+        match node {
+            NodeRef::ForOfStatement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for FunctionDeclaration {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&FunctionDeclaration> {
+        match node {
+            NodeRef::FunctionDeclaration(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for VariableDeclaration {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&VariableDeclaration> {
+        match node {
+            NodeRef::VariableDeclaration(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for VariableDeclarator {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&VariableDeclarator> {
+        match node {
+            NodeRef::VariableDeclarator(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ThisExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ThisExpression> {
+        match node {
+            NodeRef::ThisExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for Super {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&Super> {
+        match node {
+            NodeRef::Super(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ArrayExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ArrayExpression> {
+        match node {
+            NodeRef::ArrayExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ObjectExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ObjectExpression> {
+        match node {
+            NodeRef::ObjectExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for Property {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&Property> {
+        match node {
+            NodeRef::Property(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for FunctionExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&FunctionExpression> {
+        match node {
+            NodeRef::FunctionExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ArrowFunctionExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ArrowFunctionExpression> {
+        match node {
+            NodeRef::ArrowFunctionExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ClassExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ClassExpression> {
+        match node {
+            NodeRef::ClassExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for UnaryExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&UnaryExpression> {
+        match node {
+            NodeRef::UnaryExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for UpdateExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&UpdateExpression> {
+        match node {
+            NodeRef::UpdateExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for BinaryExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&BinaryExpression> {
+        match node {
+            NodeRef::BinaryExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for AssignmentExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&AssignmentExpression> {
+        match node {
+            NodeRef::AssignmentExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for LogicalExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&LogicalExpression> {
+        match node {
+            NodeRef::LogicalExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for MemberExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&MemberExpression> {
+        match node {
+            NodeRef::MemberExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ConditionalExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ConditionalExpression> {
+        match node {
+            NodeRef::ConditionalExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for CallExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&CallExpression> {
+        match node {
+            NodeRef::CallExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ChainExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ChainExpression> {
+        match node {
+            NodeRef::ChainExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for YieldExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&YieldExpression> {
+        match node {
+            NodeRef::YieldExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for TemplateLiteral {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&TemplateLiteral> {
+        match node {
+            NodeRef::TemplateLiteral(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for TaggedTemplateExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&TaggedTemplateExpression> {
+        match node {
+            NodeRef::TaggedTemplateExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for TemplateElement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&TemplateElement> {
+        match node {
+            NodeRef::TemplateElement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for SpreadElement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&SpreadElement> {
+        match node {
+            NodeRef::SpreadElement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for NewExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&NewExpression> {
+        match node {
+            NodeRef::NewExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for MetaProperty {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&MetaProperty> {
+        match node {
+            NodeRef::MetaProperty(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ImportExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ImportExpression> {
+        match node {
+            NodeRef::ImportExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for SequenceExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&SequenceExpression> {
+        match node {
+            NodeRef::SequenceExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ParenthesizedExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ParenthesizedExpression> {
+        match node {
+            NodeRef::ParenthesizedExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for AwaitExpression {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&AwaitExpression> {
+        match node {
+            NodeRef::AwaitExpression(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for PrivateIdentifier {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&PrivateIdentifier> {
+        match node {
+            NodeRef::PrivateIdentifier(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ObjectPattern {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ObjectPattern> {
+        match node {
+            NodeRef::ObjectPattern(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ArrayPattern {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ArrayPattern> {
+        match node {
+            NodeRef::ArrayPattern(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for RestElement {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&RestElement> {
+        match node {
+            NodeRef::RestElement(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for AssignmentProperty {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&AssignmentProperty> {
+        match node {
+            NodeRef::AssignmentProperty(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for AssignmentPattern {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&AssignmentPattern> {
+        match node {
+            NodeRef::AssignmentPattern(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ClassBody {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ClassBody> {
+        match node {
+            NodeRef::ClassBody(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for MethodDefinition {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&MethodDefinition> {
+        match node {
+            NodeRef::MethodDefinition(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for PropertyDefinition {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&PropertyDefinition> {
+        match node {
+            NodeRef::PropertyDefinition(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for StaticBlock {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&StaticBlock> {
+        match node {
+            NodeRef::StaticBlock(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ClassDeclaration {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ClassDeclaration> {
+        match node {
+            NodeRef::ClassDeclaration(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ImportDeclaration {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ImportDeclaration> {
+        match node {
+            NodeRef::ImportDeclaration(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ImportSpecifier {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ImportSpecifier> {
+        match node {
+            NodeRef::ImportSpecifier(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ImportDefaultSpecifier {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ImportDefaultSpecifier> {
+        match node {
+            NodeRef::ImportDefaultSpecifier(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ImportNamespaceSpecifier {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ImportNamespaceSpecifier> {
+        match node {
+            NodeRef::ImportNamespaceSpecifier(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ExportNamedDeclaration {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ExportNamedDeclaration> {
+        match node {
+            NodeRef::ExportNamedDeclaration(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ExportSpecifier {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ExportSpecifier> {
+        match node {
+            NodeRef::ExportSpecifier(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ExportDefaultDeclaration {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ExportDefaultDeclaration> {
+        match node {
+            NodeRef::ExportDefaultDeclaration(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ExportAllDeclaration {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ExportAllDeclaration> {
+        match node {
+            NodeRef::ExportAllDeclaration(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+impl FromNodeRef for ErrorNode {
+    fn from_node_ref(node: NodeRef<'_>) -> Option<&ErrorNode> {
+        match node {
+            NodeRef::ErrorNode(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+struct Children<'a>(Vec<NodeRef<'a>>);
+
+impl<'a> Visitor<'a> for Children<'a> {
+    fn visit_program(&mut self, node: &'a Program) {
+        self.0.push(NodeRef::Program(node));
+    }
+
+    fn visit_identifier(&mut self, node: &'a Identifier) {
+        self.0.push(NodeRef::Identifier(node));
+    }
+
+    fn visit_literal(&mut self, node: &'a Literal) {
+        self.0.push(NodeRef::Literal(node));
+    }
+
+    fn visit_expression_statement(&mut self, node: &'a ExpressionStatement) {
+        self.0.push(NodeRef::ExpressionStatement(node));
+    }
+
+    fn visit_directive(&mut self, node: &'a Directive) {
+        self.0.push(NodeRef::Directive(node));
+    }
+
+    fn visit_block_statement(&mut self, node: &'a BlockStatement) {
+        self.0.push(NodeRef::BlockStatement(node));
+    }
+
+    fn visit_function_body(&mut self, node: &'a FunctionBody) {
+        self.0.push(NodeRef::FunctionBody(node));
+    }
+
+    fn visit_empty_statement(&mut self, node: &'a EmptyStatement) {
+        self.0.push(NodeRef::EmptyStatement(node));
+    }
+
+    fn visit_debugger_statement(&mut self, node: &'a DebuggerStatement) {
+        self.0.push(NodeRef::DebuggerStatement(node));
+    }
+
+    fn visit_with_statement(&mut self, node: &'a WithStatement) {
+        self.0.push(NodeRef::WithStatement(node));
+    }
+
+    fn visit_return_statement(&mut self, node: &'a ReturnStatement) {
+        self.0.push(NodeRef::ReturnStatement(node));
+    }
+
+    fn visit_labeled_statement(&mut self, node: &'a LabeledStatement) {
+        self.0.push(NodeRef::LabeledStatement(node));
+    }
+
+    fn visit_break_statement(&mut self, node: &'a BreakStatement) {
+        self.0.push(NodeRef::BreakStatement(node));
+    }
+
+    fn visit_continue_statement(&mut self, node: &'a ContinueStatement) {
+        self.0.push(NodeRef::ContinueStatement(node));
+    }
+
+    fn visit_if_statement(&mut self, node: &'a IfStatement) {
+        self.0.push(NodeRef::IfStatement(node));
+    }
+
+    fn visit_switch_statement(&mut self, node: &'a SwitchStatement) {
+        self.0.push(NodeRef::SwitchStatement(node));
+    }
+
+    fn visit_switch_case(&mut self, node: &'a SwitchCase) {
+        self.0.push(NodeRef::SwitchCase(node));
+    }
+
+    fn visit_throw_statement(&mut self, node: &'a ThrowStatement) {
+        self.0.push(NodeRef::ThrowStatement(node));
+    }
+
+    fn visit_try_statement(&mut self, node: &'a TryStatement) {
+        self.0.push(NodeRef::TryStatement(node));
+    }
+
+    fn visit_catch_clause(&mut self, node: &'a CatchClause) {
+        self.0.push(NodeRef::CatchClause(node));
+    }
+
+    fn visit_while_statement(&mut self, node: &'a WhileStatement) {
+        self.0.push(NodeRef::WhileStatement(node));
+    }
+
+    fn visit_do_while_statement(&mut self, node: &'a DoWhileStatement) {
+        self.0.push(NodeRef::DoWhileStatement(node));
+    }
+
+    fn visit_for_statement(&mut self, node: &'a ForStatement) {
+        self.0.push(NodeRef::ForStatement(node));
+    }
+
+    fn visit_for_in_statement(&mut self, node: &'a ForInStatement) {
+        self.0.push(NodeRef::ForInStatement(node));
+    }
+
+    fn visit_for_of_statement(&mut self, node: &'a ForOfStatement) {
+        self.0.push(NodeRef::ForOfStatement(node));
+    }
+
+    fn visit_function_declaration(&mut self, node: &'a FunctionDeclaration) {
+        self.0.push(NodeRef::FunctionDeclaration(node));
+    }
+
+    fn visit_variable_declaration(&mut self, node: &'a VariableDeclaration) {
+        self.0.push(NodeRef::VariableDeclaration(node));
+    }
+
+    fn visit_variable_declarator(&mut self, node: &'a VariableDeclarator) {
+        self.0.push(NodeRef::VariableDeclarator(node));
+    }
+
+    fn visit_this_expression(&mut self, node: &'a ThisExpression) {
+        self.0.push(NodeRef::ThisExpression(node));
+    }
+
+    fn visit_super(&mut self, node: &'a Super) {
+        self.0.push(NodeRef::Super(node));
+    }
+
+    fn visit_array_expression(&mut self, node: &'a ArrayExpression) {
+        self.0.push(NodeRef::ArrayExpression(node));
+    }
+
+    fn visit_object_expression(&mut self, node: &'a ObjectExpression) {
+        self.0.push(NodeRef::ObjectExpression(node));
+    }
+
+    fn visit_property(&mut self, node: &'a Property) {
+        self.0.push(NodeRef::Property(node));
+    }
+
+    fn visit_function_expression(&mut self, node: &'a FunctionExpression) {
+        self.0.push(NodeRef::FunctionExpression(node));
+    }
+
+    fn visit_arrow_function_expression(&mut self, node: &'a ArrowFunctionExpression) {
+        self.0.push(NodeRef::ArrowFunctionExpression(node));
+    }
+
+    fn visit_class_expression(&mut self, node: &'a ClassExpression) {
+        self.0.push(NodeRef::ClassExpression(node));
+    }
+
+    fn visit_unary_expression(&mut self, node: &'a UnaryExpression) {
+        self.0.push(NodeRef::UnaryExpression(node));
+    }
+
+    fn visit_update_expression(&mut self, node: &'a UpdateExpression) {
+        self.0.push(NodeRef::UpdateExpression(node));
+    }
+
+    fn visit_binary_expression(&mut self, node: &'a BinaryExpression) {
+        self.0.push(NodeRef::BinaryExpression(node));
+    }
+
+    fn visit_assignment_expression(&mut self, node: &'a AssignmentExpression) {
+        self.0.push(NodeRef::AssignmentExpression(node));
+    }
+
+    fn visit_logical_expression(&mut self, node: &'a LogicalExpression) {
+        self.0.push(NodeRef::LogicalExpression(node));
+    }
+
+    fn visit_member_expression(&mut self, node: &'a MemberExpression) {
+        self.0.push(NodeRef::MemberExpression(node));
+    }
+
+    fn visit_conditional_expression(&mut self, node: &'a ConditionalExpression) {
+        self.0.push(NodeRef::ConditionalExpression(node));
+    }
+
+    fn visit_call_expression(&mut self, node: &'a CallExpression) {
+        self.0.push(NodeRef::CallExpression(node));
+    }
+
+    fn visit_chain_expression(&mut self, node: &'a ChainExpression) {
+        self.0.push(NodeRef::ChainExpression(node));
+    }
+
+    fn visit_yield_expression(&mut self, node: &'a YieldExpression) {
+        self.0.push(NodeRef::YieldExpression(node));
+    }
+
+    fn visit_template_literal(&mut self, node: &'a TemplateLiteral) {
+        self.0.push(NodeRef::TemplateLiteral(node));
+    }
+
+    fn visit_tagged_template_expression(&mut self, node: &'a TaggedTemplateExpression) {
+        self.0.push(NodeRef::TaggedTemplateExpression(node));
+    }
+
+    fn visit_template_element(&mut self, node: &'a TemplateElement) {
+        self.0.push(NodeRef::TemplateElement(node));
+    }
+
+    fn visit_spread_element(&mut self, node: &'a SpreadElement) {
+        self.0.push(NodeRef::SpreadElement(node));
+    }
+
+    fn visit_new_expression(&mut self, node: &'a NewExpression) {
+        self.0.push(NodeRef::NewExpression(node));
+    }
+
+    fn visit_meta_property(&mut self, node: &'a MetaProperty) {
+        self.0.push(NodeRef::MetaProperty(node));
+    }
+
+    fn visit_import_expression(&mut self, node: &'a ImportExpression) {
+        self.0.push(NodeRef::ImportExpression(node));
+    }
+
+    fn visit_sequence_expression(&mut self, node: &'a SequenceExpression) {
+        self.0.push(NodeRef::SequenceExpression(node));
+    }
+
+    fn visit_parenthesized_expression(&mut self, node: &'a ParenthesizedExpression) {
+        self.0.push(NodeRef::ParenthesizedExpression(node));
+    }
+
+    fn visit_await_expression(&mut self, node: &'a AwaitExpression) {
+        self.0.push(NodeRef::AwaitExpression(node));
+    }
+
+    fn visit_private_identifier(&mut self, node: &'a PrivateIdentifier) {
+        self.0.push(NodeRef::PrivateIdentifier(node));
+    }
+
+    fn visit_object_pattern(&mut self, node: &'a ObjectPattern) {
+        self.0.push(NodeRef::ObjectPattern(node));
+    }
+
+    fn visit_array_pattern(&mut self, node: &'a ArrayPattern) {
+        self.0.push(NodeRef::ArrayPattern(node));
+    }
+
+    fn visit_rest_element(&mut self, node: &'a RestElement) {
+        self.0.push(NodeRef::RestElement(node));
+    }
+
+    fn visit_assignment_property(&mut self, node: &'a AssignmentProperty) {
+        self.0.push(NodeRef::AssignmentProperty(node));
+    }
+
+    fn visit_assignment_pattern(&mut self, node: &'a AssignmentPattern) {
+        self.0.push(NodeRef::AssignmentPattern(node));
+    }
+
+    fn visit_class_body(&mut self, node: &'a ClassBody) {
+        self.0.push(NodeRef::ClassBody(node));
+    }
+
+    fn visit_method_definition(&mut self, node: &'a MethodDefinition) {
+        self.0.push(NodeRef::MethodDefinition(node));
+    }
+
+    fn visit_property_definition(&mut self, node: &'a PropertyDefinition) {
+        self.0.push(NodeRef::PropertyDefinition(node));
+    }
+
+    fn visit_static_block(&mut self, node: &'a StaticBlock) {
+        self.0.push(NodeRef::StaticBlock(node));
+    }
+
+    fn visit_class_declaration(&mut self, node: &'a ClassDeclaration) {
+        self.0.push(NodeRef::ClassDeclaration(node));
+    }
+
+    fn visit_import_declaration(&mut self, node: &'a ImportDeclaration) {
+        self.0.push(NodeRef::ImportDeclaration(node));
+    }
+
+    fn visit_import_specifier(&mut self, node: &'a ImportSpecifier) {
+        self.0.push(NodeRef::ImportSpecifier(node));
+    }
+
+    fn visit_import_default_specifier(&mut self, node: &'a ImportDefaultSpecifier) {
+        self.0.push(NodeRef::ImportDefaultSpecifier(node));
+    }
+
+    fn visit_import_namespace_specifier(&mut self, node: &'a ImportNamespaceSpecifier) {
+        self.0.push(NodeRef::ImportNamespaceSpecifier(node));
+    }
+
+    fn visit_export_named_declaration(&mut self, node: &'a ExportNamedDeclaration) {
+        self.0.push(NodeRef::ExportNamedDeclaration(node));
+    }
+
+    fn visit_export_specifier(&mut self, node: &'a ExportSpecifier) {
+        self.0.push(NodeRef::ExportSpecifier(node));
+    }
+
+    fn visit_export_default_declaration(&mut self, node: &'a ExportDefaultDeclaration) {
+        self.0.push(NodeRef::ExportDefaultDeclaration(node));
+    }
+
+    fn visit_export_all_declaration(&mut self, node: &'a ExportAllDeclaration) {
+        self.0.push(NodeRef::ExportAllDeclaration(node));
+    }
+
+    fn visit_error_node(&mut self, node: &'a ErrorNode) {
+        self.0.push(NodeRef::ErrorNode(node));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use crate::{node::Node, node_ref::NodeRef, parser::parse};
+
+    #[test]
+    fn children_can_count_nodes_by_type() {
+        fn count<'a>(node: NodeRef<'a>, counts: &mut HashMap<&'static str, usize>) {
+            *counts.entry(node.node_type()).or_default() += 1;
+            for child in node.children() {
+                count(child, counts);
+            }
+        }
+
+        let program = parse("a(b, ...c) + d; for (;;) break;").unwrap();
+        let mut counts = HashMap::new();
+        count(NodeRef::Program(&program), &mut counts);
+        assert_eq!(counts["Identifier"], 4);
+        assert_eq!(counts["CallExpression"], 1);
+        assert_eq!(counts["SpreadElement"], 1);
+        assert_eq!(counts["BreakStatement"], 1);
+        assert_eq!(counts.values().sum::<usize>(), 11);
+    }
+
+    #[test]
+    fn children_cover_optional_fields_and_patterns() {
+        let program = parse("f = (a = 1, ...[b]) => {}; if (c) ; else d;").unwrap();
+        let assignment = program.body[0].children()[0];
+        let arrow = assignment.children()[1];
+        let types: Vec<&str> = arrow
+            .children()
+            .iter()
+            .map(|child| child.node_type())
+            .collect();
+        assert_eq!(
+            types,
+            ["AssignmentPattern", "RestElement", "BlockStatement"]
+        );
+        let types: Vec<&str> = program.body[1]
+            .children()
+            .iter()
+            .map(|child| child.node_type())
+            .collect();
+        assert_eq!(
+            types,
+            ["Identifier", "EmptyStatement", "ExpressionStatement"]
+        );
+    }
+
+    #[test]
+    fn children_can_find_the_deepest_node_at_an_offset() {
+        fn deepest(node: NodeRef, offset: usize) -> NodeRef {
+            node.children()
+                .into_iter()
+                .find(|child| child.loc().range.0 <= offset && offset < child.loc().range.1)
+                .map_or(node, |child| deepest(child, offset))
+        }
+
+        let program = parse("x = [1, foo.bar(2)];").unwrap();
+        let node = deepest(NodeRef::Program(&program), 13);
+        assert!(matches!(node, NodeRef::Identifier(identifier) if identifier.name == "bar"));
+    }
+}
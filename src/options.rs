@@ -0,0 +1,252 @@
+// What `parse_with_options` accepts, after acorn's options. Every node
+// always has its line, column and offsets, which errors, comments and the
+// analysis rely on, so unlike acorn there are no `locations` or `ranges`
+// switches; those only decide what acorn writes out.
+use crate::node::SourceType;
+
+// the newest edition of the language the parser knows
+pub const LATEST_ECMA_VERSION: u32 = 2022;
+
+// how deeply statements and expressions may nest unless the options say
+// otherwise, well past what anyone writes by hand
+pub const DEFAULT_MAX_NESTING: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParserOptions {
+    pub source_type: SourceType,
+    // the year of the edition syntax is checked against, as in 2020 for
+    // ES2020; newer syntax is an error
+    pub ecma_version: u32,
+    // allow `return` at the top level, for code that will be wrapped in a
+    // function, as CommonJS modules are
+    pub allow_return_outside_function: bool,
+    // keep `ParenthesizedExpression` nodes instead of dropping the parentheses
+    pub preserve_parens: bool,
+    // collect the comments of the source
+    pub comments: bool,
+    // record errors and carry on instead of stopping at the first one
+    pub tolerant: bool,
+    // how deeply statements and expressions may nest before parsing fails,
+    // rather than running out of stack on input such as a hundred thousand
+    // parentheses
+    pub max_nesting: usize,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        ParserOptions {
+            source_type: SourceType::Script,
+            ecma_version: LATEST_ECMA_VERSION,
+            allow_return_outside_function: false,
+            preserve_parens: false,
+            comments: false,
+            tolerant: false,
+            max_nesting: DEFAULT_MAX_NESTING,
+        }
+    }
+}
+
+impl ParserOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn source_type(self, source_type: SourceType) -> Self {
+        ParserOptions {
+            source_type,
+            ..self
+        }
+    }
+
+    pub fn ecma_version(self, ecma_version: u32) -> Self {
+        ParserOptions {
+            ecma_version,
+            ..self
+        }
+    }
+
+    pub fn allow_return_outside_function(self, allow_return_outside_function: bool) -> Self {
+        ParserOptions {
+            allow_return_outside_function,
+            ..self
+        }
+    }
+
+    pub fn preserve_parens(self, preserve_parens: bool) -> Self {
+        ParserOptions {
+            preserve_parens,
+            ..self
+        }
+    }
+
+    pub fn comments(self, comments: bool) -> Self {
+        ParserOptions { comments, ..self }
+    }
+
+    pub fn tolerant(self, tolerant: bool) -> Self {
+        ParserOptions { tolerant, ..self }
+    }
+
+    pub fn max_nesting(self, max_nesting: usize) -> Self {
+        ParserOptions {
+            max_nesting,
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ParseErrorKind,
+        node::{Expression, SourceType, Statement},
+    };
+
+    #[test]
+    fn options_gate_syntax_by_edition() {
+        use crate::{options::ParserOptions, parser::parse_with_options};
+        let error_in = |src: &str, source_type, ecma_version| {
+            let options = ParserOptions::new()
+                .source_type(source_type)
+                .ecma_version(ecma_version);
+            let error = parse_with_options(src, &options).unwrap_err();
+            assert_eq!(error.kind, ParseErrorKind::UnsupportedSyntax);
+            (error.start.column, error.message)
+        };
+        let cases = [
+            (
+                "a = 1_000",
+                SourceType::Script,
+                2021,
+                4,
+                "Numeric separators",
+            ),
+            ("a?.b", SourceType::Script, 2020, 1, "Optional chains"),
+            (
+                "a ?? b",
+                SourceType::Script,
+                2020,
+                2,
+                "Nullish coalescing operators",
+            ),
+            (
+                "a ||= b",
+                SourceType::Script,
+                2021,
+                2,
+                "Logical assignment operators",
+            ),
+            (
+                "class A { #a = 1 }",
+                SourceType::Script,
+                2022,
+                10,
+                "Class fields",
+            ),
+            (
+                "class A { a }",
+                SourceType::Script,
+                2022,
+                10,
+                "Class fields",
+            ),
+            (
+                "class A { #m() {} }",
+                SourceType::Script,
+                2022,
+                10,
+                "Private methods",
+            ),
+            (
+                "class A { static get #m() {} }",
+                SourceType::Script,
+                2022,
+                21,
+                "Private methods",
+            ),
+            (
+                "class A { static { a } }",
+                SourceType::Script,
+                2022,
+                10,
+                "Class static blocks",
+            ),
+            (
+                "await a",
+                SourceType::Module,
+                2022,
+                0,
+                "Top-level 'await' expressions",
+            ),
+        ];
+        for (src, source_type, version, column, feature) in cases {
+            assert_eq!(
+                error_in(src, source_type, version - 1),
+                (
+                    column,
+                    format!("{} are not available before ES{}", feature, version)
+                )
+            );
+            let options = ParserOptions::new()
+                .source_type(source_type)
+                .ecma_version(version);
+            assert!(parse_with_options(src, &options).is_ok(), "{}", src);
+        }
+        let options = ParserOptions::new().ecma_version(2021);
+        assert!(parse_with_options("async function f() { await a }", &options).is_ok());
+    }
+
+    #[test]
+    fn options_choose_what_the_output_holds() {
+        use crate::{options::ParserOptions, parser::parse_with_options};
+        let src = "// one\nreturn (a);";
+        let options = ParserOptions::new()
+            .allow_return_outside_function(true)
+            .preserve_parens(true)
+            .comments(true);
+        let output = parse_with_options(src, &options).unwrap();
+        assert_eq!(output.comments.len(), 1);
+        assert!(output.errors.is_empty());
+        let Statement::Return(statement) = &output.program.body[0] else {
+            panic!("expected a return statement");
+        };
+        assert!(matches!(
+            statement.argument,
+            Some(Expression::Parenthesized(_))
+        ));
+
+        let output = parse_with_options(src, &ParserOptions::new().tolerant(true)).unwrap();
+        assert!(output.comments.is_empty());
+        assert_eq!(output.errors.len(), 1);
+        assert_eq!(output.errors[0].kind, ParseErrorKind::MisplacedReturn);
+        let output = parse_with_options("'a", &ParserOptions::new().tolerant(true)).unwrap();
+        assert_eq!(output.errors[0].kind, ParseErrorKind::UnterminatedString);
+    }
+
+    #[test]
+    fn options_limit_how_deeply_the_source_nests() {
+        use crate::{options::ParserOptions, parser::parse_with_options};
+        // the statement and the expression in it are two levels already
+        let options = ParserOptions::new().max_nesting(4);
+        let cases = [
+            ("((a))", "(((a)))"),
+            ("!!a", "!!!a"),
+            ("a ** a ** a", "a ** a ** a ** a"),
+            ("new new A", "new new new A"),
+            ("{{{{}}}}", "{{{{{}}}}}"),
+        ];
+        for (deepest, too_deep) in cases {
+            assert!(parse_with_options(deepest, &options).is_ok(), "{}", deepest);
+            let error = parse_with_options(too_deep, &options).unwrap_err();
+            assert_eq!(error.kind, ParseErrorKind::NestingTooDeep, "{}", too_deep);
+        }
+        for src in ["a + a + a + a + a", "a.b.c.d.e()()()"] {
+            assert!(parse_with_options(src, &options).is_ok(), "{}", src);
+        }
+        let error = parse_with_options("[[[[[a]]]]]", &options).unwrap_err();
+        assert_eq!(
+            (error.start.line, error.start.column, error.message.as_str()),
+            (1, 3, "Nesting too deep")
+        );
+    }
+}
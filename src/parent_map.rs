@@ -0,0 +1,148 @@
+// The tree only points from parents to children, so questions about where a
+// node sits, such as whether an identifier is the callee of a call, go
+// through a `ParentMap` built from it by one traversal.
+//
+// Nodes are numbered in pre-order, the program being 0, so the same tree
+// gives its nodes the same ids every time. A node is found again by its
+// address along with its kind, as a node stored inline at the start of its
+// parent shares the parent's address.
+use std::{collections::HashMap, mem::Discriminant};
+
+use crate::{node::Program, node_ref::NodeRef};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(pub u32);
+
+pub struct ParentMap<'a> {
+    nodes: Vec<NodeRef<'a>>,
+    parents: Vec<Option<NodeId>>,
+    ids: HashMap<(usize, Discriminant<NodeRef<'a>>), NodeId>,
+}
+
+impl<'a> ParentMap<'a> {
+    // walked with a stack of its own rather than by recursion, so that no
+    // tree is too deep for it
+    pub fn new(program: &'a Program) -> Self {
+        let mut map = ParentMap {
+            nodes: vec![],
+            parents: vec![],
+            ids: HashMap::new(),
+        };
+        let mut stack = vec![(NodeRef::Program(program), None)];
+        while let Some((node, parent)) = stack.pop() {
+            let id = NodeId(map.nodes.len() as u32);
+            map.nodes.push(node);
+            map.parents.push(parent);
+            map.ids.insert(key(node), id);
+            for child in node.children().into_iter().rev() {
+                stack.push((child, Some(id)));
+            }
+        }
+        map
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn id_of(&self, node: NodeRef<'a>) -> Option<NodeId> {
+        self.ids.get(&key(node)).copied()
+    }
+
+    pub fn node(&self, id: NodeId) -> Option<NodeRef<'a>> {
+        self.nodes.get(id.0 as usize).copied()
+    }
+
+    pub fn parent_id_of(&self, id: NodeId) -> Option<NodeId> {
+        self.parents.get(id.0 as usize).copied().flatten()
+    }
+
+    pub fn parent_of(&self, id: NodeId) -> Option<NodeRef<'a>> {
+        self.parent_id_of(id).and_then(|parent| self.node(parent))
+    }
+
+    // the parent, grandparent and so on up to the program
+    pub fn ancestors_of(&self, id: NodeId) -> impl Iterator<Item = NodeRef<'a>> + '_ {
+        let mut current = self.parent_id_of(id);
+        std::iter::from_fn(move || {
+            let id = current?;
+            current = self.parent_id_of(id);
+            self.node(id)
+        })
+    }
+}
+
+fn key(node: NodeRef) -> (usize, Discriminant<NodeRef>) {
+    (node.as_ptr() as usize, std::mem::discriminant(&node))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::{Expression, Statement},
+        node_ref::NodeRef,
+        parent_map::{NodeId, ParentMap},
+        parser::parse,
+    };
+
+    #[test]
+    fn parent_maps_find_the_parent_of_a_node() {
+        let program = parse("if (a) f(g);").unwrap();
+        let parents = ParentMap::new(&program);
+        assert_eq!(parents.len(), 7);
+        assert_eq!(parents.parent_of(NodeId(0)), None);
+
+        let call = match &program.body[0] {
+            Statement::If(statement) => match &statement.consequent {
+                Statement::Expression(statement) => match &statement.expression {
+                    Expression::Call(call) => call,
+                    _ => panic!("expected a call"),
+                },
+                _ => panic!("expected an expression statement"),
+            },
+            _ => panic!("expected an if statement"),
+        };
+        let callee = match &call.callee {
+            Expression::Identifier(callee) => callee,
+            _ => panic!("expected an identifier"),
+        };
+        let id = parents.id_of(NodeRef::Identifier(callee)).unwrap();
+        assert_eq!(id, NodeId(5));
+        assert_eq!(parents.node(id), Some(NodeRef::Identifier(callee)));
+        let parent = parents.parent_of(id).unwrap();
+        assert_eq!(parent.as_ptr(), NodeRef::CallExpression(call).as_ptr());
+        let ancestors: Vec<&str> = parents
+            .ancestors_of(id)
+            .map(|node| node.node_type())
+            .collect();
+        assert_eq!(
+            ancestors,
+            [
+                "CallExpression",
+                "ExpressionStatement",
+                "IfStatement",
+                "Program"
+            ]
+        );
+    }
+
+    #[test]
+    fn parent_maps_tell_apart_a_node_and_its_first_field() {
+        let program = parse("try {} catch (e) {}").unwrap();
+        let parents = ParentMap::new(&program);
+        let handler = match &program.body[0] {
+            Statement::Try(statement) => statement.handler.as_ref().unwrap(),
+            _ => panic!("expected a try statement"),
+        };
+        let clause = parents.id_of(NodeRef::CatchClause(handler)).unwrap();
+        let body = parents
+            .id_of(NodeRef::BlockStatement(&handler.body))
+            .unwrap();
+        assert_ne!(clause, body);
+        assert_eq!(parents.parent_id_of(body), Some(clause));
+    }
+}
@@ -1,564 +1,1442 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
 use crate::{
-    context::Context,
-    node::{FunctionDeclaration, FunctionExpression, Identifier, Position, Program},
-    string::ReadonlyString,
-    token::*,
+    context::{Context, CoverErrors, DeclarationKind, Target, TargetKind},
+    error::{ParseError, ParseErrorKind, ParseResult},
+    node::{
+        Comment, Expression, Identifier, Pattern, Position, Program, SourceLocation, SourceType,
+        Statement,
+    },
+    options::ParserOptions,
+    token::{Lexeme, Token, TokenKind},
 };
 
-lazy_static! {
-    static ref REG_IDENTIFIER: Regex = Regex::new("[0-9a-zA-Z$_]").unwrap();
-    static ref REG_STRING_BOUNDARY: Regex = Regex::new("['\"]").unwrap();
-    static ref REG_NUMBERIC: Regex = Regex::new(r"[0-9]").unwrap();
-    static ref REG_OPERATOR: Regex = Regex::new(r"[=!<>+\-*/%?=(){}\[\];:]").unwrap();
-    static ref REG_WHITESPACE: Regex = Regex::new(r"\s").unwrap();
-    static ref REG_LINE_BREAK: Regex = Regex::new(r"[\n\r]").unwrap();
-}
-fn get_char(src: &ReadonlyString, position: usize) -> &str {
-    src.slice(position, position + 1)
+use self::cursor::TokenCursor;
+
+// left free on the stack before parsing a level deeper, and the size of each
+// segment allocated once it's used up
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+mod class;
+pub(crate) mod cursor;
+mod expression;
+mod function;
+mod module;
+mod pattern;
+mod statement;
+
+struct Parser<'a> {
+    tokens: TokenCursor<'a>,
+    end_offset: usize,
+    context: Context,
+    // the errors recovered from so far, in recovery mode
+    errors: Vec<ParseError>,
+    // the index of the token an assignment expression is being parsed from,
+    // as only something there can be the parameters of an arrow function
+    potential_arrow_at: usize,
 }
 
-fn get_operator_by_chars(chars: &str) -> Option<Token> {
-    match chars {
-        "==" => Some(Token::Comparation(Comparation::DoubleE)),
-        "===" => Some(Token::Comparation(Comparation::TripleE)),
-        "!=" => Some(Token::Comparation(Comparation::DoubleNE)),
-        "!==" => Some(Token::Comparation(Comparation::TripleNE)),
-        "<" => Some(Token::Comparation(Comparation::LT)),
-        "<=" => Some(Token::Comparation(Comparation::LTE)),
-        ">" => Some(Token::Comparation(Comparation::GT)),
-        ">=" => Some(Token::Comparation(Comparation::GTE)),
-
-        "+" => Some(Token::Arithmetic(Arithmetic::Plus)),
-        "-" => Some(Token::Arithmetic(Arithmetic::Minus)),
-        "*" => Some(Token::Arithmetic(Arithmetic::Multiple)),
-        "/" => Some(Token::Arithmetic(Arithmetic::Divide)),
-        "%" => Some(Token::Arithmetic(Arithmetic::Modulo)),
-
-        "=" => Some(Token::Assign(Assign::Normal)),
-        "+=" => Some(Token::Assign(Assign::Addition)),
-        "-=" => Some(Token::Assign(Assign::Subtraction)),
-        "*=" => Some(Token::Assign(Assign::Multiplication)),
-        "/=" => Some(Token::Assign(Assign::Division)),
-        "??=" => Some(Token::Assign(Assign::NullishCoalescing)),
-
-        "(" => Some(Token::ParenL),
-        ")" => Some(Token::ParenR),
-        "[" => Some(Token::BracketL),
-        "]" => Some(Token::BracketR),
-        "{" => Some(Token::BraceL),
-        "}" => Some(Token::BraceR),
-
-        "." => Some(Token::Dot),
-        "?." => Some(Token::QuestionDot),
-        ";" => Some(Token::Semi),
-        "," => Some(Token::Comma),
-        ":" => Some(Token::Colon),
-        "?" => Some(Token::Question),
-        "??" => Some(Token::NullishCoalesce),
-        "!" => Some(Token::LogicalInversion),
-        "~" => Some(Token::BitwiseInversion),
-        "||" => Some(Token::LogicalOR),
-        "|" => Some(Token::BitwiseOR),
-        "&&" => Some(Token::LogicalAND),
-        "&" => Some(Token::BitwiseAND),
-        "++" => Some(Token::Increment),
-        "--" => Some(Token::Decrement),
-        "=>" => Some(Token::Arrow),
-
-        _ => None,
+impl<'a> Parser<'a> {
+    fn new(src: &'a str, context: Context) -> Self {
+        Parser {
+            tokens: TokenCursor::new(src, context.recover),
+            end_offset: src.len(),
+            context,
+            errors: vec![],
+            potential_arrow_at: 0,
+        }
     }
-}
 
-fn read_string(
-    src: &ReadonlyString,
-    position: &mut usize,
-    tokens: &mut Vec<Token>,
-    line: usize,
-    column: &mut usize,
-) {
-    let start = *position;
-    let boundary = get_char(src, start); // ' or "
-
-    // read content
-    *position += 1;
-
-    // escape context flag
-    let mut esc = false;
-
-    // read until boundary or line break
-    let mut current_char = get_char(src, *position);
-    while *position < src.length
-        && (current_char != boundary || esc)
-        && !REG_LINE_BREAK.is_match(current_char)
-    {
-        if esc {
-            esc = false;
-        } else if current_char == "\\" {
-            esc = true;
-        }
-
-        *position += 1;
-        *column += 1;
-        current_char = get_char(src, *position);
-    }
-
-    // unexpected boundary such as line break or ending of code
-    if current_char != boundary {
-        panic!(
-            "Unexpected character '{}' at line:{}, column:{}.",
-            match current_char {
-                "\n" => "\\n",
-                "\r" => "\\r",
-                _ => "",
-            },
-            line,
-            column
-        );
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.peek().map(|lexeme| &lexeme.token)
     }
 
-    // ready to read next token
-    *position += 1;
+    fn peek2(&self) -> Option<&Token> {
+        self.tokens.peek2().map(|lexeme| &lexeme.token)
+    }
 
-    let raw = src.slice(start, *position);
-    let content = utf8_slice::slice(raw, 1, utf8_slice::len(raw));
-    tokens.push(Token::String(raw.to_string(), content.to_string()));
-}
+    fn peek_nth(&mut self, offset: usize) -> Option<&Token> {
+        self.tokens.peek_nth(offset).map(|lexeme| &lexeme.token)
+    }
 
-fn read_numberic(
-    src: &ReadonlyString,
-    position: &mut usize,
-    tokens: &mut Vec<Token>,
-    _line: usize,
-    column: &mut usize,
-) {
-    let start = *position;
-    let mut current_char = get_char(src, start);
-
-    // find number system
-    let system = match (current_char, get_char(src, start + 1)) {
-        ("0", "b") => {
-            *position += 2;
-            NumberSystem::Binary
-        }
-        ("0", "x") => {
-            *position += 2;
-            NumberSystem::Hex
-        }
-        ("0", _) => {
-            *position += 1;
-            NumberSystem::Octal
-        }
-        _ => NumberSystem::Decimal,
-    };
+    fn is(&self, token: &Token) -> bool {
+        self.peek() == Some(token)
+    }
 
-    // cannot use separator at the begining of numeric content
-    current_char = get_char(src, *position);
-    if current_char == "_" {
-        panic!("Numeric separators are not allowed at the first of numeric literals");
+    // `import.meta` and `import(source)` are expressions rather than the
+    // start of an import declaration
+    fn is_import_expression(&self) -> bool {
+        self.is(&Token::Import) && matches!(self.peek2(), Some(Token::Dot) | Some(Token::ParenL))
     }
 
-    let content_start = *position;
+    // contextual keywords such as `static` or `get` are lexed as plain names
+    fn is_name(&self, name: &str) -> bool {
+        matches!(self.peek(), Some(Token::Name(current)) if current == name)
+    }
 
-    // separator context flag
-    let mut separate = false;
+    fn bump(&mut self) -> ParseResult<Token> {
+        match self.tokens.bump() {
+            Some(lexeme) => Ok(lexeme.token.clone()),
+            None => self.unexpected(),
+        }
+    }
 
-    // read until non-numeric except numeric separator
-    while *position < src.length && (REG_NUMBERIC.is_match(current_char) || current_char == "_") {
-        // cannot use separator constantly
-        if current_char == "_" {
-            if separate {
-                panic!("Only one underscore is allowed as numeric separator");
-            } else {
-                separate = true;
-            }
+    fn eat_name(&mut self, name: &str) -> bool {
+        if self.is_name(name) {
+            self.tokens.bump();
+            true
         } else {
-            separate = false;
+            false
         }
+    }
 
-        *position += 1;
-        *column += 1;
-        current_char = get_char(src, *position);
+    fn eat(&mut self, token: &Token) -> bool {
+        self.tokens.eat(&token.kind())
     }
 
-    if current_char == "n" {
-        // parse bigint
-        let raw = src.slice(start, *position + 1);
-        let content = src.slice(content_start, *position);
-        tokens.push(Token::Bigint(
-            raw.to_string(),
-            system,
-            content.parse::<i128>().unwrap(),
-        ));
-    } else {
-        // parse number
-        let raw = src.slice(start, *position);
-        let content = src.slice(content_start, *position);
-        tokens.push(Token::Number(
-            raw.to_string(),
-            system,
-            content.parse::<f64>().unwrap(),
-        ));
+    fn expect(&mut self, token: &Token) -> ParseResult<()> {
+        self.tokens.expect(&token.kind()).map(|_| ())
     }
-}
 
-fn read_reg_exp(
-    src: &ReadonlyString,
-    position: &mut usize,
-    tokens: &mut Vec<Token>,
-    line: usize,
-    column: &mut usize,
-) {
-    let start = *position;
-
-    // read content
-    *position += 1;
-
-    let mut esc = false;
-    let mut current_char = get_char(src, *position);
-    while *position < src.length
-        && (current_char != "/" || esc)
-        && !REG_LINE_BREAK.is_match(current_char)
-    {
-        if esc {
-            esc = false;
-        } else if current_char == "\\" {
-            esc = true;
-        }
-
-        *position += 1;
-        *column += 1;
-        current_char = get_char(src, *position);
-    }
-
-    // unexpected boundary such as line break or ending of code
-    if current_char != "/" && current_char != "i" && current_char != "g" {
-        panic!(
-            "Unexpected character '{}' at line:{}, column:{}.",
-            match current_char {
-                "\n" => "\\n",
-                "\r" => "\\r",
-                _ => current_char,
-            },
-            line,
-            column
-        );
+    // the comma after an item of a list, unless `close` ends the list there
+    fn expect_separator(&mut self, close: &Token) -> ParseResult<()> {
+        if !self.is(close) && !self.eat(&Token::Comma) {
+            return self.expected(vec![TokenKind::Comma, close.kind()]);
+        }
+        Ok(())
     }
 
-    *position += 1;
+    fn expect_name(&mut self, name: &str) -> ParseResult<()> {
+        if !self.eat_name(name) {
+            return self.unexpected();
+        }
+        Ok(())
+    }
 
-    let modifier = match src.slice(*position, *position + 1) {
-        "i" => {
-            *position += 1;
-            Some(RegExpModifier::I)
+    fn has_newline_before(&self) -> bool {
+        match self.tokens.peek() {
+            Some(lexeme) => lexeme.newline_before,
+            None => false,
         }
-        "g" => {
-            *position += 1;
-            Some(RegExpModifier::G)
+    }
+
+    fn can_insert_semicolon(&self) -> bool {
+        matches!(self.peek(), None | Some(Token::BraceR)) || self.has_newline_before()
+    }
+
+    fn consume_semicolon(&mut self) -> ParseResult<()> {
+        if !self.eat(&Token::Semi) && !self.can_insert_semicolon() {
+            return self.expected(vec![TokenKind::Semi]);
         }
-        _ => None,
-    };
+        Ok(())
+    }
 
-    let raw = src.slice(start, *position);
-    let content = src.slice(
-        start + 1,
-        match modifier {
-            Some(_) => *position - 2,
-            None => *position,
-        },
-    );
-    tokens.push(Token::RegExp(
-        raw.to_string(),
-        content.to_string(),
-        modifier,
-    ));
-}
+    // line and column of the current token, or of the end of input
+    fn position(&self) -> (usize, usize) {
+        match self.tokens.peek() {
+            Some(lexeme) => (lexeme.line, lexeme.column),
+            None => self.tokens.end(),
+        }
+    }
 
-fn read_private_name(
-    src: &ReadonlyString,
-    position: &mut usize,
-    tokens: &mut Vec<Token>,
-    _line: usize,
-    column: &mut usize,
-) {
-    let start = *position;
+    // line and column right after the previous token
+    fn last_token_end(&self) -> (usize, usize) {
+        match self.tokens.previous() {
+            Some(lexeme) => (lexeme.end_line, lexeme.end_column),
+            None => (1, 0),
+        }
+    }
 
-    // read name
-    *position += 1;
+    fn start_loc(&self) -> SourceLocation {
+        let (line, column) = self.position();
+        let offset = match self.tokens.peek() {
+            Some(lexeme) => lexeme.offset,
+            None => self.end_offset,
+        };
+        SourceLocation::new(line, column, offset)
+    }
 
-    while *position < src.length && REG_IDENTIFIER.is_match(get_char(src, *position)) {
-        *position += 1;
-        *column += 1;
+    // closes a node started at `loc` with the end of the last token consumed
+    fn finish(&self, loc: SourceLocation) -> SourceLocation {
+        let (line, column) = self.last_token_end();
+        let end_offset = match self.tokens.previous() {
+            Some(lexeme) => lexeme.end_offset,
+            None => 0,
+        };
+        SourceLocation {
+            end: Position::new(line, column),
+            range: (loc.range.0, end_offset),
+            ..loc
+        }
     }
 
-    let raw = src.slice(start, *position);
-    let content = src.slice(start + 1, *position);
-    tokens.push(Token::PrivateName(raw.to_string(), content.to_string()));
-}
+    fn unexpected<T>(&self) -> ParseResult<T> {
+        Err(self.unexpected_error())
+    }
 
-fn read_keyword_or_name(
-    src: &ReadonlyString,
-    context: &mut Context,
-    position: &mut usize,
-    tokens: &mut Vec<Token>,
-    line: usize,
-    column: &mut usize,
-) {
-    let start = *position;
-    let startPos = Position::new(line, column);
-    while *position < src.length && REG_IDENTIFIER.is_match(get_char(src, *position)) {
-        *position += 1;
-        *column += 1;
-    }
-    let identifier = src.slice(start, *position);
-    let token = match identifier {
-        "var" => Token::Var,
-        "let" => Token::Let,
-        "const" => Token::Const,
-
-        "function" => Token::Function,
-        "return" => Token::Return,
-
-        "for" => Token::For,
-        "of" => Token::Of,
-        "do" => Token::Do,
-        "while" => Token::While,
-        "break" => Token::Break,
-        "continue" => Token::Continue,
-
-        "switch" => Token::Switch,
-        "case" => Token::Case,
-
-        "throw" => Token::Throw,
-        "try" => Token::Try,
-        "catch" => Token::Catch,
-        "finally" => Token::Finally,
-
-        "if" => Token::If,
-        "else" => Token::Else,
-
-        "new" => Token::New,
-        "this" => Token::This,
-        "super" => Token::Super,
-        "delete" => Token::Delete,
-        "class" => Token::Class,
-        "extends" => Token::Extends,
-        "instanceof" => Token::Instanceof,
-        "typeof" => Token::Typeof,
-
-        "import" => Token::Import,
-        "export" => Token::Export,
-        "default" => Token::Default,
-
-        "null" => Token::Null,
-        "undefined" => Token::Undefined,
-        "true" => Token::True,
-        "false" => Token::False,
-        "void" => Token::Void,
-
-        "in" => Token::In,
-
-        _ => {
-            context.is_function_identifier = false;
-            Token::Name(identifier.to_string())
+    // spans the unexpected token, or is at the end of input; an invalid
+    // token has its own error
+    fn unexpected_error(&self) -> ParseError {
+        let (line, column) = self.position();
+        let error = match self.tokens.peek() {
+            Some(Lexeme {
+                token: Token::Invalid(error),
+                ..
+            }) => return (**error).clone(),
+            Some(lexeme) => ParseError::new(
+                ParseErrorKind::UnexpectedToken,
+                &format!("Unexpected token '{}'", lexeme.token),
+                Position::new(line, column),
+                Position::new(lexeme.end_line, lexeme.end_column),
+            ),
+            None => ParseError::at(
+                ParseErrorKind::UnexpectedEnd,
+                "Unexpected end of input",
+                line,
+                column,
+            ),
+        };
+        ParseError {
+            found: self.peek().map(Token::kind),
+            ..error
         }
-    };
+    }
 
-    // validate token
-    match token {
-        Token::Name(_) => {
-            context.is_function_identifier = false;
-            context
-                .statements
-                .push(FunctionDeclaration::new(Identifier::new(identifier)))
-        }
-        Token::Function => {
-            if let Some(expressions) = &mut context.expressions {
-                expressions.push(Box::new(FunctionExpression::new()));
-            }
-            context.is_function_identifier = true;
-        }
-        _ => {
-            if context.is_function_identifier {
-                panic!(
-                    "Unexpected token '{}' at line:{}, column:{}",
-                    identifier, line, column
-                );
-            }
+    // an error at the current token, which isn't one of `expected`
+    fn expected<T>(&self, expected: Vec<TokenKind>) -> ParseResult<T> {
+        Err(self.tokens.expected(expected))
+    }
+
+    // `in` is an operator again within any kind of brackets, even inside the
+    // head of a `for`
+    fn allow_in<T>(&mut self, parse: impl FnOnce(&mut Self) -> T) -> T {
+        let no_in = std::mem::replace(&mut self.context.no_in, false);
+        let result = parse(self);
+        self.context.no_in = no_in;
+        result
+    }
+
+    // runs `parse` one level deeper, failing once that is deeper than the
+    // options allow and growing the stack as it goes, so that deeply nested
+    // input is an error rather than a stack overflow
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        if self.context.nesting >= self.context.max_nesting {
+            let (line, column) = self.position();
+            return self.raise(
+                ParseErrorKind::NestingTooDeep,
+                line,
+                column,
+                "Nesting too deep",
+            );
         }
-    };
+        self.context.nesting += 1;
+        let result = stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || parse(self));
+        self.context.nesting -= 1;
+        result
+    }
 
-    tokens.push(token);
-}
+    // runs `parse` in a block of its own, for the declarations in it
+    fn in_block<T>(&mut self, parse: impl FnOnce(&mut Self) -> ParseResult<T>) -> ParseResult<T> {
+        self.context.push_block();
+        let result = parse(self)?;
+        self.context.pop_block();
+        Ok(result)
+    }
 
-fn read_identifier(
-    src: &ReadonlyString,
-    context: &mut Context,
-    position: &mut usize,
-    tokens: &mut Vec<Token>,
-    line: usize,
-    column: &mut usize,
-) {
-    let start = *position;
-    let first_char = src.slice(start, start + 1);
-    if REG_NUMBERIC.is_match(first_char) {
-        read_numberic(src, position, tokens, line, column);
-    } else if first_char == "/" {
-        read_reg_exp(src, position, tokens, line, column);
-    } else if first_char == "#" {
-        read_private_name(src, position, tokens, line, column);
-    } else {
-        read_keyword_or_name(src, context, position, tokens, line, column);
+    // runs `parse` as the statement `break` or `continue` may refer to by
+    // `kind`, which starts at the current token
+    fn in_target<T>(
+        &mut self,
+        kind: TargetKind,
+        parse: impl FnOnce(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<T> {
+        let (line, column) = self.position();
+        let target = Target { kind, line, column };
+        self.context.scope_mut().targets.push(target);
+        let result = parse(self)?;
+        self.context.scope_mut().targets.pop();
+        Ok(result)
     }
-}
 
-fn read_operator(
-    src: &ReadonlyString,
-    position: &mut usize,
-    tokens: &mut Vec<Token>,
-    line: usize,
-    column: &mut usize,
-) {
-    let char3 = src.slice(*position, *position + 3);
-
-    match get_operator_by_chars(char3) {
-        Some(token) => {
-            *position += 3;
-            *column += 3;
-            tokens.push(token);
-        }
-        None => {
-            let char2 = src.slice(*position, *position + 2);
-            match get_operator_by_chars(char2) {
-                Some(token) => {
-                    *position += 2;
-                    *column += 2;
-                    tokens.push(token);
-                }
-                None => {
-                    let char1 = get_char(src, *position);
-                    *position += 1;
-                    *column += 1;
-                    tokens.push(
-                        get_operator_by_chars(char1).expect(
-                            format!(
-                                "Unexpected character '{}' at line:{}, column:{}.",
-                                char1, line, column,
-                            )
-                            .as_str(),
-                        ),
-                    );
-                }
-            }
+    fn declare(&mut self, identifier: &Identifier, kind: DeclarationKind) -> ParseResult<()> {
+        let start = identifier.loc.start;
+        let Err((first_line, first_column)) =
+            self.context
+                .declare(&identifier.name, kind, start.line, start.column)
+        else {
+            return Ok(());
+        };
+        let message = format!(
+            "Identifier '{}' has already been declared (first declared at line:{}, column:{})",
+            identifier.name, first_line, first_column
+        );
+        self.early(
+            ParseErrorKind::Redeclaration,
+            start.line,
+            start.column,
+            &message,
+        )
+    }
+
+    // runs `parse` with the cover errors found so far set aside, returning
+    // the ones it found along with its result
+    fn collect_cover_errors<T>(&mut self, parse: impl FnOnce(&mut Self) -> T) -> (T, CoverErrors) {
+        let outer = std::mem::take(&mut self.context.cover_errors);
+        let result = parse(self);
+        let errors = std::mem::replace(&mut self.context.cover_errors, outer);
+        (result, errors)
+    }
+
+    // once an expression is known not to be a pattern, anything in it that
+    // only a pattern allows is an error
+    fn check_pattern_errors(&self, errors: &CoverErrors) -> ParseResult<()> {
+        if let Some((line, column, message)) = errors.pattern {
+            return self.raise(ParseErrorKind::InvalidSyntax, line, column, message);
         }
+        Ok(())
     }
-}
 
-fn find_prev_char_ignore_whitespace(str: &ReadonlyString, start: usize) -> &str {
-    if start == 0 {
-        return "";
+    // once an expression is reinterpreted as a destructuring target, what
+    // no pattern allows is an error
+    fn check_destructuring_errors(&self, errors: &CoverErrors) -> ParseResult<()> {
+        if let Some((line, column, message)) = errors.assignment {
+            return self.raise(ParseErrorKind::InvalidTarget, line, column, message);
+        }
+        Ok(())
     }
 
-    let mut i: isize = (start - 1) as isize;
-    let mut current_char = get_char(str, i as usize);
-    while i >= 0 && REG_WHITESPACE.is_match(current_char) {
-        i -= 1;
-        current_char = get_char(str, i as usize);
+    // `message` at the start of a target that is invalid as a whole, as in
+    // `a + b = c`, or an error at the invalid part of a destructuring target
+    // such as the `1` of `[1] = a`
+    fn raise_invalid_target<T>(
+        &self,
+        (line, column): (usize, usize),
+        invalid: &SourceLocation,
+        message: &str,
+    ) -> ParseResult<T> {
+        let start = invalid.start;
+        if (start.line, start.column) == (line, column) {
+            return self.raise(ParseErrorKind::InvalidTarget, line, column, message);
+        }
+        self.raise(
+            ParseErrorKind::InvalidTarget,
+            start.line,
+            start.column,
+            "Invalid destructuring assignment target",
+        )
     }
-    match i {
-        -1 => "",
-        _ => current_char,
+
+    // keeps an error to carry on past in recovery mode, returning the index
+    // of the diagnostic an error node there refers to; an error where the
+    // last one was is most likely caused by it and is dropped
+    fn record(&mut self, error: ParseError) -> usize {
+        // one of an invalid token is the error the parser ran into it with
+        let skipped = self.tokens.take_invalid(error.start);
+        let skipped = skipped
+            .into_iter()
+            .filter(|skipped| skipped.start != error.start);
+        self.errors.extend(skipped);
+        if self.errors.last().map(|last| last.start) != Some(error.start) {
+            self.errors.push(error);
+        }
+        self.errors.len() - 1
     }
-}
 
-fn validate_token(context: &Context, char: &str, line: usize, column: usize) {
-    if context.is_function_identifier {
-        panic!(
-            "Unexpected character '{}' at line:{}, column:{}",
-            char, line, column
-        );
+    // an empty location at the current token, for an error node that
+    // skipped nothing
+    fn empty_loc(&self) -> SourceLocation {
+        let loc = self.start_loc();
+        SourceLocation {
+            end: loc.start,
+            range: (loc.range.0, loc.range.0),
+            ..loc
+        }
+    }
+
+    // `eval` and `arguments` can't be bound or assigned to in strict code;
+    // `action` says which, as in "Binding eval in strict mode"
+    fn check_eval_or_arguments(
+        &mut self,
+        identifier: &Identifier,
+        is_strict: bool,
+        action: &str,
+    ) -> ParseResult<()> {
+        if is_strict && matches!(identifier.name.as_str(), "eval" | "arguments") {
+            let start = identifier.loc.start;
+            let message = format!("{} {} in strict mode", action, identifier.name);
+            return self.early(
+                ParseErrorKind::StrictMode,
+                start.line,
+                start.column,
+                &message,
+            );
+        }
+        Ok(())
     }
-}
 
-pub fn parse(src: &str) -> Program {
-    let readonly_string = ReadonlyString::new(src);
-    let mut position: usize = 0;
-    let mut line: usize = 1;
-    let mut column: usize = 0;
-    let mut tokens: Vec<Token> = vec![];
-    let mut program = Program::new(line, column);
-    let mut context = Context::new(&mut program.body);
-
-    while position < readonly_string.length {
-        let char = get_char(&readonly_string, position);
-
-        if REG_WHITESPACE.is_match(char) {
-            position += 1;
-            if REG_LINE_BREAK.is_match(char) {
-                line += 1;
-                column = 0;
-            } else {
-                column += 1;
+    // the words only strict code reserves, for names read before a
+    // directive made the code strict, as a function's name and parameters
+    // are
+    fn check_strict_reserved(
+        &mut self,
+        identifier: &Identifier,
+        is_strict: bool,
+    ) -> ParseResult<()> {
+        if is_strict && is_strict_reserved(&identifier.name) {
+            let start = identifier.loc.start;
+            let message = format!("The keyword '{}' is reserved", identifier.name);
+            return self.early(
+                ParseErrorKind::ReservedWord,
+                start.line,
+                start.column,
+                &message,
+            );
+        }
+        Ok(())
+    }
+
+    // every name an assignment target writes to, as in `[eval] = a`
+    fn check_assignment_names(&mut self, pattern: &Pattern) -> ParseResult<()> {
+        for name in pattern.bound_names() {
+            self.check_eval_or_arguments(name, self.context.scope().is_strict, "Assigning to")?;
+        }
+        Ok(())
+    }
+
+    // syntax added in the `version` edition, which the options may rule out
+    fn check_ecma_version(
+        &mut self,
+        version: u32,
+        feature: &str,
+        line: usize,
+        column: usize,
+    ) -> ParseResult<()> {
+        if self.context.ecma_version >= version {
+            return Ok(());
+        }
+        let message = format!("{} are not available before ES{}", feature, version);
+        self.early(ParseErrorKind::UnsupportedSyntax, line, column, &message)
+    }
+
+    // checks the literal at the current token: numeric separators need
+    // ES2021, and legacy octal literals such as `017` and octal escapes such
+    // as `'\01'` are errors in strict code
+    fn check_literal(&mut self) -> ParseResult<()> {
+        if let Some(Token::Number(_)) | Some(Token::Bigint(_)) = self.peek() {
+            if self.tokens.text().contains('_') {
+                let (line, column) = self.position();
+                self.check_ecma_version(2021, "Numeric separators", line, column)?;
             }
-        } else if char == "/" {
-            validate_token(&context, char, line, column);
-            let prev_char = find_prev_char_ignore_whitespace(&readonly_string, position);
-            if REG_IDENTIFIER.is_match(prev_char) || prev_char == ")" || prev_char == "]" {
-                read_operator(
-                    &readonly_string,
-                    &mut position,
-                    &mut tokens,
-                    line,
-                    &mut column,
-                );
-            } else {
-                read_reg_exp(
-                    &readonly_string,
-                    &mut position,
-                    &mut tokens,
-                    line,
-                    &mut column,
-                );
+        }
+        if !self.context.scope().is_strict {
+            return Ok(());
+        }
+        let message = match self.peek() {
+            Some(Token::Number(_)) if is_legacy_octal(self.tokens.text()) => {
+                "Octal literal in strict mode"
+            }
+            Some(Token::String(_)) if has_octal_escape(self.tokens.text()) => {
+                "Octal escape sequences are not allowed in strict mode"
             }
-        } else if REG_STRING_BOUNDARY.is_match(char) {
-            validate_token(&context, char, line, column);
-            read_string(
-                &readonly_string,
-                &mut position,
-                &mut tokens,
+            _ => return Ok(()),
+        };
+        let (line, column) = self.position();
+        self.early(ParseErrorKind::StrictMode, line, column, message)
+    }
+
+    // an early error leaves what was parsed as it is, so in recovery mode it
+    // is recorded and parsing carries on past it
+    fn early(
+        &mut self,
+        kind: ParseErrorKind,
+        line: usize,
+        column: usize,
+        message: &str,
+    ) -> ParseResult<()> {
+        let error = ParseError::at(kind, message, line, column);
+        if !self.context.recover {
+            return Err(error);
+        }
+        self.record(error);
+        Ok(())
+    }
+
+    fn raise<T>(
+        &self,
+        kind: ParseErrorKind,
+        line: usize,
+        column: usize,
+        message: &str,
+    ) -> ParseResult<T> {
+        Err(ParseError::at(kind, message, line, column))
+    }
+
+    fn parse_identifier(&mut self) -> ParseResult<Identifier> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        let name = match self.peek() {
+            Some(Token::Name(name)) => name.clone(),
+            Some(Token::Of) => "of".into(),
+            Some(Token::Undefined) => "undefined".into(),
+            Some(Token::Let) => "let".into(),
+            _ => return self.expected(vec![TokenKind::Name]),
+        };
+        // `let` is a keyword only where it starts a declaration, and reserved
+        // as a name in strict code
+        if name == "let" && (self.context.scope().is_strict || self.context.is_module()) {
+            return self.raise(
+                ParseErrorKind::ReservedWord,
                 line,
-                &mut column,
+                column,
+                "Cannot use 'let' as an identifier in strict mode",
             );
-        } else if char == "#" {
-            validate_token(&context, char, line, column);
-            read_private_name(
-                &readonly_string,
-                &mut position,
-                &mut tokens,
+        }
+        let function_scope = self.context.function_scope();
+        if name == "arguments"
+            && (function_scope.is_field_initializer || function_scope.is_static_block)
+        {
+            return self.raise(
+                ParseErrorKind::Misplaced,
                 line,
-                &mut column,
+                column,
+                "'arguments' is not allowed in class field initializer or static initialization block",
             );
-        } else if REG_IDENTIFIER.is_match(char) {
-            read_identifier(
-                &readonly_string,
-                &mut context,
-                &mut position,
-                &mut tokens,
+        }
+        if name == "await" && self.context.function_scope().is_static_block {
+            return self.raise(
+                ParseErrorKind::ReservedWord,
                 line,
-                &mut column,
+                column,
+                "Cannot use 'await' as an identifier inside a class static block",
             );
-        } else {
-            validate_token(&context, char, line, column);
-            read_operator(
-                &readonly_string,
-                &mut position,
-                &mut tokens,
+        }
+        if name == "await" && self.context.scope().is_async && !self.context.is_module() {
+            return self.raise(
+                ParseErrorKind::ReservedWord,
                 line,
-                &mut column,
+                column,
+                "Cannot use 'await' as an identifier inside an async function",
             );
         }
+        if name == "yield" && self.context.scope().is_generator {
+            return self.raise(
+                ParseErrorKind::ReservedWord,
+                line,
+                column,
+                "Cannot use 'yield' as an identifier inside a generator",
+            );
+        }
+        if self.context.scope().is_strict && is_strict_reserved(&name) {
+            let message = format!("The keyword '{}' is reserved", name);
+            return self.raise(ParseErrorKind::ReservedWord, line, column, &message);
+        }
+        if name == "yield" && self.context.scope().is_strict && !self.context.is_module() {
+            return self.raise(
+                ParseErrorKind::ReservedWord,
+                line,
+                column,
+                "Cannot use 'yield' as an identifier in strict mode",
+            );
+        }
+        // modules are always strict and reserve `await` as well
+        if (name == "await" || name == "yield") && self.context.is_module() {
+            let message = format!("Cannot use '{}' as an identifier in a module", name);
+            return self.raise(ParseErrorKind::ReservedWord, line, column, &message);
+        }
+        self.tokens.bump();
+        Ok(Identifier::new(name, self.finish(loc)))
+    }
+
+    // any word, including reserved ones, as used for property and member names
+    fn parse_identifier_name(&mut self) -> ParseResult<Identifier> {
+        match self.peek() {
+            Some(token) if token.is_keyword() => {
+                let loc = self.start_loc();
+                let name = token.to_string();
+                self.tokens.bump();
+                Ok(Identifier::new(name, self.finish(loc)))
+            }
+            // nor is a word only strict code reserves
+            Some(Token::Name(name)) => {
+                let (loc, name) = (self.start_loc(), name.clone());
+                self.tokens.bump();
+                Ok(Identifier::new(name, self.finish(loc)))
+            }
+            _ => self.parse_identifier(),
+        }
+    }
+
+    fn parse_program(&mut self) -> ParseResult<Program> {
+        let mut program = Program::new(self.context.source_type);
+        self.context.is_directive = true;
+        while self.peek().is_some() {
+            let statement = self.recover(|parser| parser.parse_module_item())?;
+            let statement = self.mark_directive(statement)?;
+            self.context.scope_mut().statements.push(statement);
+        }
+        self.check_exported_locals()?;
+        program.body = self.context.pop_scope().statements;
+        program.loc = self.finish(program.loc);
+        Ok(program)
+    }
+
+    // a lone expression, which has to use up every token
+    fn parse_lone_expression(&mut self) -> ParseResult<Expression> {
+        let is_statement = match self.peek() {
+            Some(Token::Import) => !self.is_import_expression(),
+            Some(token) => matches!(
+                token,
+                Token::Var
+                    | Token::Let
+                    | Token::Const
+                    | Token::If
+                    | Token::For
+                    | Token::While
+                    | Token::Do
+                    | Token::Return
+                    | Token::Break
+                    | Token::Continue
+                    | Token::Throw
+                    | Token::Try
+                    | Token::Switch
+                    | Token::With
+                    | Token::Export
+            ),
+            None => false,
+        };
+        if is_statement {
+            let message = format!(
+                "Expected an expression but found the statement keyword '{}'",
+                self.peek().unwrap()
+            );
+            return Err(ParseError {
+                message,
+                ..self.unexpected_error()
+            });
+        }
+        let expression = self.parse_expression()?;
+        if self.peek().is_some() {
+            let error = self.unexpected_error();
+            let message = format!("{} after the end of the expression", error.message);
+            return Err(ParseError { message, ..error });
+        }
+        Ok(expression)
+    }
+
+    // import and export declarations are only allowed at the top level of a module
+    // `import.meta` and `import()` start an expression statement rather than
+    // an import
+    fn parse_module_item(&mut self) -> ParseResult<Statement> {
+        if self.is_import_expression() {
+            return self.parse_statement();
+        }
+        match self.peek() {
+            Some(Token::Import) | Some(Token::Export) if !self.context.is_module() => {
+                let (line, column) = self.position();
+                self.raise(
+                    ParseErrorKind::Misplaced,
+                    line,
+                    column,
+                    "'import' and 'export' may appear only with 'sourceType: module'",
+                )
+            }
+            Some(Token::Import) => Ok(Statement::ImportDeclaration(Box::new(
+                self.parse_import_declaration()?,
+            ))),
+            Some(Token::Export) => self.parse_export_declaration(),
+            _ => self.parse_statement(),
+        }
+    }
+}
+
+// words that are names in sloppy code but reserved in strict code, besides
+// `let` and `yield` which have their own messages
+fn is_strict_reserved(name: &str) -> bool {
+    matches!(
+        name,
+        "implements" | "interface" | "package" | "private" | "protected" | "public" | "static"
+    )
+}
+
+// `017`, and `08` which is decimal but just as deprecated
+fn is_legacy_octal(raw: &str) -> bool {
+    let mut chars = raw.chars();
+    chars.next() == Some('0') && chars.next().is_some_and(|char| char.is_ascii_digit())
+}
+
+// `\0` followed by a digit, or `\1` to `\9`
+fn has_octal_escape(raw: &str) -> bool {
+    let mut chars = raw.chars().peekable();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            continue;
+        }
+        match chars.next() {
+            Some('0') if chars.peek().is_some_and(|next| next.is_ascii_digit()) => return true,
+            Some('1'..='9') => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+// scripts remain the default so existing callers keep their behavior
+pub fn parse(src: &str) -> ParseResult<Program> {
+    parse_with_source_type(src, SourceType::Script)
+}
+
+pub fn parse_script(src: &str) -> ParseResult<Program> {
+    parse_with_source_type(src, SourceType::Script)
+}
+
+pub fn parse_module(src: &str) -> ParseResult<Program> {
+    parse_with_source_type(src, SourceType::Module)
+}
+
+pub fn parse_with_source_type(src: &str, source_type: SourceType) -> ParseResult<Program> {
+    let options = ParserOptions::new().source_type(source_type);
+    parse_with_options(src, &options).map(|output| output.program)
+}
+
+// keeps grouping parentheses as `ParenthesizedExpression` nodes, for tools
+// such as formatters that have to reproduce them
+pub fn parse_preserving_parens(src: &str, source_type: SourceType) -> ParseResult<Program> {
+    let options = ParserOptions::new()
+        .source_type(source_type)
+        .preserve_parens(true);
+    parse_with_options(src, &options).map(|output| output.program)
+}
+
+// a single expression instead of a program, for REPLs and evaluators; it may
+// be surrounded by whitespace and comments but by nothing else
+pub fn parse_expression(src: &str) -> ParseResult<Expression> {
+    let mut parser = Parser::new(src, Context::new(SourceType::Script));
+    let expression = parser.parse_lone_expression();
+    match parser.tokens.error() {
+        Some(error) => Err(error),
+        None => expression,
+    }
+}
+
+// allows `return` outside of functions, for code that a bundler or Node
+// wraps in a function before running it
+pub fn parse_allowing_return_outside_function(
+    src: &str,
+    source_type: SourceType,
+) -> ParseResult<Program> {
+    let options = ParserOptions::new()
+        .source_type(source_type)
+        .allow_return_outside_function(true);
+    parse_with_options(src, &options).map(|output| output.program)
+}
+
+// the comments of the source as well, in order, for tools such as formatters
+// that have to keep them
+pub fn parse_with_comments(
+    src: &str,
+    source_type: SourceType,
+) -> ParseResult<(Program, Vec<Comment>)> {
+    let options = ParserOptions::new().source_type(source_type).comments(true);
+    parse_with_options(src, &options).map(|output| (output.program, output.comments))
+}
+
+// every error in the source rather than only the first, for editors; what
+// couldn't be parsed is left in the program as error nodes, which refer to
+// the errors by their index
+pub fn parse_with_recovery(src: &str, source_type: SourceType) -> (Program, Vec<ParseError>) {
+    let options = ParserOptions::new().source_type(source_type).tolerant(true);
+    match parse_with_options(src, &options) {
+        Ok(output) => (output.program, output.errors),
+        Err(error) => (Program::new(source_type), vec![error]),
+    }
+}
+
+// what `parse_with_options` gives back; the comments are only collected
+// with `comments` and the errors with `tolerant`, which never fails
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseOutput {
+    pub program: Program,
+    pub comments: Vec<Comment>,
+    pub errors: Vec<ParseError>,
+}
+
+pub fn parse_with_options(src: &str, options: &ParserOptions) -> ParseResult<ParseOutput> {
+    let mut parser = Parser::new(src, Context::with_options(options));
+    let program = parser.parse_program();
+    // the tokens are lexed as the parser needs them, and an error lexing them
+    // is the only one reported, as it is what the parser stumbled on; only a
+    // lexer that isn't tolerant stops at one
+    if let Some(error) = parser.tokens.error() {
+        return Err(error);
+    }
+    let program = match program {
+        Ok(program) => program,
+        Err(error) if options.tolerant => {
+            parser.errors.push(error);
+            Program::new(options.source_type)
+        }
+        Err(error) => return Err(error),
+    };
+    // a tolerant lexer never stops, leaving invalid tokens, whose errors are
+    // those the parser ran into them with unless it skipped them
+    let end = Position::new(usize::MAX, 0);
+    let skipped = parser.tokens.take_invalid(end);
+    parser.errors.extend(skipped);
+    Ok(ParseOutput {
+        program,
+        comments: if options.comments {
+            parser.tokens.take_comments()
+        } else {
+            vec![]
+        },
+        errors: parser.errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        codegen::generate,
+        error::ParseErrorKind,
+        node::{Expression, Identifier, LogicalOperator, Node, Pattern, SourceType, Statement},
+        parser::parse,
+        sexpr::to_sexpr,
+        test_support::{error_at, README_SRC},
+        tokenize,
+    };
+
+    #[test]
+    fn debug_output_names_every_node() {
+        let output = format!("{:?}", parse(README_SRC).unwrap());
+        for name in [
+            "Program",
+            "VariableDeclaration",
+            "LogicalExpression",
+            "RegExpValue",
+            "ClassDeclaration",
+            "PropertyDefinition",
+            "PrivateIdentifier",
+            "FunctionDeclaration",
+            "ReturnStatement",
+            "BinaryExpression",
+            "ArrowFunctionExpression",
+            "CallExpression",
+        ] {
+            assert!(output.contains(name), "{} missing from {}", name, output);
+        }
+    }
+
+    #[test]
+    fn reparsing_gives_an_equal_tree() {
+        let program = parse(README_SRC).unwrap();
+        assert!(program == parse(README_SRC).unwrap());
+        assert!(program.clone() == program);
+        assert!(parse("a = b;").unwrap() != parse("a = c;").unwrap());
+    }
+
+    #[test]
+    fn function_parameters_can_be_read_from_the_tree() {
+        let program = parse(README_SRC).unwrap();
+        let mut names = vec![];
+        for statement in &program.body {
+            if let Statement::FunctionDeclaration(function) = statement {
+                for param in &function.params {
+                    if let Pattern::Identifier(identifier) = param {
+                        names.push(identifier.name.as_str());
+                    }
+                }
+            }
+        }
+        assert_eq!(names, ["a", "b"]);
+    }
+
+    #[test]
+    fn nodes_span_from_their_first_to_their_last_token() {
+        let program = parse("\nfunction add(a, b) {\n  return a + b;\n}\n").unwrap();
+        let function = match &program.body[0] {
+            Statement::FunctionDeclaration(function) => function,
+            _ => panic!("expected a function declaration"),
+        };
+        let loc = &function.loc;
+        assert_eq!((loc.start.line, loc.start.column), (2, 0));
+        assert_eq!((loc.end.line, loc.end.column), (4, 1));
+        let loc = &function.body.loc;
+        assert_eq!((loc.start.line, loc.start.column), (2, 19));
+        assert_eq!((loc.end.line, loc.end.column), (4, 1));
+        let loc = &program.loc;
+        assert_eq!((loc.end.line, loc.end.column), (4, 1));
+    }
+
+    #[test]
+    fn identifiers_are_located_where_they_are_written() {
+        let program = parse("let first;\n  second = first;").unwrap();
+        let identifier = match &program.body[1] {
+            Statement::Expression(statement) => match &statement.expression {
+                Expression::Assignment(assignment) => match &assignment.right {
+                    Expression::Identifier(identifier) => identifier,
+                    _ => panic!("expected an identifier"),
+                },
+                _ => panic!("expected an assignment"),
+            },
+            _ => panic!("expected an expression statement"),
+        };
+        assert_eq!(identifier.name, "first");
+        let loc = &identifier.loc;
+        assert_eq!((loc.start.line, loc.start.column), (2, 11));
+        assert_eq!((loc.end.line, loc.end.column), (2, 16));
+        assert_eq!(loc.range, (22, 27));
+        assert_eq!(loc.source, None);
+    }
+
+    #[test]
+    fn identifiers_of_the_same_name_share_it() {
+        let program = parse("let a = b;\nfunction f(a) { return a + b; }").unwrap();
+        let identifiers = program.find_all::<Identifier>();
+        let named = |name: &str| {
+            identifiers
+                .iter()
+                .filter(|identifier| identifier.name == name)
+                .collect::<Vec<_>>()
+        };
+        for name in ["a", "b"] {
+            let named = named(name);
+            assert!(named.len() > 1);
+            assert!(named.iter().all(|other| other.name.ptr_eq(&named[0].name)));
+        }
+        assert!(!named("a")[0].name.ptr_eq(&named("b")[0].name));
+    }
+
+    #[test]
+    fn programs_span_the_whole_source() {
+        let program = parse("").unwrap();
+        assert!(program.body.is_empty());
+        assert_eq!(program.source_type, SourceType::Script);
+        let loc = &program.loc;
+        assert_eq!((loc.start.line, loc.start.column), (1, 0));
+        assert_eq!((loc.end.line, loc.end.column), (1, 0));
+
+        let program = crate::parser::parse_module("  a;\nfoo();").unwrap();
+        assert_eq!(program.source_type, SourceType::Module);
+        let loc = &program.loc;
+        assert_eq!((loc.start.line, loc.start.column), (1, 0));
+        assert_eq!((loc.end.line, loc.end.column), (2, 6));
+        assert_eq!(loc.range, (0, 11));
+    }
+
+    #[test]
+    fn ranges_are_byte_offsets() {
+        let program = parse("a = \"é\"; b;").unwrap();
+        assert_eq!(program.body[0].loc().range, (0, 9));
+        assert_eq!(program.body[1].loc().range, (10, 12));
+        assert_eq!(program.loc.range, (0, 12));
+    }
+
+    #[test]
+    fn columns_count_characters_past_non_ascii_text() {
+        let src = "a = 'é'; b = `ü${c}ö`; /* ß */ d = /ñ/;\u{a0}e;\n'\u{1F600}'\u{2003}f";
+        let lexemes = tokenize(src).unwrap();
+        let at = |text: &str| {
+            let lexeme = lexemes
+                .iter()
+                .find(|lexeme| &src[lexeme.offset..lexeme.end_offset] == text)
+                .unwrap();
+            (lexeme.line, lexeme.column, lexeme.end_column)
+        };
+        assert_eq!(at("'é'"), (1, 4, 7));
+        assert_eq!(at("c"), (1, 17, 18));
+        assert_eq!(at("d"), (1, 31, 32));
+        assert_eq!(at("/ñ/"), (1, 35, 38));
+        // no-break and em spaces separate tokens as spaces do
+        assert_eq!(at("e"), (1, 40, 41));
+        assert_eq!(at("f"), (2, 4, 5));
+    }
+
+    #[test]
+    fn functions_record_whether_they_are_async_or_generators() {
+        let program =
+            parse("async function a() {} function* b() {} function c() {} x = async () => {};")
+                .unwrap();
+        let flags: Vec<(bool, bool)> = program.body[..3]
+            .iter()
+            .map(|statement| match statement {
+                Statement::FunctionDeclaration(function) => (function.is_async, function.generator),
+                _ => panic!("expected a function declaration"),
+            })
+            .collect();
+        assert_eq!(flags, [(true, false), (false, true), (false, false)]);
+        let arrow = match &program.body[3] {
+            Statement::Expression(statement) => match &statement.expression {
+                Expression::Assignment(assignment) => match &assignment.right {
+                    Expression::ArrowFunction(arrow) => arrow,
+                    _ => panic!("expected an arrow function"),
+                },
+                _ => panic!("expected an assignment"),
+            },
+            _ => panic!("expected an expression statement"),
+        };
+        assert!(arrow.is_async);
+    }
+
+    #[test]
+    fn nullish_coalescing_is_a_logical_expression() {
+        let program = parse("a ?? b.c;").unwrap();
+        let expression = match &program.body[0] {
+            Statement::Expression(statement) => &statement.expression,
+            _ => panic!("expected an expression statement"),
+        };
+        let logical = match expression {
+            Expression::Logical(logical) => logical,
+            _ => panic!("expected a logical expression"),
+        };
+        assert_eq!(logical.operator, LogicalOperator::NullishCoalescing);
+        assert!(matches!(logical.right, Expression::Member(_)));
+    }
+
+    #[test]
+    fn directive_prologues_become_directives() {
+        let program =
+            parse(r#"'use strict'; "a"; ("b"); "c"; function f() { "use\u0020strict"; x; "d" }"#)
+                .unwrap();
+        let directive = |statement: &Statement| match statement {
+            Statement::Directive(directive) => Some(directive.directive.clone()),
+            _ => None,
+        };
+        let directives: Vec<Option<String>> = program.body.iter().map(directive).collect();
+        assert_eq!(
+            directives,
+            [
+                Some("use strict".to_string()),
+                Some("a".to_string()),
+                None,
+                None,
+                None
+            ]
+        );
+        let Statement::FunctionDeclaration(function) = &program.body[4] else {
+            panic!("expected a function declaration");
+        };
+        let directives: Vec<Option<String>> = function.body.body.iter().map(directive).collect();
+        assert_eq!(
+            directives,
+            [Some(r"use\u0020strict".to_string()), None, None]
+        );
+    }
+
+    #[test]
+    fn only_an_unescaped_use_strict_makes_code_strict() {
+        let is_strict =
+            |src| parse(src).is_err_and(|error| error.kind == ParseErrorKind::StrictMode);
+        assert!(is_strict("'use strict'; with (a) {}"));
+        assert!(is_strict("function f() { 'use strict'; with (a) {} }"));
+        assert!(is_strict("'use strict'; function f() { with (a) {} }"));
+        assert!(!is_strict(r"'use\u0020strict'; with (a) {}"));
+        assert!(!is_strict("x; 'use strict'; with (a) {}"));
+        assert!(!is_strict("('use strict'); with (a) {}"));
+        assert!(!is_strict("function f() { 'use strict' } with (a) {}"));
+        assert!(!is_strict(
+            "class A { static { 'use strict' } } with (a) {}"
+        ));
+    }
+
+    #[test]
+    fn strict_code_reports_early_errors() {
+        use ParseErrorKind::*;
+        assert_eq!(error_at("'use strict'; eval = 1"), (StrictMode, 1, 14));
+        assert_eq!(error_at("'use strict'; arguments++"), (StrictMode, 1, 14));
+        assert_eq!(error_at("'use strict'; --eval"), (StrictMode, 1, 16));
+        assert_eq!(error_at("'use strict'; [a, eval] = b"), (StrictMode, 1, 18));
+        assert_eq!(
+            error_at("'use strict'; for (eval in a);"),
+            (StrictMode, 1, 19)
+        );
+        assert_eq!(error_at("'use strict'; var eval"), (StrictMode, 1, 18));
+        assert_eq!(
+            error_at("'use strict'; try {} catch (arguments) {}"),
+            (StrictMode, 1, 28)
+        );
+        assert_eq!(error_at("class eval {}"), (StrictMode, 1, 6));
+        assert_eq!(
+            error_at("function eval() { 'use strict' }"),
+            (StrictMode, 1, 9)
+        );
+        assert_eq!(
+            error_at("function f(a, a) { 'use strict' }"),
+            (InvalidParameters, 1, 14)
+        );
+        assert_eq!(error_at("'use strict'; a = 017"), (StrictMode, 1, 18));
+        assert_eq!(error_at(r"'use strict'; a = '\01'"), (StrictMode, 1, 18));
+        assert_eq!(
+            error_at(r"'use strict'; ({ '\01': 1 })"),
+            (StrictMode, 1, 17)
+        );
+        assert_eq!(
+            error_at(r"function f() { '\01'; 'use strict' }"),
+            (StrictMode, 1, 15)
+        );
+        let error = crate::parser::parse_module("import { a as eval } from 'm'").unwrap_err();
+        assert_eq!(
+            (error.kind, error.start.line, error.start.column),
+            (StrictMode, 1, 14)
+        );
+        assert_eq!(error.message, "Binding eval in strict mode");
+    }
+
+    #[test]
+    fn strict_code_reserves_more_words() {
+        use ParseErrorKind::*;
+        for word in [
+            "implements",
+            "interface",
+            "package",
+            "private",
+            "protected",
+            "public",
+        ] {
+            let src = format!("'use strict'; var {};", word);
+            assert_eq!(error_at(&src), (ReservedWord, 1, 18), "{}", src);
+            assert!(parse(&format!("var {0}; {0} = 1;", word)).is_ok());
+        }
+        assert_eq!(error_at("'use strict'; static = 1"), (ReservedWord, 1, 14));
+        assert_eq!(
+            error_at("function f(public) { 'use strict' }"),
+            (ReservedWord, 1, 11)
+        );
+        assert!(parse("'use strict'; ({ public: 1 }).public; a.let; a.yield").is_ok());
+    }
+
+    #[test]
+    fn strict_code_has_no_annex_b_statements() {
+        use ParseErrorKind::*;
+        assert_eq!(
+            error_at("'use strict'; for (var a = 1 in b);"),
+            (InvalidSyntax, 1, 19)
+        );
+        assert_eq!(
+            error_at("'use strict'; l: function f() {}"),
+            (StrictMode, 1, 17)
+        );
+        assert_eq!(
+            error_at("'use strict'; if (a) function f() {}"),
+            (StrictMode, 1, 21)
+        );
+        assert_eq!(
+            error_at("'use strict'; if (a) {} else function f() {}"),
+            (StrictMode, 1, 29)
+        );
+        assert!(parse("for (var a = 1 in b); l: function f() {} if (a) function g() {}").is_ok());
+        // which even sloppy code only allows for a single `var` name
+        assert_eq!(error_at("for (let a = 1 in b);"), (InvalidSyntax, 1, 5));
+        assert_eq!(error_at("for (var [a] = 1 in b);"), (InvalidSyntax, 1, 5));
+        assert_eq!(error_at("for (var a = 1 of b);"), (InvalidSyntax, 1, 5));
+        assert_eq!(error_at("for (var a, b in c);"), (InvalidSyntax, 1, 5));
+    }
+
+    #[test]
+    fn strictness_applies_to_nested_functions_only() {
+        assert!(parse("eval = 1; arguments++; var eval; a = 017; a = '\\01'").is_ok());
+        assert!(parse("function f(a, a) {} function eval(arguments) {}").is_ok());
+        assert!(parse("function f() { 'use strict' } eval = 1").is_ok());
+        assert!(parse("'use strict'; function f(a, a) {}").is_err());
+        assert!(parse("'use strict'; function f() { eval = 1 }").is_err());
+        assert!(parse("(function () { 'use strict'; return () => eval = 1 })").is_err());
+        // methods and arrows never allow duplicate parameters
+        assert!(parse("({ m(a, a) {} })").is_err());
+        assert!(parse("(a, a) => 1").is_err());
+    }
+
+    #[test]
+    fn strict_mode_errors_are_recovered_from() {
+        let (program, errors) = crate::parser::parse_with_recovery(
+            "'use strict'; var eval = 1; a;",
+            SourceType::Script,
+        );
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].kind, ParseErrorKind::StrictMode);
+        assert_eq!(program.body.len(), 3);
+    }
+
+    #[test]
+    fn early_errors_leave_the_statement_in_place() {
+        let (program, errors) = crate::parser::parse_with_recovery(
+            "let x; function x() { return 1 } foo(;",
+            SourceType::Script,
+        );
+        let errors: Vec<_> = errors
+            .iter()
+            .map(|error| (error.kind, error.start.column))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                (ParseErrorKind::Redeclaration, 16),
+                (ParseErrorKind::UnexpectedToken, 37)
+            ]
+        );
+        assert!(matches!(
+            program.body[..],
+            [
+                Statement::VariableDeclaration(_),
+                Statement::FunctionDeclaration(_),
+                Statement::Error(_)
+            ]
+        ));
+
+        let (program, errors) = crate::parser::parse_with_recovery(
+            "function f(a, a) { 'use strict'; '\\01'; } g();",
+            SourceType::Script,
+        );
+        let messages: Vec<_> = errors.iter().map(|error| error.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            [
+                "Octal escape sequences are not allowed in strict mode",
+                "Argument name clash"
+            ]
+        );
+        assert_eq!(program.body.len(), 2);
+    }
+
+    #[test]
+    fn conflicting_declarations_are_reported_with_both_positions() {
+        use ParseErrorKind::Redeclaration;
+        let error = parse("let x = 1;\nlet x = 2;").unwrap_err();
+        assert_eq!(
+            (error.kind, error.start.line, error.start.column),
+            (Redeclaration, 2, 4)
+        );
+        assert_eq!(
+            error.message,
+            "Identifier 'x' has already been declared (first declared at line:1, column:4)"
+        );
+        assert_eq!(error_at("{ const y = 1; var y; }"), (Redeclaration, 1, 19));
+        assert_eq!(error_at("let z; { var z; }"), (Redeclaration, 1, 13));
+        assert_eq!(error_at("{ var z; } let z;"), (Redeclaration, 1, 15));
+        assert_eq!(
+            error_at("{ function f() {} let f; }"),
+            (Redeclaration, 1, 22)
+        );
+        assert_eq!(error_at("let f; function f() {}"), (Redeclaration, 1, 16));
+        assert_eq!(error_at("class A {} var A;"), (Redeclaration, 1, 15));
+        assert_eq!(error_at("function f(a) { let a; }"), (Redeclaration, 1, 20));
+        assert_eq!(error_at("(a) => { const a = 1 }"), (Redeclaration, 1, 15));
+        assert_eq!(
+            error_at("try {} catch (e) { let e; }"),
+            (Redeclaration, 1, 23)
+        );
+        assert_eq!(error_at("for (let i;;) { var i; }"), (Redeclaration, 1, 20));
+        assert_eq!(
+            error_at("switch (a) { case 1: let b; case 2: let b; }"),
+            (Redeclaration, 1, 40)
+        );
+        assert_eq!(
+            error_at("'use strict'; { function f() {} function f() {} }"),
+            (Redeclaration, 1, 41)
+        );
+        let error = crate::parser::parse_module("import a from 'm'; var a;").unwrap_err();
+        assert_eq!(error.kind, Redeclaration);
+        let error = crate::parser::parse_module("function f() {} var f;").unwrap_err();
+        assert_eq!(error.kind, Redeclaration);
+    }
+
+    #[test]
+    fn compatible_declarations_are_allowed() {
+        assert!(parse("var x; var x; function x() {} var x;").is_ok());
+        assert!(parse("function f(a) { var a; function a() {} }").is_ok());
+        assert!(parse("let a; { let a; { var b; } } function g() { let a; }").is_ok());
+        assert!(parse("{ function f() {} function f() {} }").is_ok());
+        assert!(parse("try {} catch (e) { var e; }").is_ok());
+        assert!(parse("for (let i;;) {} for (let i;;) { let i; }").is_ok());
+        assert!(parse("(function f() { let f; })").is_ok());
+        assert!(parse("class A { static { var a; } m(a) {} } var a;").is_ok());
+    }
+
+    #[test]
+    fn break_and_continue_need_a_target() {
+        use ParseErrorKind::*;
+        assert_eq!(error_at("a; break;"), (Misplaced, 1, 3));
+        assert_eq!(error_at("if (a) continue;"), (Misplaced, 1, 7));
+        assert_eq!(error_at("while (a) { break b; }"), (InvalidSyntax, 1, 18));
+        assert_eq!(
+            error_at("while (a) { function f() { break; } }"),
+            (Misplaced, 1, 27)
+        );
+        assert_eq!(error_at("a: a: b;"), (InvalidSyntax, 1, 3));
+
+        let error = parse("switch (a) { case 1: continue; }").unwrap_err();
+        assert_eq!(
+            error.message,
+            "Illegal continue statement: no surrounding iteration statement \
+             (the nearest enclosing is the switch at line:1, column:0)"
+        );
+        let error = parse("while (a) b: { continue b; }").unwrap_err();
+        assert_eq!((error.start.line, error.start.column), (1, 24));
+        assert_eq!(
+            error.message,
+            "Illegal continue statement: 'b' does not denote an iteration statement \
+             (it labels the statement labeled 'b' at line:1, column:10)"
+        );
+    }
+
+    #[test]
+    fn break_and_continue_find_their_targets() {
+        assert!(parse("while (a) { if (b) break; else continue; }").is_ok());
+        assert!(parse("do { switch (a) { case 1: continue; default: break; } } while (b)").is_ok());
+        assert!(parse("a: { b: { break a; } }").is_ok());
+        assert!(parse("a: b: for (;;) { c: { continue a; } }").is_ok());
+        assert!(parse("a: while (x) { (() => { a: while (y) break a; })(); }").is_ok());
+    }
+
+    #[test]
+    fn misplaced_return_new_target_and_super_have_their_own_codes() {
+        use ParseErrorKind::*;
+        assert_eq!(error_at("a;\nreturn 1;"), (MisplacedReturn, 2, 0));
+        assert_eq!(error_at("a = new.target"), (MisplacedNewTarget, 1, 4));
+        assert_eq!(error_at("() => new.target"), (MisplacedNewTarget, 1, 6));
+        assert_eq!(error_at("a = super.x"), (MisplacedSuperProperty, 1, 4));
+        assert_eq!(
+            error_at("function f() { super[x] }"),
+            (MisplacedSuperProperty, 1, 15)
+        );
+        assert_eq!(
+            error_at("class A { constructor() { super() } }"),
+            (MisplacedSuperCall, 1, 26)
+        );
+        assert_eq!(
+            error_at("class A extends B { m() { super() } }"),
+            (MisplacedSuperCall, 1, 26)
+        );
+        assert_eq!(error_at("class A { m() { super } }"), (Misplaced, 1, 16));
+        assert!(parse("class A extends B { constructor() { super(); () => super.x } }").is_ok());
+        assert!(parse("function f() { new.target; return () => new.target }").is_ok());
+    }
+
+    #[test]
+    fn import_calls_take_one_argument_in_scripts_too() {
+        use ParseErrorKind::*;
+        assert!(parse("import('a').then(f); if (a) import(b + '.js');").is_ok());
+        assert_eq!(
+            generate(&parse("new (import('a'))").unwrap()),
+            "new (import('a'))();\n"
+        );
+        assert_eq!(error_at("new import('a')"), (InvalidSyntax, 1, 4));
+        assert_eq!(error_at("import('a', b)"), (UnexpectedToken, 1, 10));
+        assert_eq!(error_at("import(...a)"), (UnexpectedToken, 1, 7));
+        assert_eq!(error_at("import()"), (UnexpectedToken, 1, 7));
+    }
+
+    #[test]
+    fn top_level_return_can_be_allowed() {
+        use crate::parser::parse_allowing_return_outside_function;
+        let program = parse_allowing_return_outside_function("return 1", SourceType::Script);
+        assert!(matches!(program.unwrap().body[0], Statement::Return(_)));
+        assert!(parse_allowing_return_outside_function("return", SourceType::Module).is_ok());
+        let error = parse_allowing_return_outside_function(
+            "class A { static { return } }",
+            SourceType::Script,
+        )
+        .unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::MisplacedReturn);
+    }
+
+    #[test]
+    fn parses_a_lone_expression() {
+        use crate::parser::parse_expression;
+        let expression = parse_expression("  a + b * f(c) // sum\n").unwrap();
+        let Expression::Binary(binary) = &expression else {
+            panic!("expected a binary expression");
+        };
+        assert!(matches!(binary.right, Expression::Binary(_)));
+        assert_eq!((binary.loc.start.column, binary.loc.end.column), (2, 14));
+        assert!(matches!(
+            parse_expression("/* x */ { a: 1 }").unwrap(),
+            Expression::Object(_)
+        ));
+        assert!(matches!(
+            parse_expression("a, b").unwrap(),
+            Expression::Sequence(_)
+        ));
+
+        let error = parse_expression("a + b c").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!((error.start.line, error.start.column), (1, 6));
+        assert_eq!(
+            error.message,
+            "Unexpected token 'c' after the end of the expression"
+        );
+        let error = parse_expression("let x = 1").unwrap_err();
+        assert_eq!((error.start.line, error.start.column), (1, 0));
+        assert_eq!(
+            error.message,
+            "Expected an expression but found the statement keyword 'let'"
+        );
+        assert!(parse_expression("a;").is_err());
+        assert_eq!(
+            parse_expression(" ").unwrap_err().kind,
+            ParseErrorKind::UnexpectedEnd
+        );
+    }
+
+    #[test]
+    fn parser_errors_have_a_kind_and_position() {
+        use ParseErrorKind::*;
+        assert_eq!(error_at("a = ;"), (UnexpectedToken, 1, 4));
+        assert_eq!(error_at("a = (1"), (UnexpectedEnd, 1, 6));
+        assert_eq!(error_at("a + b = c"), (InvalidTarget, 1, 0));
+        assert_eq!(
+            error_at("function* g() { var yield }"),
+            (ReservedWord, 1, 20)
+        );
+        assert_eq!(error_at("return 1"), (MisplacedReturn, 1, 0));
+        assert_eq!(error_at("'use strict'; with (a) {}"), (StrictMode, 1, 14));
+        assert_eq!(
+            error_at("class A { constructor() {} constructor() {} }"),
+            (InvalidClassElement, 1, 27)
+        );
+        assert_eq!(error_at("({ get a(b) {} })"), (InvalidParameters, 1, 8));
+        assert_eq!(error_at("({ a = 1 })"), (InvalidSyntax, 1, 5));
+        assert_eq!(error_at(r"a = `\u{g}`"), (InvalidEscape, 1, 4));
+    }
+
+    #[test]
+    fn keywords_name_properties_but_not_bindings() {
+        let program = parse("obj.default({ new: 1, if() {} });").unwrap();
+        assert_eq!(generate(&program), "obj.default({ new: 1, if() {} });\n");
+        let module = crate::parser::parse_module(
+            "import { default as a, class as b } from 'm'; export { a as if, b as new };",
+        );
+        assert!(module.is_ok());
+
+        assert!(parse("({ if });").is_err());
+        assert!(parse("let { if } = o;").is_err());
+        assert!(crate::parser::parse_module("import { if } from 'm';").is_err());
+        assert!(crate::parser::parse_module("export { if };").is_err());
+    }
+
+    #[test]
+    fn let_is_a_name_unless_it_starts_a_declaration() {
+        let program = parse("var let = 1; let(x); let.prop; let = 2; let: for (;;) break let;");
+        assert_eq!(
+            to_sexpr(&program.unwrap()),
+            "(Program
+  (VariableDeclaration var (VariableDeclarator let 1))
+  (Expression (Call let x))
+  (Expression (Member let prop))
+  (Expression (Assignment = let 2))
+  (Labeled let (For () () () (Break let))))"
+        );
+        let program = parse("let [a] = b; let\nc = 1;").unwrap();
+        assert_eq!(
+            to_sexpr(&program),
+            "(Program
+  (VariableDeclaration let (VariableDeclarator (ArrayPattern a) b))
+  (VariableDeclaration let (VariableDeclarator c 1)))"
+        );
+
+        let error = parse("let let = 1;").unwrap_err();
+        assert_eq!(error.message, "let is disallowed as a lexically bound name");
+        assert!(parse("const [let] = a;").is_err());
+        let error = parse("'use strict'; var let = 1;").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::ReservedWord);
+        assert!(parse("'use strict'; let(x);").is_err());
+        assert!(crate::parser::parse_module("let: 1;").is_err());
     }
-    program
 }
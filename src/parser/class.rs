@@ -0,0 +1,503 @@
+use crate::{
+    context::{DeclarationKind, PrivateKind, PrivateNames},
+    error::{ParseErrorKind, ParseResult},
+    name::Name,
+    node::{
+        ClassBody, ClassDeclaration, ClassElement, ClassExpression, Expression, Identifier,
+        Literal, LiteralValue, MethodDefinition, MethodDefinitionKind, PrivateIdentifier,
+        PropertyDefinition, SourceLocation, StaticBlock,
+    },
+    token::{Arithmetic, Assign, Token, TokenKind},
+};
+
+use super::Parser;
+
+impl Parser<'_> {
+    // the name may only be left out under `export default`
+    pub(super) fn parse_class_declaration(
+        &mut self,
+        is_default_export: bool,
+    ) -> ParseResult<ClassDeclaration> {
+        let loc = self.start_loc();
+        let (id, super_class, body) = self.parse_class(is_default_export)?;
+        if let Some(id) = &id {
+            self.declare(id, DeclarationKind::Lexical)?;
+        }
+        Ok(ClassDeclaration::new(
+            id,
+            super_class,
+            body,
+            self.finish(loc),
+        ))
+    }
+
+    pub(super) fn parse_class_expression(&mut self) -> ParseResult<ClassExpression> {
+        let loc = self.start_loc();
+        let (id, super_class, body) = self.parse_class(true)?;
+        Ok(ClassExpression::new(
+            id,
+            super_class,
+            body,
+            self.finish(loc),
+        ))
+    }
+
+    // the name, superclass and body shared by declarations and expressions;
+    // every part of a class is strict mode code, whatever surrounds it
+    fn parse_class(
+        &mut self,
+        is_name_optional: bool,
+    ) -> ParseResult<(Option<Identifier>, Option<Expression>, ClassBody)> {
+        self.expect(&Token::Class)?;
+        let was_strict = std::mem::replace(&mut self.context.scope_mut().is_strict, true);
+        let id = if is_name_optional && (self.is(&Token::BraceL) || self.is(&Token::Extends)) {
+            None
+        } else {
+            let id = self.parse_identifier()?;
+            self.check_eval_or_arguments(&id, true, "Binding")?;
+            Some(id)
+        };
+        let super_class = if self.eat(&Token::Extends) {
+            Some(self.parse_call_expression()?)
+        } else {
+            None
+        };
+        let body = self.parse_class_body(super_class.is_some())?;
+        self.context.scope_mut().is_strict = was_strict;
+        Ok((id, super_class, body))
+    }
+
+    // only the constructor of a derived class may call `super()`
+    fn parse_class_body(&mut self, is_derived: bool) -> ParseResult<ClassBody> {
+        let loc = self.start_loc();
+        self.expect(&Token::BraceL)?;
+        self.context.classes.push(PrivateNames::default());
+        let mut body = vec![];
+        let mut has_constructor = false;
+        while !self.eat(&Token::BraceR) {
+            if self.peek().is_none() {
+                return self.expected(vec![TokenKind::BraceR]);
+            }
+            let (line, column) = self.position();
+            if let Some(element) = self.parse_class_element(is_derived)? {
+                if let ClassElement::MethodDefinition(method) = &element {
+                    if method.is_constructor() {
+                        if has_constructor {
+                            return self.raise(
+                                ParseErrorKind::InvalidClassElement,
+                                line,
+                                column,
+                                "Duplicate constructor in the same class",
+                            );
+                        }
+                        has_constructor = true;
+                    }
+                }
+                body.push(element);
+            }
+        }
+        self.resolve_private_names()?;
+        Ok(ClassBody::new(body, self.finish(loc)))
+    }
+
+    // a private name is used on `this.#a` and the like, and must be declared
+    // somewhere in the class it's used in or one around it, though maybe
+    // further down
+    pub(super) fn use_private_name(&mut self, name: &Name) -> ParseResult<()> {
+        let (line, column) = self.position();
+        match self.context.classes.last_mut() {
+            Some(class) => {
+                class.used.push((name.clone(), line, column));
+                Ok(())
+            }
+            None => self.undeclared_private_name(name, line, column),
+        }
+    }
+
+    // once the body is done, the names it used but didn't declare are left
+    // to the class around it, if there is one
+    fn resolve_private_names(&mut self) -> ParseResult<()> {
+        let class = self
+            .context
+            .classes
+            .pop()
+            .expect("class bodies are pushed and popped in pairs");
+        let undeclared = class
+            .used
+            .into_iter()
+            .filter(|(name, ..)| !class.declared.contains_key(name));
+        for (name, line, column) in undeclared {
+            match self.context.classes.last_mut() {
+                Some(outer) => outer.used.push((name, line, column)),
+                None => self.undeclared_private_name(&name, line, column)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn undeclared_private_name(
+        &mut self,
+        name: &Name,
+        line: usize,
+        column: usize,
+    ) -> ParseResult<()> {
+        let message = format!(
+            "Private field '#{}' must be declared in an enclosing class",
+            name
+        );
+        self.early(ParseErrorKind::InvalidSyntax, line, column, &message)
+    }
+
+    // each private name is declared once, but for a getter and a setter of
+    // the same name that are both static or both not
+    fn declare_private_name(
+        &mut self,
+        key: &Expression,
+        kind: PrivateKind,
+        is_static: bool,
+    ) -> ParseResult<()> {
+        let Expression::PrivateIdentifier(identifier) = key else {
+            return Ok(());
+        };
+        let class = self
+            .context
+            .classes
+            .last_mut()
+            .expect("class elements are parsed in a class body");
+        let name: Name = identifier.name.as_str().into();
+        let declared = match class.declared.get(&name) {
+            None => kind,
+            Some(&(PrivateKind::Get, was_static))
+                if kind == PrivateKind::Set && was_static == is_static =>
+            {
+                PrivateKind::Other
+            }
+            Some(&(PrivateKind::Set, was_static))
+                if kind == PrivateKind::Get && was_static == is_static =>
+            {
+                PrivateKind::Other
+            }
+            Some(_) => {
+                let start = identifier.loc.start;
+                let message = format!("Identifier '#{}' has already been declared", name);
+                return self.early(
+                    ParseErrorKind::Redeclaration,
+                    start.line,
+                    start.column,
+                    &message,
+                );
+            }
+        };
+        class.declared.insert(name, (declared, is_static));
+        Ok(())
+    }
+
+    fn is_class_element_name_start(&self) -> bool {
+        match self.peek() {
+            Some(Token::Name(_))
+            | Some(Token::PrivateName(_))
+            | Some(Token::Number(_))
+            | Some(Token::String(_))
+            | Some(Token::BracketL) => true,
+            Some(token) => token.is_keyword(),
+            None => false,
+        }
+    }
+
+    fn parse_class_element(&mut self, is_derived: bool) -> ParseResult<Option<ClassElement>> {
+        let loc = self.start_loc();
+        if self.eat(&Token::Semi) {
+            return Ok(None);
+        }
+
+        // `static` is only a modifier when a member name follows it, otherwise it
+        // is the name itself, as in `static() {}` or `static = 1`
+        let mut is_static = false;
+        let mut key = None;
+        if self.is_name("static") {
+            let static_loc = self.start_loc();
+            self.tokens.bump();
+            if self.is(&Token::BraceL) {
+                let start = static_loc.start;
+                self.check_ecma_version(2022, "Class static blocks", start.line, start.column)?;
+                return Ok(Some(ClassElement::StaticBlock(
+                    self.parse_static_block(loc)?,
+                )));
+            }
+            if self.is_class_element_name_start()
+                || self.is(&Token::Arithmetic(Arithmetic::Multiple))
+            {
+                is_static = true;
+            } else {
+                let static_key = Expression::Identifier(Box::new(Identifier::new(
+                    "static",
+                    self.finish(static_loc),
+                )));
+                key = Some((static_key, false, Some("static".into())));
+            }
+        }
+
+        // likewise `async` marks a method when a member name follows on the
+        // same line, and `get`/`set` an accessor when a member name follows
+        let mut is_async = false;
+        if key.is_none() && self.is_name("async") {
+            let async_loc = self.start_loc();
+            self.tokens.bump();
+            let is_modifier = self.is_class_element_name_start()
+                || self.is(&Token::Arithmetic(Arithmetic::Multiple));
+            if is_modifier && !self.has_newline_before() {
+                is_async = true;
+            } else {
+                let async_key = Expression::Identifier(Box::new(Identifier::new(
+                    "async",
+                    self.finish(async_loc),
+                )));
+                key = Some((async_key, false, Some("async".into())));
+            }
+        }
+
+        let generator = key.is_none() && self.eat(&Token::Arithmetic(Arithmetic::Multiple));
+
+        let mut kind = MethodDefinitionKind::Method;
+        if key.is_none() && !is_async && !generator && (self.is_name("get") || self.is_name("set"))
+        {
+            let accessor_loc = self.start_loc();
+            let name = self.bump()?.to_string();
+            if self.is_class_element_name_start() {
+                kind = if name == "get" {
+                    MethodDefinitionKind::Get
+                } else {
+                    MethodDefinitionKind::Set
+                };
+            } else {
+                let accessor_key = Expression::Identifier(Box::new(Identifier::new(
+                    name.as_str(),
+                    self.finish(accessor_loc),
+                )));
+                key = Some((accessor_key, false, Some(name.into())));
+            }
+        }
+
+        let (line, column) = self.position();
+        let (key, computed, key_name) = match key {
+            Some(key) => key,
+            None => self.parse_class_element_name()?,
+        };
+
+        if (is_async || generator) && !self.is(&Token::ParenL) {
+            return self.expected(vec![TokenKind::ParenL]);
+        }
+        let is_accessor = !matches!(kind, MethodDefinitionKind::Method);
+        if is_accessor || self.is(&Token::ParenL) {
+            if let Expression::PrivateIdentifier(_) = key {
+                self.check_ecma_version(2022, "Private methods", line, column)?;
+            }
+            if is_static && key_name.as_deref() == Some("prototype") {
+                return self.raise(
+                    ParseErrorKind::InvalidClassElement,
+                    line,
+                    column,
+                    "Classes may not have a static property named prototype",
+                );
+            }
+            if !is_static && key_name.as_deref() == Some("constructor") {
+                if is_accessor {
+                    return self.raise(
+                        ParseErrorKind::InvalidClassElement,
+                        line,
+                        column,
+                        "Constructor can't have get/set modifier",
+                    );
+                }
+                if is_async {
+                    return self.raise(
+                        ParseErrorKind::InvalidClassElement,
+                        line,
+                        column,
+                        "Constructor can't be an async method",
+                    );
+                }
+                if generator {
+                    return self.raise(
+                        ParseErrorKind::InvalidClassElement,
+                        line,
+                        column,
+                        "Constructor can't be a generator",
+                    );
+                }
+                kind = MethodDefinitionKind::Constructor;
+            }
+            let private_kind = match kind {
+                MethodDefinitionKind::Get => PrivateKind::Get,
+                MethodDefinitionKind::Set => PrivateKind::Set,
+                _ => PrivateKind::Other,
+            };
+            self.declare_private_name(&key, private_kind, is_static)?;
+            let value = match kind {
+                MethodDefinitionKind::Get => self.parse_accessor(false)?,
+                MethodDefinitionKind::Set => self.parse_accessor(true)?,
+                MethodDefinitionKind::Constructor => {
+                    self.parse_method(is_async, generator, is_derived)?
+                }
+                _ => self.parse_method(is_async, generator, false)?,
+            };
+            return Ok(Some(ClassElement::MethodDefinition(MethodDefinition::new(
+                key,
+                value,
+                kind,
+                computed,
+                is_static,
+                self.finish(loc),
+            ))));
+        }
+
+        self.check_ecma_version(2022, "Class fields", line, column)?;
+        match key_name.as_deref() {
+            Some("constructor") => {
+                return self.raise(
+                    ParseErrorKind::InvalidClassElement,
+                    line,
+                    column,
+                    "Classes can't have a field named 'constructor'",
+                )
+            }
+            Some("prototype") if is_static => {
+                return self.raise(
+                    ParseErrorKind::InvalidClassElement,
+                    line,
+                    column,
+                    "Classes can't have a static field named 'prototype'",
+                )
+            }
+            _ => {}
+        }
+        self.declare_private_name(&key, PrivateKind::Other, is_static)?;
+        // an initializer runs like a method body of its own, where
+        // `new.target` and `super.x` are allowed
+        let value = if self.eat(&Token::Assign(Assign::Normal)) {
+            self.context.push_method_scope(false, false, false);
+            self.context.scope_mut().is_field_initializer = true;
+            let value = self.parse_assignment_expression()?;
+            self.context.pop_scope();
+            Some(value)
+        } else {
+            None
+        };
+        self.consume_semicolon()?;
+        Ok(Some(ClassElement::PropertyDefinition(
+            PropertyDefinition::new(key, value, computed, is_static, self.finish(loc)),
+        )))
+    }
+
+    // the key of a class member, whether it is computed, and its static name
+    // when one is known (used for the `constructor`/`prototype` checks)
+    fn parse_class_element_name(&mut self) -> ParseResult<(Expression, bool, Option<Name>)> {
+        let loc = self.start_loc();
+        match self.peek() {
+            Some(Token::BracketL) => {
+                self.tokens.bump();
+                let key = self.parse_assignment_expression()?;
+                self.expect(&Token::BracketR)?;
+                Ok((key, true, None))
+            }
+            Some(Token::PrivateName(name)) => {
+                let name = name.to_string();
+                if name == "constructor" {
+                    let (line, column) = self.position();
+                    return self.raise(
+                        ParseErrorKind::InvalidClassElement,
+                        line,
+                        column,
+                        "Classes can't have an element named '#constructor'",
+                    );
+                }
+                self.tokens.bump();
+                Ok((
+                    Expression::PrivateIdentifier(Box::new(PrivateIdentifier::new(
+                        name,
+                        self.finish(loc),
+                    ))),
+                    false,
+                    None,
+                ))
+            }
+            Some(Token::String(content)) => {
+                let (raw, content) = (self.tokens.text().to_string(), content.clone());
+                self.check_literal()?;
+                self.tokens.bump();
+                let value = LiteralValue::String(content.to_string());
+                let key = Literal::new(value, raw, self.finish(loc));
+                Ok((Expression::Literal(Box::new(key)), false, Some(content)))
+            }
+            Some(Token::Number(value)) => {
+                let (raw, value) = (self.tokens.text().to_string(), *value);
+                self.check_literal()?;
+                self.tokens.bump();
+                let key = Literal::new(LiteralValue::Number(value), raw, self.finish(loc));
+                Ok((Expression::Literal(Box::new(key)), false, None))
+            }
+            _ => {
+                let key = self.parse_identifier_name()?;
+                let name = key.name.clone();
+                Ok((Expression::Identifier(Box::new(key)), false, Some(name)))
+            }
+        }
+    }
+
+    fn parse_static_block(&mut self, loc: SourceLocation) -> ParseResult<StaticBlock> {
+        self.expect(&Token::BraceL)?;
+        self.context.push_static_block_scope();
+        let body = self.parse_scope_statements(false)?;
+        Ok(StaticBlock::new(body, self.finish(loc)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ParseErrorKind, parser::parse, test_support::error_at};
+
+    #[test]
+    fn private_names_are_declared_once_in_an_enclosing_class() {
+        use ParseErrorKind::*;
+        assert_eq!(error_at("class C { #a; #a }"), (Redeclaration, 1, 14));
+        assert_eq!(error_at("class C { #a; #a() {} }"), (Redeclaration, 1, 14));
+        assert_eq!(
+            error_at("class C { get #a() {} get #a() {} }"),
+            (Redeclaration, 1, 26)
+        );
+        assert_eq!(
+            error_at("class C { static get #a() {} set #a(v) {} }"),
+            (Redeclaration, 1, 33)
+        );
+        assert_eq!(
+            error_at("class C { m() { this.#b } }"),
+            (InvalidSyntax, 1, 21)
+        );
+        assert_eq!(error_at("this.#a"), (InvalidSyntax, 1, 5));
+        // a getter and a setter make a pair, and a name may be used before
+        // it is declared or in a class nested inside
+        assert!(parse("class C { get #a() {} set #a(v) {} }").is_ok());
+        assert!(parse("class C { static set #a(v) {} static get #a() {} }").is_ok());
+        assert!(parse("class C { m() { this.#b; this?.#b } #b }").is_ok());
+        assert!(parse("class C { #b; m() { class D { n() { this.#b } } } }").is_ok());
+    }
+
+    #[test]
+    fn field_initializers_cannot_use_arguments() {
+        assert_eq!(
+            error_at("class C { x = arguments }"),
+            (ParseErrorKind::Misplaced, 1, 14)
+        );
+        assert_eq!(
+            error_at("class C { x = () => arguments }"),
+            (ParseErrorKind::Misplaced, 1, 20)
+        );
+        assert_eq!(
+            error_at("class C { static { arguments } }"),
+            (ParseErrorKind::Misplaced, 1, 19)
+        );
+        assert!(
+            parse("class C { x = function () { return arguments }; m() { arguments } }").is_ok()
+        );
+    }
+}
@@ -0,0 +1,328 @@
+use std::collections::VecDeque;
+
+use crate::{
+    error::{ParseError, ParseResult},
+    lexer::Lexer,
+    node::{Comment, Position},
+    token::{Lexeme, Token, TokenKind},
+};
+
+// how many tokens are kept behind the current one, for the end of the last
+// node and the token before the current one
+const BEHIND: usize = 2;
+
+// the tokens of a source as the parser pulls them from the lexer, holding
+// only the current one, the next and those few behind, unless the parser
+// looks further ahead or has a checkpoint to go back to
+pub(crate) struct TokenCursor<'a> {
+    lexer: Lexer<'a>,
+    buffer: VecDeque<Lexeme>,
+    // the index among all the tokens of the first one in the buffer
+    start: usize,
+    index: usize,
+    // where the checkpoints not yet rewound to or committed are, whose
+    // tokens are kept from the oldest on
+    checkpoints: Vec<usize>,
+    // the error the lexer stopped at, after which the tokens seem to end
+    error: Option<ParseError>,
+    // those of the invalid tokens lexed so far, when the lexer is tolerant
+    invalid: Vec<ParseError>,
+}
+
+// a place in the tokens to go back to, for the few places where what was
+// parsed turns out to be something else
+#[must_use]
+pub(crate) struct Checkpoint {
+    index: usize,
+}
+
+impl<'a> TokenCursor<'a> {
+    pub(crate) fn new(src: &'a str, tolerant: bool) -> Self {
+        let mut cursor = TokenCursor {
+            lexer: Lexer::new(src).tolerant(tolerant),
+            buffer: VecDeque::new(),
+            start: 0,
+            index: 0,
+            checkpoints: vec![],
+            error: None,
+            invalid: vec![],
+        };
+        cursor.fill(1);
+        cursor
+    }
+
+    // lexes until the buffer holds the token `offset` after the current one,
+    // if there is one
+    fn fill(&mut self, offset: usize) {
+        while self.start + self.buffer.len() <= self.index + offset && self.error.is_none() {
+            match self.lexer.next() {
+                Some(Ok(lexeme)) => {
+                    if let Token::Invalid(error) = &lexeme.token {
+                        self.invalid.push((**error).clone());
+                    }
+                    self.buffer.push_back(lexeme);
+                }
+                Some(Err(error)) => self.error = Some(error),
+                None => break,
+            }
+        }
+    }
+
+    // drops the tokens neither just behind the current one nor after a
+    // checkpoint
+    fn trim(&mut self) {
+        let oldest = self
+            .checkpoints
+            .iter()
+            .copied()
+            .fold(self.index, usize::min);
+        while self.start + BEHIND < oldest {
+            self.buffer.pop_front();
+            self.start += 1;
+        }
+    }
+
+    // the index of the current token among all the tokens, for telling
+    // whether the parser has moved on
+    pub(crate) fn index(&self) -> usize {
+        self.index
+    }
+
+    fn get(&self, index: usize) -> Option<&Lexeme> {
+        index
+            .checked_sub(self.start)
+            .and_then(|index| self.buffer.get(index))
+    }
+
+    pub(crate) fn peek(&self) -> Option<&Lexeme> {
+        self.get(self.index)
+    }
+
+    pub(crate) fn peek2(&self) -> Option<&Lexeme> {
+        self.get(self.index + 1)
+    }
+
+    // any number of tokens ahead, lexing them if need be
+    pub(crate) fn peek_nth(&mut self, offset: usize) -> Option<&Lexeme> {
+        self.fill(offset);
+        self.get(self.index + offset)
+    }
+
+    pub(crate) fn previous(&self) -> Option<&Lexeme> {
+        self.index.checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    // the current token as written, empty at the end
+    pub(crate) fn text(&self) -> &'a str {
+        self.peek()
+            .map_or("", |lexeme| lexeme.text(self.lexer.src()))
+    }
+
+    pub(crate) fn is(&self, kind: &TokenKind) -> bool {
+        self.peek()
+            .is_some_and(|lexeme| lexeme.token.kind() == *kind)
+    }
+
+    // moves on from the current token and gives it back, or `None` at the end
+    pub(crate) fn bump(&mut self) -> Option<&Lexeme> {
+        self.peek()?;
+        self.index += 1;
+        self.trim();
+        self.fill(1);
+        self.previous()
+    }
+
+    pub(crate) fn eat(&mut self, kind: &TokenKind) -> bool {
+        let is = self.is(kind);
+        if is {
+            self.bump();
+        }
+        is
+    }
+
+    pub(crate) fn expect(&mut self, kind: &TokenKind) -> ParseResult<&Token> {
+        if !self.is(kind) {
+            return Err(self.expected(vec![kind.clone()]));
+        }
+        Ok(&self.bump().unwrap().token)
+    }
+
+    // an error at the current token, which isn't one of `expected`, unless
+    // it is an invalid one, whose own error comes first
+    pub(crate) fn expected(&self, expected: Vec<TokenKind>) -> ParseError {
+        let (found, start, end) = match self.peek() {
+            Some(Lexeme {
+                token: Token::Invalid(error),
+                ..
+            }) => return (**error).clone(),
+            Some(lexeme) => (
+                Some(lexeme.token.kind()),
+                Position::new(lexeme.line, lexeme.column),
+                Position::new(lexeme.end_line, lexeme.end_column),
+            ),
+            None => {
+                let (line, column) = self.end();
+                let end = Position::new(line, column);
+                (None, end, end)
+            }
+        };
+        ParseError::expected(expected, found, (start, end))
+    }
+
+    pub(crate) fn checkpoint(&mut self) -> Checkpoint {
+        self.checkpoints.push(self.index);
+        Checkpoint { index: self.index }
+    }
+
+    // goes back to the checkpoint, which is done with
+    pub(crate) fn rewind(&mut self, checkpoint: Checkpoint) {
+        self.index = checkpoint.index;
+        self.commit(checkpoint);
+    }
+
+    // stays where the parser got to since the checkpoint, which is done with
+    pub(crate) fn commit(&mut self, checkpoint: Checkpoint) {
+        if let Some(position) = self
+            .checkpoints
+            .iter()
+            .rposition(|index| *index == checkpoint.index)
+        {
+            self.checkpoints.remove(position);
+        }
+        self.trim();
+    }
+
+    // how many checkpoints are still to be rewound to or committed, for
+    // letting go of those an error skipped
+    pub(crate) fn checkpoints(&self) -> usize {
+        self.checkpoints.len()
+    }
+
+    pub(crate) fn truncate_checkpoints(&mut self, len: usize) {
+        self.checkpoints.truncate(len);
+        self.trim();
+    }
+
+    // the error the lexer stops at, lexing the rest of the source for it if
+    // the parser stopped short, as an error in the tokens is reported before
+    // any the parser found in them
+    pub(crate) fn error(&mut self) -> Option<ParseError> {
+        if self.error.is_none() {
+            self.error = self.lexer.by_ref().find_map(Result::err);
+        }
+        self.error.take()
+    }
+
+    // the line and column right after the last token once there are no more
+    pub(crate) fn end(&self) -> (usize, usize) {
+        self.lexer.end()
+    }
+
+    pub(crate) fn take_comments(&mut self) -> Vec<Comment> {
+        self.lexer.take_comments()
+    }
+
+    // the errors of the invalid tokens lexed so far that start up to
+    // `start`, which the parser may have skipped without running into them
+    pub(crate) fn take_invalid(&mut self, start: Position) -> Vec<ParseError> {
+        let before = self
+            .invalid
+            .iter()
+            .take_while(|error| {
+                (error.start.line, error.start.column) <= (start.line, start.column)
+            })
+            .count();
+        self.invalid.drain(..before).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ParseErrorKind,
+        node::Position,
+        parser::cursor::TokenCursor,
+        token::{Lexeme, Token, TokenKind},
+    };
+
+    #[test]
+    fn token_cursors_peek_and_bump() {
+        let mut tokens = TokenCursor::new("a ( 1 ) ;", false);
+        let token = |lexeme: Option<&Lexeme>| lexeme.map(|lexeme| lexeme.token.kind());
+        assert_eq!(token(tokens.peek()), Some(TokenKind::Name));
+        assert_eq!(token(tokens.peek2()), Some(TokenKind::ParenL));
+        assert_eq!(token(tokens.peek_nth(4)), Some(TokenKind::Semi));
+        assert_eq!(token(tokens.peek_nth(5)), None);
+        assert_eq!(token(tokens.bump()), Some(TokenKind::Name));
+        assert_eq!(token(tokens.previous()), Some(TokenKind::Name));
+        assert!(!tokens.eat(&TokenKind::ParenR));
+        assert!(tokens.eat(&TokenKind::ParenL));
+        assert_eq!(tokens.index(), 2);
+        for _ in 0..3 {
+            tokens.bump();
+        }
+        assert_eq!(token(tokens.peek()), None);
+        assert_eq!(token(tokens.bump()), None);
+        assert_eq!(tokens.index(), 5);
+        assert_eq!(tokens.end(), (1, 9));
+    }
+
+    #[test]
+    fn token_cursors_expect_a_kind_or_report_what_they_found() {
+        let mut tokens = TokenCursor::new("( {", false);
+        assert_eq!(tokens.expect(&TokenKind::ParenL), Ok(&Token::ParenL));
+        let error = tokens.expect(&TokenKind::ParenR).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedToken);
+        assert_eq!(error.message, "Expected `)` but found `{`");
+        assert_eq!(
+            (error.start, error.end),
+            (Position::new(1, 2), Position::new(1, 3))
+        );
+        assert_eq!(error.found, Some(TokenKind::BraceL));
+        tokens.bump();
+        let error = tokens.expect(&TokenKind::BraceR).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+        assert_eq!(error.start, Position::new(1, 3));
+    }
+
+    #[test]
+    fn token_cursors_rewind_to_checkpoints() {
+        let src = (0..10).map(|n| n.to_string()).collect::<Vec<_>>().join(" ");
+        let mut tokens = TokenCursor::new(&src, false);
+        let number = |tokens: &TokenCursor| match tokens.peek().map(|lexeme| &lexeme.token) {
+            Some(Token::Number(_)) => tokens.text().to_string(),
+            _ => panic!("expected a number"),
+        };
+        tokens.bump();
+        let outer = tokens.checkpoint();
+        for _ in 0..3 {
+            tokens.bump();
+        }
+        let inner = tokens.checkpoint();
+        for _ in 0..4 {
+            tokens.bump();
+        }
+        assert_eq!(number(&tokens), "8");
+        tokens.rewind(inner);
+        assert_eq!(number(&tokens), "4");
+        assert_eq!(tokens.previous().map(|lexeme| lexeme.column), Some(6));
+        tokens.bump();
+        tokens.rewind(outer);
+        assert_eq!((tokens.index(), number(&tokens)), (1, "1".to_string()));
+        let committed = tokens.checkpoint();
+        tokens.bump();
+        tokens.commit(committed);
+        assert_eq!(number(&tokens), "2");
+        assert_eq!(tokens.checkpoints(), 0);
+    }
+
+    #[test]
+    fn token_cursors_stop_at_a_lexer_error() {
+        let mut tokens = TokenCursor::new("a 'b", false);
+        assert!(tokens.eat(&TokenKind::Name));
+        assert!(tokens.peek().is_none());
+        let error = tokens.error().unwrap();
+        assert_eq!(error.kind, ParseErrorKind::UnterminatedString);
+        assert_eq!(tokens.error(), None);
+    }
+}
@@ -0,0 +1,1675 @@
+use crate::{
+    context::CoverErrors,
+    error::{ParseErrorKind, ParseResult},
+    name::Name,
+    node::{
+        ArrayExpression, ArrayExpressionElement, AssignmentExpression, AssignmentExpressionLeft,
+        AssignmentOperator, AwaitExpression, BinaryExpression, BinaryOperator, CallExpression,
+        CallExpressionArgument, ChainExpression, ConditionalExpression, ErrorNode, Expression,
+        Identifier, ImportExpression, Literal, LiteralValue, LogicalExpression, LogicalOperator,
+        MemberExpression, MetaProperty, NewExpression, Node, Null, ObjectExpression,
+        ObjectExpressionProperty, ParenthesizedExpression, Pattern, Position, PrivateIdentifier,
+        Property, PropertyKey, PropertyKind, SequenceExpression, SourceLocation, SpreadElement,
+        Super, TaggedTemplateExpression, TemplateElement, TemplateLiteral, ThisExpression,
+        UnaryExpression, UnaryOperator, UpdateExpression, UpdateOperator, YieldExpression,
+    },
+    token::{Arithmetic, Assign, Comparation, Token, TokenKind},
+};
+
+use super::Parser;
+
+// the items between a pair of parentheses, each with the line and column it
+// starts at, read before it is known whether they are arguments, a
+// parenthesized expression or the parameters of an arrow function
+pub(super) struct CoverList {
+    pub(super) items: Vec<(CallExpressionArgument, usize, usize)>,
+    pub(super) trailing_comma: Option<(usize, usize)>,
+    // where the closing paren is
+    pub(super) end: (usize, usize),
+    pub(super) errors: CoverErrors,
+}
+
+impl CoverList {
+    fn into_arguments(self) -> Vec<CallExpressionArgument> {
+        self.items.into_iter().map(|(item, _, _)| item).collect()
+    }
+}
+
+fn update_operator(token: &Token) -> Option<UpdateOperator> {
+    match token {
+        Token::Increment => Some(UpdateOperator::Increment),
+        Token::Decrement => Some(UpdateOperator::Decrement),
+        _ => None,
+    }
+}
+
+fn assignment_operator(assign: &Assign) -> AssignmentOperator {
+    match assign {
+        Assign::Normal => AssignmentOperator::Normal,
+        Assign::Addition => AssignmentOperator::Addition,
+        Assign::Subtraction => AssignmentOperator::Subtraction,
+        Assign::Multiplication => AssignmentOperator::Multiplication,
+        Assign::Division => AssignmentOperator::Division,
+        Assign::Remainder => AssignmentOperator::Remainder,
+        Assign::Exponentiation => AssignmentOperator::Exponentiation,
+        Assign::LeftShift => AssignmentOperator::LeftShift,
+        Assign::RightShift => AssignmentOperator::RightShift,
+        Assign::URightShift => AssignmentOperator::URightShift,
+        Assign::BitwiseOR => AssignmentOperator::BitwiseOR,
+        Assign::BitwiseXOR => AssignmentOperator::BitwiseXOR,
+        Assign::BitwiseAND => AssignmentOperator::BitwiseAND,
+        Assign::LogicalOR => AssignmentOperator::LogicalOR,
+        Assign::LogicalAND => AssignmentOperator::LogicalAND,
+        Assign::NullishCoalescing => AssignmentOperator::NullishCoalescing,
+    }
+}
+
+enum InfixOperator {
+    Binary(BinaryOperator),
+    Logical(LogicalOperator),
+}
+
+// binary and logical operators and how tightly they bind, loosest first
+fn infix_operator(token: &Token) -> Option<(InfixOperator, u8)> {
+    use InfixOperator::{Binary, Logical};
+    let operator = match token {
+        Token::LogicalOR => (Logical(LogicalOperator::LogicalOR), 1),
+        Token::NullishCoalesce => (Logical(LogicalOperator::NullishCoalescing), 1),
+        Token::LogicalAND => (Logical(LogicalOperator::LogicalAND), 2),
+        Token::BitwiseOR => (Binary(BinaryOperator::BitwiseOR), 3),
+        Token::BitwiseXOR => (Binary(BinaryOperator::BitwiseXOR), 4),
+        Token::BitwiseAND => (Binary(BinaryOperator::BitwiseAND), 5),
+        Token::Comparation(Comparation::DoubleE) => (Binary(BinaryOperator::DoubleE), 6),
+        Token::Comparation(Comparation::DoubleNE) => (Binary(BinaryOperator::DoubleNE), 6),
+        Token::Comparation(Comparation::TripleE) => (Binary(BinaryOperator::TripleE), 6),
+        Token::Comparation(Comparation::TripleNE) => (Binary(BinaryOperator::TripleNE), 6),
+        Token::Comparation(Comparation::LT) => (Binary(BinaryOperator::LT), 7),
+        Token::Comparation(Comparation::LTE) => (Binary(BinaryOperator::LTE), 7),
+        Token::Comparation(Comparation::GT) => (Binary(BinaryOperator::GT), 7),
+        Token::Comparation(Comparation::GTE) => (Binary(BinaryOperator::GTE), 7),
+        Token::Instanceof => (Binary(BinaryOperator::Instanceof), 7),
+        Token::In => (Binary(BinaryOperator::In), 7),
+        Token::LeftShift => (Binary(BinaryOperator::LeftShift), 8),
+        Token::RightShift => (Binary(BinaryOperator::RightShift), 8),
+        Token::URightShift => (Binary(BinaryOperator::URightShift), 8),
+        Token::Arithmetic(Arithmetic::Plus) => (Binary(BinaryOperator::Plus), 9),
+        Token::Arithmetic(Arithmetic::Minus) => (Binary(BinaryOperator::Minus), 9),
+        Token::Arithmetic(Arithmetic::Multiple) => (Binary(BinaryOperator::Multiple), 10),
+        Token::Arithmetic(Arithmetic::Divide) => (Binary(BinaryOperator::Divide), 10),
+        Token::Arithmetic(Arithmetic::Modulo) => (Binary(BinaryOperator::Modulo), 10),
+        Token::Arithmetic(Arithmetic::Exponent) => (Binary(BinaryOperator::Exponent), 11),
+        _ => return None,
+    };
+    Some(operator)
+}
+
+// `&&` and `||` on one side of `??` and the other operator on the other
+fn is_mixed_coalesce(operator: &InfixOperator, next: &InfixOperator) -> bool {
+    use InfixOperator::Logical;
+    match (operator, next) {
+        (Logical(LogicalOperator::NullishCoalescing), Logical(next)) => {
+            !matches!(next, LogicalOperator::NullishCoalescing)
+        }
+        (Logical(_), Logical(LogicalOperator::NullishCoalescing)) => true,
+        _ => false,
+    }
+}
+
+impl Parser<'_> {
+    // a full expression, where commas build a sequence; places that only take
+    // a single value, like arguments and initializers, use
+    // `parse_assignment_expression` instead
+    pub(super) fn parse_expression(&mut self) -> ParseResult<Expression> {
+        let (expression, errors) =
+            self.collect_cover_errors(|parser| parser.parse_cover_expression());
+        let expression = expression?;
+        self.check_pattern_errors(&errors)?;
+        Ok(expression)
+    }
+
+    // an expression that may still turn out to be a pattern, like the left
+    // side of a for-in, leaving its cover errors to the caller
+    pub(super) fn parse_cover_expression(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let expression = self.parse_cover_assignment_expression()?;
+        if !self.is(&Token::Comma) {
+            return Ok(expression);
+        }
+        let mut expressions = vec![expression];
+        while self.eat(&Token::Comma) {
+            expressions.push(self.parse_cover_assignment_expression()?);
+        }
+        Ok(Expression::Sequence(Box::new(SequenceExpression::new(
+            expressions,
+            self.finish(loc),
+        ))))
+    }
+
+    // `yield` takes no argument when a line break or anything that can't
+    // start an expression follows, as in `yield;` or `f(yield)`
+    fn parse_yield_expression(&mut self) -> ParseResult<YieldExpression> {
+        let loc = self.start_loc();
+        self.tokens.bump();
+        if self.has_newline_before() {
+            return Ok(YieldExpression::new(None, false, self.finish(loc)));
+        }
+        let delegate = self.eat(&Token::Arithmetic(Arithmetic::Multiple));
+        let argument = match self.peek() {
+            None
+            | Some(Token::ParenR)
+            | Some(Token::BracketR)
+            | Some(Token::BraceR)
+            | Some(Token::Comma)
+            | Some(Token::Semi)
+            | Some(Token::Colon)
+            | Some(Token::In)
+                if !delegate =>
+            {
+                None
+            }
+            Some(Token::Template(..)) if !delegate && self.tokens.text().starts_with('}') => None,
+            _ => Some(self.parse_assignment_expression()?),
+        };
+        Ok(YieldExpression::new(argument, delegate, self.finish(loc)))
+    }
+
+    // an arrow function ends the expression it starts unless it is wrapped in
+    // parentheses, as `() => {}` can't be called or be an operand; an arrow
+    // whose body ends in a paren has already taken whatever could follow
+    fn is_bare_arrow_function(&self, expression: &Expression) -> bool {
+        expression.is_arrow_function()
+            && self.tokens.previous().map(|lexeme| &lexeme.token) != Some(&Token::ParenR)
+    }
+
+    pub(super) fn parse_assignment_expression(&mut self) -> ParseResult<Expression> {
+        let (expression, errors) =
+            self.collect_cover_errors(|parser| parser.parse_cover_assignment_expression());
+        let expression = expression?;
+        self.check_pattern_errors(&errors)?;
+        Ok(expression)
+    }
+
+    // assignment is right-associative, so `a = b = c` assigns `b = c` to `a`;
+    // what only a pattern allows is left to the caller, as `[{ a = 1 }]` may
+    // still be the left side of an enclosing assignment
+    fn parse_cover_assignment_expression(&mut self) -> ParseResult<Expression> {
+        self.nested(|parser| parser.parse_bare_cover_assignment_expression())
+    }
+
+    fn parse_bare_cover_assignment_expression(&mut self) -> ParseResult<Expression> {
+        if self.context.scope().is_generator && self.is_name("yield") {
+            return Ok(Expression::Yield(Box::new(self.parse_yield_expression()?)));
+        }
+        self.potential_arrow_at = self.tokens.index();
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        let is_parenthesized = self.is(&Token::ParenL);
+        let (left, errors) =
+            self.collect_cover_errors(|parser| parser.parse_conditional_expression());
+        let left = left?;
+        let operator = match self.peek() {
+            Some(Token::Assign(assign)) if !self.is_bare_arrow_function(&left) => {
+                assignment_operator(assign)
+            }
+            _ => {
+                self.context.cover_errors.merge(errors);
+                return Ok(left);
+            }
+        };
+        if matches!(
+            operator,
+            AssignmentOperator::LogicalOR
+                | AssignmentOperator::LogicalAND
+                | AssignmentOperator::NullishCoalescing
+        ) {
+            let (line, column) = self.position();
+            self.check_ecma_version(2021, "Logical assignment operators", line, column)?;
+        }
+        self.tokens.bump();
+
+        // only `=` destructures, compound operators need a plain target, and
+        // `({ a }) = b` or `(a = 1) = b` put parentheses around a pattern
+        let left = if left.is_simple_assignment_target() {
+            self.check_pattern_errors(&errors)?;
+            if let Some(identifier) = left.as_identifier() {
+                self.check_eval_or_arguments(
+                    identifier,
+                    self.context.scope().is_strict,
+                    "Assigning to",
+                )?;
+            }
+            AssignmentExpressionLeft::Expression(left)
+        } else if is_parenthesized || !matches!(operator, AssignmentOperator::Normal) {
+            return self.raise(
+                ParseErrorKind::InvalidTarget,
+                line,
+                column,
+                "Invalid left-hand side in assignment",
+            );
+        } else {
+            match left.into_pattern() {
+                Ok(pattern) => {
+                    self.check_destructuring_errors(&errors)?;
+                    self.check_assignment_names(&pattern)?;
+                    AssignmentExpressionLeft::Pattern(pattern)
+                }
+                Err(invalid) => {
+                    return self.raise_invalid_target(
+                        (line, column),
+                        &invalid,
+                        "Invalid left-hand side in assignment",
+                    )
+                }
+            }
+        };
+        // the target may still be reinterpreted as a binding, as in
+        // `([(a)] = b) => a`, which its parentheses rule out
+        self.context.cover_errors.merge(CoverErrors {
+            pattern: None,
+            binding: errors.binding,
+            assignment: None,
+        });
+        let right = self.parse_assignment_expression()?;
+        Ok(Expression::Assignment(Box::new(AssignmentExpression::new(
+            operator,
+            left,
+            right,
+            self.finish(loc),
+        ))))
+    }
+
+    // `test ? consequent : alternate`, where both branches are assignment
+    // expressions so `a ? b : c ? d : e` nests in the alternate
+    fn parse_conditional_expression(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let test = self.parse_binary_expression(0)?;
+        if self.is_bare_arrow_function(&test) || !self.eat(&Token::Question) {
+            return Ok(test);
+        }
+        // the middle of a conditional is delimited by `?` and `:`, so it
+        // may contain `in` even in the head of a `for`
+        let consequent = self.allow_in(|parser| parser.parse_assignment_expression())?;
+        self.expect(&Token::Colon)?;
+        let alternate = self.parse_assignment_expression()?;
+        Ok(Expression::Conditional(Box::new(
+            ConditionalExpression::new(test, consequent, alternate, self.finish(loc)),
+        )))
+    }
+
+    // precedence climbing: operators binding tighter than `min_precedence` are
+    // folded into `left` in a loop, so left-associative chains such as
+    // `a + b + c` recurse once per precedence level rather than per operator
+    fn parse_binary_expression(&mut self, min_precedence: u8) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let mut is_unary = self.is_unary_operator_start();
+        let mut left = self.parse_unary_expression()?;
+        if self.is_bare_arrow_function(&left) {
+            return Ok(left);
+        }
+        while let Some((operator, precedence)) = self.peek().and_then(infix_operator) {
+            if precedence <= min_precedence || (self.context.no_in && self.is(&Token::In)) {
+                break;
+            }
+            let is_exponent = matches!(operator, InfixOperator::Binary(BinaryOperator::Exponent));
+            // `-a ** b` could mean either `(-a) ** b` or `-(a ** b)`
+            if is_exponent && is_unary {
+                let (line, column) = self.position();
+                return self.raise(
+                    ParseErrorKind::InvalidSyntax,
+                    line,
+                    column,
+                    "Unary operator used immediately before exponentiation expression",
+                );
+            }
+            if matches!(
+                operator,
+                InfixOperator::Logical(LogicalOperator::NullishCoalescing)
+            ) {
+                let (line, column) = self.position();
+                self.check_ecma_version(2020, "Nullish coalescing operators", line, column)?;
+            }
+            let operator_text = self.bump()?.to_string();
+
+            // the right side of `??` stops at `&&` as well, so that mixing it
+            // with `&&` or `||` on either side always shows up as the next token,
+            // while `**` is right-associative and takes in further `**`s
+            let right = match operator {
+                InfixOperator::Logical(LogicalOperator::NullishCoalescing) => {
+                    self.parse_binary_expression(precedence + 1)?
+                }
+                _ if is_exponent => {
+                    self.nested(|parser| parser.parse_binary_expression(precedence - 1))?
+                }
+                _ => self.parse_binary_expression(precedence)?,
+            };
+            is_unary = false;
+            if let Some((next, _)) = self.peek().and_then(infix_operator) {
+                if is_mixed_coalesce(&operator, &next) {
+                    let (line, column) = self.position();
+                    let message = format!(
+                        "Cannot mix '{}' and '{}' without parentheses",
+                        operator_text,
+                        self.bump()?
+                    );
+                    return self.raise(ParseErrorKind::InvalidSyntax, line, column, &message);
+                }
+            }
+
+            left = match operator {
+                InfixOperator::Binary(operator) => Expression::Binary(Box::new(
+                    BinaryExpression::new(operator, left, right, self.finish(loc.clone())),
+                )),
+                InfixOperator::Logical(operator) => Expression::Logical(Box::new(
+                    LogicalExpression::new(operator, left, right, self.finish(loc.clone())),
+                )),
+            };
+        }
+        Ok(left)
+    }
+
+    // whether a unary operator (not `++`/`--`) or `await` starts here
+    fn is_unary_operator_start(&self) -> bool {
+        match self.peek() {
+            Some(Token::LogicalInversion)
+            | Some(Token::BitwiseInversion)
+            | Some(Token::Arithmetic(Arithmetic::Plus))
+            | Some(Token::Arithmetic(Arithmetic::Minus))
+            | Some(Token::Typeof)
+            | Some(Token::Void)
+            | Some(Token::Delete) => true,
+            _ => self.context.scope().is_async && self.is_name("await"),
+        }
+    }
+
+    // prefix operators bind tighter than any binary operator but looser than
+    // calls, so `typeof f()` is the type of the call's result
+    fn parse_unary_expression(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        if self.context.scope().is_async && self.is_name("await") {
+            if !self.context.scope().is_function {
+                let (line, column) = self.position();
+                self.check_ecma_version(2022, "Top-level 'await' expressions", line, column)?;
+            }
+            self.tokens.bump();
+            let argument = self.nested(|parser| parser.parse_unary_expression())?;
+            return Ok(Expression::Await(Box::new(AwaitExpression::new(
+                argument,
+                self.finish(loc),
+            ))));
+        }
+
+        if let Some(operator) = self.peek().and_then(update_operator) {
+            self.tokens.bump();
+            let argument = self.nested(|parser| parser.parse_unary_expression())?;
+            if !argument.is_simple_assignment_target() {
+                let start = argument.loc().start;
+                return self.raise(
+                    ParseErrorKind::InvalidTarget,
+                    start.line,
+                    start.column,
+                    "Invalid left-hand side expression in prefix operation",
+                );
+            }
+            if let Some(identifier) = argument.as_identifier() {
+                self.check_eval_or_arguments(
+                    identifier,
+                    self.context.scope().is_strict,
+                    "Assigning to",
+                )?;
+            }
+            return Ok(Expression::Update(Box::new(UpdateExpression::new(
+                operator,
+                true,
+                argument,
+                self.finish(loc),
+            ))));
+        }
+
+        let operator = match self.peek() {
+            Some(Token::LogicalInversion) => UnaryOperator::LogicalInversion,
+            Some(Token::BitwiseInversion) => UnaryOperator::BitwiseInversion,
+            Some(Token::Arithmetic(Arithmetic::Plus)) => UnaryOperator::Positive,
+            Some(Token::Arithmetic(Arithmetic::Minus)) => UnaryOperator::Negative,
+            Some(Token::Typeof) => UnaryOperator::Typeof,
+            Some(Token::Void) => UnaryOperator::Void,
+            Some(Token::Delete) => UnaryOperator::Delete,
+            _ => return self.parse_postfix_expression(),
+        };
+        let (line, column) = self.position();
+        self.tokens.bump();
+        let argument = self.nested(|parser| parser.parse_unary_expression())?;
+        if matches!(operator, UnaryOperator::Delete)
+            && self.context.scope().is_strict
+            && argument.as_identifier().is_some()
+        {
+            return self.raise(
+                ParseErrorKind::StrictMode,
+                line,
+                column,
+                "Deleting local variable in strict mode",
+            );
+        }
+        Ok(Expression::Unary(Box::new(UnaryExpression::new(
+            operator,
+            argument,
+            self.finish(loc),
+        ))))
+    }
+
+    // a line break before `++`/`--` ends the expression, so `a\n++b` is `a; ++b`
+    fn parse_postfix_expression(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let argument = self.parse_call_expression()?;
+        let operator = match self.peek().and_then(update_operator) {
+            Some(operator) if !self.has_newline_before() => operator,
+            _ => return Ok(argument),
+        };
+        if !argument.is_simple_assignment_target() {
+            let start = argument.loc().start;
+            return self.raise(
+                ParseErrorKind::InvalidTarget,
+                start.line,
+                start.column,
+                "Invalid left-hand side expression in postfix operation",
+            );
+        }
+        if let Some(identifier) = argument.as_identifier() {
+            self.check_eval_or_arguments(
+                identifier,
+                self.context.scope().is_strict,
+                "Assigning to",
+            )?;
+        }
+        self.tokens.bump();
+        Ok(Expression::Update(Box::new(UpdateExpression::new(
+            operator,
+            false,
+            argument,
+            self.finish(loc),
+        ))))
+    }
+
+    // `( Expression )` as used by if, while and switch heads
+    pub(super) fn parse_paren_expression(&mut self) -> ParseResult<Expression> {
+        self.expect(&Token::ParenL)?;
+        let expression = self.parse_expression()?;
+        self.expect(&Token::ParenR)?;
+        Ok(expression)
+    }
+
+    pub(super) fn parse_call_expression(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let expression = self.parse_primary_expression()?;
+        if self.is_bare_arrow_function(&expression) {
+            return Ok(expression);
+        }
+        self.parse_subscripts(expression, loc, false)
+    }
+
+    // member accesses and calls following `expression`; the callee of `new`
+    // takes no calls, as the first argument list belongs to `new` itself
+    fn parse_subscripts(
+        &mut self,
+        mut expression: Expression,
+        loc: SourceLocation,
+        no_calls: bool,
+    ) -> ParseResult<Expression> {
+        // a chain containing `?.` anywhere is wrapped in a `ChainExpression`,
+        // which is as far as the short-circuit of `a?.b.c` reaches
+        let mut is_chain = false;
+        loop {
+            if no_calls && self.is(&Token::QuestionDot) {
+                let (line, column) = self.position();
+                return self.raise(
+                    ParseErrorKind::InvalidSyntax,
+                    line,
+                    column,
+                    "Invalid optional chain from new expression",
+                );
+            }
+            if self.is(&Token::QuestionDot) {
+                let (line, column) = self.position();
+                self.check_ecma_version(2020, "Optional chains", line, column)?;
+            }
+            let optional = self.eat(&Token::QuestionDot);
+            is_chain = is_chain || optional;
+            expression = match self.peek() {
+                Some(Token::Dot) if !optional => {
+                    self.tokens.bump();
+                    let property = self.parse_member_property()?;
+                    let member = MemberExpression::new(
+                        expression,
+                        property,
+                        false,
+                        false,
+                        self.finish(loc.clone()),
+                    );
+                    Expression::Member(Box::new(member))
+                }
+                Some(Token::BracketL) => {
+                    self.tokens.bump();
+                    let property = self.allow_in(|parser| parser.parse_expression())?;
+                    self.expect(&Token::BracketR)?;
+                    let member = MemberExpression::new(
+                        expression,
+                        property,
+                        true,
+                        optional,
+                        self.finish(loc.clone()),
+                    );
+                    Expression::Member(Box::new(member))
+                }
+                // a tag can't be optional, so `a?.b` is as far as `a?.b`c`` gets;
+                // a piece starting with "}" continues an enclosing template
+                Some(Token::Template(..)) if self.tokens.text().starts_with('`') => {
+                    if is_chain {
+                        let (line, column) = self.position();
+                        return self.raise(
+                            ParseErrorKind::InvalidSyntax,
+                            line,
+                            column,
+                            "Invalid tagged template on optional chain",
+                        );
+                    }
+                    let quasi = self.parse_template_literal(true)?;
+                    Expression::TaggedTemplate(Box::new(TaggedTemplateExpression::new(
+                        expression,
+                        quasi,
+                        self.finish(loc.clone()),
+                    )))
+                }
+                Some(Token::ParenL) if !no_calls => {
+                    let arguments = self.allow_in(|parser| parser.parse_arguments())?;
+                    Expression::Call(Box::new(CallExpression::new(
+                        expression,
+                        arguments,
+                        optional,
+                        self.finish(loc.clone()),
+                    )))
+                }
+                // `a?.b`, where the name follows `?.` directly
+                _ if optional => {
+                    let property = self.parse_member_property()?;
+                    let member = MemberExpression::new(
+                        expression,
+                        property,
+                        false,
+                        true,
+                        self.finish(loc.clone()),
+                    );
+                    Expression::Member(Box::new(member))
+                }
+                _ if is_chain => {
+                    return Ok(Expression::Chain(Box::new(ChainExpression::new(
+                        expression,
+                        self.finish(loc),
+                    ))))
+                }
+                _ => return Ok(expression),
+            };
+        }
+    }
+
+    // the name after a dot, which may be a reserved word or a private name
+    fn parse_member_property(&mut self) -> ParseResult<Expression> {
+        match self.peek() {
+            Some(Token::PrivateName(name)) => {
+                let (loc, name) = (self.start_loc(), name.clone());
+                self.use_private_name(&name)?;
+                self.tokens.bump();
+                let property = PrivateIdentifier::new(name.to_string(), self.finish(loc));
+                Ok(Expression::PrivateIdentifier(Box::new(property)))
+            }
+            _ => Ok(Expression::Identifier(Box::new(
+                self.parse_identifier_name()?,
+            ))),
+        }
+    }
+
+    // the pieces of a template alternate with its substitutions, so there is
+    // always one more quasi than expressions; only a tagged template may
+    // contain escapes that can't be cooked, such as `\unicode`
+    fn parse_template_literal(&mut self, is_tagged: bool) -> ParseResult<TemplateLiteral> {
+        let loc = self.start_loc();
+        let mut quasis = vec![];
+        let mut expressions = vec![];
+        loop {
+            let (line, column) = self.position();
+            let (raw, cooked, tail) = match self.peek() {
+                Some(Token::Template(value, tail)) => {
+                    (value.raw.clone(), value.cooked.clone(), *tail)
+                }
+                _ => return self.expected(vec![TokenKind::Template]),
+            };
+            if cooked.is_none() && !is_tagged {
+                return self.raise(
+                    ParseErrorKind::InvalidEscape,
+                    line,
+                    column,
+                    "Bad escape sequence in untagged template literal",
+                );
+            }
+            let token_loc = self.start_loc();
+            self.tokens.bump();
+            let token_loc = self.finish(token_loc);
+            // the element starts after the "`" or "}" in front of it and ends
+            // before the "`" or "${" after it
+            let delimiter = if tail { 1 } else { 2 };
+            let mut element_loc = SourceLocation::new(line, column + 1, token_loc.range.0 + 1);
+            element_loc.end = Position::new(token_loc.end.line, token_loc.end.column - delimiter);
+            element_loc.range.1 = token_loc.range.1 - delimiter;
+            quasis.push(TemplateElement::new(raw, cooked, tail, element_loc));
+            if tail {
+                return Ok(TemplateLiteral::new(quasis, expressions, self.finish(loc)));
+            }
+            expressions.push(self.allow_in(|parser| parser.parse_expression())?);
+        }
+    }
+
+    // `(a, ...b,)`, where a trailing comma is allowed
+    fn parse_arguments(&mut self) -> ParseResult<Vec<CallExpressionArgument>> {
+        let list = self.parse_cover_list()?;
+        self.check_pattern_errors(&list.errors)?;
+        Ok(list.into_arguments())
+    }
+
+    // a parenthesized list of assignment expressions and spreads, whose
+    // cover errors are collected for the caller to report or drop
+    pub(super) fn parse_cover_list(&mut self) -> ParseResult<CoverList> {
+        let (paren_line, paren_column) = self.position();
+        self.expect(&Token::ParenL)?;
+        let outer = std::mem::take(&mut self.context.cover_errors);
+        let mut items = vec![];
+        let mut trailing_comma = None;
+        while !self.is(&Token::ParenR) {
+            if self.peek().is_none() {
+                let (line, column) = self.position();
+                let message = format!(
+                    "Expected ')' to close '(' from line:{}, column:{}",
+                    paren_line, paren_column
+                );
+                return self.raise(ParseErrorKind::UnexpectedEnd, line, column, &message);
+            }
+            let loc = self.start_loc();
+            let (line, column) = self.position();
+            let item = if self.eat(&Token::Ellipsis) {
+                let argument = self.parse_cover_assignment_expression()?;
+                CallExpressionArgument::SpreadElement(SpreadElement::new(
+                    argument,
+                    self.finish(loc),
+                ))
+            } else {
+                CallExpressionArgument::Expression(self.parse_cover_assignment_expression()?)
+            };
+            items.push((item, line, column));
+            if self.is(&Token::Comma) && self.peek2() == Some(&Token::ParenR) {
+                trailing_comma = Some(self.position());
+            }
+            // the end of input is reported along with the opening paren above
+            if self.peek().is_some() {
+                self.expect_separator(&Token::ParenR)?;
+            }
+        }
+        let end = self.position();
+        self.tokens.bump();
+        Ok(CoverList {
+            items,
+            trailing_comma,
+            end,
+            errors: std::mem::replace(&mut self.context.cover_errors, outer),
+        })
+    }
+
+    fn parse_primary_expression(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let can_be_arrow = self.tokens.index() == self.potential_arrow_at;
+        let value = match self.peek() {
+            Some(Token::Name(_)) if self.is_async_function() => {
+                return Ok(Expression::Function(Box::new(
+                    self.allow_in(|parser| parser.parse_function_expression())?,
+                )))
+            }
+            Some(Token::Name(_)) if can_be_arrow && self.is_name("async") => {
+                return self.parse_async_arrow_or_call()
+            }
+            Some(Token::Name(_)) | Some(Token::Of) | Some(Token::Undefined) | Some(Token::Let) => {
+                let identifier = self.parse_identifier()?;
+                if can_be_arrow && self.is(&Token::Arrow) {
+                    let params = vec![Pattern::Identifier(Box::new(identifier))];
+                    return Ok(Expression::ArrowFunction(Box::new(
+                        self.parse_arrow_function_expression(params, false, loc)?,
+                    )));
+                }
+                return Ok(Expression::Identifier(Box::new(identifier)));
+            }
+            Some(Token::Function) => {
+                return Ok(Expression::Function(Box::new(
+                    self.allow_in(|parser| parser.parse_function_expression())?,
+                )))
+            }
+            Some(Token::Class) => {
+                return Ok(Expression::Class(Box::new(
+                    self.allow_in(|parser| parser.parse_class_expression())?,
+                )))
+            }
+            Some(Token::BraceL) => {
+                return Ok(Expression::Object(Box::new(
+                    self.allow_in(|parser| parser.parse_object_expression())?,
+                )))
+            }
+            Some(Token::BracketL) => {
+                return Ok(Expression::Array(Box::new(
+                    self.allow_in(|parser| parser.parse_array_expression())?,
+                )))
+            }
+            Some(Token::ParenL) => return self.parse_parenthesized_expression(can_be_arrow),
+            Some(Token::Template(..)) if self.tokens.text().starts_with('`') => {
+                return Ok(Expression::TemplateLiteral(Box::new(
+                    self.parse_template_literal(false)?,
+                )))
+            }
+            Some(Token::New) => return self.parse_new_expression(),
+            Some(Token::This) => {
+                self.tokens.bump();
+                return Ok(Expression::This(Box::new(ThisExpression::new(
+                    self.finish(loc),
+                ))));
+            }
+            Some(Token::Super) => return Ok(Expression::Super(Box::new(self.parse_super()?))),
+            Some(Token::Import) if self.peek2() == Some(&Token::Dot) => {
+                return Ok(Expression::MetaProperty(Box::new(
+                    self.parse_import_meta()?,
+                )))
+            }
+            Some(Token::Import) if self.peek2() == Some(&Token::ParenL) => {
+                return Ok(Expression::Import(Box::new(self.parse_import_call()?)))
+            }
+            Some(Token::Number(value)) => LiteralValue::Number(*value),
+            Some(Token::Bigint(digits)) => LiteralValue::Bigint(digits.to_string()),
+            Some(Token::RegExp(regexp)) => LiteralValue::RegExp((**regexp).clone()),
+            Some(Token::String(content)) => LiteralValue::String(content.to_string()),
+            Some(Token::True) => LiteralValue::Boolean(true),
+            Some(Token::False) => LiteralValue::Boolean(false),
+            Some(Token::Null) => LiteralValue::Null(Null),
+            // the missing expression is left as an error node in recovery
+            // mode, for the statement around it to carry on with the token
+            _ if self.context.recover => {
+                let diagnostic = self.record(self.unexpected_error());
+                return Ok(Expression::Error(Box::new(ErrorNode::new(
+                    diagnostic,
+                    self.empty_loc(),
+                ))));
+            }
+            _ => return self.unexpected(),
+        };
+        self.check_literal()?;
+        let raw = self.tokens.text().to_string();
+        self.tokens.bump();
+        Ok(Expression::Literal(Box::new(Literal::new(
+            value,
+            raw,
+            self.finish(loc),
+        ))))
+    }
+
+    // `super` is bound by the closest method, so arrows see the one around them
+    fn parse_super(&mut self) -> ParseResult<Super> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        self.tokens.bump();
+        let scope = self.context.function_scope();
+        let (is_allowed, kind, message) = match self.peek() {
+            Some(Token::ParenL) => (
+                scope.allows_super_call,
+                ParseErrorKind::MisplacedSuperCall,
+                "'super' call is only allowed in the constructor of a derived class",
+            ),
+            Some(Token::Dot) | Some(Token::BracketL) => (
+                scope.allows_super_property,
+                ParseErrorKind::MisplacedSuperProperty,
+                "'super' property access is only allowed in methods and class fields",
+            ),
+            _ => (
+                false,
+                ParseErrorKind::Misplaced,
+                "'super' keyword unexpected here",
+            ),
+        };
+        if !is_allowed {
+            return self.raise(kind, line, column, message);
+        }
+        Ok(Super::new(self.finish(loc)))
+    }
+
+    // `new a.b(c)` calls `a.b`, while `new a.b` without arguments still
+    // constructs it; `new new F()()` nests through the callee
+    fn parse_new_expression(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        self.expect(&Token::New)?;
+        if self.is(&Token::Dot) {
+            let meta = Identifier::new("new".to_string(), self.finish(loc.clone()));
+            self.tokens.bump();
+            let meta_property = self.parse_new_target(meta, loc, line, column)?;
+            return Ok(Expression::MetaProperty(Box::new(meta_property)));
+        }
+        if self.is(&Token::Import) && self.peek2() == Some(&Token::ParenL) {
+            let (line, column) = self.position();
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                line,
+                column,
+                "Cannot use new with import()",
+            );
+        }
+        let callee_loc = self.start_loc();
+        let callee = self.nested(|parser| parser.parse_primary_expression())?;
+        let callee = self.parse_subscripts(callee, callee_loc, true)?;
+        let arguments = if self.is(&Token::ParenL) {
+            self.allow_in(|parser| parser.parse_arguments())?
+        } else {
+            vec![]
+        };
+        Ok(Expression::New(Box::new(NewExpression::new(
+            callee,
+            arguments,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_new_target(
+        &mut self,
+        meta: Identifier,
+        loc: SourceLocation,
+        line: usize,
+        column: usize,
+    ) -> ParseResult<MetaProperty> {
+        if !self.is_name("target") {
+            let (line, column) = self.position();
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                line,
+                column,
+                "The only valid meta property for new is 'new.target'",
+            );
+        }
+        let property = self.parse_identifier()?;
+        let scope = self.context.function_scope();
+        if !scope.is_function && !scope.is_static_block {
+            return self.raise(
+                ParseErrorKind::MisplacedNewTarget,
+                line,
+                column,
+                "'new.target' can only be used in functions and class static block",
+            );
+        }
+        Ok(MetaProperty::new(meta, property, self.finish(loc)))
+    }
+
+    fn parse_import_meta(&mut self) -> ParseResult<MetaProperty> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        self.expect(&Token::Import)?;
+        let meta = Identifier::new("import".to_string(), self.finish(loc.clone()));
+        self.expect(&Token::Dot)?;
+        if !self.is_name("meta") {
+            let (line, column) = self.position();
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                line,
+                column,
+                "The only valid meta property for import is 'import.meta'",
+            );
+        }
+        let property = self.parse_identifier()?;
+        if !self.context.is_module() {
+            return self.raise(
+                ParseErrorKind::Misplaced,
+                line,
+                column,
+                "Cannot use 'import.meta' outside a module",
+            );
+        }
+        Ok(MetaProperty::new(meta, property, self.finish(loc)))
+    }
+
+    // `import(source)` loads a module when it runs, in scripts as well; it
+    // takes exactly one argument, with no spread or trailing comma
+    fn parse_import_call(&mut self) -> ParseResult<ImportExpression> {
+        let loc = self.start_loc();
+        self.expect(&Token::Import)?;
+        self.expect(&Token::ParenL)?;
+        let source = self.allow_in(|parser| parser.parse_assignment_expression())?;
+        self.expect(&Token::ParenR)?;
+        Ok(ImportExpression::new(source, self.finish(loc)))
+    }
+
+    // `async(a)` calls a function named async unless `=>` follows, which makes
+    // it the parameters of an async arrow function, as is `async a => b`
+    fn parse_async_arrow_or_call(&mut self) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let name = self.parse_identifier()?;
+        // `async => async` is an arrow whose parameter is named `async`
+        if self.is(&Token::Arrow) {
+            let params = vec![Pattern::Identifier(Box::new(name))];
+            return Ok(Expression::ArrowFunction(Box::new(
+                self.parse_arrow_function_expression(params, false, loc)?,
+            )));
+        }
+        let callee = Expression::Identifier(Box::new(name));
+        if self.has_newline_before() {
+            return Ok(callee);
+        }
+        match self.peek() {
+            Some(Token::Name(_)) | Some(Token::Of) | Some(Token::Undefined) | Some(Token::Let)
+                if self.peek2() == Some(&Token::Arrow) =>
+            {
+                let params = vec![Pattern::Identifier(Box::new(self.parse_identifier()?))];
+                Ok(Expression::ArrowFunction(Box::new(
+                    self.parse_arrow_function_expression(params, true, loc)?,
+                )))
+            }
+            Some(Token::ParenL) => {
+                let list = self.allow_in(|parser| parser.parse_cover_list())?;
+                if self.is(&Token::Arrow) {
+                    let params = self.cover_list_into_params(list)?;
+                    return Ok(Expression::ArrowFunction(Box::new(
+                        self.parse_arrow_function_expression(params, true, loc)?,
+                    )));
+                }
+                self.check_pattern_errors(&list.errors)?;
+                let arguments = list.into_arguments();
+                Ok(Expression::Call(Box::new(CallExpression::new(
+                    callee,
+                    arguments,
+                    false,
+                    self.finish(loc),
+                ))))
+            }
+            _ => Ok(callee),
+        }
+    }
+
+    // `(a, b)` reads the same as the parameters of `(a, b) => c` up to the
+    // `=>`, so the list is parsed permissively and only then checked as
+    // whichever of the two it turns out to be; grouping parentheses are
+    // dropped unless the caller asked to keep them
+    fn parse_parenthesized_expression(&mut self, can_be_arrow: bool) -> ParseResult<Expression> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        let list = self.allow_in(|parser| parser.parse_cover_list())?;
+        // the body of an arrow is no more allowed an `in` than the arrow is
+        if can_be_arrow && self.is(&Token::Arrow) {
+            let params = self.cover_list_into_params(list)?;
+            return Ok(Expression::ArrowFunction(Box::new(
+                self.parse_arrow_function_expression(params, false, loc)?,
+            )));
+        }
+
+        // a rest element, a trailing comma and an empty list are only valid
+        // as parameters
+        self.check_pattern_errors(&list.errors)?;
+        let spread = list
+            .items
+            .iter()
+            .find(|(item, _, _)| matches!(item, CallExpressionArgument::SpreadElement(_)));
+        if let Some((_, spread_line, spread_column)) = spread {
+            return self.raise(
+                ParseErrorKind::UnexpectedToken,
+                *spread_line,
+                *spread_column,
+                "Unexpected token '...', expected an expression",
+            );
+        }
+        if list.items.is_empty() || list.trailing_comma.is_some() {
+            let (end_line, end_column) = list.end;
+            return self.raise(
+                ParseErrorKind::UnexpectedToken,
+                end_line,
+                end_column,
+                "Unexpected token ')', expected an expression",
+            );
+        }
+        let mut expressions: Vec<Expression> = list
+            .items
+            .into_iter()
+            .filter_map(|(item, _, _)| match item {
+                CallExpressionArgument::Expression(expression) => Some(expression),
+                CallExpressionArgument::SpreadElement(_) => None,
+            })
+            .collect();
+        let expression = if expressions.len() == 1 {
+            expressions.remove(0)
+        } else {
+            // the `)` has been consumed, so the sequence spans from its first
+            // item to its last
+            let first = expressions[0].loc();
+            let last = expressions[expressions.len() - 1].loc();
+            let mut sequence_loc =
+                SourceLocation::new(first.start.line, first.start.column, first.range.0);
+            sequence_loc.end = last.end;
+            sequence_loc.range.1 = last.range.1;
+            Expression::Sequence(Box::new(SequenceExpression::new(expressions, sequence_loc)))
+        };
+        // `((a)) => a` or `([(a)]) => a` can't be bound, though `(a) = 1` can
+        // be assigned to, unlike `[([a])] = b`
+        let is_simple = expression.is_simple_assignment_target();
+        self.context.cover_errors.merge(CoverErrors {
+            pattern: None,
+            binding: Some((line, column, "Parenthesized pattern")),
+            assignment: (!is_simple).then_some((line, column, "Parenthesized pattern")),
+        });
+        if self.context.preserve_parens {
+            Ok(Expression::Parenthesized(Box::new(
+                ParenthesizedExpression::new(expression, self.finish(loc)),
+            )))
+        } else {
+            Ok(expression)
+        }
+    }
+
+    // a comma right after an element only ends it, so `[a,]` has one element
+    // while `[,]` is a single hole and `[a,,]` is `a` followed by a hole
+    fn parse_array_expression(&mut self) -> ParseResult<ArrayExpression> {
+        let loc = self.start_loc();
+        self.expect(&Token::BracketL)?;
+        let mut elements = vec![];
+        while !self.eat(&Token::BracketR) {
+            if self.eat(&Token::Comma) {
+                elements.push(None);
+                continue;
+            }
+            if self.is(&Token::Ellipsis) {
+                let loc = self.start_loc();
+                self.tokens.bump();
+                let argument = self.parse_cover_assignment_expression()?;
+                elements.push(Some(ArrayExpressionElement::SpreadElement(
+                    SpreadElement::new(argument, self.finish(loc)),
+                )));
+                self.note_comma_after_spread();
+            } else {
+                elements.push(Some(ArrayExpressionElement::Expression(
+                    self.parse_cover_assignment_expression()?,
+                )));
+            }
+            self.expect_separator(&Token::BracketR)?;
+        }
+        Ok(ArrayExpression::new(elements, self.finish(loc)))
+    }
+
+    // `[...a,]` is an array like any other, but as a pattern the spread is a
+    // rest element, which nothing may follow
+    fn note_comma_after_spread(&mut self) {
+        if self.is(&Token::Comma) {
+            let (line, column) = self.position();
+            let error = Some((
+                line,
+                column,
+                "Comma is not permitted after the rest element",
+            ));
+            self.context.cover_errors.merge(CoverErrors {
+                pattern: None,
+                binding: error,
+                assignment: error,
+            });
+        }
+    }
+
+    fn parse_object_expression(&mut self) -> ParseResult<ObjectExpression> {
+        let loc = self.start_loc();
+        self.expect(&Token::BraceL)?;
+        let mut properties = vec![];
+        let mut has_proto = false;
+        while !self.eat(&Token::BraceR) {
+            let (line, column) = self.position();
+            let (property, is_proto) = self.parse_property()?;
+            if is_proto {
+                // a pattern may name `__proto__` any number of times
+                if has_proto {
+                    self.context.cover_errors.merge(CoverErrors {
+                        pattern: Some((line, column, "Redefinition of __proto__ property")),
+                        binding: None,
+                        assignment: None,
+                    });
+                }
+                has_proto = true;
+            }
+            properties.push(property);
+            self.expect_separator(&Token::BraceR)?;
+        }
+        Ok(ObjectExpression::new(properties, self.finish(loc)))
+    }
+
+    fn is_property_name_start(&self) -> bool {
+        match self.peek() {
+            Some(Token::Name(_))
+            | Some(Token::Number(_))
+            | Some(Token::String(_))
+            | Some(Token::BracketL) => true,
+            Some(token) => token.is_keyword(),
+            None => false,
+        }
+    }
+
+    // the property, and whether it is a `__proto__: value` that sets the
+    // prototype, of which a literal may only have one
+    fn parse_property(&mut self) -> ParseResult<(ObjectExpressionProperty, bool)> {
+        let loc = self.start_loc();
+
+        if self.is(&Token::Ellipsis) {
+            self.tokens.bump();
+            let argument = self.parse_cover_assignment_expression()?;
+            let spread = SpreadElement::new(argument, self.finish(loc));
+            self.note_comma_after_spread();
+            return Ok((ObjectExpressionProperty::SpreadElement(spread), false));
+        }
+
+        // `async` marks a method only when a property name follows on the same
+        // line, otherwise it is the key itself as in `{ async: 1 }`
+        let mut is_async = false;
+        if self.is_name("async") {
+            let checkpoint = self.tokens.checkpoint();
+            self.tokens.bump();
+            let is_modifier =
+                self.is_property_name_start() || self.is(&Token::Arithmetic(Arithmetic::Multiple));
+            if is_modifier && !self.has_newline_before() {
+                is_async = true;
+                self.tokens.commit(checkpoint);
+            } else {
+                self.tokens.rewind(checkpoint);
+            }
+        }
+        let generator = self.eat(&Token::Arithmetic(Arithmetic::Multiple));
+
+        // likewise `get`/`set` start an accessor only when a property name
+        // follows, as opposed to `{ get: 1 }`
+        if !is_async && !generator && (self.is_name("get") || self.is_name("set")) {
+            let is_setter = self.is_name("set");
+            let checkpoint = self.tokens.checkpoint();
+            self.tokens.bump();
+            if self.is_property_name_start() {
+                self.tokens.commit(checkpoint);
+                let (key, computed, _) = self.parse_property_name()?;
+                let value = self.parse_accessor(is_setter)?;
+                let kind = if is_setter {
+                    PropertyKind::Set
+                } else {
+                    PropertyKind::Get
+                };
+                let property = Property::new(
+                    key,
+                    Expression::Function(Box::new(value)),
+                    kind,
+                    false,
+                    false,
+                    computed,
+                    self.finish(loc),
+                );
+                return Ok((ObjectExpressionProperty::Property(property), false));
+            }
+            self.tokens.rewind(checkpoint);
+        }
+
+        let checkpoint = self.tokens.checkpoint();
+        let (key, computed, key_name) = self.parse_property_name()?;
+        if (is_async || generator) && !self.is(&Token::ParenL) {
+            return self.expected(vec![TokenKind::ParenL]);
+        }
+        // `{ a }` is short for `{ a: a }`, so the key has to be a plain
+        // identifier reference rather than any property name, which it is
+        // parsed again as; `{ a = 1 }` is only valid as a pattern and is kept
+        // as an assignment until the object is reinterpreted as one
+        let is_shorthand = !computed
+            && matches!(key, PropertyKey::Identifier(_))
+            && matches!(
+                self.peek(),
+                Some(Token::Comma) | Some(Token::BraceR) | Some(Token::Assign(Assign::Normal))
+            );
+        if is_shorthand {
+            self.tokens.rewind(checkpoint);
+        } else {
+            self.tokens.commit(checkpoint);
+        }
+        let property = match self.peek() {
+            Some(Token::ParenL) => {
+                let value = self.parse_method(is_async, generator, false)?;
+                Property::new(
+                    key,
+                    Expression::Function(Box::new(value)),
+                    PropertyKind::Init,
+                    true,
+                    false,
+                    computed,
+                    self.finish(loc),
+                )
+            }
+            _ if is_shorthand => {
+                if !matches!(
+                    self.peek(),
+                    Some(Token::Name(_))
+                        | Some(Token::Of)
+                        | Some(Token::Undefined)
+                        | Some(Token::Let)
+                ) {
+                    return self.unexpected();
+                }
+                let value_loc = self.start_loc();
+                let identifier = self.parse_identifier()?;
+                let mut value = Expression::Identifier(Box::new(identifier));
+                if self.is(&Token::Assign(Assign::Normal)) {
+                    let (line, column) = self.position();
+                    self.tokens.bump();
+                    let right = self.parse_assignment_expression()?;
+                    value = Expression::Assignment(Box::new(AssignmentExpression::new(
+                        AssignmentOperator::Normal,
+                        AssignmentExpressionLeft::Expression(value),
+                        right,
+                        self.finish(value_loc),
+                    )));
+                    self.context.cover_errors.merge(CoverErrors {
+                        pattern: Some((
+                            line,
+                            column,
+                            "Shorthand property assignments are valid only in destructuring patterns",
+                        )),
+                        binding: None,
+                        assignment: None,
+                    });
+                }
+                Property::new(
+                    key,
+                    value,
+                    PropertyKind::Init,
+                    false,
+                    true,
+                    false,
+                    self.finish(loc),
+                )
+            }
+            _ => {
+                self.expect(&Token::Colon)?;
+                let value = self.parse_cover_assignment_expression()?;
+                let is_proto = !computed && key_name.as_deref() == Some("__proto__");
+                let property = Property::new(
+                    key,
+                    value,
+                    PropertyKind::Init,
+                    false,
+                    false,
+                    computed,
+                    self.finish(loc),
+                );
+                return Ok((ObjectExpressionProperty::Property(property), is_proto));
+            }
+        };
+        Ok((ObjectExpressionProperty::Property(property), false))
+    }
+
+    // the key, whether it is computed, and its static name when one is known
+    pub(super) fn parse_property_name(&mut self) -> ParseResult<(PropertyKey, bool, Option<Name>)> {
+        let loc = self.start_loc();
+        match self.peek() {
+            Some(Token::BracketL) => {
+                self.tokens.bump();
+                let key = self.parse_assignment_expression()?;
+                self.expect(&Token::BracketR)?;
+                Ok((PropertyKey::Expression(key), true, None))
+            }
+            Some(Token::String(content)) => {
+                let (raw, content) = (self.tokens.text().to_string(), content.clone());
+                self.check_literal()?;
+                self.tokens.bump();
+                let value = LiteralValue::String(content.to_string());
+                let literal = Literal::new(value, raw, self.finish(loc));
+                Ok((PropertyKey::Literal(literal), false, Some(content)))
+            }
+            Some(Token::Number(value)) => {
+                let (raw, value) = (self.tokens.text().to_string(), *value);
+                self.check_literal()?;
+                self.tokens.bump();
+                let literal = Literal::new(LiteralValue::Number(value), raw, self.finish(loc));
+                Ok((PropertyKey::Literal(literal), false, None))
+            }
+            _ => {
+                let key = self.parse_identifier_name()?;
+                let name = key.name.clone();
+                Ok((PropertyKey::Identifier(key), false, Some(name)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ParseErrorKind,
+        node::{AssignmentExpressionLeft, Expression, Pattern, Statement},
+        parser::parse,
+        sexpr::to_sexpr,
+        test_support::error_at,
+    };
+
+    fn sexpr(src: &str) -> String {
+        to_sexpr(&parse(src).unwrap())
+    }
+
+    #[test]
+    fn conditionals_nest_in_their_alternate() {
+        assert_eq!(
+            sexpr("a ? b : c ? d : e;"),
+            "(Program\n  (Expression (Conditional a b (Conditional c d e))))"
+        );
+        assert_eq!(
+            sexpr("a ?? b ? c : d;"),
+            "(Program\n  (Expression (Conditional (Logical ?? a b) c d)))"
+        );
+        assert_eq!(
+            sexpr("x = c ? 1 : 2;"),
+            "(Program\n  (Expression (Assignment = x (Conditional c 1 2))))"
+        );
+        // `?.` is a chain, and `?.5` a question mark before a number
+        assert_eq!(
+            sexpr("a?.b ? c : d;"),
+            "(Program\n  (Expression (Conditional (Chain (Member optional a b)) c d)))"
+        );
+        assert_eq!(
+            sexpr("a?.5:1;"),
+            "(Program\n  (Expression (Conditional a .5 1)))"
+        );
+        assert_eq!(error_at("a ? b;"), (ParseErrorKind::UnexpectedToken, 1, 5));
+    }
+
+    #[test]
+    fn nullish_coalescing_needs_parentheses_to_mix() {
+        assert_eq!(
+            sexpr("a && b || c;"),
+            "(Program\n  (Expression (Logical || (Logical && a b) c)))"
+        );
+        assert_eq!(
+            sexpr("a ?? b ?? c;"),
+            "(Program\n  (Expression (Logical ?? (Logical ?? a b) c)))"
+        );
+        assert_eq!(
+            sexpr("(a ?? b) || c;"),
+            "(Program\n  (Expression (Logical || (Logical ?? a b) c)))"
+        );
+        assert_eq!(
+            sexpr("a ?? (b && c);"),
+            "(Program\n  (Expression (Logical ?? a (Logical && b c))))"
+        );
+        for (src, message) in [
+            (
+                "a ?? b || c;",
+                "Cannot mix '??' and '||' without parentheses",
+            ),
+            (
+                "a || b ?? c;",
+                "Cannot mix '||' and '??' without parentheses",
+            ),
+            (
+                "a && b ?? c;",
+                "Cannot mix '&&' and '??' without parentheses",
+            ),
+            (
+                "a ?? b && c;",
+                "Cannot mix '??' and '&&' without parentheses",
+            ),
+        ] {
+            let error = parse(src).unwrap_err();
+            assert_eq!(error.kind, ParseErrorKind::InvalidSyntax, "{}", src);
+            assert_eq!(error.message, message, "{}", src);
+        }
+    }
+
+    #[test]
+    fn unary_operators_bind_tighter_than_binary_ones() {
+        assert_eq!(
+            sexpr("!x; ~x; +x; void 0; delete o.k;"),
+            "(Program\n  (Expression (Unary ! x))\n  (Expression (Unary ~ x))\n  \
+             (Expression (Unary + x))\n  (Expression (Unary void 0))\n  \
+             (Expression (Unary delete (Member o k))))"
+        );
+        assert_eq!(
+            sexpr("typeof f(); -a.b + c;"),
+            "(Program\n  (Expression (Unary typeof (Call f)))\n  \
+             (Expression (Binary + (Unary - (Member a b)) c)))"
+        );
+        assert_eq!(
+            sexpr("!!x; typeof typeof x;"),
+            "(Program\n  (Expression (Unary ! (Unary ! x)))\n  \
+             (Expression (Unary typeof (Unary typeof x))))"
+        );
+        assert_eq!(
+            error_at("'use strict'; delete x;"),
+            (ParseErrorKind::StrictMode, 1, 14)
+        );
+        assert!(parse("'use strict'; delete x.y;").is_ok());
+        // `-a ** b` could mean either, so the unary has to be parenthesized
+        assert_eq!(error_at("-a ** b;"), (ParseErrorKind::InvalidSyntax, 1, 3));
+        assert_eq!(
+            error_at("typeof a ** b;"),
+            (ParseErrorKind::InvalidSyntax, 1, 9)
+        );
+    }
+
+    #[test]
+    fn updates_are_prefix_or_postfix() {
+        assert_eq!(
+            sexpr("i++; --j; o.count++; a[0]--;"),
+            "(Program\n  (Expression (Update ++ i))\n  (Expression (Update prefix -- j))\n  \
+             (Expression (Update ++ (Member o count)))\n  \
+             (Expression (Update -- (Member computed a 0))))"
+        );
+        // a line break before `++` ends the statement, so it is `a; ++b`
+        assert_eq!(
+            sexpr("a\n++b"),
+            "(Program\n  (Expression a)\n  (Expression (Update prefix ++ b)))"
+        );
+        for (src, column) in [("1++;", 0), ("(a+b)++;", 1), ("++(a+b);", 3), ("f()--;", 0)] {
+            assert_eq!(
+                error_at(src),
+                (ParseErrorKind::InvalidTarget, 1, column),
+                "{}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn optional_chains_are_wrapped_whole() {
+        assert_eq!(
+            sexpr("a?.b.c?.d();"),
+            "(Program\n  (Expression (Chain (Call (Member optional (Member (Member optional a b) c) d)))))"
+        );
+        assert_eq!(
+            sexpr("f?.(); a?.[k];"),
+            "(Program\n  (Expression (Chain (Call optional f)))\n  \
+             (Expression (Chain (Member computed optional a k))))"
+        );
+        for (src, column, message) in [
+            (
+                "new a?.b();",
+                5,
+                "Invalid optional chain from new expression",
+            ),
+            (
+                "new a?.();",
+                5,
+                "Invalid optional chain from new expression",
+            ),
+            ("a?.`x`;", 3, "Invalid tagged template on optional chain"),
+            ("a?.b`x`;", 4, "Invalid tagged template on optional chain"),
+        ] {
+            let error = parse(src).unwrap_err();
+            assert_eq!(
+                (error.kind, error.start.column, error.message.as_str()),
+                (ParseErrorKind::InvalidSyntax, column, message),
+                "{}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn commas_separate_arguments_but_sequence_elsewhere() {
+        assert_eq!(sexpr("f(a, b);"), "(Program\n  (Expression (Call f a b)))");
+        assert_eq!(
+            sexpr("f((a, b));"),
+            "(Program\n  (Expression (Call f (Sequence a b))))"
+        );
+        assert_eq!(
+            sexpr("a = (b(), c(), d);"),
+            "(Program\n  (Expression (Assignment = a (Sequence (Call b) (Call c) d))))"
+        );
+        assert_eq!(
+            sexpr("for (;; i++, j++);"),
+            "(Program\n  (For () () (Sequence (Update ++ i) (Update ++ j)) (Empty)))"
+        );
+        assert_eq!(
+            sexpr("a = b, c = d;"),
+            "(Program\n  (Expression (Sequence (Assignment = a b) (Assignment = c d))))"
+        );
+    }
+
+    #[test]
+    fn an_object_sets_its_prototype_once() {
+        let error = parse("({ __proto__: 1, '__proto__': 2 });").unwrap_err();
+        assert_eq!(
+            (error.kind, error.start.column, error.message.as_str()),
+            (
+                ParseErrorKind::InvalidSyntax,
+                17,
+                "Redefinition of __proto__ property"
+            )
+        );
+        // computed, shorthand and method keys don't set the prototype, and a
+        // pattern only reads it
+        assert!(parse("({ __proto__: 1, ['__proto__']: 2, __proto__() {} });").is_ok());
+        assert!(parse("({ __proto__: 1, __proto__ });").is_ok());
+        assert!(parse("({ __proto__: a, __proto__: b } = c);").is_ok());
+    }
+
+    #[test]
+    fn meta_properties_need_their_one_property_and_place() {
+        let program = crate::parser::parse_module("x = import.meta.url;").unwrap();
+        assert_eq!(
+            to_sexpr(&program),
+            "(Program\n  (Expression (Assignment = x (Member (MetaProperty import meta) url))))"
+        );
+        assert_eq!(
+            sexpr("function f() { new.target }"),
+            "(Program\n  (FunctionDeclaration f (params) (Block (Expression (MetaProperty new target)))))"
+        );
+        assert_eq!(error_at("import.meta;"), (ParseErrorKind::Misplaced, 1, 0));
+        assert_eq!(
+            error_at("import.foo;"),
+            (ParseErrorKind::InvalidSyntax, 1, 7)
+        );
+        assert_eq!(
+            error_at("function f() { new.foo }"),
+            (ParseErrorKind::InvalidSyntax, 1, 19)
+        );
+    }
+
+    #[test]
+    fn exponents_are_right_associative_without_a_unary_base() {
+        assert_eq!(
+            sexpr("2 ** 3 ** 2;"),
+            "(Program\n  (Expression (Binary ** 2 (Binary ** 3 2))))"
+        );
+        assert_eq!(
+            sexpr("(-2) ** 2;"),
+            "(Program\n  (Expression (Binary ** (Unary - 2) 2)))"
+        );
+        assert_eq!(
+            sexpr("2 ** -2;"),
+            "(Program\n  (Expression (Binary ** 2 (Unary - 2))))"
+        );
+        assert_eq!(
+            sexpr("a++ ** 2 * b;"),
+            "(Program\n  (Expression (Binary * (Binary ** (Update ++ a) 2) b)))"
+        );
+        for src in ["-2 ** 2;", "!a ** 2;", "2 ** -a ** 2;"] {
+            assert_eq!(
+                parse(src).unwrap_err().message,
+                "Unary operator used immediately before exponentiation expression",
+                "{}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn parentheses_are_params_only_before_an_arrow() {
+        assert_eq!(
+            sexpr("(a, b = 1) => a;"),
+            "(Program\n  (Expression (ArrowFunction (params a (AssignmentPattern b 1)) a)))"
+        );
+        assert_eq!(sexpr("(a, b);"), "(Program\n  (Expression (Sequence a b)))");
+        assert_eq!(
+            sexpr("(a, ...b) => b;"),
+            "(Program\n  (Expression (ArrowFunction (params a (RestElement b)) b)))"
+        );
+        // a shorthand default is only a pattern's, and a rest only a list of
+        // params'
+        assert_eq!(
+            error_at("({a = 1});"),
+            (ParseErrorKind::InvalidSyntax, 1, 4)
+        );
+        assert_eq!(
+            error_at("(a, ...b);"),
+            (ParseErrorKind::UnexpectedToken, 1, 4)
+        );
+        assert!(parse("(a + 1) => a;").is_err());
+    }
+
+    #[test]
+    fn async_is_an_arrow_a_call_or_a_name() {
+        assert_eq!(
+            sexpr("x = async => async;"),
+            "(Program\n  (Expression (Assignment = x (ArrowFunction (params async) async))))"
+        );
+        assert_eq!(
+            sexpr("async (a) => a;"),
+            "(Program\n  (Expression (ArrowFunction async (params a) a)))"
+        );
+        assert_eq!(
+            sexpr("async(a, b = 1);"),
+            "(Program\n  (Expression (Call async a (Assignment = b 1))))"
+        );
+        assert_eq!(
+            error_at("async\n=> 1"),
+            (ParseErrorKind::InvalidSyntax, 2, 0)
+        );
+    }
+
+    // which elements of the array the statement starts with are holes
+    fn holes(src: &str) -> Vec<bool> {
+        let program = parse(src).unwrap();
+        let Statement::Expression(statement) = &program.body[0] else {
+            panic!("expected an expression statement");
+        };
+        match &statement.expression {
+            Expression::Array(array) => array.elements.iter().map(Option::is_none).collect(),
+            Expression::Assignment(assignment) => {
+                let AssignmentExpressionLeft::Pattern(Pattern::Array(array)) = &assignment.left
+                else {
+                    panic!("expected an array pattern");
+                };
+                array.elements.iter().map(Option::is_none).collect()
+            }
+            _ => panic!("expected an array"),
+        }
+    }
+
+    #[test]
+    fn array_holes_keep_their_place() {
+        // a trailing comma ends the last element rather than adding a hole
+        assert_eq!(holes("[,];"), [true]);
+        assert_eq!(holes("[,,];"), [true, true]);
+        assert_eq!(holes("[a,,b];"), [false, true, false]);
+        assert_eq!(holes("[a,];"), [false]);
+        assert_eq!(holes("[,...a] = x;"), [true, false]);
+        assert_eq!(holes("[a,,] = x;"), [false, true]);
+    }
+}
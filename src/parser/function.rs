@@ -0,0 +1,583 @@
+use crate::{
+    context::{CoverErrors, DeclarationKind},
+    error::{ParseError, ParseErrorKind, ParseResult},
+    node::{
+        ArrowFunctionExpression, ArrowFunctionExpressionBody, AwaitExpression,
+        CallExpressionArgument, ErrorNode, FunctionBody, FunctionDeclaration, FunctionExpression,
+        Identifier, Pattern, Position, SourceLocation, Statement, YieldExpression,
+    },
+    token::{Arithmetic, Token, TokenKind},
+    visit::Visitor,
+};
+
+use super::{expression::CoverList, Parser};
+
+#[derive(Default)]
+struct ParamExpressionFinder {
+    found: Option<(Position, &'static str)>,
+}
+
+impl<'ast> Visitor<'ast> for ParamExpressionFinder {
+    fn visit_yield_expression(&mut self, node: &'ast YieldExpression) {
+        self.found
+            .get_or_insert((node.loc.start, "Yield expression cannot be a default value"));
+    }
+
+    fn visit_await_expression(&mut self, node: &'ast AwaitExpression) {
+        self.found
+            .get_or_insert((node.loc.start, "Await expression cannot be a default value"));
+    }
+
+    fn visit_function_expression(&mut self, _: &'ast FunctionExpression) {}
+
+    fn visit_arrow_function_expression(&mut self, _: &'ast ArrowFunctionExpression) {}
+}
+
+impl Parser<'_> {
+    // `async function`, where no line break may follow `async`
+    pub(super) fn is_async_function(&self) -> bool {
+        self.is_name("async")
+            && self.peek2() == Some(&Token::Function)
+            && self
+                .tokens
+                .peek2()
+                .is_some_and(|lexeme| !lexeme.newline_before)
+    }
+
+    // the name may only be left out under `export default`
+    pub(super) fn parse_function_declaration(
+        &mut self,
+        is_default_export: bool,
+    ) -> ParseResult<FunctionDeclaration> {
+        let loc = self.start_loc();
+        let is_async = self.eat_name("async");
+        self.expect(&Token::Function)?;
+        let generator = self.eat(&Token::Arithmetic(Arithmetic::Multiple));
+        let id = if is_default_export && self.is(&Token::ParenL) {
+            None
+        } else {
+            let id = self.parse_identifier()?;
+            self.declare(&id, DeclarationKind::Function)?;
+            Some(id)
+        };
+        self.context.push_function_scope(is_async, generator);
+        let params = self.parse_function_params()?;
+        let body = self.parse_function_body()?;
+        self.check_strict_function(id.as_ref(), &params, &body.body, false)?;
+        Ok(FunctionDeclaration::new(
+            id,
+            params,
+            body,
+            is_async,
+            generator,
+            self.finish(loc),
+        ))
+    }
+
+    pub(super) fn parse_function_expression(&mut self) -> ParseResult<FunctionExpression> {
+        let loc = self.start_loc();
+        let is_async = self.eat_name("async");
+        self.expect(&Token::Function)?;
+        let generator = self.eat(&Token::Arithmetic(Arithmetic::Multiple));
+        // the name of a function expression is bound inside it, so an async
+        // function can't be called `await` nor a generator `yield`
+        self.context.push_function_scope(is_async, generator);
+        let id = if self.is(&Token::ParenL) {
+            None
+        } else {
+            Some(self.parse_identifier()?)
+        };
+        let params = self.parse_function_params()?;
+        let body = self.parse_function_body()?;
+        self.check_strict_function(id.as_ref(), &params, &body.body, false)?;
+        Ok(FunctionExpression::new(
+            id,
+            params,
+            body,
+            is_async,
+            generator,
+            self.finish(loc),
+        ))
+    }
+
+    // the anonymous function behind a method, starting at its parameter list
+    pub(super) fn parse_method(
+        &mut self,
+        is_async: bool,
+        generator: bool,
+        allows_super_call: bool,
+    ) -> ParseResult<FunctionExpression> {
+        let loc = self.start_loc();
+        self.context
+            .push_method_scope(is_async, generator, allows_super_call);
+        let params = self.parse_function_params()?;
+        let body = self.parse_function_body()?;
+        self.check_strict_function(None, &params, &body.body, true)?;
+        Ok(FunctionExpression::new(
+            None,
+            params,
+            body,
+            is_async,
+            generator,
+            self.finish(loc),
+        ))
+    }
+
+    // getters take no parameters and setters exactly one
+    pub(super) fn parse_accessor(&mut self, is_setter: bool) -> ParseResult<FunctionExpression> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        self.context.push_method_scope(false, false, false);
+        let params = self.parse_function_params()?;
+        if is_setter && params.len() != 1 {
+            return self.raise(
+                ParseErrorKind::InvalidParameters,
+                line,
+                column,
+                "Setter must have exactly one formal parameter",
+            );
+        }
+        if is_setter && matches!(params[0], Pattern::Rest(_)) {
+            return self.raise(
+                ParseErrorKind::InvalidParameters,
+                line,
+                column,
+                "Setter cannot use rest params",
+            );
+        }
+        if !is_setter && !params.is_empty() {
+            return self.raise(
+                ParseErrorKind::InvalidParameters,
+                line,
+                column,
+                "Getter must not have any formal parameters",
+            );
+        }
+        let body = self.parse_function_body()?;
+        self.check_strict_function(None, &params, &body.body, true)?;
+        Ok(FunctionExpression::new(
+            None,
+            params,
+            body,
+            false,
+            false,
+            self.finish(loc),
+        ))
+    }
+
+    // the `=>` and body of an arrow function whose parameters have been read
+    pub(super) fn parse_arrow_function_expression(
+        &mut self,
+        params: Vec<Pattern>,
+        is_async: bool,
+        loc: SourceLocation,
+    ) -> ParseResult<ArrowFunctionExpression> {
+        if self.has_newline_before() {
+            let (line, column) = self.position();
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                line,
+                column,
+                "Line terminator not permitted before arrow",
+            );
+        }
+        self.expect(&Token::Arrow)?;
+        self.check_param_expressions(&params)?;
+        // read as the arguments of a call to `async`, so `await` was a name
+        // there until the `=>`
+        if is_async {
+            let mut bound = params.iter().flat_map(|param| param.bound_names());
+            if let Some(name) = bound.find(|name| name.name == "await") {
+                let start = name.loc.start;
+                self.early(
+                    ParseErrorKind::ReservedWord,
+                    start.line,
+                    start.column,
+                    "Cannot use 'await' as an identifier inside an async function",
+                )?;
+            }
+        }
+
+        self.context.push_arrow_scope(is_async);
+        for name in params.iter().flat_map(|param| param.bound_names()) {
+            self.declare(name, DeclarationKind::Param)?;
+        }
+        let body = if self.is(&Token::BraceL) {
+            let body_loc = self.start_loc();
+            self.tokens.bump();
+            let body = self.allow_in(|parser| parser.parse_scope_statements(true))?;
+            ArrowFunctionExpressionBody::FunctionBody(FunctionBody::new(
+                body,
+                self.finish(body_loc),
+            ))
+        } else {
+            let body = self.parse_assignment_expression()?;
+            self.context.pop_scope();
+            ArrowFunctionExpressionBody::Expression(body)
+        };
+        let statements = match &body {
+            ArrowFunctionExpressionBody::FunctionBody(body) => body.body.as_slice(),
+            ArrowFunctionExpressionBody::Expression(_) => &[],
+        };
+        self.check_strict_function(None, &params, statements, true)?;
+        Ok(ArrowFunctionExpression::new(
+            params,
+            body,
+            is_async,
+            self.finish(loc),
+        ))
+    }
+
+    // the list in front of `=>` was parsed as expressions, as the cover
+    // grammar has it, and each one is now reinterpreted as the pattern it
+    // stands for, so `(a = 1) =>` has a default rather than an assignment
+    pub(super) fn cover_list_into_params(&mut self, list: CoverList) -> ParseResult<Vec<Pattern>> {
+        if let Some((line, column, message)) = list.errors.binding {
+            return self.raise(ParseErrorKind::InvalidTarget, line, column, message);
+        }
+        let count = list.items.len();
+        let mut params: Vec<Pattern> = vec![];
+        for (index, (item, line, column)) in list.items.into_iter().enumerate() {
+            let param: Result<Pattern, SourceLocation> = match item {
+                CallExpressionArgument::Expression(expression) => expression.into_pattern(),
+                CallExpressionArgument::SpreadElement(spread) => {
+                    if index + 1 != count {
+                        return self.raise(
+                            ParseErrorKind::InvalidTarget,
+                            line,
+                            column,
+                            "Rest element must be last element",
+                        );
+                    }
+                    if let Some((line, column)) = list.trailing_comma {
+                        return self.raise(
+                            ParseErrorKind::InvalidTarget,
+                            line,
+                            column,
+                            "Comma is not permitted after the rest element",
+                        );
+                    }
+                    spread
+                        .into_rest_element()
+                        .map(|rest| Pattern::Rest(Box::new(rest)))
+                }
+            };
+            let param = match param {
+                Ok(param) => param,
+                Err(invalid) => {
+                    return self.raise(
+                        ParseErrorKind::InvalidTarget,
+                        invalid.start.line,
+                        invalid.start.column,
+                        "Invalid arrow function parameter",
+                    )
+                }
+            };
+            if !param.is_binding() {
+                return self.raise(
+                    ParseErrorKind::InvalidTarget,
+                    line,
+                    column,
+                    "Binding member expression",
+                );
+            }
+            for name in param.bound_names() {
+                let clashes = params
+                    .iter()
+                    .flat_map(|param| param.bound_names())
+                    .any(|bound| bound.name == name.name);
+                if clashes {
+                    return self.raise(
+                        ParseErrorKind::InvalidParameters,
+                        line,
+                        column,
+                        "Argument name clash",
+                    );
+                }
+            }
+            params.push(param);
+        }
+        Ok(params)
+    }
+
+    // a "use strict" directive makes the name and parameters strict too,
+    // which is only known once the body has been parsed; duplicate
+    // parameters are also an error in methods and arrows, strict or not, and
+    // in any function whose parameters aren't all plain names, which can't
+    // be made strict by their body either
+    fn check_strict_function(
+        &mut self,
+        id: Option<&Identifier>,
+        params: &[Pattern],
+        body: &[Statement],
+        unique: bool,
+    ) -> ParseResult<()> {
+        let use_strict = body.iter().find_map(|statement| match statement {
+            Statement::Directive(directive) if directive.directive == "use strict" => {
+                Some(directive)
+            }
+            _ => None,
+        });
+        let is_simple = params
+            .iter()
+            .all(|param| matches!(param, Pattern::Identifier(_)));
+        if let (Some(directive), false) = (use_strict, is_simple) {
+            let start = directive.loc.start;
+            return self.early(
+                ParseErrorKind::InvalidSyntax,
+                start.line,
+                start.column,
+                "Illegal 'use strict' directive in function with non-simple parameter list",
+            );
+        }
+        let is_strict = self.context.scope().is_strict || use_strict.is_some();
+        let unique = unique || !is_simple;
+        if let Some(id) = id {
+            self.check_eval_or_arguments(id, is_strict, "Binding")?;
+            self.check_strict_reserved(id, is_strict)?;
+        }
+        let names: Vec<&Identifier> = params
+            .iter()
+            .flat_map(|param| param.bound_names())
+            .collect();
+        for (index, name) in names.iter().enumerate() {
+            self.check_eval_or_arguments(name, is_strict, "Binding")?;
+            self.check_strict_reserved(name, is_strict)?;
+            let clashes = names[..index].iter().any(|other| other.name == name.name);
+            if clashes && (is_strict || unique) {
+                let start = name.loc.start;
+                return self.early(
+                    ParseErrorKind::InvalidParameters,
+                    start.line,
+                    start.column,
+                    "Argument name clash",
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_function_params(&mut self) -> ParseResult<Vec<Pattern>> {
+        self.expect(&Token::ParenL)?;
+        let mut params: Vec<Pattern> = vec![];
+        while !self.eat(&Token::ParenR) {
+            let start = self.tokens.index();
+            let loc = self.start_loc();
+            let depth = self.context.depth();
+            let checkpoints = self.tokens.checkpoints();
+            let checkpoint = self.tokens.checkpoint();
+            let is_rest = self.is(&Token::Ellipsis);
+            let param = self.parse_function_param().and_then(|param| {
+                for name in param.bound_names() {
+                    self.declare(name, DeclarationKind::Param)?;
+                }
+                if is_rest {
+                    self.expect(&Token::ParenR)?;
+                } else {
+                    self.expect_separator(&Token::ParenR)?;
+                }
+                Ok(param)
+            });
+            match param {
+                Ok(param) => {
+                    self.tokens.commit(checkpoint);
+                    params.push(param);
+                    if is_rest {
+                        break;
+                    }
+                }
+                Err(error) if self.context.recover => {
+                    self.context.truncate(depth);
+                    self.tokens.truncate_checkpoints(checkpoints + 1);
+                    self.tokens.rewind(checkpoint);
+                    self.context.is_pattern = false;
+                    self.context.cover_errors = CoverErrors::default();
+                    params.push(self.skip_params(error, start, loc));
+                    break;
+                }
+                Err(error) => {
+                    self.tokens.commit(checkpoint);
+                    return Err(error);
+                }
+            }
+        }
+        self.check_param_expressions(&params)?;
+        Ok(params)
+    }
+
+    // the defaults and computed keys of parameters are evaluated before the
+    // body starts, so can't `yield` or `await`; those of functions nested in them are
+    // their own
+    fn check_param_expressions(&mut self, params: &[Pattern]) -> ParseResult<()> {
+        let mut finder = ParamExpressionFinder::default();
+        for param in params {
+            finder.visit_pattern(param);
+        }
+        match finder.found {
+            Some((start, message)) => self.early(
+                ParseErrorKind::InvalidParameters,
+                start.line,
+                start.column,
+                message,
+            ),
+            None => Ok(()),
+        }
+    }
+
+    // a name or pattern with an optional default, or a rest parameter
+    fn parse_function_param(&mut self) -> ParseResult<Pattern> {
+        if self.is(&Token::Ellipsis) {
+            let rest = self.parse_rest_binding(&Token::ParenR)?;
+            return Ok(Pattern::Rest(Box::new(rest)));
+        }
+        self.parse_binding_element()
+    }
+
+    // in recovery mode, the rest of a parameter list with an error becomes
+    // an error node, from the parameter the error is in up to the closing
+    // paren or, if that's missing, up to the `{` of the body so that the body
+    // is still parsed; a body right where the parameter starts was taken for
+    // an object pattern, so the error is the missing paren instead
+    fn skip_params(&mut self, error: ParseError, start: usize, loc: SourceLocation) -> Pattern {
+        let end = self.params_end();
+        let error = if end == 0 && self.is(&Token::BraceL) {
+            self.tokens.expected(vec![TokenKind::ParenR])
+        } else {
+            error
+        };
+        let diagnostic = self.record(error);
+        for _ in 0..end {
+            self.tokens.bump();
+        }
+        let loc = if self.tokens.index() > start {
+            self.finish(loc)
+        } else {
+            self.empty_loc()
+        };
+        self.eat(&Token::ParenR);
+        Pattern::Error(Box::new(ErrorNode::new(diagnostic, loc)))
+    }
+
+    // how many tokens ahead the closing paren of the parameters is, or the
+    // first `{` outside any brackets if there is no closing paren
+    fn params_end(&mut self) -> usize {
+        let mut depth = 0;
+        let mut body = None;
+        let mut offset = 0;
+        while let Some(lexeme) = self.tokens.peek_nth(offset) {
+            match lexeme.token {
+                Token::BraceL if depth == 0 => {
+                    body.get_or_insert(offset);
+                    depth += 1;
+                }
+                Token::ParenL | Token::BraceL | Token::BracketL => depth += 1,
+                Token::ParenR if depth == 0 => return offset,
+                Token::ParenR | Token::BraceR | Token::BracketR if depth > 0 => depth -= 1,
+                Token::BraceR | Token::BracketR => break,
+                _ => {}
+            }
+            offset += 1;
+        }
+        body.unwrap_or(offset)
+    }
+
+    // the scope of the function is pushed before its parameters, which are
+    // parsed inside it, and popped here along with the body
+    fn parse_function_body(&mut self) -> ParseResult<FunctionBody> {
+        let loc = self.start_loc();
+        self.expect(&Token::BraceL)?;
+        let body = self.parse_scope_statements(true)?;
+
+        Ok(FunctionBody::new(body, self.finish(loc)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ParseErrorKind, parser::parse, test_support::error_at};
+
+    #[test]
+    fn generator_params_cannot_yield() {
+        let invalid = [
+            ("function* g(a = yield) {}", 16),
+            ("function* g([a = yield 1]) {}", 17),
+            ("function* g({ [yield]: a }) {}", 15),
+            ("function* g() { function* h(a = yield) {} }", 32),
+            ("({ *m(a = yield) {} })", 10),
+            ("function* g() { (a = yield) => a; }", 21),
+        ];
+        for (src, column) in invalid {
+            assert_eq!(
+                error_at(src),
+                (ParseErrorKind::InvalidParameters, 1, column),
+                "{}",
+                src
+            );
+        }
+        // `yield` is a name outside generators, and a nested function's
+        // params are its own
+        assert!(parse("function g(a = yield) {}").is_ok());
+        assert!(parse("function* g(a = function (b = yield) {}) {}").is_ok());
+    }
+
+    #[test]
+    fn async_params_cannot_await() {
+        let invalid = [
+            ("async function f(a = await 1) {}", 21),
+            ("async function f({ a = await b }) {}", 23),
+            ("(async function (a = await 1) {})", 21),
+            ("({ async m(a = await 1) {} })", 15),
+            ("async function f() { async (a = await b) => a; }", 32),
+            ("async function f() { (a = await b) => a; }", 26),
+        ];
+        for (src, column) in invalid {
+            assert_eq!(
+                error_at(src),
+                (ParseErrorKind::InvalidParameters, 1, column),
+                "{}",
+                src
+            );
+        }
+        assert!(parse("async function f(a = async () => await b) {}").is_ok());
+        assert!(parse("async function f() { g(a = await b); }").is_ok());
+    }
+
+    #[test]
+    fn async_arrow_params_cannot_be_await() {
+        use ParseErrorKind::ReservedWord;
+        assert_eq!(error_at("async (await) => 1"), (ReservedWord, 1, 7));
+        assert_eq!(error_at("async ({await}) => 1"), (ReservedWord, 1, 8));
+        assert_eq!(
+            error_at("async ([a, ...await]) => 1"),
+            (ReservedWord, 1, 14)
+        );
+        // a call to `async` or an arrow that isn't async may still have it
+        assert!(parse("async (await)").is_ok());
+        assert!(parse("(await) => 1").is_ok());
+        assert!(parse("async ({ await: a }) => 1").is_ok());
+    }
+
+    #[test]
+    fn accessors_take_the_right_parameters() {
+        for (src, column) in [
+            ("class C { get a(x){} }", 15),
+            ("class C { set a(){} }", 15),
+            ("class C { set a(x, y){} }", 15),
+            ("class C { set a(...x){} }", 15),
+            ("({ get a(x){} })", 8),
+            ("({ set a(...x){} })", 8),
+        ] {
+            assert_eq!(
+                error_at(src),
+                (ParseErrorKind::InvalidParameters, 1, column),
+                "{}",
+                src
+            );
+        }
+        // `get`, `set` and `static` are still names of methods and fields
+        assert!(parse("class C { get(){} set(x){} static(){} }").is_ok());
+        assert!(parse("class C { static; get; set }").is_ok());
+        assert!(parse("class C { static get(){} static static(){} }").is_ok());
+        assert!(parse("({ get(){}, set(){}, get: 1 })").is_ok());
+    }
+}
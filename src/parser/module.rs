@@ -0,0 +1,363 @@
+use crate::{
+    context::DeclarationKind,
+    error::{ParseErrorKind, ParseResult},
+    node::{
+        ExportAllDeclaration, ExportDefaultDeclaration, ExportDefaultDeclarationKind,
+        ExportNamedDeclaration, ExportSpecifier, Identifier, ImportDeclaration,
+        ImportDeclarationSpecifier, ImportDefaultSpecifier, ImportNamespaceSpecifier,
+        ImportSpecifier, Literal, LiteralValue, SourceLocation, Statement,
+    },
+    token::{Arithmetic, Token, TokenKind},
+};
+
+use super::Parser;
+
+impl Parser<'_> {
+    pub(super) fn parse_import_declaration(&mut self) -> ParseResult<ImportDeclaration> {
+        let loc = self.start_loc();
+        self.expect(&Token::Import)?;
+
+        // `import "m"` only evaluates the module
+        let mut specifiers = vec![];
+        if !matches!(self.peek(), Some(Token::String(_))) {
+            let mut has_next = true;
+            if !self.is(&Token::BraceL) && !self.is(&Token::Arithmetic(Arithmetic::Multiple)) {
+                let specifier_loc = self.start_loc();
+                let local = self.parse_identifier()?;
+                self.check_eval_or_arguments(&local, true, "Binding")?;
+                self.declare(&local, DeclarationKind::Lexical)?;
+                specifiers.push(ImportDeclarationSpecifier::ImportDefaultSpecifier(
+                    ImportDefaultSpecifier::new(local, self.finish(specifier_loc)),
+                ));
+                has_next = self.eat(&Token::Comma);
+            }
+
+            if has_next {
+                if self.is(&Token::Arithmetic(Arithmetic::Multiple)) {
+                    let specifier_loc = self.start_loc();
+                    self.tokens.bump();
+                    self.expect_name("as")?;
+                    let local = self.parse_identifier()?;
+                    self.check_eval_or_arguments(&local, true, "Binding")?;
+                    self.declare(&local, DeclarationKind::Lexical)?;
+                    specifiers.push(ImportDeclarationSpecifier::ImportNamespaceSpecifier(
+                        ImportNamespaceSpecifier::new(local, self.finish(specifier_loc)),
+                    ));
+                } else {
+                    self.parse_import_specifiers(&mut specifiers)?;
+                }
+            }
+            self.expect_name("from")?;
+        }
+
+        let source = self.parse_module_source()?;
+        self.consume_semicolon()?;
+        Ok(ImportDeclaration::new(specifiers, source, self.finish(loc)))
+    }
+
+    // `{ a, b as c }`
+    fn parse_import_specifiers(
+        &mut self,
+        specifiers: &mut Vec<ImportDeclarationSpecifier>,
+    ) -> ParseResult<()> {
+        self.expect(&Token::BraceL)?;
+        while !self.eat(&Token::BraceR) {
+            let specifier_loc = self.start_loc();
+            let is_keyword = matches!(self.peek(), Some(token) if token.is_keyword());
+            let checkpoint = self.tokens.checkpoint();
+            let imported = self.parse_identifier_name()?;
+            let local = if self.eat_name("as") {
+                self.tokens.commit(checkpoint);
+                self.parse_identifier()?
+            } else {
+                // reserved words are fine as imported names but can't be bound
+                if is_keyword {
+                    self.tokens.rewind(checkpoint);
+                    return self.unexpected();
+                }
+                self.tokens.commit(checkpoint);
+                imported.clone()
+            };
+            self.check_eval_or_arguments(&local, true, "Binding")?;
+            self.declare(&local, DeclarationKind::Lexical)?;
+            specifiers.push(ImportDeclarationSpecifier::ImportSpecifier(
+                ImportSpecifier::new(imported, local, self.finish(specifier_loc)),
+            ));
+            self.expect_separator(&Token::BraceR)?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn parse_export_declaration(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        self.expect(&Token::Export)?;
+        match self.peek() {
+            Some(Token::Default) => Ok(Statement::ExportDefaultDeclaration(Box::new(
+                self.parse_export_default_declaration(loc)?,
+            ))),
+            Some(Token::Arithmetic(Arithmetic::Multiple)) => Ok(Statement::ExportAllDeclaration(
+                Box::new(self.parse_export_all_declaration(loc)?),
+            )),
+            Some(Token::BraceL) => Ok(Statement::ExportNamedDeclaration(Box::new(
+                self.parse_export_specifiers(loc)?,
+            ))),
+            _ => Ok(Statement::ExportNamedDeclaration(Box::new(
+                self.parse_export_named_declaration(loc)?,
+            ))),
+        }
+    }
+
+    // `export default` takes a function or class declaration, whose name is
+    // optional there, and any other expression otherwise
+    fn parse_export_default_declaration(
+        &mut self,
+        loc: SourceLocation,
+    ) -> ParseResult<ExportDefaultDeclaration> {
+        let (line, column) = self.position();
+        self.expect(&Token::Default)?;
+        self.add_exported_name("default", line, column)?;
+        let declaration = match self.peek() {
+            Some(Token::Function) => ExportDefaultDeclarationKind::FunctionDeclaration(
+                self.parse_function_declaration(true)?,
+            ),
+            Some(Token::Name(_)) if self.is_async_function() => {
+                ExportDefaultDeclarationKind::FunctionDeclaration(
+                    self.parse_function_declaration(true)?,
+                )
+            }
+            Some(Token::Class) => {
+                ExportDefaultDeclarationKind::ClassDeclaration(self.parse_class_declaration(true)?)
+            }
+            _ => {
+                let expression = self.parse_assignment_expression()?;
+                self.consume_semicolon()?;
+                ExportDefaultDeclarationKind::Expression(expression)
+            }
+        };
+        Ok(ExportDefaultDeclaration::new(declaration, self.finish(loc)))
+    }
+
+    // `export * from "m"` and `export * as ns from "m"`
+    fn parse_export_all_declaration(
+        &mut self,
+        loc: SourceLocation,
+    ) -> ParseResult<ExportAllDeclaration> {
+        self.expect(&Token::Arithmetic(Arithmetic::Multiple))?;
+        let exported = if self.eat_name("as") {
+            let exported = self.parse_identifier_name()?;
+            self.add_exported_name(
+                &exported.name,
+                exported.loc.start.line,
+                exported.loc.start.column,
+            )?;
+            Some(exported)
+        } else {
+            None
+        };
+        self.expect_name("from")?;
+        let source = self.parse_module_source()?;
+        self.consume_semicolon()?;
+        Ok(ExportAllDeclaration::new(
+            exported,
+            source,
+            self.finish(loc),
+        ))
+    }
+
+    // `export { a, b as c }`, optionally re-exported `from "m"`
+    fn parse_export_specifiers(
+        &mut self,
+        loc: SourceLocation,
+    ) -> ParseResult<ExportNamedDeclaration> {
+        self.expect(&Token::BraceL)?;
+        let mut specifiers = vec![];
+        let mut reserved_local: Option<(usize, usize)> = None;
+        while !self.eat(&Token::BraceR) {
+            let specifier_loc = self.start_loc();
+            if reserved_local.is_none() && matches!(self.peek(), Some(token) if token.is_keyword())
+            {
+                reserved_local = Some(self.position());
+            }
+            let local = self.parse_identifier_name()?;
+            let start = local.loc.start;
+            self.context
+                .exported_locals
+                .push((local.name.to_string(), start.line, start.column));
+            let exported = if self.eat_name("as") {
+                self.parse_identifier_name()?
+            } else {
+                local.clone()
+            };
+            self.add_exported_name(
+                &exported.name,
+                exported.loc.start.line,
+                exported.loc.start.column,
+            )?;
+            specifiers.push(ExportSpecifier::new(
+                local,
+                exported,
+                self.finish(specifier_loc),
+            ));
+            self.expect_separator(&Token::BraceR)?;
+        }
+
+        let source = if self.eat_name("from") {
+            Some(self.parse_module_source()?)
+        } else {
+            // reserved words can be re-exported from another module but can't
+            // refer to a local binding
+            if let Some((line, column)) = reserved_local {
+                return self.raise(
+                    ParseErrorKind::ReservedWord,
+                    line,
+                    column,
+                    "Unexpected reserved word in export",
+                );
+            }
+            None
+        };
+        // what's re-exported needn't be declared here
+        if source.is_some() {
+            let count = self.context.exported_locals.len() - specifiers.len();
+            self.context.exported_locals.truncate(count);
+        }
+        self.consume_semicolon()?;
+        Ok(ExportNamedDeclaration::new(
+            None,
+            specifiers,
+            source,
+            self.finish(loc),
+        ))
+    }
+
+    fn parse_export_named_declaration(
+        &mut self,
+        loc: SourceLocation,
+    ) -> ParseResult<ExportNamedDeclaration> {
+        let (declaration, names): (Statement, Vec<Identifier>) = match self.peek() {
+            Some(Token::Var) | Some(Token::Let) | Some(Token::Const) => {
+                let declaration = self.parse_variable_declaration(false)?;
+                let names = declaration.bound_names().into_iter().cloned().collect();
+                (Statement::VariableDeclaration(Box::new(declaration)), names)
+            }
+            _ if self.is(&Token::Function) || self.is_async_function() => {
+                let declaration = self.parse_function_declaration(false)?;
+                let names = declaration.id.iter().cloned().collect();
+                (Statement::FunctionDeclaration(Box::new(declaration)), names)
+            }
+            Some(Token::Class) => {
+                let declaration = self.parse_class_declaration(false)?;
+                let names = declaration.id.iter().cloned().collect();
+                (Statement::ClassDeclaration(Box::new(declaration)), names)
+            }
+            _ => return self.unexpected(),
+        };
+        for name in names {
+            self.add_exported_name(&name.name, name.loc.start.line, name.loc.start.column)?;
+        }
+        Ok(ExportNamedDeclaration::new(
+            Some(declaration),
+            vec![],
+            None,
+            self.finish(loc),
+        ))
+    }
+
+    // once the whole module is read, every local name it exports has to
+    // have been declared at its top level, before or after the export
+    pub(super) fn check_exported_locals(&mut self) -> ParseResult<()> {
+        let locals = std::mem::take(&mut self.context.exported_locals);
+        for (name, line, column) in locals {
+            if !self.context.scope().blocks[0].names.contains_key(&name) {
+                let message = format!("Export '{}' is not defined", name);
+                self.early(ParseErrorKind::InvalidSyntax, line, column, &message)?;
+            }
+        }
+        Ok(())
+    }
+
+    // a module may export each name only once
+    fn add_exported_name(&mut self, name: &str, line: usize, column: usize) -> ParseResult<()> {
+        if let Some((first_line, first_column)) = self.context.exported_names.get(name) {
+            let message = format!(
+                "Duplicate export of '{}' (first exported at line:{}, column:{})",
+                name, first_line, first_column
+            );
+            return self.raise(ParseErrorKind::InvalidSyntax, line, column, &message);
+        }
+        self.context
+            .exported_names
+            .insert(name.to_string(), (line, column));
+        Ok(())
+    }
+
+    fn parse_module_source(&mut self) -> ParseResult<Literal> {
+        let loc = self.start_loc();
+        match self.peek() {
+            Some(Token::String(content)) => {
+                let value = LiteralValue::String(content.to_string());
+                let raw = self.tokens.text().to_string();
+                self.tokens.bump();
+                Ok(Literal::new(value, raw, self.finish(loc)))
+            }
+            _ => self.expected(vec![TokenKind::String]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        error::ParseErrorKind,
+        parser::{parse_module, ParseResult},
+    };
+
+    fn error_at<T>(result: ParseResult<T>) -> (ParseErrorKind, usize, usize) {
+        let error = result.err().expect("should not parse");
+        (error.kind, error.start.line, error.start.column)
+    }
+
+    #[test]
+    fn each_name_is_exported_once() {
+        use ParseErrorKind::*;
+        assert_eq!(
+            error_at(parse_module("export { a, a }; var a;")),
+            (InvalidSyntax, 1, 12)
+        );
+        assert_eq!(
+            error_at(parse_module("export default 1; export default 2;")),
+            (InvalidSyntax, 1, 25)
+        );
+        assert_eq!(
+            error_at(parse_module(
+                "export default 1; var x; export { x as default };"
+            )),
+            (InvalidSyntax, 1, 39)
+        );
+        assert!(parse_module("export { a, a as b }; var a;").is_ok());
+    }
+
+    #[test]
+    fn exported_names_are_declared_at_the_top_level() {
+        use ParseErrorKind::*;
+        for (src, column) in [
+            ("export { x };", 9),
+            ("export { y, x as z }; var y;", 12),
+            ("export { x }; function f() { var x; }", 9),
+            ("export { x }; { let x; }", 9),
+        ] {
+            assert_eq!(
+                error_at(parse_module(src)),
+                (InvalidSyntax, 1, column),
+                "{}",
+                src
+            );
+        }
+        // a declaration may come after the export, and what's re-exported
+        // is declared by the other module
+        assert!(parse_module("export { x }; var x;").is_ok());
+        assert!(parse_module("export { f, C }; function f() {} class C {}").is_ok());
+        assert!(parse_module("import { y } from \"m\"; export { y as z };").is_ok());
+        assert!(parse_module("export { x } from \"m\";").is_ok());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{
+    error::{ParseErrorKind, ParseResult},
+    node::{
+        ArrayPattern, AssignmentPattern, AssignmentProperty, ObjectPattern, ObjectPatternProperty,
+        Pattern, PropertyKey, RestElement,
+    },
+    token::{Assign, Token},
+};
+
+use super::Parser;
+
+// Binding patterns, as declarations, parameters and `catch` clauses have,
+// are parsed as patterns from the start, unlike destructuring assignments
+// which are parsed as expressions and reinterpreted once the `=` is found.
+// The names they bind are declared by the caller, which knows as what.
+impl Parser<'_> {
+    // a target with a default if `=` follows, as an element of a pattern or
+    // a parameter may have
+    pub(super) fn parse_binding_element(&mut self) -> ParseResult<Pattern> {
+        let loc = self.start_loc();
+        let target = self.parse_binding_target()?;
+        if !self.eat(&Token::Assign(Assign::Normal)) {
+            return Ok(target);
+        }
+        let right = self.allow_in(|parser| parser.parse_assignment_expression())?;
+        Ok(Pattern::Assignment(Box::new(AssignmentPattern::new(
+            target,
+            right,
+            self.finish(loc),
+        ))))
+    }
+
+    // a name, or an array or object pattern of further elements
+    pub(super) fn parse_binding_target(&mut self) -> ParseResult<Pattern> {
+        match self.peek() {
+            Some(Token::BracketL) => self.nested(|parser| parser.parse_array_pattern()),
+            Some(Token::BraceL) => self.nested(|parser| parser.parse_object_pattern()),
+            _ => Ok(Pattern::Identifier(Box::new(self.parse_identifier()?))),
+        }
+    }
+
+    // `...a` and `...[a, b]`, which must come last and have no default; the
+    // caller expects the `close` after it
+    pub(super) fn parse_rest_binding(&mut self, close: &Token) -> ParseResult<RestElement> {
+        let loc = self.start_loc();
+        self.expect(&Token::Ellipsis)?;
+        let argument = self.parse_binding_target()?;
+        let rest = RestElement::new(argument, self.finish(loc));
+        self.expect_rest_end(close)?;
+        Ok(rest)
+    }
+
+    fn expect_rest_end(&mut self, close: &Token) -> ParseResult<()> {
+        let (line, column) = self.position();
+        let message = match self.peek() {
+            Some(Token::Assign(Assign::Normal)) => "Rest elements cannot have a default value",
+            Some(Token::Comma) if self.peek2() == Some(close) => {
+                "Comma is not permitted after the rest element"
+            }
+            Some(Token::Comma) => "Rest element must be last element",
+            _ => return Ok(()),
+        };
+        self.raise(ParseErrorKind::InvalidTarget, line, column, message)
+    }
+
+    // `[a, , b = 1, ...c]`, where each hole is None
+    fn parse_array_pattern(&mut self) -> ParseResult<Pattern> {
+        let loc = self.start_loc();
+        self.expect(&Token::BracketL)?;
+        let mut elements = vec![];
+        while !self.eat(&Token::BracketR) {
+            if self.eat(&Token::Comma) {
+                elements.push(None);
+                continue;
+            }
+            if self.is(&Token::Ellipsis) {
+                let rest = self.parse_rest_binding(&Token::BracketR)?;
+                elements.push(Some(Pattern::Rest(Box::new(rest))));
+                self.expect(&Token::BracketR)?;
+                break;
+            }
+            elements.push(Some(self.parse_binding_element()?));
+            self.expect_separator(&Token::BracketR)?;
+        }
+        Ok(Pattern::Array(Box::new(ArrayPattern::new(
+            elements,
+            self.finish(loc),
+        ))))
+    }
+
+    // `{ a, b: [c], d = 1, ...e }`, where the rest can only be a name
+    fn parse_object_pattern(&mut self) -> ParseResult<Pattern> {
+        let loc = self.start_loc();
+        self.expect(&Token::BraceL)?;
+        let mut properties = vec![];
+        while !self.eat(&Token::BraceR) {
+            let property_loc = self.start_loc();
+            if self.eat(&Token::Ellipsis) {
+                let argument = Pattern::Identifier(Box::new(self.parse_identifier()?));
+                let rest = RestElement::new(argument, self.finish(property_loc));
+                self.expect_rest_end(&Token::BraceR)?;
+                properties.push(ObjectPatternProperty::RestElement(rest));
+                self.expect(&Token::BraceR)?;
+                break;
+            }
+
+            // `{ a }` and `{ a = 1 }` bind the name they're keyed by, which
+            // has to be an identifier rather than any property name
+            let checkpoint = self.tokens.checkpoint();
+            let (key, computed, _) = self.parse_property_name()?;
+            let is_shorthand = !computed
+                && matches!(key, PropertyKey::Identifier(_))
+                && matches!(
+                    self.peek(),
+                    Some(Token::Comma) | Some(Token::BraceR) | Some(Token::Assign(Assign::Normal))
+                );
+            let value = if is_shorthand {
+                self.tokens.rewind(checkpoint);
+                self.parse_binding_element()?
+            } else {
+                self.tokens.commit(checkpoint);
+                self.expect(&Token::Colon)?;
+                self.parse_binding_element()?
+            };
+            properties.push(ObjectPatternProperty::AssignmentProperty(
+                AssignmentProperty::new(
+                    key,
+                    value,
+                    is_shorthand,
+                    computed,
+                    self.finish(property_loc),
+                ),
+            ));
+            self.expect_separator(&Token::BraceR)?;
+        }
+        Ok(Pattern::Object(Box::new(ObjectPattern::new(
+            properties,
+            self.finish(loc),
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        codegen::generate,
+        node::{AssignmentExpressionLeft, Expression, Pattern, Statement},
+        parser::parse,
+    };
+
+    #[test]
+    fn binding_patterns_nest_with_defaults_and_holes() {
+        let src = "const { a: { b = 1 } = {}, c: [d, ...e] = [] } = obj;\n";
+        assert_eq!(generate(&parse(src).unwrap()), src);
+
+        let program = parse("let [, x, , ...[y]] = a;").unwrap();
+        let Statement::VariableDeclaration(declaration) = &program.body[0] else {
+            panic!("expected a variable declaration");
+        };
+        let Pattern::Array(array) = &declaration.declarations[0].id else {
+            panic!("expected an array pattern");
+        };
+        assert!(matches!(
+            array.elements[..],
+            [
+                None,
+                Some(Pattern::Identifier(_)),
+                None,
+                Some(Pattern::Rest(_))
+            ]
+        ));
+        assert_eq!(
+            declaration
+                .bound_names()
+                .iter()
+                .map(|name| name.name.as_str())
+                .collect::<Vec<_>>(),
+            ["x", "y"]
+        );
+        assert!(
+            parse("function f({ a }, [b] = [], ...{ length }) {} try {} catch ([c]) {}").is_ok()
+        );
+        assert!(parse("for (const [k, { v }] of m);").is_ok());
+    }
+
+    #[test]
+    fn invalid_binding_patterns_are_errors() {
+        let message = |src| parse(src).unwrap_err().message;
+        assert_eq!(
+            message("let [...r = []] = a;"),
+            "Rest elements cannot have a default value"
+        );
+        assert_eq!(
+            message("[...r = []] = a;"),
+            "Invalid destructuring assignment target"
+        );
+        assert_eq!(
+            message("let [...r, s] = a;"),
+            "Rest element must be last element"
+        );
+        assert_eq!(
+            message("function f(...r,) {}"),
+            "Comma is not permitted after the rest element"
+        );
+        assert_eq!(
+            message("let { a };"),
+            "Complex binding patterns require an initialization value"
+        );
+        assert_eq!(message("function f(a, { a }) {}"), "Argument name clash");
+        assert_eq!(
+            message("function f([a]) { 'use strict'; }"),
+            "Illegal 'use strict' directive in function with non-simple parameter list"
+        );
+        assert_eq!(
+            message("({ set a(...b) {} })"),
+            "Setter cannot use rest params"
+        );
+        assert!(message("try {} catch ({ e }) { var e; }").contains("already been declared"));
+    }
+
+    #[test]
+    fn assignments_destructure_into_their_targets() {
+        let src = "({ a, b: { c } } = payload);\n[x, [y = 2], ...rest] = arr;\n";
+        let program = parse(src).unwrap();
+        assert_eq!(generate(&program), src);
+        let Statement::Expression(statement) = &program.body[1] else {
+            panic!("expected an expression statement");
+        };
+        let Expression::Assignment(assignment) = &statement.expression else {
+            panic!("expected an assignment");
+        };
+        let AssignmentExpressionLeft::Pattern(Pattern::Array(array)) = &assignment.left else {
+            panic!("expected an array pattern");
+        };
+        let [Some(Pattern::Identifier(_)), Some(Pattern::Array(inner)), Some(Pattern::Rest(_))] =
+            &array.elements[..]
+        else {
+            panic!("expected a name, a nested pattern and a rest element");
+        };
+        assert!(matches!(inner.elements[..], [Some(Pattern::Assignment(_))]));
+        assert!(parse("[(a), (b.c)] = d; for ([(e)] of f);").is_ok());
+    }
+
+    #[test]
+    fn invalid_destructuring_targets_are_errors() {
+        let message = |src| parse(src).unwrap_err().message;
+        assert_eq!(
+            message("[f()] = x;"),
+            "Invalid destructuring assignment target"
+        );
+        assert_eq!(
+            message("({ a: 1 } = x);"),
+            "Invalid destructuring assignment target"
+        );
+        assert_eq!(message("[([a])] = b;"), "Parenthesized pattern");
+        assert_eq!(message("for ([({ a })] of b);"), "Parenthesized pattern");
+        for src in [
+            "[...a,] = b;",
+            "({ ...a, } = b);",
+            "({ x: [...a,] } = b);",
+            "([...a,]) => 1;",
+        ] {
+            assert_eq!(
+                message(src),
+                "Comma is not permitted after the rest element",
+                "{}",
+                src
+            );
+        }
+        assert_eq!(
+            message("({ ...{ a } } = b);"),
+            "Invalid destructuring assignment target"
+        );
+        assert_eq!(
+            message("({ ...[a] } = b);"),
+            "Invalid destructuring assignment target"
+        );
+        // as expressions they're fine, and the rest of an array may nest
+        assert!(parse("x = [...a,]; x = { ...a, }; [x = [...a,]] = b;").is_ok());
+        assert!(parse("({ ...a.b } = c); [...[a]] = b;").is_ok());
+        let error = parse("x;\n{ a, b } = c;").unwrap_err();
+        assert_eq!((error.start.line, error.start.column), (2, 0));
+        assert_eq!(
+            error.message,
+            "A statement can't start with an object pattern; wrap the assignment in parentheses, as in `({ a } = b)`"
+        );
+    }
+}
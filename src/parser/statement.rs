@@ -0,0 +1,1102 @@
+use crate::{
+    context::{CoverErrors, DeclarationKind, Target, TargetKind},
+    error::{ParseErrorKind, ParseResult},
+    node::{
+        BlockStatement, BreakStatement, CatchClause, ContinueStatement, Directive,
+        DoWhileStatement, EmptyStatement, ErrorNode, Expression, ExpressionStatement,
+        ForInStatement, ForInStatementLeft, ForOfStatement, ForStatement, ForStatementInit,
+        Identifier, IfStatement, LabeledStatement, LiteralValue, Pattern, ReturnStatement,
+        SourceLocation, Statement, SwitchCase, SwitchStatement, ThrowStatement, TryStatement,
+        VariableDeclaration, VariableDeclarator, WhileStatement, WithStatement,
+    },
+    token::{Arithmetic, Assign, Token, TokenKind},
+};
+
+use super::{has_octal_escape, Parser};
+
+// what a statement is the body of, which decides whether it may be a
+// function declaration
+#[derive(Clone, Copy, PartialEq)]
+enum BodyOf {
+    If,
+    Label,
+    LoopOrWith,
+}
+
+impl Parser<'_> {
+    pub(super) fn parse_statement(&mut self) -> ParseResult<Statement> {
+        self.nested(|parser| parser.parse_bare_statement())
+    }
+
+    fn parse_bare_statement(&mut self) -> ParseResult<Statement> {
+        match self.peek() {
+            Some(Token::BraceL) => {
+                let (line, column) = self.position();
+                let block = self.parse_block()?;
+                // `{ a } = b` is a block and a stray `=`, though it was most
+                // likely meant to destructure
+                if self.is(&Token::Assign(Assign::Normal)) {
+                    return self.raise(
+                        ParseErrorKind::InvalidTarget,
+                        line,
+                        column,
+                        "A statement can't start with an object pattern; wrap the assignment in parentheses, as in `({ a } = b)`",
+                    );
+                }
+                Ok(Statement::Block(Box::new(block)))
+            }
+            Some(Token::Semi) => {
+                let loc = self.start_loc();
+                self.tokens.bump();
+                Ok(Statement::Empty(Box::new(EmptyStatement::new(
+                    self.finish(loc),
+                ))))
+            }
+            Some(Token::Var) | Some(Token::Const) => {
+                let declaration = self.parse_variable_declaration(false)?;
+                Ok(Statement::VariableDeclaration(Box::new(declaration)))
+            }
+            Some(Token::Let) if self.is_let_declaration() => {
+                let declaration = self.parse_variable_declaration(false)?;
+                Ok(Statement::VariableDeclaration(Box::new(declaration)))
+            }
+            Some(Token::Function) => Ok(Statement::FunctionDeclaration(Box::new(
+                self.parse_function_declaration(false)?,
+            ))),
+            Some(Token::Name(_)) if self.is_async_function() => Ok(Statement::FunctionDeclaration(
+                Box::new(self.parse_function_declaration(false)?),
+            )),
+            Some(Token::Class) => Ok(Statement::ClassDeclaration(Box::new(
+                self.parse_class_declaration(false)?,
+            ))),
+            Some(Token::Return) => self.parse_return_statement(),
+            Some(Token::If) => self.parse_if_statement(),
+            Some(Token::While) => {
+                self.in_target(TargetKind::Loop, |parser| parser.parse_while_statement())
+            }
+            Some(Token::Do) => {
+                self.in_target(TargetKind::Loop, |parser| parser.parse_do_while_statement())
+            }
+            Some(Token::For) => self.in_block(|parser| {
+                parser.in_target(TargetKind::Loop, |parser| parser.parse_for_statement())
+            }),
+            Some(Token::Break) | Some(Token::Continue) => self.parse_break_continue_statement(),
+            Some(Token::Throw) => self.parse_throw_statement(),
+            Some(Token::Try) => self.parse_try_statement(),
+            Some(Token::Switch) => self.in_block(|parser| {
+                parser.in_target(TargetKind::Switch, |parser| parser.parse_switch_statement())
+            }),
+            Some(Token::With) => self.parse_with_statement(),
+            Some(Token::Import) | Some(Token::Export) if !self.is_import_expression() => {
+                let (line, column) = self.position();
+                self.raise(
+                    ParseErrorKind::Misplaced,
+                    line,
+                    column,
+                    "'import' and 'export' may only appear at the top level",
+                )
+            }
+            Some(Token::Name(_)) | Some(Token::Let) if self.peek2() == Some(&Token::Colon) => {
+                self.parse_labeled_statement()
+            }
+            _ => {
+                let loc = self.start_loc();
+                let expression = self.parse_expression()?;
+                self.consume_semicolon()?;
+                Ok(Statement::Expression(Box::new(ExpressionStatement::new(
+                    expression,
+                    self.finish(loc),
+                ))))
+            }
+        }
+    }
+
+    // statements until the closing brace, which is consumed
+    pub(super) fn parse_statement_list(&mut self) -> ParseResult<Vec<Statement>> {
+        let mut body = vec![];
+        while !self.eat(&Token::BraceR) {
+            if self.peek().is_none() {
+                return self.expected(vec![TokenKind::BraceR]);
+            }
+            body.push(self.recover(|parser| parser.parse_statement())?);
+        }
+        Ok(body)
+    }
+
+    // like `parse_statement_list`, but for the body of a scope pushed by the
+    // caller, which is popped once the closing brace is consumed; function
+    // bodies start with a prologue of directives, static blocks don't
+    pub(super) fn parse_scope_statements(
+        &mut self,
+        with_directives: bool,
+    ) -> ParseResult<Vec<Statement>> {
+        self.context.is_directive = with_directives;
+        while !self.eat(&Token::BraceR) {
+            if self.peek().is_none() {
+                return self.expected(vec![TokenKind::BraceR]);
+            }
+            let statement = self.recover(|parser| parser.parse_statement())?;
+            let statement = self.mark_directive(statement)?;
+            self.context.scope_mut().statements.push(statement);
+        }
+        Ok(self.context.pop_scope().statements)
+    }
+
+    // in recovery mode, an error in a statement is recorded and the tokens
+    // up to where the next statement probably starts are skipped, leaving
+    // an error node in place of the statement; otherwise the error is
+    // returned as it is
+    pub(super) fn recover(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> ParseResult<Statement>,
+    ) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        let start = self.tokens.index();
+        let depth = self.context.depth();
+        let checkpoints = self.tokens.checkpoints();
+        let diagnostic = match parse(self) {
+            Ok(statement) if self.tokens.index() > start => return Ok(statement),
+            // nothing but an error node, which has been recorded already
+            Ok(_) => self.errors.len() - 1,
+            Err(error) if self.context.recover => {
+                self.context.truncate(depth);
+                self.tokens.truncate_checkpoints(checkpoints);
+                self.context.is_directive = false;
+                self.context.is_pattern = false;
+                self.context.no_in = false;
+                self.context.cover_errors = CoverErrors::default();
+                self.record(error)
+            }
+            Err(error) => return Err(error),
+        };
+        self.synchronize(start);
+        let loc = self.finish(loc);
+        Ok(Statement::Error(Box::new(ErrorNode::new(diagnostic, loc))))
+    }
+
+    // skips past the next `;`, or up to a `}` or a keyword that starts a
+    // statement, always moving on from the token a failed statement started
+    // at so that recovery can't get stuck on it
+    fn synchronize(&mut self, start: usize) {
+        if self.tokens.index() == start {
+            self.tokens.bump();
+        }
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Semi => {
+                    self.tokens.bump();
+                    return;
+                }
+                Token::BraceR
+                | Token::Var
+                | Token::Let
+                | Token::Const
+                | Token::Function
+                | Token::Class
+                | Token::If
+                | Token::For
+                | Token::While
+                | Token::Do
+                | Token::Return
+                | Token::Break
+                | Token::Continue
+                | Token::Switch
+                | Token::Throw
+                | Token::Try
+                | Token::With
+                | Token::Import
+                | Token::Export => return,
+                _ => {
+                    self.tokens.bump();
+                }
+            }
+        }
+    }
+
+    // in a prologue, a statement of nothing but a string literal is a
+    // directive, and the prologue ends with the first statement that isn't;
+    // only "use strict" written without escapes makes the scope strict,
+    // which is why the directive keeps the raw text
+    pub(super) fn mark_directive(&mut self, statement: Statement) -> ParseResult<Statement> {
+        if !self.context.is_directive {
+            return Ok(statement);
+        }
+        let Statement::Expression(statement) = statement else {
+            self.context.is_directive = false;
+            return Ok(statement);
+        };
+        match statement.expression {
+            // a parenthesized string starts after its statement does
+            Expression::Literal(literal)
+                if matches!(literal.value, LiteralValue::String(_))
+                    && literal.loc.range.0 == statement.loc.range.0 =>
+            {
+                let directive = literal.raw[1..literal.raw.len() - 1].to_string();
+                if directive == "use strict" {
+                    self.check_octal_directives()?;
+                    self.context.scope_mut().is_strict = true;
+                }
+                Ok(Statement::Directive(Box::new(Directive::new(
+                    *literal,
+                    directive,
+                    statement.loc,
+                ))))
+            }
+            expression => {
+                self.context.is_directive = false;
+                Ok(Statement::Expression(Box::new(ExpressionStatement {
+                    expression,
+                    ..*statement
+                })))
+            }
+        }
+    }
+
+    // the directives before a "use strict" were parsed before the scope was
+    // known to be strict, so `'\01'; 'use strict'` is only an error now
+    fn check_octal_directives(&mut self) -> ParseResult<()> {
+        let octal = self
+            .context
+            .scope()
+            .statements
+            .iter()
+            .find_map(|statement| match statement {
+                Statement::Directive(directive) if has_octal_escape(&directive.directive) => {
+                    Some(directive.loc.start)
+                }
+                _ => None,
+            });
+        match octal {
+            Some(start) => self.early(
+                ParseErrorKind::StrictMode,
+                start.line,
+                start.column,
+                "Octal escape sequences are not allowed in strict mode",
+            ),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) fn parse_block(&mut self) -> ParseResult<BlockStatement> {
+        self.in_block(|parser| parser.parse_block_in_place())
+    }
+
+    // a block whose own declarations go with those of the block around it,
+    // as a catch clause's body does with its parameter
+    fn parse_block_in_place(&mut self) -> ParseResult<BlockStatement> {
+        let loc = self.start_loc();
+        self.expect(&Token::BraceL)?;
+        let body = self.parse_statement_list()?;
+        Ok(BlockStatement::new(body, self.finish(loc)))
+    }
+
+    // `let` declares only when what follows can be bound, so `let [a] = b`
+    // is a declaration while `let(x)`, `let.a` and `let = 1` use a variable
+    // named `let`, which sloppy code may have
+    fn is_let_declaration(&self) -> bool {
+        matches!(
+            self.peek2(),
+            Some(Token::Name(_))
+                | Some(Token::Let)
+                | Some(Token::Of)
+                | Some(Token::Undefined)
+                | Some(Token::BracketL)
+                | Some(Token::BraceL)
+        )
+    }
+
+    // `in_for_head` allows `const` without initializer, as in `for (const x of xs)`,
+    // and leaves the semicolon to the `for`
+    pub(super) fn parse_variable_declaration(
+        &mut self,
+        in_for_head: bool,
+    ) -> ParseResult<VariableDeclaration> {
+        let loc = self.start_loc();
+        let kind = match self.bump()? {
+            Token::Var => "var",
+            Token::Let => "let",
+            _ => "const",
+        };
+
+        let mut declarations = vec![];
+        loop {
+            let declarator_loc = self.start_loc();
+            let (line, column) = self.position();
+            let id = self.parse_binding_target()?;
+            let declaration_kind = if kind == "var" {
+                DeclarationKind::Var
+            } else {
+                DeclarationKind::Lexical
+            };
+            for name in id.bound_names() {
+                if name.name == "let" && kind != "var" {
+                    let start = name.loc.start;
+                    return self.raise(
+                        ParseErrorKind::ReservedWord,
+                        start.line,
+                        start.column,
+                        "let is disallowed as a lexically bound name",
+                    );
+                }
+                self.check_eval_or_arguments(name, self.context.scope().is_strict, "Binding")?;
+                self.declare(name, declaration_kind)?;
+            }
+            let init = if self.eat(&Token::Assign(Assign::Normal)) {
+                Some(self.parse_assignment_expression()?)
+            } else {
+                let in_for_in_or_of =
+                    in_for_head && matches!(self.peek(), Some(Token::Of) | Some(Token::In));
+                // a pattern has nothing to destructure without a value
+                let message = match id {
+                    _ if in_for_in_or_of => None,
+                    _ if kind == "const" => Some("Missing initializer in const declaration"),
+                    Pattern::Identifier(_) => None,
+                    _ => Some("Complex binding patterns require an initialization value"),
+                };
+                if let Some(message) = message {
+                    return self.raise(ParseErrorKind::InvalidSyntax, line, column, message);
+                }
+                None
+            };
+            declarations.push(VariableDeclarator::new(
+                id,
+                init,
+                self.finish(declarator_loc),
+            ));
+
+            if !self.eat(&Token::Comma) {
+                break;
+            }
+        }
+
+        if !in_for_head {
+            self.consume_semicolon()?;
+        }
+        Ok(VariableDeclaration::new(
+            declarations,
+            kind,
+            self.finish(loc),
+        ))
+    }
+
+    fn parse_return_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        self.expect(&Token::Return)?;
+        let scope = self.context.scope();
+        let is_allowed = scope.is_function
+            || (self.context.allow_return_outside_function && !scope.is_static_block);
+        if !is_allowed {
+            return self.raise(
+                ParseErrorKind::MisplacedReturn,
+                line,
+                column,
+                "Illegal return statement",
+            );
+        }
+        let argument = if self.is(&Token::Semi) || self.can_insert_semicolon() {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+        self.consume_semicolon()?;
+        Ok(Statement::Return(Box::new(ReturnStatement::new(
+            argument,
+            self.finish(loc),
+        ))))
+    }
+
+    // the declaration before the `in` or `of` of a for head binds one name
+    // and, but for a sloppy `for (var a = b in c)` as Annex B allows, has
+    // no initializer
+    fn check_for_in_declaration(
+        &mut self,
+        declaration: &VariableDeclaration,
+        is_of: bool,
+    ) -> ParseResult<()> {
+        let start = declaration.loc.start;
+        let loop_kind = if is_of { "for-of" } else { "for-in" };
+        let [declarator] = declaration.declarations.as_slice() else {
+            let message = format!(
+                "Invalid left-hand side in {} loop: Must have a single binding.",
+                loop_kind
+            );
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                start.line,
+                start.column,
+                &message,
+            );
+        };
+        let allowed = !is_of
+            && !self.context.scope().is_strict
+            && declaration.kind == "var"
+            && matches!(declarator.id, Pattern::Identifier(_))
+            && self.context.ecma_version >= 2017;
+        if declarator.init.is_some() && !allowed {
+            let message = format!(
+                "{} loop variable declaration may not have an initializer",
+                loop_kind
+            );
+            return self.early(
+                ParseErrorKind::InvalidSyntax,
+                start.line,
+                start.column,
+                &message,
+            );
+        }
+        Ok(())
+    }
+
+    // the body of an `if`, a loop, a `with` or a label, which can't be a
+    // function declaration but for what Annex B allows in sloppy code: a
+    // plain function as the body of an `if` or a label, though not one with
+    // labels of its own as the body of anything but a label
+    fn parse_substatement(&mut self, body_of: BodyOf) -> ParseResult<Statement> {
+        let (line, column) = self.position();
+        let offset = self.labels_ahead();
+        let is_special = match self.peek_nth(offset) {
+            Some(Token::Function) => {
+                Some(self.peek_nth(offset + 1) == Some(&Token::Arithmetic(Arithmetic::Multiple)))
+            }
+            Some(Token::Name(name)) if name == "async" => self
+                .tokens
+                .peek_nth(offset + 1)
+                .filter(|lexeme| lexeme.token == Token::Function && !lexeme.newline_before)
+                .map(|_| true),
+            _ => None,
+        };
+        let error = match is_special {
+            // the label's own body is looked at once the label is read
+            Some(_) if offset > 0 && body_of == BodyOf::Label => None,
+            Some(true) if offset == 0 => Some((
+                ParseErrorKind::Misplaced,
+                "Generators and async functions can only be declared at top level or inside a block",
+            )),
+            Some(false) if offset == 0 && body_of != BodyOf::LoopOrWith => {
+                self.context.scope().is_strict.then_some((
+                    ParseErrorKind::StrictMode,
+                    "In strict mode code, functions can only be declared at top level or inside a block",
+                ))
+            }
+            Some(_) => Some((
+                ParseErrorKind::Misplaced,
+                "Functions can only be declared at top level or inside a block",
+            )),
+            None => None,
+        };
+        if let Some((kind, message)) = error {
+            self.early(kind, line, column, message)?;
+        }
+        self.parse_statement()
+    }
+
+    // how many tokens the labels starting here take, as in `a: b: c;`
+    fn labels_ahead(&mut self) -> usize {
+        let mut offset = 0;
+        while matches!(
+            self.peek_nth(offset),
+            Some(Token::Name(_)) | Some(Token::Let)
+        ) && self.peek_nth(offset + 1) == Some(&Token::Colon)
+        {
+            offset += 2;
+        }
+        offset
+    }
+
+    fn parse_if_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        self.expect(&Token::If)?;
+        let test = self.parse_paren_expression()?;
+        let consequent = self.parse_substatement(BodyOf::If)?;
+        let alternate = if self.eat(&Token::Else) {
+            Some(self.parse_substatement(BodyOf::If)?)
+        } else {
+            None
+        };
+        Ok(Statement::If(Box::new(IfStatement::new(
+            test,
+            consequent,
+            alternate,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_while_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        self.expect(&Token::While)?;
+        let test = self.parse_paren_expression()?;
+        let body = self.parse_substatement(BodyOf::LoopOrWith)?;
+        Ok(Statement::While(Box::new(WhileStatement::new(
+            test,
+            body,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_with_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        self.expect(&Token::With)?;
+        if self.context.scope().is_strict {
+            return self.raise(
+                ParseErrorKind::StrictMode,
+                line,
+                column,
+                "Strict mode code may not include a with statement",
+            );
+        }
+        let object = self.parse_paren_expression()?;
+        let body = self.parse_substatement(BodyOf::LoopOrWith)?;
+        Ok(Statement::With(Box::new(WithStatement::new(
+            object,
+            body,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_do_while_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        self.expect(&Token::Do)?;
+        let body = self.parse_substatement(BodyOf::LoopOrWith)?;
+        self.expect(&Token::While)?;
+        let test = self.parse_paren_expression()?;
+        // a semicolon is always inserted after do-while
+        self.eat(&Token::Semi);
+        Ok(Statement::DoWhile(Box::new(DoWhileStatement::new(
+            test,
+            body,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_for_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        self.expect(&Token::For)?;
+        let (line, column) = self.position();
+        let is_await = self.context.scope().is_async && self.is_name("await");
+        if is_await {
+            if !self.context.scope().is_function {
+                let (line, column) = self.position();
+                self.check_ecma_version(2022, "Top-level 'for await' loops", line, column)?;
+            }
+            self.tokens.bump();
+        }
+        self.expect(&Token::ParenL)?;
+
+        let (init_line, init_column) = self.position();
+        // `for (async of` would be ambiguous with an async arrow taking `of`,
+        // so it can only start a `for await`
+        let starts_async_of = self.is_name("async") && self.peek2() == Some(&Token::Of);
+        self.context.no_in = true;
+        // an expression before `in` or `of` is a pattern, so what only a
+        // pattern allows is reported once it is known to be neither
+        let (init, errors) = self.collect_cover_errors(|parser| {
+            Ok(match parser.peek() {
+                Some(Token::Semi) => None,
+                Some(Token::Var) | Some(Token::Const) => Some(
+                    ForStatementInit::VariableDeclaration(parser.parse_variable_declaration(true)?),
+                ),
+                Some(Token::Let) if parser.is_let_declaration() => Some(
+                    ForStatementInit::VariableDeclaration(parser.parse_variable_declaration(true)?),
+                ),
+                _ => Some(ForStatementInit::Expression(
+                    parser.parse_cover_expression()?,
+                )),
+            })
+        });
+        let init = init?;
+        self.context.no_in = false;
+        if !self.is(&Token::Of) && !self.is(&Token::In) {
+            self.check_pattern_errors(&errors)?;
+        }
+
+        if let Some(init) = init {
+            let is_of = self.is(&Token::Of);
+            if is_await && !is_of {
+                return self.raise(
+                    ParseErrorKind::InvalidSyntax,
+                    line,
+                    column,
+                    "'for await' loops must use 'of'",
+                );
+            }
+            if is_of && starts_async_of && !is_await {
+                return self.raise(
+                    ParseErrorKind::InvalidTarget,
+                    init_line,
+                    init_column,
+                    "The left-hand side of a for-of loop may not be 'async'",
+                );
+            }
+            if is_of || self.is(&Token::In) {
+                self.tokens.bump();
+                let left = match init {
+                    ForStatementInit::VariableDeclaration(declaration) => {
+                        self.check_for_in_declaration(&declaration, is_of)?;
+                        ForInStatementLeft::VariableDeclaration(declaration)
+                    }
+                    ForStatementInit::Expression(expression) => {
+                        let message = if is_of {
+                            "Invalid left-hand side in for-of loop"
+                        } else {
+                            "Invalid left-hand side in for-in loop"
+                        };
+                        match expression.into_pattern() {
+                            Ok(pattern) if !pattern.has_default() => {
+                                self.check_destructuring_errors(&errors)?;
+                                self.check_assignment_names(&pattern)?;
+                                ForInStatementLeft::Pattern(pattern)
+                            }
+                            Ok(_) => {
+                                return self.raise(
+                                    ParseErrorKind::InvalidTarget,
+                                    init_line,
+                                    init_column,
+                                    message,
+                                )
+                            }
+                            Err(invalid) => {
+                                return self.raise_invalid_target(
+                                    (init_line, init_column),
+                                    &invalid,
+                                    message,
+                                )
+                            }
+                        }
+                    }
+                };
+                // `for (x of a, b)` is a syntax error where `for (x in a, b)` is not
+                let right = if is_of {
+                    self.parse_assignment_expression()?
+                } else {
+                    self.parse_expression()?
+                };
+                self.expect(&Token::ParenR)?;
+                let body = self.parse_substatement(BodyOf::LoopOrWith)?;
+                return if is_of {
+                    Ok(Statement::ForOf(Box::new(ForOfStatement::new(
+                        left,
+                        right,
+                        body,
+                        is_await,
+                        self.finish(loc),
+                    ))))
+                } else {
+                    Ok(Statement::ForIn(Box::new(ForInStatement::new(
+                        left,
+                        right,
+                        body,
+                        self.finish(loc),
+                    ))))
+                };
+            }
+            return self.parse_for_rest(Some(init), loc);
+        }
+        if is_await {
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                line,
+                column,
+                "'for await' loops must use 'of'",
+            );
+        }
+        self.parse_for_rest(None, loc)
+    }
+
+    // the `; test; update) body` part of a plain for statement
+    fn parse_for_rest(
+        &mut self,
+        init: Option<ForStatementInit>,
+        loc: SourceLocation,
+    ) -> ParseResult<Statement> {
+        self.expect(&Token::Semi)?;
+        let test = if self.is(&Token::Semi) {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+        self.expect(&Token::Semi)?;
+        let update = if self.is(&Token::ParenR) {
+            None
+        } else {
+            Some(self.parse_expression()?)
+        };
+        self.expect(&Token::ParenR)?;
+        let body = self.parse_substatement(BodyOf::LoopOrWith)?;
+        Ok(Statement::For(Box::new(ForStatement::new(
+            init,
+            test,
+            update,
+            body,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_break_continue_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        let is_break = self.bump()? == Token::Break;
+        let label = if matches!(self.peek(), Some(Token::Name(_)) | Some(Token::Let))
+            && !self.has_newline_before()
+        {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
+        self.check_jump_target(is_break, label.as_ref(), line, column)?;
+        self.consume_semicolon()?;
+        if is_break {
+            Ok(Statement::Break(Box::new(BreakStatement::new(
+                label,
+                self.finish(loc),
+            ))))
+        } else {
+            Ok(Statement::Continue(Box::new(ContinueStatement::new(
+                label,
+                self.finish(loc),
+            ))))
+        }
+    }
+
+    fn parse_throw_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        self.expect(&Token::Throw)?;
+        if self.has_newline_before() {
+            let (line, column) = self.position();
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                line,
+                column,
+                "Illegal newline after throw",
+            );
+        }
+        let argument = self.parse_expression()?;
+        self.consume_semicolon()?;
+        Ok(Statement::Throw(Box::new(ThrowStatement::new(
+            argument,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_try_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        let (line, column) = self.position();
+        self.expect(&Token::Try)?;
+        let block = self.parse_block()?;
+
+        let handler = if self.is(&Token::Catch) {
+            Some(self.in_block(|parser| parser.parse_catch_clause())?)
+        } else {
+            None
+        };
+
+        let finalizer = if self.eat(&Token::Finally) {
+            Some(self.parse_block()?)
+        } else {
+            None
+        };
+
+        if handler.is_none() && finalizer.is_none() {
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                line,
+                column,
+                "Missing catch or finally after try",
+            );
+        }
+
+        Ok(Statement::Try(Box::new(TryStatement::new(
+            block,
+            handler,
+            finalizer,
+            self.finish(loc),
+        ))))
+    }
+
+    fn parse_catch_clause(&mut self) -> ParseResult<CatchClause> {
+        let loc = self.start_loc();
+        self.expect(&Token::Catch)?;
+        let param = if self.eat(&Token::ParenL) {
+            let param = self.parse_binding_target()?;
+            // the names of a pattern are bound like `let`, clashing with each
+            // other and with a `var` of the same name in the body
+            let kind = match param {
+                Pattern::Identifier(_) => DeclarationKind::Param,
+                _ => DeclarationKind::Lexical,
+            };
+            for name in param.bound_names() {
+                self.check_eval_or_arguments(name, self.context.scope().is_strict, "Binding")?;
+                self.declare(name, kind)?;
+            }
+            self.expect(&Token::ParenR)?;
+            Some(param)
+        } else {
+            None
+        };
+        let body = self.parse_block_in_place()?;
+        Ok(CatchClause::new(param, body, self.finish(loc)))
+    }
+
+    fn parse_switch_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        self.expect(&Token::Switch)?;
+        let discriminant = self.parse_paren_expression()?;
+        self.expect(&Token::BraceL)?;
+
+        let mut cases = vec![];
+        let mut has_default = false;
+        while !self.eat(&Token::BraceR) {
+            let case_loc = self.start_loc();
+            let (line, column) = self.position();
+            let test = match self.peek() {
+                Some(Token::Case) => {
+                    self.tokens.bump();
+                    Some(self.parse_expression()?)
+                }
+                Some(Token::Default) => {
+                    if has_default {
+                        return self.raise(
+                            ParseErrorKind::InvalidSyntax,
+                            line,
+                            column,
+                            "Multiple default clauses",
+                        );
+                    }
+                    has_default = true;
+                    self.tokens.bump();
+                    None
+                }
+                _ => {
+                    return self.expected(vec![
+                        TokenKind::Case,
+                        TokenKind::Default,
+                        TokenKind::BraceR,
+                    ]);
+                }
+            };
+            self.expect(&Token::Colon)?;
+
+            let mut consequent = vec![];
+            while !matches!(
+                self.peek(),
+                Some(Token::Case) | Some(Token::Default) | Some(Token::BraceR)
+            ) {
+                if self.peek().is_none() {
+                    return self.expected(vec![
+                        TokenKind::Case,
+                        TokenKind::Default,
+                        TokenKind::BraceR,
+                    ]);
+                }
+                consequent.push(self.recover(|parser| parser.parse_statement())?);
+            }
+            cases.push(SwitchCase::new(test, consequent, self.finish(case_loc)));
+        }
+
+        Ok(Statement::Switch(Box::new(SwitchStatement::new(
+            discriminant,
+            cases,
+            self.finish(loc),
+        ))))
+    }
+
+    // `break` needs a loop or switch around it and `continue` a loop, or a
+    // label on one, within the same function; labels on other statements
+    // can only be broken out of
+    fn check_jump_target(
+        &self,
+        is_break: bool,
+        label: Option<&Identifier>,
+        line: usize,
+        column: usize,
+    ) -> ParseResult<()> {
+        let targets = &self.context.scope().targets;
+        let nearest = match targets.last() {
+            Some(target) => format!(" (the nearest enclosing is the {})", target.describe()),
+            None => String::new(),
+        };
+        let Some(label) = label else {
+            let found = targets.iter().any(|target| match target.kind {
+                TargetKind::Loop => true,
+                TargetKind::Switch => is_break,
+                TargetKind::Label { .. } => false,
+            });
+            if found {
+                return Ok(());
+            }
+            let message = if is_break {
+                format!("Illegal break statement{}", nearest)
+            } else {
+                format!(
+                    "Illegal continue statement: no surrounding iteration statement{}",
+                    nearest
+                )
+            };
+            return self.raise(ParseErrorKind::Misplaced, line, column, &message);
+        };
+
+        let start = label.loc.start;
+        let target = targets.iter().rev().find(
+            |target| matches!(&target.kind, TargetKind::Label { name, .. } if *name == label.name),
+        );
+        let Some(target) = target else {
+            let message = format!("Undefined label '{}'{}", label.name, nearest);
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                start.line,
+                start.column,
+                &message,
+            );
+        };
+        if !is_break && matches!(target.kind, TargetKind::Label { is_loop: false, .. }) {
+            let message = format!(
+                "Illegal continue statement: '{}' does not denote an iteration statement (it labels the {})",
+                label.name,
+                target.describe()
+            );
+            return self.raise(
+                ParseErrorKind::Misplaced,
+                start.line,
+                start.column,
+                &message,
+            );
+        }
+        Ok(())
+    }
+
+    fn parse_labeled_statement(&mut self) -> ParseResult<Statement> {
+        let loc = self.start_loc();
+        let label = self.parse_identifier()?;
+        self.expect(&Token::Colon)?;
+        let is_duplicate = self.context.scope().targets.iter().any(
+            |target| matches!(&target.kind, TargetKind::Label { name, .. } if *name == label.name),
+        );
+        if is_duplicate {
+            let start = label.loc.start;
+            let message = format!("Label '{}' has already been declared", label.name);
+            return self.raise(
+                ParseErrorKind::InvalidSyntax,
+                start.line,
+                start.column,
+                &message,
+            );
+        }
+        // in `a: b: while (x) {}` both labels are on the loop
+        let offset = self.labels_ahead();
+        let is_loop = matches!(
+            self.peek_nth(offset),
+            Some(Token::For) | Some(Token::While) | Some(Token::Do)
+        );
+        let kind = TargetKind::Label {
+            name: label.name.clone(),
+            is_loop,
+        };
+        let start = label.loc.start;
+        self.context.scope_mut().targets.push(Target {
+            kind,
+            line: start.line,
+            column: start.column,
+        });
+        let body = self.parse_substatement(BodyOf::Label)?;
+        self.context.scope_mut().targets.pop();
+        Ok(Statement::Labeled(Box::new(LabeledStatement::new(
+            label,
+            body,
+            self.finish(loc),
+        ))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ParseErrorKind, parser::parse, sexpr::to_sexpr, test_support::error_at};
+
+    fn sexpr(src: &str) -> String {
+        to_sexpr(&parse(src).unwrap())
+    }
+
+    // each as acorn parses it
+    #[test]
+    fn an_in_at_the_top_of_a_for_init_starts_a_for_in() {
+        assert_eq!(sexpr("for (a in b) ;"), "(Program\n  (ForIn a b (Empty)))");
+        assert_eq!(
+            sexpr("for (var a = (x in y);;);"),
+            "(Program\n  (For (VariableDeclaration var (VariableDeclarator a (Binary in x y))) () () (Empty)))"
+        );
+        // brackets of any kind, and the middle of a conditional, allow it again
+        assert_eq!(
+            sexpr("for (a ? b in c : d;;);"),
+            "(Program\n  (For (Conditional a (Binary in b c) d) () () (Empty)))"
+        );
+        assert_eq!(
+            sexpr("for ([a in b], f(c in d);;);"),
+            "(Program\n  (For (Sequence (Array (Binary in a b)) (Call f (Binary in c d))) () () (Empty)))"
+        );
+        assert_eq!(
+            sexpr("for (x = () => { a in b };;);"),
+            "(Program\n  (For (Assignment = x (ArrowFunction (params) (Block (Expression (Binary in a b))))) () () (Empty)))"
+        );
+        assert_eq!(
+            error_at("for (a in b;;);"),
+            (ParseErrorKind::UnexpectedToken, 1, 11)
+        );
+        // `a = b` is the left of a for-in, which it can't be
+        for src in [
+            "for (a = b in c in d;;);",
+            "for (a = (b in c) in d;;);",
+            "for (x = () => a in b;;);",
+            "for (x = y => a in b;;);",
+            "for (x = async () => a in b;;);",
+        ] {
+            assert_eq!(
+                error_at(src),
+                (ParseErrorKind::InvalidTarget, 1, 5),
+                "{}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn statement_bodies_are_not_function_declarations() {
+        let invalid = [
+            ("while (a) function f() {}", 10),
+            ("for (;;) function f() {}", 9),
+            ("for (a of b) function f() {}", 13),
+            ("do function f() {} while (a)", 3),
+            ("with (a) function f() {}", 9),
+            ("while (a) l: function f() {}", 10),
+            ("if (a) l: function f() {}", 7),
+            ("label: function* g() {}", 7),
+            ("label: async function g() {}", 7),
+            ("a: b: function* g() {}", 6),
+            ("if (a) function* g() {}", 7),
+        ];
+        for (src, column) in invalid {
+            assert_eq!(
+                error_at(src),
+                (ParseErrorKind::Misplaced, 1, column),
+                "{}",
+                src
+            );
+        }
+        // Annex B still lets sloppy code have a plain one under an `if` or
+        // a label
+        assert!(parse("if (a) function f() {} else function g() {}").is_ok());
+        assert!(parse("a: b: function f() {}").is_ok());
+        assert!(parse("label: async\nfunction f() {}").is_ok());
+        assert!(parse("while (a) { function f() {} function* g() {} }").is_ok());
+    }
+
+    #[test]
+    fn async_can_only_start_a_for_of_head_under_await() {
+        assert_eq!(
+            error_at("for (async of x);"),
+            (ParseErrorKind::InvalidTarget, 1, 5)
+        );
+        assert!(parse("for (async of => x;;);").is_ok());
+        assert!(parse("async function f() { for await (async of x); }").is_ok());
+        assert!(parse("for ((async) of x); for (async.a of x); for (async in x);").is_ok());
+    }
+}
@@ -0,0 +1,97 @@
+// Lookups over a whole program, for tools such as editors that start from a
+// node type or a cursor position rather than from the root.
+use crate::{
+    node::{Node, Program, SourceLocation},
+    node_ref::{FromNodeRef, NodeRef},
+};
+
+impl Program {
+    // every node of one type, in the order their starts appear in the source,
+    // so an outer call comes before the calls in its arguments
+    pub fn find_all<T: FromNodeRef>(&self) -> Vec<&T> {
+        let mut found = vec![];
+        let mut stack = vec![NodeRef::Program(self)];
+        while let Some(node) = stack.pop() {
+            if let Some(node) = node.downcast::<T>() {
+                found.push(node);
+            }
+            stack.extend(node.children().into_iter().rev());
+        }
+        found
+    }
+
+    // the innermost node containing a byte offset, where a node contains both
+    // its start and its end; at the boundary of two siblings, such as the tag
+    // and the template of ``f`x` ``, the earlier one wins
+    pub fn node_at_offset(&self, offset: usize) -> Option<NodeRef<'_>> {
+        self.innermost(|loc| loc.range.0 <= offset && offset <= loc.range.1)
+    }
+
+    // as `node_at_offset`, with the line starting at 1 and the column at 0
+    pub fn node_at(&self, line: usize, column: usize) -> Option<NodeRef<'_>> {
+        let position = (line, column);
+        self.innermost(|loc| {
+            (loc.start.line, loc.start.column) <= position
+                && position <= (loc.end.line, loc.end.column)
+        })
+    }
+
+    fn innermost(&self, contains: impl Fn(&SourceLocation) -> bool) -> Option<NodeRef<'_>> {
+        let mut node = NodeRef::Program(self);
+        if !contains(node.loc()) {
+            return None;
+        }
+        while let Some(child) = node
+            .children()
+            .into_iter()
+            .find(|child| contains(child.loc()))
+        {
+            node = child;
+        }
+        Some(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::{CallExpression, Expression, Identifier},
+        node_ref::NodeRef,
+        parser::parse,
+    };
+
+    #[test]
+    fn find_all_gives_nodes_in_source_order() {
+        let program = parse("f(g(1)); x = () => h();").unwrap();
+        let callees: Vec<&str> = program
+            .find_all::<CallExpression>()
+            .iter()
+            .map(|call| match &call.callee {
+                Expression::Identifier(callee) => callee.name.as_str(),
+                _ => panic!("expected an identifier"),
+            })
+            .collect();
+        assert_eq!(callees, ["f", "g", "h"]);
+        assert_eq!(program.find_all::<Identifier>().len(), 4);
+    }
+
+    #[test]
+    fn node_at_gives_the_innermost_node() {
+        let program = parse("a.b(c);\nfoo + bar;\nf`x`;").unwrap();
+        let name = |node: Option<NodeRef>| match node {
+            Some(NodeRef::Identifier(identifier)) => identifier.name.to_string(),
+            Some(node) => node.node_type().to_string(),
+            None => "none".to_string(),
+        };
+        assert_eq!(name(program.node_at_offset(4)), "c");
+        assert_eq!(name(program.node_at_offset(1)), "a");
+        assert_eq!(name(program.node_at_offset(2)), "b");
+        assert_eq!(name(program.node_at_offset(6)), "CallExpression");
+        // `f` ends where the template starts, and the earlier sibling wins
+        assert_eq!(name(program.node_at(3, 1)), "f");
+        assert_eq!(name(program.node_at(3, 2)), "TemplateElement");
+        assert_eq!(name(program.node_at(2, 7)), "bar");
+        assert_eq!(name(program.node_at(2, 4)), "BinaryExpression");
+        assert_eq!(name(program.node_at_offset(100)), "none");
+    }
+}
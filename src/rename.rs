@@ -0,0 +1,413 @@
+// Renaming a binding along with every reference to it, the refactoring
+// built on the scope analysis.
+//
+// The identifiers to rename are told apart by where they are in the source,
+// which is the same for both halves of a shorthand: `{ a }` becomes
+// `{ a: b }` and `import { a }` becomes `import { a as b }`, so that the
+// property and the imported name stay as they were. A rename that would
+// change what any name refers to is refused, as is one of a binding other
+// modules or a direct `eval` may know by its name, or one used inside a
+// `with` body, where the name may be a property of the object instead.
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    analyze::{analyze, Analysis, BindingId, BindingKind, ReferenceId, ScopeId},
+    node::{
+        AssignmentProperty, ExportSpecifier, Expression, Identifier, ImportSpecifier, Node,
+        Pattern, Program, Property, SourceLocation, WithStatement,
+    },
+    token::Token,
+    tokenize,
+    visit::{self, Visitor},
+    visit_mut::{self, VisitorMut},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenameError {
+    // not an identifier written without escapes, or a name reserved in
+    // some context
+    InvalidName,
+    // exported from the module, `arguments`, which nothing declares, or
+    // used inside a `with` body
+    NotRenamable,
+    // a direct `eval` in sight of the binding might use it by name
+    SeenByEval,
+    // a binding of the new name the binding or one of its references would
+    // clash with or be shadowed by
+    Conflict(BindingId),
+    // a reference to another binding of the new name, or to a global, that
+    // would refer to the renamed binding instead
+    Captured(ReferenceId),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RenameError::InvalidName => write!(f, "The new name isn't a valid identifier"),
+            RenameError::NotRenamable => write!(f, "The binding can't be renamed"),
+            RenameError::SeenByEval => write!(f, "A direct eval may use the binding by name"),
+            RenameError::Conflict(_) => write!(f, "The new name is already declared"),
+            RenameError::Captured(_) => write!(f, "A reference would refer to the renamed binding"),
+        }
+    }
+}
+
+impl std::error::Error for RenameError {}
+
+// names that lex as identifiers but can't be bound everywhere
+const RESERVED: [&str; 13] = [
+    "arguments",
+    "await",
+    "enum",
+    "eval",
+    "implements",
+    "interface",
+    "let",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "static",
+    "yield",
+];
+
+// `binding` is one of the bindings `analyze` finds in the program
+pub fn rename_binding(
+    program: &mut Program,
+    binding: BindingId,
+    new_name: &str,
+) -> Result<(), RenameError> {
+    let analysis = analyze(program);
+    check(program, &analysis, binding, new_name)?;
+    let declaration = analysis.binding(binding);
+    let mut ranges: HashSet<(usize, usize)> = declaration
+        .redeclarations
+        .iter()
+        .chain([&declaration.loc])
+        .map(|loc| loc.range)
+        .collect();
+    ranges.extend(
+        analysis
+            .references_to(binding)
+            .iter()
+            .map(|reference| reference.loc.range),
+    );
+    Renamer {
+        ranges,
+        name: new_name,
+    }
+    .visit_program(program);
+    Ok(())
+}
+
+fn check(
+    program: &Program,
+    analysis: &Analysis,
+    id: BindingId,
+    new_name: &str,
+) -> Result<(), RenameError> {
+    let is_identifier = matches!(
+        tokenize(new_name).as_deref(),
+        Ok([lexeme]) if matches!(lexeme.token, Token::Name(_)) && lexeme.text(new_name) == new_name
+    );
+    if !is_identifier || new_name.contains('\\') || RESERVED.contains(&new_name) {
+        return Err(RenameError::InvalidName);
+    }
+    let binding = analysis.binding(id);
+    if binding.is_exported || binding.kind == BindingKind::Arguments {
+        return Err(RenameError::NotRenamable);
+    }
+    if binding.name == new_name {
+        return Ok(());
+    }
+    // which object a name inside a `with` body is looked up on is only
+    // known when it runs
+    let mut with_bodies = WithBodies(vec![]);
+    with_bodies.visit_program(program);
+    let in_with_body = |loc: &SourceLocation| {
+        with_bodies
+            .0
+            .iter()
+            .any(|&(start, end)| start <= loc.range.0 && loc.range.1 <= end)
+    };
+    if binding
+        .redeclarations
+        .iter()
+        .chain([&binding.loc])
+        .any(in_with_body)
+        || analysis
+            .references_to(id)
+            .iter()
+            .any(|reference| in_with_body(&reference.loc))
+    {
+        return Err(RenameError::NotRenamable);
+    }
+    let within = |scope: ScopeId| {
+        let mut scope = Some(scope);
+        while let Some(id) = scope {
+            if id == binding.scope {
+                return true;
+            }
+            scope = analysis.scope(id).parent;
+        }
+        false
+    };
+    if (0..analysis.scopes.len() as u32)
+        .map(ScopeId)
+        .any(|scope| analysis.scope(scope).calls_eval && within(scope))
+    {
+        return Err(RenameError::SeenByEval);
+    }
+
+    // the new name has to be free from wherever the binding is written or
+    // referred to up to its scope; a `var` is declared where it's written,
+    // which may be in a block inside that scope
+    let mut from: Vec<ScopeId> = analysis
+        .references_to(id)
+        .iter()
+        .map(|reference| reference.scope)
+        .collect();
+    if binding.kind == BindingKind::Var {
+        from.extend(
+            binding
+                .redeclarations
+                .iter()
+                .chain([&binding.loc])
+                .map(|loc| innermost_scope(analysis, loc)),
+        );
+    }
+    from.push(binding.scope);
+    for scope in from {
+        let mut scope = Some(scope);
+        while let Some(current) = scope {
+            if let Some(other) = declared_in(analysis, current, new_name) {
+                return Err(RenameError::Conflict(other));
+            }
+            if current == binding.scope {
+                break;
+            }
+            scope = analysis.scope(current).parent;
+        }
+    }
+
+    // and names already written as the new one have to keep referring to
+    // what they do, rather than finding the binding first
+    for (index, reference) in analysis.references.iter().enumerate() {
+        if reference.name != new_name {
+            continue;
+        }
+        let mut scope = Some(reference.scope);
+        while let Some(current) = scope {
+            if current == binding.scope {
+                return Err(RenameError::Captured(ReferenceId(index as u32)));
+            }
+            if declared_in(analysis, current, new_name).is_some() {
+                break;
+            }
+            scope = analysis.scope(current).parent;
+        }
+    }
+    Ok(())
+}
+
+fn declared_in(analysis: &Analysis, scope: ScopeId, name: &str) -> Option<BindingId> {
+    analysis
+        .scope(scope)
+        .bindings
+        .iter()
+        .copied()
+        .find(|&binding| analysis.binding(binding).name == name)
+}
+
+// scopes are listed in the order they open, so the last one around a
+// location is the innermost
+fn innermost_scope(analysis: &Analysis, loc: &SourceLocation) -> ScopeId {
+    let index = analysis
+        .scopes
+        .iter()
+        .rposition(|scope| scope.loc.range.0 <= loc.range.0 && loc.range.1 <= scope.loc.range.1)
+        .unwrap_or(0);
+    ScopeId(index as u32)
+}
+
+// the ranges of the bodies of `with` statements
+struct WithBodies(Vec<(usize, usize)>);
+
+impl<'ast> Visitor<'ast> for WithBodies {
+    fn visit_with_statement(&mut self, node: &'ast WithStatement) {
+        self.0.push(node.body.loc().range);
+        visit::walk_with_statement(self, node);
+    }
+}
+
+struct Renamer<'a> {
+    ranges: HashSet<(usize, usize)>,
+    name: &'a str,
+}
+
+impl Renamer<'_> {
+    fn renames(&self, loc: &SourceLocation) -> bool {
+        self.ranges.contains(&loc.range)
+    }
+}
+
+impl VisitorMut for Renamer<'_> {
+    fn visit_identifier(&mut self, node: &mut Identifier) {
+        if self.renames(&node.loc) {
+            node.name = self.name.into();
+        }
+    }
+
+    // the key of a shorthand is the same identifier as its value, and keeps
+    // the old name
+    fn visit_property(&mut self, node: &mut Property) {
+        match &node.value {
+            Expression::Identifier(value) if node.shorthand && self.renames(&value.loc) => {
+                node.shorthand = false;
+                self.visit_expression(&mut node.value);
+            }
+            _ => visit_mut::walk_property(self, node),
+        }
+    }
+
+    fn visit_assignment_property(&mut self, node: &mut AssignmentProperty) {
+        let value = match &node.value {
+            Pattern::Identifier(value) => Some(value),
+            Pattern::Assignment(value) => match &value.left {
+                Pattern::Identifier(left) => Some(left),
+                _ => None,
+            },
+            _ => None,
+        };
+        match value {
+            Some(value) if node.shorthand && self.renames(&value.loc) => {
+                node.shorthand = false;
+                self.visit_pattern(&mut node.value);
+            }
+            _ => visit_mut::walk_assignment_property(self, node),
+        }
+    }
+
+    fn visit_import_specifier(&mut self, node: &mut ImportSpecifier) {
+        self.visit_identifier(&mut node.local);
+    }
+
+    fn visit_export_specifier(&mut self, node: &mut ExportSpecifier) {
+        self.visit_identifier(&mut node.local);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        analyze::analyze,
+        codegen::generate,
+        rename::{rename_binding, RenameError},
+    };
+
+    // renames the binding declared or referred to at a byte offset
+    fn renamed(src: &str, offset: usize, new_name: &str) -> Result<String, RenameError> {
+        let mut program = crate::parser::parse_module(src).unwrap();
+        let binding = analyze(&program).resolve_at(offset).unwrap();
+        rename_binding(&mut program, binding, new_name)?;
+        Ok(generate(&program))
+    }
+
+    #[test]
+    fn rename_changes_the_declaration_and_its_references() {
+        assert_eq!(
+            renamed("let a = 1; { let a = 2; a; } a++; f(a);", 4, "b").unwrap(),
+            "let b = 1;\n{\n  let a = 2;\n  a;\n}\nb++;\nf(b);\n"
+        );
+        assert_eq!(
+            renamed("var a; if (x) { var a = 1; } a.a;", 4, "b").unwrap(),
+            "var b;\nif (x) {\n  var b = 1;\n}\nb.a;\n"
+        );
+        assert_eq!(
+            renamed("function f(a) { return () => a; }", 11, "b").unwrap(),
+            "function f(b) {\n  return () => b;\n}\n"
+        );
+    }
+
+    #[test]
+    fn rename_keeps_the_names_of_shorthand_properties() {
+        assert_eq!(
+            renamed("let a; x = { a, b: a };", 4, "c").unwrap(),
+            "let c;\nx = { a: c, b: c };\n"
+        );
+        assert_eq!(
+            renamed("import { a } from 'm'; let b = 1; export { b }; a;", 9, "c").unwrap(),
+            "import { a as c } from 'm';\nlet b = 1;\nexport { b };\nc;\n"
+        );
+        assert_eq!(
+            renamed("let a = 1; export { a };", 4, "b").unwrap(),
+            "let b = 1;\nexport { b as a };\n"
+        );
+    }
+
+    #[test]
+    fn rename_keeps_the_keys_of_shorthand_patterns() {
+        assert_eq!(
+            renamed("let { a, b: [c = a] } = o; a;", 6, "d").unwrap(),
+            "let { a: d, b: [c = d] } = o;\nd;\n"
+        );
+        assert_eq!(
+            renamed("function f({ a = 1 }) { return a; }", 13, "b").unwrap(),
+            "function f({ a: b = 1 }) {\n  return b;\n}\n"
+        );
+    }
+
+    #[test]
+    fn rename_refuses_to_change_what_names_refer_to() {
+        let conflict =
+            |src, new_name| matches!(renamed(src, 4, new_name), Err(RenameError::Conflict(_)));
+        let captured =
+            |src, new_name| matches!(renamed(src, 4, new_name), Err(RenameError::Captured(_)));
+        assert!(conflict("let a, b;", "b"));
+        assert!(conflict("let a; { let b; a; }", "b"));
+        assert!(conflict("var a; { let b; var a; }", "b"));
+        assert!(captured("let a; function f() { b; } let x = b;", "b"));
+        assert!(captured("let a; console.log(a);", "console"));
+        assert!(captured("let a; { let b; } function f() { b; }", "b"));
+        assert!(renamed("let a; { let b; b; }", 4, "b").is_ok());
+        assert!(renamed("let a; function f() { let b; b; }", 4, "b").is_ok());
+        assert_eq!(
+            renamed("let a; eval('a');", 4, "b"),
+            Err(RenameError::SeenByEval)
+        );
+        assert_eq!(
+            renamed("export let a = 1;", 11, "b"),
+            Err(RenameError::NotRenamable)
+        );
+        for name in ["1a", "a b", "if", "this", "let", "eval", "\\u0062", ""] {
+            assert_eq!(renamed("let a;", 4, name), Err(RenameError::InvalidName));
+        }
+        assert_eq!(renamed("let a;", 4, "a").unwrap(), "let a;\n");
+    }
+
+    #[test]
+    fn rename_refuses_bindings_used_inside_with() {
+        let renamed = |src, offset| {
+            let mut program = crate::parser::parse_script(src).unwrap();
+            let binding = analyze(&program).resolve_at(offset).unwrap();
+            rename_binding(&mut program, binding, "b").map(|()| generate(&program))
+        };
+        assert_eq!(
+            renamed("var a; with (o) { a; }", 4),
+            Err(RenameError::NotRenamable)
+        );
+        assert_eq!(
+            renamed("var a; with (o) f(() => a);", 4),
+            Err(RenameError::NotRenamable)
+        );
+        assert_eq!(
+            renamed("with (o) { var a = 1; }", 15),
+            Err(RenameError::NotRenamable)
+        );
+        // the object itself is looked up as usual
+        assert_eq!(
+            renamed("var a; with (a) { x; }", 4).unwrap(),
+            "var b;\nwith (b) {\n  x;\n}\n"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+// ESTree-shaped serialization for the nodes whose JSON can't be derived:
+// locations are flattened into every node the way acorn lays them out, and
+// a few nodes carry fields that have no counterpart in the tree.
+use serde::ser::{Serialize, SerializeMap, Serializer};
+
+use crate::{
+    error::ParseError,
+    node::{
+        ArrowFunctionExpression, AssignmentProperty, FunctionDeclaration, FunctionExpression,
+        Literal, LiteralValue, SourceLocation,
+    },
+};
+
+// `start`/`end` offsets, the `loc` object and the `range` array, as acorn
+// gives them with `locations: true` and `ranges: true`
+fn serialize_loc<M: SerializeMap>(map: &mut M, loc: &SourceLocation) -> Result<(), M::Error> {
+    map.serialize_entry("start", &loc.range.0)?;
+    map.serialize_entry("end", &loc.range.1)?;
+    map.serialize_entry("loc", &LocObject(loc))?;
+    map.serialize_entry("range", &[loc.range.0, loc.range.1])
+}
+
+struct LocObject<'a>(&'a SourceLocation);
+
+impl Serialize for LocObject<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("start", &self.0.start)?;
+        map.serialize_entry("end", &self.0.end)?;
+        if let Some(source) = &self.0.source {
+            map.serialize_entry("source", source)?;
+        }
+        map.end()
+    }
+}
+
+// flattened into the node holding it
+impl Serialize for SourceLocation {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        serialize_loc(&mut map, self)?;
+        map.end()
+    }
+}
+
+// regular expressions and bigints have no JSON value, so ESTree leaves
+// `value` null and describes them in `regex` and `bigint` instead; whole
+// numbers are written without a fraction, as JavaScript prints them
+impl Serialize for Literal {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "Literal")?;
+        serialize_loc(&mut map, &self.loc)?;
+        match &self.value {
+            LiteralValue::String(value) => map.serialize_entry("value", value)?,
+            LiteralValue::Boolean(value) => map.serialize_entry("value", value)?,
+            LiteralValue::Null(_) => map.serialize_entry("value", &())?,
+            LiteralValue::Number(value) if value.fract() == 0.0 && value.abs() < 2f64.powi(53) => {
+                map.serialize_entry("value", &(*value as i64))?
+            }
+            LiteralValue::Number(value) => map.serialize_entry("value", value)?,
+            LiteralValue::Bigint(_) | LiteralValue::RegExp(_) => {
+                map.serialize_entry("value", &())?
+            }
+        }
+        map.serialize_entry("raw", &self.raw)?;
+        match &self.value {
+            LiteralValue::RegExp(regex) => map.serialize_entry("regex", regex)?,
+            LiteralValue::Bigint(digits) => map.serialize_entry("bigint", digits)?,
+            _ => {}
+        }
+        map.end()
+    }
+}
+
+// `expression` tells an arrow with a bare expression body from one with a
+// block; acorn sets it on every function
+impl Serialize for FunctionDeclaration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "FunctionDeclaration")?;
+        serialize_loc(&mut map, &self.loc)?;
+        map.serialize_entry("id", &self.id)?;
+        map.serialize_entry("expression", &false)?;
+        map.serialize_entry("generator", &self.generator)?;
+        map.serialize_entry("async", &self.is_async)?;
+        map.serialize_entry("params", &self.params)?;
+        map.serialize_entry("body", &self.body)?;
+        map.end()
+    }
+}
+
+impl Serialize for FunctionExpression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "FunctionExpression")?;
+        serialize_loc(&mut map, &self.loc)?;
+        map.serialize_entry("id", &self.id)?;
+        map.serialize_entry("expression", &false)?;
+        map.serialize_entry("generator", &self.generator)?;
+        map.serialize_entry("async", &self.is_async)?;
+        map.serialize_entry("params", &self.params)?;
+        map.serialize_entry("body", &self.body)?;
+        map.end()
+    }
+}
+
+// arrows are never named nor generators, but still have both fields
+impl Serialize for ArrowFunctionExpression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "ArrowFunctionExpression")?;
+        serialize_loc(&mut map, &self.loc)?;
+        map.serialize_entry("id", &())?;
+        map.serialize_entry("expression", &self.expression)?;
+        map.serialize_entry("generator", &false)?;
+        map.serialize_entry("async", &self.is_async)?;
+        map.serialize_entry("params", &self.params)?;
+        map.serialize_entry("body", &self.body)?;
+        map.end()
+    }
+}
+
+// a property of an object pattern is a plain `Property` with kind "init"
+impl Serialize for AssignmentProperty {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("type", "Property")?;
+        serialize_loc(&mut map, &self.loc)?;
+        map.serialize_entry("method", &false)?;
+        map.serialize_entry("shorthand", &self.shorthand)?;
+        map.serialize_entry("computed", &self.computed)?;
+        map.serialize_entry("key", &self.key)?;
+        map.serialize_entry("kind", "init")?;
+        map.serialize_entry("value", &self.value)?;
+        map.end()
+    }
+}
+
+// diagnostics aren't nodes, but go out alongside them as JSON for editors,
+// identified by their code, with tokens named as in messages
+impl Serialize for ParseError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("code", self.code())?;
+        map.serialize_entry("message", &self.message)?;
+        map.serialize_entry("start", &self.start)?;
+        map.serialize_entry("end", &self.end)?;
+        let expected: Vec<String> = self.expected.iter().map(ToString::to_string).collect();
+        map.serialize_entry("expected", &expected)?;
+        map.serialize_entry("found", &self.found.as_ref().map(ToString::to_string))?;
+        map.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::{Expression, Identifier, LogicalOperator, SourceLocation, SourceType},
+        parser::parse,
+        test_support::README_SRC,
+    };
+
+    #[test]
+    fn serializes_logical_and_member_expressions() {
+        use crate::node::{LogicalExpression, MemberExpression};
+
+        let identifier = |name: &str| {
+            Expression::Identifier(Box::new(Identifier::new(
+                name.to_string(),
+                SourceLocation::new(1, 0, 0),
+            )))
+        };
+        let member = MemberExpression::new(
+            identifier("b"),
+            identifier("c"),
+            false,
+            false,
+            SourceLocation::new(1, 0, 0),
+        );
+        let logical = LogicalExpression::new(
+            LogicalOperator::NullishCoalescing,
+            identifier("a"),
+            Expression::Member(Box::new(member.clone())),
+            SourceLocation::new(1, 0, 0),
+        );
+        let json = serde_json::to_value(&logical).unwrap();
+        assert_eq!(json["type"], "LogicalExpression");
+        assert_eq!(json["operator"], "??");
+        let json = serde_json::to_value(&member).unwrap();
+        assert_eq!(json["type"], "MemberExpression");
+        assert!(json.get("operator").is_none());
+    }
+
+    #[test]
+    fn error_nodes_serialize_with_their_own_type() {
+        let (program, _) = crate::parser::parse_with_recovery("a = ;", SourceType::Script);
+        let json = serde_json::to_value(&program).unwrap();
+        assert_eq!(json["body"][0]["expression"]["right"]["type"], "ErrorNode");
+        assert_eq!(json["body"][0]["expression"]["right"]["diagnostic"], 0);
+        let json = serde_json::to_string(&program).unwrap();
+        assert_eq!(crate::deserialize::from_json(&json).unwrap(), program);
+    }
+
+    // the README example as acorn parses it with `locations` and `ranges`,
+    // except that the regular expression's `value` is null rather than the
+    // `{}` a RegExp object turns into
+    #[test]
+    fn serializes_to_estree_json() {
+        let program = parse(README_SRC.trim_end()).unwrap();
+        let actual = serde_json::to_value(&program).unwrap();
+        let expected: serde_json::Value =
+            serde_json::from_str(include_str!("../tests/fixtures/readme.json")).unwrap();
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,280 @@
+// A compact dump of the tree as s-expressions, for snapshot tests that
+// should diff better than JSON:
+//
+//   (Program
+//     (FunctionDeclaration plus (params a b) (Block (Return (Binary + a b)))))
+//
+// A node is its ESTree type without the `Statement` or `Expression` on the
+// end, the operators, kinds and flags that tell it apart from others of its
+// type, and then its children in the order a visitor visits them.
+// Identifiers are written as their names, literals as their source text and
+// the text of a template as a string. A hole in an array and a part missing
+// from the head of a `for` are written as `()` to keep the place of what
+// follows. Nodes down to `depth` levels below the
+// program put each child on a line of its own, indented; deeper ones are
+// written on one line.
+use crate::{
+    codegen::{
+        assignment_operator, binary_operator, literal_text, logical_operator, unary_operator,
+    },
+    node::{MethodDefinitionKind, Program, PropertyKind, UpdateOperator},
+    node_ref::NodeRef,
+};
+
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+// what a node has in its parentheses after its type
+enum Item<'a> {
+    Node(NodeRef<'a>),
+    // the parameters of a function, in parentheses of their own
+    Params(Vec<NodeRef<'a>>),
+    // where a child could be but isn't
+    Hole,
+}
+
+// with each statement of the program on a line of its own
+pub fn to_sexpr(program: &Program) -> String {
+    to_sexpr_with_depth(program, 1)
+}
+
+pub fn to_sexpr_with_depth(program: &Program, depth: usize) -> String {
+    let mut out = String::new();
+    write_node(&mut out, NodeRef::Program(program), 0, depth);
+    out
+}
+
+fn write_node(out: &mut String, node: NodeRef, level: usize, depth: usize) {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match node {
+        NodeRef::Identifier(identifier) => out.push_str(&identifier.name),
+        NodeRef::PrivateIdentifier(identifier) => {
+            out.push('#');
+            out.push_str(&identifier.name);
+        }
+        NodeRef::Literal(literal) if literal.raw.is_empty() => out.push_str(&literal_text(literal)),
+        NodeRef::Literal(literal) => out.push_str(&literal.raw),
+        NodeRef::TemplateElement(element) => out.push_str(&format!("{:?}", element.value.raw)),
+        _ => {
+            let node_type = node.node_type();
+            let name = node_type
+                .strip_suffix("Statement")
+                .or_else(|| node_type.strip_suffix("Expression"))
+                .filter(|name| !name.is_empty())
+                .unwrap_or(node_type);
+            let mut head = vec![name];
+            head.extend(attributes(node));
+            write_list(out, &head, items(node), level, depth);
+        }
+    })
+}
+
+fn write_list(out: &mut String, head: &[&str], items: Vec<Item>, level: usize, depth: usize) {
+    out.push('(');
+    out.push_str(&head.join(" "));
+    for item in items {
+        if level < depth {
+            out.push('\n');
+            out.push_str(&"  ".repeat(level + 1));
+        } else {
+            out.push(' ');
+        }
+        match item {
+            Item::Node(node) => write_node(out, node, level + 1, depth),
+            Item::Hole => out.push_str("()"),
+            Item::Params(params) => write_list(
+                out,
+                &["params"],
+                params.into_iter().map(Item::Node).collect(),
+                level + 1,
+                depth,
+            ),
+        }
+    }
+    out.push(')');
+}
+
+fn items(node: NodeRef) -> Vec<Item> {
+    let mut children = node.children().into_iter();
+    // where the parameters are among the children
+    let params = match node {
+        NodeRef::FunctionDeclaration(function) => {
+            Some((function.id.is_some() as usize, function.params.len()))
+        }
+        NodeRef::FunctionExpression(function) => {
+            Some((function.id.is_some() as usize, function.params.len()))
+        }
+        NodeRef::ArrowFunctionExpression(arrow) => Some((0, arrow.params.len())),
+        _ => None,
+    };
+    if let Some((start, count)) = params {
+        let mut items: Vec<Item> = children.by_ref().take(start).map(Item::Node).collect();
+        items.push(Item::Params(children.by_ref().take(count).collect()));
+        items.extend(children.map(Item::Node));
+        return items;
+    }
+    // whether each of the leading children that may be missing is there
+    let slots = match node {
+        NodeRef::ArrayExpression(array) => array.elements.iter().map(Option::is_some).collect(),
+        NodeRef::ArrayPattern(array) => array.elements.iter().map(Option::is_some).collect(),
+        NodeRef::ForStatement(statement) => vec![
+            statement.init.is_some(),
+            statement.test.is_some(),
+            statement.update.is_some(),
+        ],
+        _ => vec![],
+    };
+    let mut items: Vec<Item> = slots
+        .into_iter()
+        .map(|filled| match filled.then(|| children.next()).flatten() {
+            Some(child) => Item::Node(child),
+            None => Item::Hole,
+        })
+        .collect();
+    items.extend(children.map(Item::Node));
+    items
+}
+
+// the operators, kinds and flags that tell a node apart from others of its type
+pub(crate) fn attributes<'a>(node: NodeRef<'a>) -> Vec<&'a str> {
+    let flags = |flags: &[(bool, &'static str)]| -> Vec<&'a str> {
+        flags
+            .iter()
+            .filter(|(set, _)| *set)
+            .map(|(_, flag)| *flag)
+            .collect()
+    };
+    match node {
+        NodeRef::VariableDeclaration(declaration) => vec![declaration.kind.as_str()],
+        NodeRef::FunctionDeclaration(function) => flags(&[
+            (function.is_async, "async"),
+            (function.generator, "generator"),
+        ]),
+        NodeRef::FunctionExpression(function) => flags(&[
+            (function.is_async, "async"),
+            (function.generator, "generator"),
+        ]),
+        NodeRef::ArrowFunctionExpression(arrow) => flags(&[(arrow.is_async, "async")]),
+        NodeRef::UnaryExpression(unary) => vec![unary_operator(&unary.operator)],
+        NodeRef::UpdateExpression(update) => {
+            let mut attributes = flags(&[(update.prefix, "prefix")]);
+            attributes.push(match update.operator {
+                UpdateOperator::Increment => "++",
+                UpdateOperator::Decrement => "--",
+            });
+            attributes
+        }
+        NodeRef::BinaryExpression(binary) => vec![binary_operator(&binary.operator)],
+        NodeRef::LogicalExpression(logical) => vec![logical_operator(&logical.operator)],
+        NodeRef::AssignmentExpression(assignment) => {
+            vec![assignment_operator(&assignment.operator)]
+        }
+        NodeRef::MemberExpression(member) => {
+            flags(&[(member.computed, "computed"), (member.optional, "optional")])
+        }
+        NodeRef::CallExpression(call) => flags(&[(call.optional, "optional")]),
+        NodeRef::YieldExpression(expression) => flags(&[(expression.delegate, "delegate")]),
+        NodeRef::ForOfStatement(statement) => flags(&[(statement.is_await, "await")]),
+        NodeRef::Property(property) => {
+            let mut attributes = match property.kind {
+                PropertyKind::Init => vec![],
+                PropertyKind::Get => vec!["get"],
+                PropertyKind::Set => vec!["set"],
+            };
+            attributes.extend(flags(&[
+                (property.method, "method"),
+                (property.shorthand, "shorthand"),
+                (property.computed, "computed"),
+            ]));
+            attributes
+        }
+        NodeRef::AssignmentProperty(property) => flags(&[
+            (property.shorthand, "shorthand"),
+            (property.computed, "computed"),
+        ]),
+        NodeRef::MethodDefinition(method) => {
+            let mut attributes = vec![match method.kind {
+                MethodDefinitionKind::Constructor => "constructor",
+                MethodDefinitionKind::Method => "method",
+                MethodDefinitionKind::Get => "get",
+                MethodDefinitionKind::Set => "set",
+            }];
+            attributes.extend(flags(&[
+                (method.is_static, "static"),
+                (method.computed, "computed"),
+            ]));
+            attributes
+        }
+        NodeRef::PropertyDefinition(property) => flags(&[
+            (property.is_static, "static"),
+            (property.computed, "computed"),
+        ]),
+        _ => vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        parser::parse,
+        sexpr::{to_sexpr, to_sexpr_with_depth},
+    };
+
+    #[test]
+    fn programs_dump_as_s_expressions() {
+        let program = parse("function plus(a, b) { return a + b; }\nplus(1, 'x');").unwrap();
+        assert_eq!(
+            to_sexpr(&program),
+            "(Program
+  (FunctionDeclaration plus (params a b) (Block (Return (Binary + a b))))
+  (Expression (Call plus 1 'x')))"
+        );
+        assert_eq!(
+            to_sexpr_with_depth(&program, 0),
+            "(Program (FunctionDeclaration plus (params a b) (Block (Return (Binary + a b)))) \
+             (Expression (Call plus 1 'x')))"
+        );
+        assert_eq!(
+            to_sexpr_with_depth(&program, 3),
+            "(Program
+  (FunctionDeclaration
+    plus
+    (params
+      a
+      b)
+    (Block
+      (Return (Binary + a b))))
+  (Expression
+    (Call
+      plus
+      1
+      'x')))"
+        );
+
+        let program = parse(
+            "let o = { get a() {}, b, [c]: 1 }; x ??= !y++ || ++z; class C { static m() {} }",
+        )
+        .unwrap();
+        assert_eq!(
+            to_sexpr(&program),
+            "(Program
+  (VariableDeclaration let (VariableDeclarator o (Object (Property get a (Function (params) (Block))) (Property shorthand b b) (Property computed c 1))))
+  (Expression (Assignment ??= x (Logical || (Unary ! (Update ++ y)) (Update prefix ++ z))))
+  (ClassDeclaration C (ClassBody (MethodDefinition method static m (Function (params) (Block))))))"
+        );
+    }
+
+    #[test]
+    fn holes_and_missing_for_parts_keep_their_place() {
+        let sexpr = |src: &str| to_sexpr_with_depth(&parse(src).unwrap(), 0);
+        assert_eq!(sexpr("[,a]"), "(Program (Expression (Array () a)))");
+        assert_eq!(sexpr("[a,,]"), "(Program (Expression (Array a ())))");
+        // a trailing comma alone makes no hole
+        assert_eq!(sexpr("[a,]"), "(Program (Expression (Array a)))");
+        assert_eq!(
+            sexpr("[, b] = c"),
+            "(Program (Expression (Assignment = (ArrayPattern () b) c)))"
+        );
+        assert_eq!(sexpr("for (;a;);"), "(Program (For () a () (Empty)))");
+        assert_eq!(sexpr("for (a;;);"), "(Program (For a () () (Empty)))");
+    }
+}
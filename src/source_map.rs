@@ -0,0 +1,158 @@
+// Source maps, version 3, relating positions in generated code to where the
+// nodes written there start in the source, so that a browser can show the
+// source when debugging what a transform produced.
+//
+// Positions are lines from 1 and columns from 0 counting characters, as
+// locations are, and become lines from 0 in the JSON. A mapping at an
+// identifier carries its name.
+use std::collections::HashMap;
+
+use crate::node::Position;
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mapping {
+    pub generated: Position,
+    pub original: Position,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceMap {
+    // the file the generated code is written to, if it has a name
+    pub file: Option<String>,
+    // the file it was generated from
+    pub source: String,
+    // in the order they come in the generated code
+    pub mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    pub fn new(source: &str) -> Self {
+        SourceMap {
+            file: None,
+            source: source.to_string(),
+            mappings: vec![],
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut names: Vec<&str> = vec![];
+        let mut indices: HashMap<&str, i64> = HashMap::new();
+        let mut mappings = String::new();
+        // each field but the generated column is relative to the same field
+        // of the mapping before, and that one to the mapping before on its
+        // line
+        let mut line = 1;
+        let mut column = 0;
+        let (mut original_line, mut original_column, mut name_index) = (0, 0, 0);
+        for (index, mapping) in self.mappings.iter().enumerate() {
+            if mapping.generated.line > line {
+                for _ in line..mapping.generated.line {
+                    mappings.push(';');
+                }
+                line = mapping.generated.line;
+                column = 0;
+            } else if index > 0 {
+                mappings.push(',');
+            }
+            vlq(&mut mappings, mapping.generated.column as i64 - column);
+            // the one source
+            vlq(&mut mappings, 0);
+            vlq(
+                &mut mappings,
+                mapping.original.line as i64 - 1 - original_line,
+            );
+            vlq(
+                &mut mappings,
+                mapping.original.column as i64 - original_column,
+            );
+            column = mapping.generated.column as i64;
+            original_line = mapping.original.line as i64 - 1;
+            original_column = mapping.original.column as i64;
+            if let Some(name) = &mapping.name {
+                let index = *indices.entry(name).or_insert_with(|| {
+                    names.push(name);
+                    names.len() as i64 - 1
+                });
+                vlq(&mut mappings, index - name_index);
+                name_index = index;
+            }
+        }
+        let mut json = "{\"version\":3,".to_string();
+        if let Some(file) = &self.file {
+            json.push_str(&format!("\"file\":{},", json_string(file)));
+        }
+        let names: Vec<String> = names.into_iter().map(json_string).collect();
+        json.push_str(&format!(
+            "\"sources\":[{}],\"names\":[{}],\"mappings\":\"{}\"}}",
+            json_string(&self.source),
+            names.join(","),
+            mappings
+        ));
+        json
+    }
+}
+
+// base64 digits of five bits each, lowest first, with the sign in the lowest
+// bit of the first and a continuation bit above each digit but the last
+pub(crate) fn vlq(out: &mut String, value: i64) {
+    let mut rest = if value < 0 {
+        (value.unsigned_abs() << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+    loop {
+        let mut digit = rest & 0b11111;
+        rest >>= 5;
+        if rest > 0 {
+            digit |= 0b100000;
+        }
+        out.push(BASE64[digit as usize] as char);
+        if rest == 0 {
+            break;
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut json = "\"".to_string();
+    for char in value.chars() {
+        match char {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            char if char.is_control() => json.push_str(&format!("\\u{:04x}", char as u32)),
+            char => json.push(char),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source_map::vlq;
+
+    #[test]
+    fn vlq_writes_five_bits_a_digit_sign_first() {
+        let cases = [
+            (0, "A"),
+            (1, "C"),
+            (-1, "D"),
+            (15, "e"),
+            (16, "gB"),
+            (-16, "hB"),
+            (123, "2H"),
+            (1 << 20, "ggggC"),
+        ];
+        for (value, expected) in cases {
+            let mut out = String::new();
+            vlq(&mut out, value);
+            assert_eq!(out, expected, "{}", value);
+        }
+    }
+}
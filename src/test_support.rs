@@ -0,0 +1,32 @@
+// Sources and helpers shared by the unit tests of several modules.
+use crate::{error::ParseErrorKind, parser::parse};
+
+// the example from the README
+pub(crate) const README_SRC: &str = "
+const a = getNumber() ?? 1;
+const b = 2;
+const str = \"Hello, World!\";
+const reg = /(?!maybe)/i;
+
+class Parent {
+  #name = 'parent';
+}
+
+class child extends Parent {
+  #name = 'child';
+}
+
+function plus(a, b) {
+  return a + b;
+}
+
+const minus = (a, b) => a - b;
+
+plus(a, b);
+minus(a, b);
+";
+
+pub(crate) fn error_at(src: &str) -> (ParseErrorKind, usize, usize) {
+    let error = parse(src).unwrap_err();
+    (error.kind, error.start.line, error.start.column)
+}
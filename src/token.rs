@@ -1,4 +1,12 @@
-#[derive(Debug)]
+use std::fmt;
+
+use crate::{
+    error::ParseError,
+    name::Name,
+    node::{RegExpValue, TemplateElementValue},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Comparation {
     DoubleE,
     TripleE,
@@ -10,30 +18,39 @@ pub enum Comparation {
     GTE,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Arithmetic {
     Plus,
     Minus,
     Multiple,
     Divide,
     Modulo,
+    Exponent,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Assign {
     Normal,
     Addition,
     Subtraction,
     Multiplication,
     Division,
+    Remainder,
+    Exponentiation,
+    LeftShift,
+    RightShift,
+    URightShift,
+    BitwiseOR,
+    BitwiseXOR,
+    BitwiseAND,
+    LogicalOR,
+    LogicalAND,
     NullishCoalescing,
 }
 
-pub type Raw = String;
-
 pub type Number = f64;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum NumberSystem {
     Binary,
     Octal,
@@ -41,20 +58,121 @@ pub enum NumberSystem {
     Hex,
 }
 
-#[derive(Debug)]
-pub enum RegExpModifier {
-    I,
-    G,
+// Tokens keep what they stand for but not how it's written, which is the
+// source between the offsets of their `Lexeme`, so that each fits in three
+// words; the payloads too large for that are rare enough to be boxed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(Number),
+    // the digits as written, without the `n` and any separators
+    Bigint(Box<str>),
+    RegExp(Box<RegExpValue>),
+    // the text between the quotes, escapes and all
+    String(Name),
+    // a piece of a template from "`" or the "}" ending a substitution up to
+    // the next "`" or "${": its raw and cooked text (`None` for a bad escape)
+    // and whether it is the last piece
+    Template(Box<TemplateElementValue>, bool),
+    Name(Name),
+    // the name without the `#`
+    PrivateName(Name),
+
+    Var,
+    Let,
+    Const,
+
+    Function,
+    Return,
+
+    For,
+    Of,
+    Do,
+    While,
+    Break,
+    Continue,
+
+    Switch,
+    Case,
+
+    Throw,
+    Try,
+    Catch,
+    Finally,
+
+    If,
+    Else,
+    With,
+
+    New,
+    This,
+    Super,
+    Delete,
+    Class,
+    Extends,
+    Instanceof,
+    Typeof,
+
+    Import,
+    Export,
+    Default,
+
+    Null,
+    Undefined,
+    True,
+    False,
+    Void,
+
+    In,
+
+    Comparation(Comparation),
+    Arithmetic(Arithmetic),
+    Assign(Assign),
+
+    ParenL,
+    ParenR,
+    BracketL,
+    BracketR,
+    BraceL,
+    BraceR,
+
+    Dot,
+    QuestionDot,
+    Semi,
+    Comma,
+    Colon,
+    Question,
+    NullishCoalesce,
+    LogicalInversion,
+    BitwiseInversion,
+    LogicalOR,
+    BitwiseOR,
+    LogicalAND,
+    BitwiseAND,
+    BitwiseXOR,
+    LeftShift,
+    RightShift,
+    URightShift,
+    Increment,
+    Decrement,
+    Arrow,
+    Ellipsis,
+
+    // what the lexer made nothing of, which only a tolerant parse goes on
+    // past, with the error it found there
+    Invalid(Box<ParseError>),
 }
 
-#[derive(Debug)]
-pub enum Token {
-    Number(Raw, NumberSystem, Number),
-    Bigint(Raw, NumberSystem, i128),
-    RegExp(Raw, String, Option<RegExpModifier>),
-    String(Raw, String),
-    Name(String),
-    PrivateName(Raw, String),
+// what a token is without the text it carries, as parse errors name the
+// tokens that were expected and found
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Number,
+    Bigint,
+    RegExp,
+    String,
+    Template,
+    Name,
+    PrivateName,
 
     Var,
     Let,
@@ -80,6 +198,7 @@ pub enum Token {
 
     If,
     Else,
+    With,
 
     New,
     This,
@@ -126,7 +245,359 @@ pub enum Token {
     BitwiseOR,
     LogicalAND,
     BitwiseAND,
+    BitwiseXOR,
+    LeftShift,
+    RightShift,
+    URightShift,
     Increment,
     Decrement,
     Arrow,
+    Ellipsis,
+
+    Invalid,
+}
+
+impl Token {
+    // reserved words, which are still valid as property names
+    pub fn is_keyword(&self) -> bool {
+        matches!(
+            self,
+            Token::Var
+                | Token::Let
+                | Token::Const
+                | Token::Function
+                | Token::Return
+                | Token::For
+                | Token::Of
+                | Token::Do
+                | Token::While
+                | Token::Break
+                | Token::Continue
+                | Token::Switch
+                | Token::Case
+                | Token::Throw
+                | Token::Try
+                | Token::Catch
+                | Token::Finally
+                | Token::If
+                | Token::Else
+                | Token::With
+                | Token::New
+                | Token::This
+                | Token::Super
+                | Token::Delete
+                | Token::Class
+                | Token::Extends
+                | Token::Instanceof
+                | Token::Typeof
+                | Token::Import
+                | Token::Export
+                | Token::Default
+                | Token::Null
+                | Token::Undefined
+                | Token::True
+                | Token::False
+                | Token::Void
+                | Token::In
+        )
+    }
+
+    pub fn kind(&self) -> TokenKind {
+        match self {
+            Token::Number(_) => TokenKind::Number,
+            Token::Bigint(_) => TokenKind::Bigint,
+            Token::RegExp(_) => TokenKind::RegExp,
+            Token::String(_) => TokenKind::String,
+            Token::Template(_, _) => TokenKind::Template,
+            Token::Name(_) => TokenKind::Name,
+            Token::PrivateName(_) => TokenKind::PrivateName,
+            Token::Var => TokenKind::Var,
+            Token::Let => TokenKind::Let,
+            Token::Const => TokenKind::Const,
+            Token::Function => TokenKind::Function,
+            Token::Return => TokenKind::Return,
+            Token::For => TokenKind::For,
+            Token::Of => TokenKind::Of,
+            Token::Do => TokenKind::Do,
+            Token::While => TokenKind::While,
+            Token::Break => TokenKind::Break,
+            Token::Continue => TokenKind::Continue,
+            Token::Switch => TokenKind::Switch,
+            Token::Case => TokenKind::Case,
+            Token::Throw => TokenKind::Throw,
+            Token::Try => TokenKind::Try,
+            Token::Catch => TokenKind::Catch,
+            Token::Finally => TokenKind::Finally,
+            Token::If => TokenKind::If,
+            Token::Else => TokenKind::Else,
+            Token::With => TokenKind::With,
+            Token::New => TokenKind::New,
+            Token::This => TokenKind::This,
+            Token::Super => TokenKind::Super,
+            Token::Delete => TokenKind::Delete,
+            Token::Class => TokenKind::Class,
+            Token::Extends => TokenKind::Extends,
+            Token::Instanceof => TokenKind::Instanceof,
+            Token::Typeof => TokenKind::Typeof,
+            Token::Import => TokenKind::Import,
+            Token::Export => TokenKind::Export,
+            Token::Default => TokenKind::Default,
+            Token::Null => TokenKind::Null,
+            Token::Undefined => TokenKind::Undefined,
+            Token::True => TokenKind::True,
+            Token::False => TokenKind::False,
+            Token::Void => TokenKind::Void,
+            Token::In => TokenKind::In,
+            Token::Comparation(comparation) => TokenKind::Comparation(comparation.clone()),
+            Token::Arithmetic(arithmetic) => TokenKind::Arithmetic(arithmetic.clone()),
+            Token::Assign(assign) => TokenKind::Assign(assign.clone()),
+            Token::ParenL => TokenKind::ParenL,
+            Token::ParenR => TokenKind::ParenR,
+            Token::BracketL => TokenKind::BracketL,
+            Token::BracketR => TokenKind::BracketR,
+            Token::BraceL => TokenKind::BraceL,
+            Token::BraceR => TokenKind::BraceR,
+            Token::Dot => TokenKind::Dot,
+            Token::QuestionDot => TokenKind::QuestionDot,
+            Token::Semi => TokenKind::Semi,
+            Token::Comma => TokenKind::Comma,
+            Token::Colon => TokenKind::Colon,
+            Token::Question => TokenKind::Question,
+            Token::NullishCoalesce => TokenKind::NullishCoalesce,
+            Token::LogicalInversion => TokenKind::LogicalInversion,
+            Token::BitwiseInversion => TokenKind::BitwiseInversion,
+            Token::LogicalOR => TokenKind::LogicalOR,
+            Token::BitwiseOR => TokenKind::BitwiseOR,
+            Token::LogicalAND => TokenKind::LogicalAND,
+            Token::BitwiseAND => TokenKind::BitwiseAND,
+            Token::BitwiseXOR => TokenKind::BitwiseXOR,
+            Token::LeftShift => TokenKind::LeftShift,
+            Token::RightShift => TokenKind::RightShift,
+            Token::URightShift => TokenKind::URightShift,
+            Token::Increment => TokenKind::Increment,
+            Token::Decrement => TokenKind::Decrement,
+            Token::Arrow => TokenKind::Arrow,
+            Token::Ellipsis => TokenKind::Ellipsis,
+            Token::Invalid(_) => TokenKind::Invalid,
+        }
+    }
+}
+
+impl TokenKind {
+    // tokens with text of their own, which are described by what they are
+    // rather than written out
+    pub fn has_value(&self) -> bool {
+        matches!(
+            self,
+            TokenKind::Number
+                | TokenKind::Bigint
+                | TokenKind::RegExp
+                | TokenKind::String
+                | TokenKind::Template
+                | TokenKind::Name
+                | TokenKind::PrivateName
+        )
+    }
+}
+
+// the text of a token where the token alone tells it; numbers, strings and
+// templates can be written in more than one way, so only their kind is
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Name(name) => f.write_str(name),
+            Token::PrivateName(name) => write!(f, "#{}", name),
+            Token::Bigint(digits) => write!(f, "{}n", digits),
+            Token::RegExp(regexp) => write!(f, "/{}/{}", regexp.pattern, regexp.flags),
+            _ => self.kind().fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            TokenKind::Number => "number",
+            TokenKind::Bigint => "bigint",
+            TokenKind::RegExp => "regular expression",
+            TokenKind::String => "string",
+            TokenKind::Template => "template",
+            TokenKind::Name => "identifier",
+            TokenKind::PrivateName => "private name",
+
+            TokenKind::Var => "var",
+            TokenKind::Let => "let",
+            TokenKind::Const => "const",
+
+            TokenKind::Function => "function",
+            TokenKind::Return => "return",
+
+            TokenKind::For => "for",
+            TokenKind::Of => "of",
+            TokenKind::Do => "do",
+            TokenKind::While => "while",
+            TokenKind::Break => "break",
+            TokenKind::Continue => "continue",
+
+            TokenKind::Switch => "switch",
+            TokenKind::Case => "case",
+
+            TokenKind::Throw => "throw",
+            TokenKind::Try => "try",
+            TokenKind::Catch => "catch",
+            TokenKind::Finally => "finally",
+
+            TokenKind::If => "if",
+            TokenKind::Else => "else",
+            TokenKind::With => "with",
+
+            TokenKind::New => "new",
+            TokenKind::This => "this",
+            TokenKind::Super => "super",
+            TokenKind::Delete => "delete",
+            TokenKind::Class => "class",
+            TokenKind::Extends => "extends",
+            TokenKind::Instanceof => "instanceof",
+            TokenKind::Typeof => "typeof",
+
+            TokenKind::Import => "import",
+            TokenKind::Export => "export",
+            TokenKind::Default => "default",
+
+            TokenKind::Null => "null",
+            TokenKind::Undefined => "undefined",
+            TokenKind::True => "true",
+            TokenKind::False => "false",
+            TokenKind::Void => "void",
+
+            TokenKind::In => "in",
+
+            TokenKind::Comparation(comparation) => match comparation {
+                Comparation::DoubleE => "==",
+                Comparation::TripleE => "===",
+                Comparation::DoubleNE => "!=",
+                Comparation::TripleNE => "!==",
+                Comparation::LT => "<",
+                Comparation::LTE => "<=",
+                Comparation::GT => ">",
+                Comparation::GTE => ">=",
+            },
+            TokenKind::Arithmetic(arithmetic) => match arithmetic {
+                Arithmetic::Plus => "+",
+                Arithmetic::Minus => "-",
+                Arithmetic::Multiple => "*",
+                Arithmetic::Divide => "/",
+                Arithmetic::Modulo => "%",
+                Arithmetic::Exponent => "**",
+            },
+            TokenKind::Assign(assign) => match assign {
+                Assign::Normal => "=",
+                Assign::Addition => "+=",
+                Assign::Subtraction => "-=",
+                Assign::Multiplication => "*=",
+                Assign::Division => "/=",
+                Assign::Remainder => "%=",
+                Assign::Exponentiation => "**=",
+                Assign::LeftShift => "<<=",
+                Assign::RightShift => ">>=",
+                Assign::URightShift => ">>>=",
+                Assign::BitwiseOR => "|=",
+                Assign::BitwiseXOR => "^=",
+                Assign::BitwiseAND => "&=",
+                Assign::LogicalOR => "||=",
+                Assign::LogicalAND => "&&=",
+                Assign::NullishCoalescing => "??=",
+            },
+
+            TokenKind::ParenL => "(",
+            TokenKind::ParenR => ")",
+            TokenKind::BracketL => "[",
+            TokenKind::BracketR => "]",
+            TokenKind::BraceL => "{",
+            TokenKind::BraceR => "}",
+
+            TokenKind::Dot => ".",
+            TokenKind::QuestionDot => "?.",
+            TokenKind::Semi => ";",
+            TokenKind::Comma => ",",
+            TokenKind::Colon => ":",
+            TokenKind::Question => "?",
+            TokenKind::NullishCoalesce => "??",
+            TokenKind::LogicalInversion => "!",
+            TokenKind::BitwiseInversion => "~",
+            TokenKind::LogicalOR => "||",
+            TokenKind::BitwiseOR => "|",
+            TokenKind::LogicalAND => "&&",
+            TokenKind::BitwiseAND => "&",
+            TokenKind::BitwiseXOR => "^",
+            TokenKind::LeftShift => "<<",
+            TokenKind::RightShift => ">>",
+            TokenKind::URightShift => ">>>",
+            TokenKind::Increment => "++",
+            TokenKind::Decrement => "--",
+            TokenKind::Arrow => "=>",
+            TokenKind::Ellipsis => "...",
+            TokenKind::Invalid => "invalid token",
+        };
+        f.write_str(text)
+    }
+}
+
+// A token along with where it starts and ends in the source, the end being
+// the position right after its last character, both as line and column and
+// as a byte offset. `newline_before` records
+// whether a line break separates it from the previous token, which automatic
+// semicolon insertion and the restricted productions rely on.
+#[derive(Debug, Clone)]
+pub struct Lexeme {
+    pub token: Token,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+    pub offset: usize,
+    pub end_offset: usize,
+    pub newline_before: bool,
+}
+
+impl Lexeme {
+    // the token as written in the source it was lexed from
+    pub fn text<'a>(&self, src: &'a str) -> &'a str {
+        &src[self.offset..self.end_offset]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{token::Token, tokenize};
+
+    #[test]
+    fn tokens_fit_in_three_words() {
+        assert_eq!(
+            std::mem::size_of::<Token>(),
+            3 * std::mem::size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn lexemes_give_their_text_from_the_source() {
+        let src = "a = 0x1_0 + 'b\\c' + `d${e}f` + /g/i + 1n + #h";
+        let lexemes = tokenize(src).unwrap();
+        let texts: Vec<&str> = lexemes.iter().map(|lexeme| lexeme.text(src)).collect();
+        assert_eq!(
+            texts,
+            [
+                "a", "=", "0x1_0", "+", "'b\\c'", "+", "`d${", "e", "}f`", "+", "/g/i", "+", "1n",
+                "+", "#h"
+            ]
+        );
+        assert_eq!(lexemes[2].token, Token::Number(16.0));
+        assert_eq!(lexemes[4].token, Token::String("bc".into()));
+        assert_eq!(lexemes[12].token, Token::Bigint("1".into()));
+        let shown: Vec<String> = [10, 12, 14]
+            .iter()
+            .map(|&index| lexemes[index].token.to_string())
+            .collect();
+        assert_eq!(shown, ["/g/i", "1n", "#h"]);
+    }
 }
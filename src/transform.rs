@@ -0,0 +1,486 @@
+// Rewrites of the tree, built on the mutable visitor.
+//
+// `fold_constants` computes operators whose operands are literals the way
+// JavaScript would at run time, so `1 + 2 * 3` becomes `7` and `"a" + 1`
+// becomes `"a1"`, while `1 / 0` stays as it is since there's no literal for
+// `Infinity`. Anything that reads a name is left alone, `undefined` and
+// `NaN` included since they can be shadowed, as are bigints and regular
+// expressions. Operators that can throw on literals, `in` and `instanceof`,
+// and those whose result has no literal, `void` and `delete`, aren't folded
+// either; nor is the operand of `delete`, which acts on what the operand is
+// rather than its value.
+use std::cmp::Ordering;
+
+use crate::{
+    codegen::literal_text,
+    node::{
+        BinaryOperator, Expression, Literal, LiteralValue, LogicalOperator, Null, Program,
+        UnaryOperator,
+    },
+    visit_mut::{walk_expression, VisitorMut},
+};
+
+pub fn fold_constants(program: &mut Program) {
+    ConstantFolder.visit_program(program);
+}
+
+// whether an expression is always truthy or always falsy, as far as folding
+// it can tell
+pub(crate) fn truthiness(expression: &Expression) -> Option<bool> {
+    evaluate(expression).map(|value| to_boolean(&value))
+}
+
+// the value folding would leave an expression as, worked out without
+// folding it; the stack grows as it does for the visitors, as this recurses
+// as deeply as they do
+fn evaluate(expression: &Expression) -> Option<Value> {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match expression {
+        Expression::Parenthesized(parenthesized) => evaluate(&parenthesized.expression),
+        Expression::Unary(unary) if matches!(unary.operator, UnaryOperator::Delete) => None,
+        Expression::Literal(_) => constant(expression),
+        _ => fold(expression, &evaluate),
+    })
+}
+
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+struct ConstantFolder;
+
+impl VisitorMut for ConstantFolder {
+    // the operands are folded first, so `1 + 2 * 3` is `1 + 6` by the time
+    // the addition is looked at
+    fn visit_expression(&mut self, node: &mut Expression) {
+        if let Expression::Unary(unary) = node {
+            if matches!(unary.operator, UnaryOperator::Delete) {
+                walk_expression(self, &mut unary.argument);
+                return;
+            }
+        }
+        walk_expression(self, node);
+        if let Some(value) = fold(node, &constant) {
+            let loc = match node {
+                Expression::Binary(binary) => binary.loc.clone(),
+                Expression::Unary(unary) => unary.loc.clone(),
+                Expression::Logical(logical) => logical.loc.clone(),
+                _ => unreachable!("only operators are folded"),
+            };
+            let mut literal = Literal::new(value.into_literal(), String::new(), loc);
+            literal.raw = literal_text(&literal);
+            *node = Expression::Literal(Box::new(literal));
+        }
+    }
+}
+
+// the value of an operator, given what `operand` makes of its operands
+fn fold(node: &Expression, operand: &dyn Fn(&Expression) -> Option<Value>) -> Option<Value> {
+    match node {
+        Expression::Binary(binary) => match (operand(&binary.left), operand(&binary.right)) {
+            (Some(left), Some(right)) => fold_binary(&binary.operator, left, right),
+            _ => None,
+        },
+        Expression::Unary(unary) => {
+            operand(&unary.argument).and_then(|argument| fold_unary(&unary.operator, argument))
+        }
+        // the right side isn't evaluated once the left decides, so it may be
+        // anything; otherwise it is the result, which is only folded when
+        // it's a literal, as `(true && a.b)()` doesn't call `b` on `a`
+        Expression::Logical(logical) => operand(&logical.left).and_then(|left| {
+            let decides = match logical.operator {
+                LogicalOperator::LogicalOR => to_boolean(&left),
+                LogicalOperator::LogicalAND => !to_boolean(&left),
+                LogicalOperator::NullishCoalescing => left != Value::Null,
+            };
+            if decides {
+                Some(left)
+            } else {
+                operand(&logical.right)
+            }
+        }),
+        _ => None,
+    }
+}
+
+// the primitive values there are literals for
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    Null,
+}
+
+impl Value {
+    fn into_literal(self) -> LiteralValue {
+        match self {
+            Value::Number(value) => LiteralValue::Number(value),
+            Value::String(value) => LiteralValue::String(value),
+            Value::Boolean(value) => LiteralValue::Boolean(value),
+            Value::Null => LiteralValue::Null(Null),
+        }
+    }
+}
+
+// the value of a literal, through any parentheses kept around it
+fn constant(expression: &Expression) -> Option<Value> {
+    match expression {
+        Expression::Literal(literal) => match &literal.value {
+            LiteralValue::Number(value) => Some(Value::Number(*value)),
+            LiteralValue::String(value) => Some(Value::String(value.clone())),
+            LiteralValue::Boolean(value) => Some(Value::Boolean(*value)),
+            LiteralValue::Null(_) => Some(Value::Null),
+            _ => None,
+        },
+        Expression::Parenthesized(parenthesized) => constant(&parenthesized.expression),
+        _ => None,
+    }
+}
+
+fn fold_unary(operator: &UnaryOperator, argument: Value) -> Option<Value> {
+    Some(match operator {
+        UnaryOperator::Positive => Value::Number(to_number(&argument)),
+        UnaryOperator::Negative => Value::Number(-to_number(&argument)),
+        UnaryOperator::LogicalInversion => Value::Boolean(!to_boolean(&argument)),
+        UnaryOperator::BitwiseInversion => Value::Number(!to_int32(&argument) as f64),
+        UnaryOperator::Typeof => Value::String(
+            match argument {
+                Value::Number(_) => "number",
+                Value::String(_) => "string",
+                Value::Boolean(_) => "boolean",
+                Value::Null => "object",
+            }
+            .to_string(),
+        ),
+        UnaryOperator::Void | UnaryOperator::Delete => return None,
+    })
+}
+
+fn fold_binary(operator: &BinaryOperator, left: Value, right: Value) -> Option<Value> {
+    let number = |value: f64| Some(Value::Number(value));
+    let boolean = |value: bool| Some(Value::Boolean(value));
+    // shifts count with the low five bits of the right side
+    let shift = to_uint32(&right) & 31;
+    match operator {
+        BinaryOperator::Plus => Some(match (left, right) {
+            (Value::String(left), right) => Value::String(left + &to_string(&right)),
+            (left, Value::String(right)) => Value::String(to_string(&left) + &right),
+            (left, right) => Value::Number(to_number(&left) + to_number(&right)),
+        }),
+        BinaryOperator::Minus => number(to_number(&left) - to_number(&right)),
+        BinaryOperator::Multiple => number(to_number(&left) * to_number(&right)),
+        BinaryOperator::Divide => number(to_number(&left) / to_number(&right)),
+        // truncated like `%` on floats in Rust, taking the sign of the left
+        BinaryOperator::Modulo => number(to_number(&left) % to_number(&right)),
+        BinaryOperator::Exponent => number(power(to_number(&left), to_number(&right))),
+        BinaryOperator::LeftShift => number(to_int32(&left).wrapping_shl(shift) as f64),
+        BinaryOperator::RightShift => number((to_int32(&left) >> shift) as f64),
+        BinaryOperator::URightShift => number((to_uint32(&left) >> shift) as f64),
+        BinaryOperator::BitwiseAND => number((to_int32(&left) & to_int32(&right)) as f64),
+        BinaryOperator::BitwiseOR => number((to_int32(&left) | to_int32(&right)) as f64),
+        BinaryOperator::BitwiseXOR => number((to_int32(&left) ^ to_int32(&right)) as f64),
+        BinaryOperator::TripleE => boolean(strict_equals(&left, &right)),
+        BinaryOperator::TripleNE => boolean(!strict_equals(&left, &right)),
+        BinaryOperator::DoubleE => boolean(loose_equals(&left, &right)),
+        BinaryOperator::DoubleNE => boolean(!loose_equals(&left, &right)),
+        BinaryOperator::LT => boolean(compare(&left, &right) == Some(Ordering::Less)),
+        BinaryOperator::GT => boolean(compare(&left, &right) == Some(Ordering::Greater)),
+        BinaryOperator::LTE => boolean(matches!(
+            compare(&left, &right),
+            Some(Ordering::Less | Ordering::Equal)
+        )),
+        BinaryOperator::GTE => boolean(matches!(
+            compare(&left, &right),
+            Some(Ordering::Greater | Ordering::Equal)
+        )),
+        BinaryOperator::In | BinaryOperator::Instanceof => None,
+    }
+}
+
+fn to_boolean(value: &Value) -> bool {
+    match value {
+        Value::Number(value) => !(*value == 0.0 || value.is_nan()),
+        Value::String(value) => !value.is_empty(),
+        Value::Boolean(value) => *value,
+        Value::Null => false,
+    }
+}
+
+fn to_number(value: &Value) -> f64 {
+    match value {
+        Value::Number(value) => *value,
+        Value::String(value) => string_to_number(value),
+        Value::Boolean(value) => *value as u8 as f64,
+        Value::Null => 0.0,
+    }
+}
+
+// a string is read as a number once the whitespace around it is trimmed,
+// and an empty one is zero; anything but a decimal, `Infinity` or an
+// unsigned binary, octal or hexadecimal integer is `NaN`
+fn string_to_number(value: &str) -> f64 {
+    let text = value.trim_matches(is_js_whitespace);
+    if text.is_empty() {
+        return 0.0;
+    }
+    let radix = match text.get(..2) {
+        Some("0x" | "0X") => 16,
+        Some("0o" | "0O") => 8,
+        Some("0b" | "0B") => 2,
+        _ => 10,
+    };
+    if radix != 10 {
+        let digits = &text[2..];
+        if digits.is_empty() || !digits.chars().all(|char| char.is_digit(radix)) {
+            return f64::NAN;
+        }
+        return digits.chars().fold(0.0, |value, digit| {
+            value * radix as f64 + digit.to_digit(radix).unwrap() as f64
+        });
+    }
+    match text.trim_start_matches(['+', '-']) {
+        "Infinity" if text.len() - "Infinity".len() <= 1 => {
+            if text.starts_with('-') {
+                f64::NEG_INFINITY
+            } else {
+                f64::INFINITY
+            }
+        }
+        // Rust also reads `inf`, `nan` and the like, which JavaScript doesn't
+        unsigned
+            if unsigned.starts_with(|char: char| char.is_ascii_digit() || char == '.')
+                && text.chars().all(|char| {
+                    char.is_ascii_digit() || matches!(char, '.' | 'e' | 'E' | '+' | '-')
+                }) =>
+        {
+            text.parse().unwrap_or(f64::NAN)
+        }
+        _ => f64::NAN,
+    }
+}
+
+// white space and line terminators, which `String.prototype.trim` removes
+fn is_js_whitespace(char: char) -> bool {
+    // Rust counts U+0085 as white space, which JavaScript doesn't
+    char == '\u{feff}' || (char != '\u{85}' && char.is_whitespace())
+}
+
+fn to_uint32(value: &Value) -> u32 {
+    let value = to_number(value);
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32
+}
+
+fn to_int32(value: &Value) -> i32 {
+    to_uint32(value) as i32
+}
+
+// `Math.pow`, which differs from `powf` where the result would be 1 in Rust
+// but isn't defined in JavaScript
+fn power(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return f64::NAN;
+    }
+    base.powf(exponent)
+}
+
+fn to_string(value: &Value) -> String {
+    match value {
+        Value::Number(value) => number_to_string(*value),
+        Value::String(value) => value.clone(),
+        Value::Boolean(value) => value.to_string(),
+        Value::Null => "null".to_string(),
+    }
+}
+
+// `Number.prototype.toString`: the shortest digits that read back as the
+// number, written out in full from 1e-7 up to 1e21 and with an exponent
+// beyond
+fn number_to_string(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    // Rust's shortest digits, as in `1.2345e-7`
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let exponent: i32 = exponent.parse().unwrap();
+    let count = digits.len() as i32;
+    // where the point goes, counted in digits from the left
+    let point = exponent + 1;
+    let text = if count <= point && point <= 21 {
+        digits + &"0".repeat((point - count) as usize)
+    } else if 0 < point && point <= 21 {
+        format!(
+            "{}.{}",
+            &digits[..point as usize],
+            &digits[point as usize..]
+        )
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        let exponent = if point > 0 {
+            format!("+{}", point - 1)
+        } else {
+            (point - 1).to_string()
+        };
+        match digits.split_at(1) {
+            (first, "") => format!("{}e{}", first, exponent),
+            (first, rest) => format!("{}.{}e{}", first, rest, exponent),
+        }
+    };
+    format!("{}{}", sign, text)
+}
+
+fn strict_equals(left: &Value, right: &Value) -> bool {
+    // floats already have NaN unequal to itself and 0 equal to -0
+    left == right
+}
+
+// values of different types are compared as numbers, but `null` only
+// equals `null` and `undefined`
+fn loose_equals(left: &Value, right: &Value) -> bool {
+    match (left, right) {
+        (Value::Null, right) => *right == Value::Null,
+        (_, Value::Null) => false,
+        (Value::Number(_), Value::Number(_))
+        | (Value::String(_), Value::String(_))
+        | (Value::Boolean(_), Value::Boolean(_)) => strict_equals(left, right),
+        _ => to_number(left) == to_number(right),
+    }
+}
+
+// strings compare by their UTF-16 code units and anything else as numbers,
+// where `NaN` is unordered
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::String(left), Value::String(right)) => {
+            Some(left.encode_utf16().cmp(right.encode_utf16()))
+        }
+        _ => to_number(left).partial_cmp(&to_number(right)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{codegen::generate, parser::parse, transform::fold_constants};
+
+    fn folded(src: &str) -> String {
+        let mut program = parse(src).unwrap();
+        fold_constants(&mut program);
+        generate(&program)
+    }
+
+    // the results are those of node
+    #[test]
+    fn constants_fold_to_what_javascript_computes() {
+        let cases = [
+            ("1 + 2 * 3", "7"),
+            ("0.1 + 0.2", "0.30000000000000004"),
+            ("1 / 0", "1 / 0"),
+            ("-1 / 0", "-1 / 0"),
+            ("0 / 0", "0 / 0"),
+            ("2 * (1 / 0)", "1 / 0"),
+            ("-5 % 3", "-2"),
+            ("2 ** -1", "0.5"),
+            ("1 ** (1 / 0)", "0 / 0"),
+            ("'a' + 'b'", "\"ab\""),
+            ("'a' + 1 + 2", "\"a12\""),
+            ("'\\n' + '\\x41'", "\"\\nA\""),
+            ("1 + 2 + 'a'", "\"3a\""),
+            ("'x' + 1 / 3", "\"x0.3333333333333333\""),
+            ("'x' + 1e21 + 1e-7 + -0", "\"x1e+211e-70\""),
+            ("'x' + null + true", "\"xnulltrue\""),
+            ("'6' * '7'", "42"),
+            ("' 0x10 ' - 0", "16"),
+            ("'1_0' - 0", "0 / 0"),
+            ("'' - 1", "-1"),
+            ("true + true", "2"),
+            ("null + 1", "1"),
+            ("1 << 31", "-2147483648"),
+            ("-1 >>> 0", "4294967295"),
+            ("2 ** 32 | 0", "0"),
+            ("~5.7", "-6"),
+            ("!true", "false"),
+            ("!''", "true"),
+            ("typeof 1", "\"number\""),
+            ("typeof null", "\"object\""),
+            ("typeof typeof 1", "\"string\""),
+            ("'1' == 1", "true"),
+            ("null == 0", "false"),
+            ("0 / 0 === 0 / 0", "false"),
+            ("0 === -0", "true"),
+            ("'10' < '9'", "true"),
+            ("10 < '9'", "false"),
+            ("0 / 0 <= 0 / 0", "false"),
+            ("0 || 'b'", "\"b\""),
+            ("null ?? 0", "0"),
+            ("1 && 2", "2"),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(
+                folded(&format!("x = {};", src)),
+                format!("x = {};\n", expected),
+                "{}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn folding_leaves_what_it_cant_compute_alone() {
+        let cases = [
+            ("x = a + 1;", "x = a + 1;\n"),
+            ("x = f() + (1 + 2);", "x = f() + 3;\n"),
+            ("x = undefined + 1;", "x = undefined + 1;\n"),
+            ("x = void 0;", "x = void 0;\n"),
+            ("x = 1n + 2n;", "x = 1n + 2n;\n"),
+            ("x = 'a' in b;", "x = 'a' in b;\n"),
+            ("x = (true && a.b)();", "x = (true && a.b)();\n"),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(folded(src), expected, "{}", src);
+        }
+        // the right side is dropped only when it isn't evaluated
+        assert_eq!(folded("x = false && f();"), "x = false;\n");
+    }
+
+    #[test]
+    fn folding_never_writes_a_name_that_could_be_shadowed() {
+        assert_eq!(
+            folded("function f(NaN, Infinity) { return [0 / 0, 2 / 0, -1 / 0]; }"),
+            "function f(NaN, Infinity) {\n  return [0 / 0, 1 / 0, -1 / 0];\n}\n"
+        );
+        assert_eq!(
+            folded("x = (1 / 0).toString();"),
+            "x = (1 / 0).toString();\n"
+        );
+        assert_eq!(folded("x = a / -(1 / 0);"), "x = a / (-1 / 0);\n");
+        assert_eq!(
+            folded("'use strict'; delete +'s'; delete (1 + 2).x;"),
+            "'use strict';\ndelete +'s';\ndelete 3..x;\n"
+        );
+        let mut program = parse("x = +'s' - -(2 / 0);").unwrap();
+        fold_constants(&mut program);
+        assert_eq!(crate::codegen::generate_minified(&program), "x=0/0");
+    }
+
+    #[test]
+    fn folding_twice_changes_nothing() {
+        let mut once = parse("x = [1 + 2, -(3 * 4), 'a' + 0.5, 1 / 0 - 1, !!0 || null];").unwrap();
+        fold_constants(&mut once);
+        let mut twice = once.clone();
+        fold_constants(&mut twice);
+        assert_eq!(once, twice);
+        assert_eq!(generate(&once), "x = [3, -12, \"a0.5\", 1 / 0, null];\n");
+        assert_eq!(generate(&parse(&generate(&once)).unwrap()), generate(&once));
+    }
+}
@@ -0,0 +1,223 @@
+// Statements that can never run, a lint on the shape of the code alone.
+//
+// Within a list of statements, those after one that always jumps away, by
+// `return`, `throw`, `break` or `continue`, are unreachable, as are the
+// bodies of `if (false)`, `while (false)` and `for (; false; )`, and the
+// `else` of `if (true)`, once folding constants shows the test to be so.
+// Function and class declarations after a jump are left alone, as they are
+// declared for the whole of their scope wherever they are written. Each
+// `case` of a `switch` is a list of its own, since falling through from the
+// one before isn't the only way in.
+use crate::{
+    node::{
+        BlockStatement, ForStatement, ForStatementInit, FunctionBody, IfStatement, Node, Program,
+        SourceLocation, Statement, StaticBlock, SwitchCase, WhileStatement,
+    },
+    transform::truthiness,
+    visit::{walk_if_statement, walk_while_statement, Visitor},
+};
+
+// the locations of the unreachable code in source order, a run of
+// statements one after the other making a single location
+pub fn find_unreachable(program: &Program) -> Vec<SourceLocation> {
+    let mut finder = UnreachableFinder { found: vec![] };
+    finder.visit_program(program);
+    finder.found.sort_by_key(|loc| loc.range.0);
+    finder.found
+}
+
+struct UnreachableFinder {
+    found: Vec<SourceLocation>,
+}
+
+impl UnreachableFinder {
+    fn visit_statements(&mut self, statements: &[Statement]) {
+        let mut jumped = false;
+        let mut run: Option<SourceLocation> = None;
+        for statement in statements {
+            if jumped && !is_hoisted(statement) {
+                let loc = statement.loc();
+                match &mut run {
+                    Some(run) => {
+                        run.end = loc.end;
+                        run.range.1 = loc.range.1;
+                    }
+                    None => run = Some(loc.clone()),
+                }
+                continue;
+            }
+            self.found.extend(run.take());
+            self.visit_statement(statement);
+            jumped = jumped || always_jumps(statement);
+        }
+        self.found.extend(run);
+    }
+}
+
+impl<'ast> Visitor<'ast> for UnreachableFinder {
+    fn visit_program(&mut self, node: &'ast Program) {
+        self.visit_statements(&node.body);
+    }
+
+    fn visit_block_statement(&mut self, node: &'ast BlockStatement) {
+        self.visit_statements(&node.body);
+    }
+
+    fn visit_function_body(&mut self, node: &'ast FunctionBody) {
+        self.visit_statements(&node.body);
+    }
+
+    fn visit_static_block(&mut self, node: &'ast StaticBlock) {
+        self.visit_statements(&node.body);
+    }
+
+    fn visit_switch_case(&mut self, node: &'ast SwitchCase) {
+        if let Some(test) = &node.test {
+            self.visit_expression(test);
+        }
+        self.visit_statements(&node.consequent);
+    }
+
+    fn visit_if_statement(&mut self, node: &'ast IfStatement) {
+        match truthiness(&node.test) {
+            Some(true) => {
+                self.visit_statement(&node.consequent);
+                if let Some(alternate) = &node.alternate {
+                    self.found.push(alternate.loc().clone());
+                }
+            }
+            Some(false) => {
+                self.found.push(node.consequent.loc().clone());
+                if let Some(alternate) = &node.alternate {
+                    self.visit_statement(alternate);
+                }
+            }
+            None => walk_if_statement(self, node),
+        }
+    }
+
+    fn visit_while_statement(&mut self, node: &'ast WhileStatement) {
+        match truthiness(&node.test) {
+            Some(false) => self.found.push(node.body.loc().clone()),
+            _ => walk_while_statement(self, node),
+        }
+    }
+
+    // the initializer runs even when the test is false at once
+    fn visit_for_statement(&mut self, node: &'ast ForStatement) {
+        match &node.init {
+            Some(ForStatementInit::VariableDeclaration(declaration)) => {
+                self.visit_variable_declaration(declaration)
+            }
+            Some(ForStatementInit::Expression(expression)) => self.visit_expression(expression),
+            None => {}
+        }
+        if let Some(test) = &node.test {
+            if truthiness(test) == Some(false) {
+                self.found.push(node.body.loc().clone());
+                return;
+            }
+            self.visit_expression(test);
+        }
+        if let Some(update) = &node.update {
+            self.visit_expression(update);
+        }
+        self.visit_statement(&node.body);
+    }
+}
+
+fn is_hoisted(statement: &Statement) -> bool {
+    matches!(
+        statement,
+        Statement::FunctionDeclaration(_) | Statement::ClassDeclaration(_)
+    )
+}
+
+// whether control never goes on to the statement after this one; a labeled
+// statement may be broken out of to there, so it doesn't count
+fn always_jumps(statement: &Statement) -> bool {
+    match statement {
+        Statement::Return(_)
+        | Statement::Throw(_)
+        | Statement::Break(_)
+        | Statement::Continue(_) => true,
+        Statement::Block(block) => block.body.iter().any(always_jumps),
+        Statement::If(node) => match truthiness(&node.test) {
+            Some(true) => always_jumps(&node.consequent),
+            Some(false) => node.alternate.as_ref().is_some_and(always_jumps),
+            None => {
+                always_jumps(&node.consequent) && node.alternate.as_ref().is_some_and(always_jumps)
+            }
+        },
+        Statement::Try(node) => {
+            node.finalizer
+                .as_ref()
+                .is_some_and(|finalizer| finalizer.body.iter().any(always_jumps))
+                || node.block.body.iter().any(always_jumps)
+                    && node
+                        .handler
+                        .as_ref()
+                        .is_none_or(|handler| handler.body.body.iter().any(always_jumps))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::parse;
+
+    // the source text of each stretch of unreachable code
+    fn unreachable(src: &str) -> Vec<&str> {
+        crate::unreachable::find_unreachable(&parse(src).unwrap())
+            .into_iter()
+            .map(|loc| &src[loc.range.0..loc.range.1])
+            .collect()
+    }
+
+    #[test]
+    fn statements_after_a_jump_are_unreachable() {
+        assert_eq!(
+            unreachable(
+                "function f(x) { if (x) { return; a(); b(); } g(); throw x; h(); function k() {} i(); }"
+            ),
+            ["a(); b();", "h();", "i();"]
+        );
+        assert_eq!(
+            unreachable("for (;;) { if (a) continue; else break; x; } y;"),
+            ["x;"]
+        );
+        assert_eq!(
+            unreachable("function f() { try { return; } catch (e) { throw e; } x; class C {} }"),
+            ["x;"]
+        );
+        assert_eq!(
+            unreachable("switch (a) { case 1: b(); break; c(); case 2: d(); }"),
+            ["c();"]
+        );
+        assert!(unreachable("a: { break a; } b(); try { throw a; } catch {} c();").is_empty());
+    }
+
+    #[test]
+    fn branches_constant_tests_skip_are_unreachable() {
+        assert_eq!(
+            unreachable(
+                "if (false) a(); if (1 > 2) { b(); } else c(); if ('x') d(); else e(); \
+                 while (0) f(); for (g(); !1; ) h(); do i(); while (false); if (x) j();"
+            ),
+            ["a();", "{ b(); }", "e();", "f();", "h();"]
+        );
+        assert_eq!(
+            unreachable("function f() { if (!0) return; a(); }"),
+            ["a();"]
+        );
+    }
+
+    #[test]
+    fn deep_tests_do_not_overflow_the_stack() {
+        let src = format!("if (0{}) a();", " + 0".repeat(10_000));
+        assert_eq!(unreachable(&src), ["a();"]);
+        let src = format!("if (x{}) a();", " + x".repeat(10_000));
+        assert!(unreachable(&src).is_empty());
+    }
+}
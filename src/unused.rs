@@ -0,0 +1,114 @@
+// Bindings that are declared but never read, the first lint built on the
+// scope analysis.
+//
+// Only reads count, so a variable that is only ever assigned to is reported.
+// Exported bindings are read by other modules, and a scope calling `eval`
+// directly may read anything it can see, so neither is reported; nor are
+// `arguments` and the names of function and class expressions, which are
+// never written down as declarations.
+use crate::{
+    analyze::{analyze, Analysis, Binding, BindingKind, ScopeKind},
+    node::Program,
+};
+
+impl Analysis {
+    // in the order they are declared in the source
+    pub fn unused_bindings(&self) -> Vec<&Binding> {
+        let mut sees_eval = vec![false; self.scopes.len()];
+        for (index, scope) in self.scopes.iter().enumerate() {
+            if scope.calls_eval {
+                sees_eval[index] = true;
+                let mut parent = scope.parent;
+                while let Some(id) = parent {
+                    sees_eval[id.0 as usize] = true;
+                    parent = self.scope(id).parent;
+                }
+            }
+        }
+        let mut unused: Vec<&Binding> = self
+            .bindings
+            .iter()
+            .filter(|binding| {
+                !binding.is_exported
+                    && !sees_eval[binding.scope.0 as usize]
+                    && !self.is_implicit(binding)
+                    && !binding
+                        .references
+                        .iter()
+                        .any(|&reference| self.reference(reference).is_read)
+            })
+            .collect();
+        unused.sort_by_key(|binding| binding.loc.range.0);
+        unused
+    }
+
+    fn is_implicit(&self, binding: &Binding) -> bool {
+        matches!(
+            (binding.kind, self.scope(binding.scope).kind),
+            (BindingKind::Arguments, _)
+                | (BindingKind::Function, ScopeKind::FunctionName)
+                | (BindingKind::Class, ScopeKind::Class)
+        )
+    }
+}
+
+pub fn find_unused(program: &Program) -> Vec<Binding> {
+    analyze(program)
+        .unused_bindings()
+        .into_iter()
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analyze::BindingKind;
+
+    #[test]
+    fn unused_bindings_are_found_with_their_declarations() {
+        let src = "import { a, b } from 'm';\n\
+                   var v = 1;\n\
+                   let w;\n\
+                   w = 2;\n\
+                   f();\n\
+                   function f(p, q) { return q; }\n\
+                   function g() {}\n\
+                   h = function self() {};\n\
+                   try {} catch (e) {}\n\
+                   b;";
+        let unused: Vec<(String, BindingKind, usize)> =
+            crate::unused::find_unused(&crate::parser::parse_module(src).unwrap())
+                .into_iter()
+                .map(|binding| (binding.name, binding.kind, binding.loc.start.line))
+                .collect();
+        assert_eq!(
+            unused,
+            [
+                ("a".to_string(), BindingKind::Import, 1),
+                ("v".to_string(), BindingKind::Var, 2),
+                ("w".to_string(), BindingKind::Let, 3),
+                ("p".to_string(), BindingKind::Param, 6),
+                ("g".to_string(), BindingKind::Function, 7),
+                ("e".to_string(), BindingKind::CatchParam, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn exported_and_eval_visible_bindings_are_not_unused() {
+        let src = "export const a = 1;\n\
+                   export function b() {}\n\
+                   export default class C {}\n\
+                   const d = 1;\n\
+                   export { d };\n\
+                   function e() { let x; return () => eval('x'); }\n\
+                   function f() { let y; window.eval('y'); }";
+        let names: Vec<String> =
+            crate::unused::find_unused(&crate::parser::parse_module(src).unwrap())
+                .into_iter()
+                .map(|binding| binding.name)
+                .collect();
+        // `e` and `f` are in sight of the `eval` too
+        assert_eq!(names, ["y"]);
+    }
+}
@@ -0,0 +1,1188 @@
+// Read-only traversal of the tree. Each `visit_*` method of a `Visitor`
+// recurses into the children of its node through the matching `walk_*`
+// function, so an override that still wants the children walked calls that
+// function itself.
+//
+// Nodes are visited in pre-order, a node before its children, and the
+// children left to right in the order they appear in the source: the test of
+// a conditional before its consequent, the key of a property before its
+// value. A shorthand property such as `{ a }` has its key and its value
+// visited, as both are in the tree.
+//
+// The tree can be deeper than the stack allows, such as with a long chain of
+// `+`, so statements, expressions and patterns walk their children on a
+// fresh stack segment when the current one runs low.
+use crate::node::{
+    ArrayExpression, ArrayExpressionElement, ArrayPattern, ArrowFunctionExpression,
+    ArrowFunctionExpressionBody, AssignmentExpression, AssignmentExpressionLeft, AssignmentPattern,
+    AssignmentProperty, AwaitExpression, BinaryExpression, BlockStatement, BreakStatement,
+    CallExpression, CallExpressionArgument, CatchClause, ChainExpression, ClassBody,
+    ClassDeclaration, ClassElement, ClassExpression, ConditionalExpression, ContinueStatement,
+    DebuggerStatement, Directive, DoWhileStatement, EmptyStatement, ErrorNode,
+    ExportAllDeclaration, ExportDefaultDeclaration, ExportDefaultDeclarationKind,
+    ExportNamedDeclaration, ExportSpecifier, Expression, ExpressionStatement, ForInStatement,
+    ForInStatementLeft, ForOfStatement, ForStatement, ForStatementInit, FunctionBody,
+    FunctionDeclaration, FunctionExpression, Identifier, IfStatement, ImportDeclaration,
+    ImportDeclarationSpecifier, ImportDefaultSpecifier, ImportExpression, ImportNamespaceSpecifier,
+    ImportSpecifier, LabeledStatement, Literal, LogicalExpression, MemberExpression, MetaProperty,
+    MethodDefinition, NewExpression, ObjectExpression, ObjectExpressionProperty, ObjectPattern,
+    ObjectPatternProperty, ParenthesizedExpression, Pattern, PrivateIdentifier, Program, Property,
+    PropertyDefinition, PropertyKey, RestElement, ReturnStatement, SequenceExpression,
+    SpreadElement, Statement, StaticBlock, Super, SwitchCase, SwitchStatement,
+    TaggedTemplateExpression, TemplateElement, TemplateLiteral, ThisExpression, ThrowStatement,
+    TryStatement, UnaryExpression, UpdateExpression, VariableDeclaration, VariableDeclarator,
+    WhileStatement, WithStatement, YieldExpression,
+};
+
+// left free on the stack before walking further, and the size of each
+// segment allocated once it's used up
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+pub trait Visitor<'ast> {
+    fn visit_program(&mut self, node: &'ast Program) {
+        walk_program(self, node);
+    }
+
+    fn visit_statement(&mut self, node: &'ast Statement) {
+        walk_statement(self, node);
+    }
+
+    fn visit_expression(&mut self, node: &'ast Expression) {
+        walk_expression(self, node);
+    }
+
+    fn visit_pattern(&mut self, node: &'ast Pattern) {
+        walk_pattern(self, node);
+    }
+
+    fn visit_identifier(&mut self, _node: &'ast Identifier) {}
+
+    fn visit_literal(&mut self, _node: &'ast Literal) {}
+
+    fn visit_expression_statement(&mut self, node: &'ast ExpressionStatement) {
+        walk_expression_statement(self, node);
+    }
+
+    fn visit_directive(&mut self, node: &'ast Directive) {
+        walk_directive(self, node);
+    }
+
+    fn visit_block_statement(&mut self, node: &'ast BlockStatement) {
+        walk_block_statement(self, node);
+    }
+
+    fn visit_function_body(&mut self, node: &'ast FunctionBody) {
+        walk_function_body(self, node);
+    }
+
+    fn visit_empty_statement(&mut self, _node: &'ast EmptyStatement) {}
+
+    fn visit_debugger_statement(&mut self, _node: &'ast DebuggerStatement) {}
+
+    fn visit_with_statement(&mut self, node: &'ast WithStatement) {
+        walk_with_statement(self, node);
+    }
+
+    fn visit_return_statement(&mut self, node: &'ast ReturnStatement) {
+        walk_return_statement(self, node);
+    }
+
+    fn visit_labeled_statement(&mut self, node: &'ast LabeledStatement) {
+        walk_labeled_statement(self, node);
+    }
+
+    fn visit_break_statement(&mut self, node: &'ast BreakStatement) {
+        walk_break_statement(self, node);
+    }
+
+    fn visit_continue_statement(&mut self, node: &'ast ContinueStatement) {
+        walk_continue_statement(self, node);
+    }
+
+    fn visit_if_statement(&mut self, node: &'ast IfStatement) {
+        walk_if_statement(self, node);
+    }
+
+    fn visit_switch_statement(&mut self, node: &'ast SwitchStatement) {
+        walk_switch_statement(self, node);
+    }
+
+    fn visit_switch_case(&mut self, node: &'ast SwitchCase) {
+        walk_switch_case(self, node);
+    }
+
+    fn visit_throw_statement(&mut self, node: &'ast ThrowStatement) {
+        walk_throw_statement(self, node);
+    }
+
+    fn visit_try_statement(&mut self, node: &'ast TryStatement) {
+        walk_try_statement(self, node);
+    }
+
+    fn visit_catch_clause(&mut self, node: &'ast CatchClause) {
+        walk_catch_clause(self, node);
+    }
+
+    fn visit_while_statement(&mut self, node: &'ast WhileStatement) {
+        walk_while_statement(self, node);
+    }
+
+    fn visit_do_while_statement(&mut self, node: &'ast DoWhileStatement) {
+        walk_do_while_statement(self, node);
+    }
+
+    fn visit_for_statement(&mut self, node: &'ast ForStatement) {
+        walk_for_statement(self, node);
+    }
+
+    fn visit_for_in_statement(&mut self, node: &'ast ForInStatement) {
+        walk_for_in_statement(self, node);
+    }
+
+    fn visit_for_of_statement(&mut self, node: &'ast ForOfStatement) {
+        walk_for_of_statement(self, node);
+    }
+
+    fn visit_function_declaration(&mut self, node: &'ast FunctionDeclaration) {
+        walk_function_declaration(self, node);
+    }
+
+    fn visit_variable_declaration(&mut self, node: &'ast VariableDeclaration) {
+        walk_variable_declaration(self, node);
+    }
+
+    fn visit_variable_declarator(&mut self, node: &'ast VariableDeclarator) {
+        walk_variable_declarator(self, node);
+    }
+
+    fn visit_this_expression(&mut self, _node: &'ast ThisExpression) {}
+
+    fn visit_super(&mut self, _node: &'ast Super) {}
+
+    fn visit_array_expression(&mut self, node: &'ast ArrayExpression) {
+        walk_array_expression(self, node);
+    }
+
+    fn visit_object_expression(&mut self, node: &'ast ObjectExpression) {
+        walk_object_expression(self, node);
+    }
+
+    fn visit_property(&mut self, node: &'ast Property) {
+        walk_property(self, node);
+    }
+
+    fn visit_function_expression(&mut self, node: &'ast FunctionExpression) {
+        walk_function_expression(self, node);
+    }
+
+    fn visit_arrow_function_expression(&mut self, node: &'ast ArrowFunctionExpression) {
+        walk_arrow_function_expression(self, node);
+    }
+
+    fn visit_class_expression(&mut self, node: &'ast ClassExpression) {
+        walk_class_expression(self, node);
+    }
+
+    fn visit_unary_expression(&mut self, node: &'ast UnaryExpression) {
+        walk_unary_expression(self, node);
+    }
+
+    fn visit_update_expression(&mut self, node: &'ast UpdateExpression) {
+        walk_update_expression(self, node);
+    }
+
+    fn visit_binary_expression(&mut self, node: &'ast BinaryExpression) {
+        walk_binary_expression(self, node);
+    }
+
+    fn visit_assignment_expression(&mut self, node: &'ast AssignmentExpression) {
+        walk_assignment_expression(self, node);
+    }
+
+    fn visit_logical_expression(&mut self, node: &'ast LogicalExpression) {
+        walk_logical_expression(self, node);
+    }
+
+    fn visit_member_expression(&mut self, node: &'ast MemberExpression) {
+        walk_member_expression(self, node);
+    }
+
+    fn visit_conditional_expression(&mut self, node: &'ast ConditionalExpression) {
+        walk_conditional_expression(self, node);
+    }
+
+    fn visit_call_expression(&mut self, node: &'ast CallExpression) {
+        walk_call_expression(self, node);
+    }
+
+    fn visit_chain_expression(&mut self, node: &'ast ChainExpression) {
+        walk_chain_expression(self, node);
+    }
+
+    fn visit_yield_expression(&mut self, node: &'ast YieldExpression) {
+        walk_yield_expression(self, node);
+    }
+
+    fn visit_template_literal(&mut self, node: &'ast TemplateLiteral) {
+        walk_template_literal(self, node);
+    }
+
+    fn visit_tagged_template_expression(&mut self, node: &'ast TaggedTemplateExpression) {
+        walk_tagged_template_expression(self, node);
+    }
+
+    fn visit_template_element(&mut self, _node: &'ast TemplateElement) {}
+
+    fn visit_spread_element(&mut self, node: &'ast SpreadElement) {
+        walk_spread_element(self, node);
+    }
+
+    fn visit_new_expression(&mut self, node: &'ast NewExpression) {
+        walk_new_expression(self, node);
+    }
+
+    fn visit_meta_property(&mut self, node: &'ast MetaProperty) {
+        walk_meta_property(self, node);
+    }
+
+    fn visit_import_expression(&mut self, node: &'ast ImportExpression) {
+        walk_import_expression(self, node);
+    }
+
+    fn visit_sequence_expression(&mut self, node: &'ast SequenceExpression) {
+        walk_sequence_expression(self, node);
+    }
+
+    fn visit_parenthesized_expression(&mut self, node: &'ast ParenthesizedExpression) {
+        walk_parenthesized_expression(self, node);
+    }
+
+    fn visit_await_expression(&mut self, node: &'ast AwaitExpression) {
+        walk_await_expression(self, node);
+    }
+
+    fn visit_private_identifier(&mut self, _node: &'ast PrivateIdentifier) {}
+
+    fn visit_object_pattern(&mut self, node: &'ast ObjectPattern) {
+        walk_object_pattern(self, node);
+    }
+
+    fn visit_array_pattern(&mut self, node: &'ast ArrayPattern) {
+        walk_array_pattern(self, node);
+    }
+
+    fn visit_rest_element(&mut self, node: &'ast RestElement) {
+        walk_rest_element(self, node);
+    }
+
+    fn visit_assignment_property(&mut self, node: &'ast AssignmentProperty) {
+        walk_assignment_property(self, node);
+    }
+
+    fn visit_assignment_pattern(&mut self, node: &'ast AssignmentPattern) {
+        walk_assignment_pattern(self, node);
+    }
+
+    fn visit_class_body(&mut self, node: &'ast ClassBody) {
+        walk_class_body(self, node);
+    }
+
+    fn visit_method_definition(&mut self, node: &'ast MethodDefinition) {
+        walk_method_definition(self, node);
+    }
+
+    fn visit_property_definition(&mut self, node: &'ast PropertyDefinition) {
+        walk_property_definition(self, node);
+    }
+
+    fn visit_static_block(&mut self, node: &'ast StaticBlock) {
+        walk_static_block(self, node);
+    }
+
+    fn visit_class_declaration(&mut self, node: &'ast ClassDeclaration) {
+        walk_class_declaration(self, node);
+    }
+
+    fn visit_import_declaration(&mut self, node: &'ast ImportDeclaration) {
+        walk_import_declaration(self, node);
+    }
+
+    fn visit_import_specifier(&mut self, node: &'ast ImportSpecifier) {
+        walk_import_specifier(self, node);
+    }
+
+    fn visit_import_default_specifier(&mut self, node: &'ast ImportDefaultSpecifier) {
+        walk_import_default_specifier(self, node);
+    }
+
+    fn visit_import_namespace_specifier(&mut self, node: &'ast ImportNamespaceSpecifier) {
+        walk_import_namespace_specifier(self, node);
+    }
+
+    fn visit_export_named_declaration(&mut self, node: &'ast ExportNamedDeclaration) {
+        walk_export_named_declaration(self, node);
+    }
+
+    fn visit_export_specifier(&mut self, node: &'ast ExportSpecifier) {
+        walk_export_specifier(self, node);
+    }
+
+    fn visit_export_default_declaration(&mut self, node: &'ast ExportDefaultDeclaration) {
+        walk_export_default_declaration(self, node);
+    }
+
+    fn visit_export_all_declaration(&mut self, node: &'ast ExportAllDeclaration) {
+        walk_export_all_declaration(self, node);
+    }
+
+    fn visit_error_node(&mut self, _node: &'ast ErrorNode) {}
+}
+
+pub fn walk_program<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Program) {
+    for statement in &node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Statement) {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match node {
+        Statement::Expression(node) => visitor.visit_expression_statement(node),
+        Statement::Directive(node) => visitor.visit_directive(node),
+        Statement::Block(node) => visitor.visit_block_statement(node),
+        Statement::Empty(node) => visitor.visit_empty_statement(node),
+        Statement::Debugger(node) => visitor.visit_debugger_statement(node),
+        Statement::With(node) => visitor.visit_with_statement(node),
+        Statement::Return(node) => visitor.visit_return_statement(node),
+        Statement::Labeled(node) => visitor.visit_labeled_statement(node),
+        Statement::Break(node) => visitor.visit_break_statement(node),
+        Statement::Continue(node) => visitor.visit_continue_statement(node),
+        Statement::If(node) => visitor.visit_if_statement(node),
+        Statement::Switch(node) => visitor.visit_switch_statement(node),
+        Statement::Throw(node) => visitor.visit_throw_statement(node),
+        Statement::Try(node) => visitor.visit_try_statement(node),
+        Statement::While(node) => visitor.visit_while_statement(node),
+        Statement::DoWhile(node) => visitor.visit_do_while_statement(node),
+        Statement::For(node) => visitor.visit_for_statement(node),
+        Statement::ForIn(node) => visitor.visit_for_in_statement(node),
+        Statement::ForOf(node) => visitor.visit_for_of_statement(node),
+        Statement::FunctionDeclaration(node) => visitor.visit_function_declaration(node),
+        Statement::VariableDeclaration(node) => visitor.visit_variable_declaration(node),
+        Statement::ClassDeclaration(node) => visitor.visit_class_declaration(node),
+        Statement::ImportDeclaration(node) => visitor.visit_import_declaration(node),
+        Statement::ExportNamedDeclaration(node) => visitor.visit_export_named_declaration(node),
+        Statement::ExportDefaultDeclaration(node) => visitor.visit_export_default_declaration(node),
+        Statement::ExportAllDeclaration(node) => visitor.visit_export_all_declaration(node),
+        Statement::Error(node) => visitor.visit_error_node(node),
+    })
+}
+
+pub fn walk_expression<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Expression) {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match node {
+        Expression::Identifier(node) => visitor.visit_identifier(node),
+        Expression::Literal(node) => visitor.visit_literal(node),
+        Expression::This(node) => visitor.visit_this_expression(node),
+        Expression::Super(node) => visitor.visit_super(node),
+        Expression::Array(node) => visitor.visit_array_expression(node),
+        Expression::Object(node) => visitor.visit_object_expression(node),
+        Expression::Function(node) => visitor.visit_function_expression(node),
+        Expression::ArrowFunction(node) => visitor.visit_arrow_function_expression(node),
+        Expression::Class(node) => visitor.visit_class_expression(node),
+        Expression::Unary(node) => visitor.visit_unary_expression(node),
+        Expression::Update(node) => visitor.visit_update_expression(node),
+        Expression::Binary(node) => visitor.visit_binary_expression(node),
+        Expression::Assignment(node) => visitor.visit_assignment_expression(node),
+        Expression::Logical(node) => visitor.visit_logical_expression(node),
+        Expression::Member(node) => visitor.visit_member_expression(node),
+        Expression::Conditional(node) => visitor.visit_conditional_expression(node),
+        Expression::Call(node) => visitor.visit_call_expression(node),
+        Expression::Chain(node) => visitor.visit_chain_expression(node),
+        Expression::Yield(node) => visitor.visit_yield_expression(node),
+        Expression::TemplateLiteral(node) => visitor.visit_template_literal(node),
+        Expression::TaggedTemplate(node) => visitor.visit_tagged_template_expression(node),
+        Expression::New(node) => visitor.visit_new_expression(node),
+        Expression::MetaProperty(node) => visitor.visit_meta_property(node),
+        Expression::Import(node) => visitor.visit_import_expression(node),
+        Expression::Sequence(node) => visitor.visit_sequence_expression(node),
+        Expression::Parenthesized(node) => visitor.visit_parenthesized_expression(node),
+        Expression::Await(node) => visitor.visit_await_expression(node),
+        Expression::PrivateIdentifier(node) => visitor.visit_private_identifier(node),
+        Expression::Error(node) => visitor.visit_error_node(node),
+    })
+}
+
+pub fn walk_pattern<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Pattern) {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match node {
+        Pattern::Identifier(node) => visitor.visit_identifier(node),
+        Pattern::Member(node) => visitor.visit_member_expression(node),
+        Pattern::Object(node) => visitor.visit_object_pattern(node),
+        Pattern::Array(node) => visitor.visit_array_pattern(node),
+        Pattern::Rest(node) => visitor.visit_rest_element(node),
+        Pattern::Assignment(node) => visitor.visit_assignment_pattern(node),
+        Pattern::Error(node) => visitor.visit_error_node(node),
+    })
+}
+
+pub fn walk_expression_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ExpressionStatement,
+) {
+    visitor.visit_expression(&node.expression);
+}
+
+pub fn walk_directive<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Directive) {
+    visitor.visit_literal(&node.expression);
+}
+
+pub fn walk_block_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast BlockStatement,
+) {
+    for statement in &node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_function_body<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast FunctionBody,
+) {
+    for statement in &node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_with_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast WithStatement,
+) {
+    visitor.visit_expression(&node.object);
+    visitor.visit_statement(&node.body);
+}
+
+pub fn walk_return_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ReturnStatement,
+) {
+    if let Some(argument) = &node.argument {
+        visitor.visit_expression(argument);
+    }
+}
+
+pub fn walk_labeled_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast LabeledStatement,
+) {
+    visitor.visit_identifier(&node.label);
+    visitor.visit_statement(&node.body);
+}
+
+pub fn walk_break_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast BreakStatement,
+) {
+    if let Some(label) = &node.label {
+        visitor.visit_identifier(label);
+    }
+}
+
+pub fn walk_continue_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ContinueStatement,
+) {
+    if let Some(label) = &node.label {
+        visitor.visit_identifier(label);
+    }
+}
+
+pub fn walk_if_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast IfStatement,
+) {
+    visitor.visit_expression(&node.test);
+    visitor.visit_statement(&node.consequent);
+    if let Some(alternate) = &node.alternate {
+        visitor.visit_statement(alternate);
+    }
+}
+
+pub fn walk_switch_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SwitchStatement,
+) {
+    visitor.visit_expression(&node.discriminant);
+    for case in &node.cases {
+        visitor.visit_switch_case(case);
+    }
+}
+
+pub fn walk_switch_case<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast SwitchCase) {
+    if let Some(test) = &node.test {
+        visitor.visit_expression(test);
+    }
+    for statement in &node.consequent {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_throw_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ThrowStatement,
+) {
+    visitor.visit_expression(&node.argument);
+}
+
+pub fn walk_try_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast TryStatement,
+) {
+    visitor.visit_block_statement(&node.block);
+    if let Some(handler) = &node.handler {
+        visitor.visit_catch_clause(handler);
+    }
+    if let Some(finalizer) = &node.finalizer {
+        visitor.visit_block_statement(finalizer);
+    }
+}
+
+pub fn walk_catch_clause<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast CatchClause,
+) {
+    if let Some(param) = &node.param {
+        visitor.visit_pattern(param);
+    }
+    visitor.visit_block_statement(&node.body);
+}
+
+pub fn walk_while_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast WhileStatement,
+) {
+    visitor.visit_expression(&node.test);
+    visitor.visit_statement(&node.body);
+}
+
+// the body of `do ... while` comes before its test
+pub fn walk_do_while_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast DoWhileStatement,
+) {
+    visitor.visit_statement(&node.body);
+    visitor.visit_expression(&node.test);
+}
+
+pub fn walk_for_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ForStatement,
+) {
+    match &node.init {
+        Some(ForStatementInit::VariableDeclaration(declaration)) => {
+            visitor.visit_variable_declaration(declaration)
+        }
+        Some(ForStatementInit::Expression(expression)) => visitor.visit_expression(expression),
+        None => {}
+    }
+    if let Some(test) = &node.test {
+        visitor.visit_expression(test);
+    }
+    if let Some(update) = &node.update {
+        visitor.visit_expression(update);
+    }
+    visitor.visit_statement(&node.body);
+}
+
+fn walk_for_in_statement_left<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ForInStatementLeft,
+) {
+    match node {
+        ForInStatementLeft::VariableDeclaration(declaration) => {
+            visitor.visit_variable_declaration(declaration)
+        }
+        ForInStatementLeft::Pattern(pattern) => visitor.visit_pattern(pattern),
+    }
+}
+
+pub fn walk_for_in_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ForInStatement,
+) {
+    walk_for_in_statement_left(visitor, &node.left);
+    visitor.visit_expression(&node.right);
+    visitor.visit_statement(&node.body);
+}
+
+pub fn walk_for_of_statement<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ForOfStatement,
+) {
+    walk_for_in_statement_left(visitor, &node.left);
+    visitor.visit_expression(&node.right);
+    visitor.visit_statement(&node.body);
+}
+
+pub fn walk_function_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast FunctionDeclaration,
+) {
+    if let Some(id) = &node.id {
+        visitor.visit_identifier(id);
+    }
+    for param in &node.params {
+        visitor.visit_pattern(param);
+    }
+    visitor.visit_function_body(&node.body);
+}
+
+pub fn walk_variable_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast VariableDeclaration,
+) {
+    for declarator in &node.declarations {
+        visitor.visit_variable_declarator(declarator);
+    }
+}
+
+pub fn walk_variable_declarator<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast VariableDeclarator,
+) {
+    visitor.visit_pattern(&node.id);
+    if let Some(init) = &node.init {
+        visitor.visit_expression(init);
+    }
+}
+
+pub fn walk_array_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ArrayExpression,
+) {
+    for element in node.elements.iter().flatten() {
+        match element {
+            ArrayExpressionElement::Expression(expression) => visitor.visit_expression(expression),
+            ArrayExpressionElement::SpreadElement(spread) => visitor.visit_spread_element(spread),
+        }
+    }
+}
+
+pub fn walk_object_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ObjectExpression,
+) {
+    for property in &node.properties {
+        match property {
+            ObjectExpressionProperty::Property(property) => visitor.visit_property(property),
+            ObjectExpressionProperty::SpreadElement(spread) => visitor.visit_spread_element(spread),
+        }
+    }
+}
+
+fn walk_property_key<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast PropertyKey) {
+    match node {
+        PropertyKey::Literal(literal) => visitor.visit_literal(literal),
+        PropertyKey::Identifier(identifier) => visitor.visit_identifier(identifier),
+        PropertyKey::Expression(expression) => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_property<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast Property) {
+    walk_property_key(visitor, &node.key);
+    visitor.visit_expression(&node.value);
+}
+
+pub fn walk_function_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast FunctionExpression,
+) {
+    if let Some(id) = &node.id {
+        visitor.visit_identifier(id);
+    }
+    for param in &node.params {
+        visitor.visit_pattern(param);
+    }
+    visitor.visit_function_body(&node.body);
+}
+
+pub fn walk_arrow_function_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ArrowFunctionExpression,
+) {
+    for param in &node.params {
+        visitor.visit_pattern(param);
+    }
+    match &node.body {
+        ArrowFunctionExpressionBody::FunctionBody(body) => visitor.visit_function_body(body),
+        ArrowFunctionExpressionBody::Expression(expression) => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_class_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ClassExpression,
+) {
+    if let Some(id) = &node.id {
+        visitor.visit_identifier(id);
+    }
+    if let Some(super_class) = &node.super_class {
+        visitor.visit_expression(super_class);
+    }
+    visitor.visit_class_body(&node.body);
+}
+
+pub fn walk_unary_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast UnaryExpression,
+) {
+    visitor.visit_expression(&node.argument);
+}
+
+pub fn walk_update_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast UpdateExpression,
+) {
+    visitor.visit_expression(&node.argument);
+}
+
+pub fn walk_binary_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast BinaryExpression,
+) {
+    visitor.visit_expression(&node.left);
+    visitor.visit_expression(&node.right);
+}
+
+pub fn walk_assignment_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast AssignmentExpression,
+) {
+    match &node.left {
+        AssignmentExpressionLeft::Pattern(pattern) => visitor.visit_pattern(pattern),
+        AssignmentExpressionLeft::Expression(expression) => visitor.visit_expression(expression),
+    }
+    visitor.visit_expression(&node.right);
+}
+
+pub fn walk_logical_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast LogicalExpression,
+) {
+    visitor.visit_expression(&node.left);
+    visitor.visit_expression(&node.right);
+}
+
+pub fn walk_member_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast MemberExpression,
+) {
+    visitor.visit_expression(&node.object);
+    visitor.visit_expression(&node.property);
+}
+
+pub fn walk_conditional_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ConditionalExpression,
+) {
+    visitor.visit_expression(&node.test);
+    visitor.visit_expression(&node.consequent);
+    visitor.visit_expression(&node.alternate);
+}
+
+fn walk_arguments<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    arguments: &'ast [CallExpressionArgument],
+) {
+    for argument in arguments {
+        match argument {
+            CallExpressionArgument::Expression(expression) => visitor.visit_expression(expression),
+            CallExpressionArgument::SpreadElement(spread) => visitor.visit_spread_element(spread),
+        }
+    }
+}
+
+pub fn walk_call_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast CallExpression,
+) {
+    visitor.visit_expression(&node.callee);
+    walk_arguments(visitor, &node.arguments);
+}
+
+pub fn walk_chain_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ChainExpression,
+) {
+    visitor.visit_expression(&node.expression);
+}
+
+pub fn walk_yield_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast YieldExpression,
+) {
+    if let Some(argument) = &node.argument {
+        visitor.visit_expression(argument);
+    }
+}
+
+// each expression sits between the quasis before and after it
+pub fn walk_template_literal<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast TemplateLiteral,
+) {
+    let mut expressions = node.expressions.iter();
+    for quasi in &node.quasis {
+        visitor.visit_template_element(quasi);
+        if let Some(expression) = expressions.next() {
+            visitor.visit_expression(expression);
+        }
+    }
+}
+
+pub fn walk_tagged_template_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast TaggedTemplateExpression,
+) {
+    visitor.visit_expression(&node.tag);
+    visitor.visit_template_literal(&node.quasi);
+}
+
+pub fn walk_spread_element<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SpreadElement,
+) {
+    visitor.visit_expression(&node.argument);
+}
+
+pub fn walk_new_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast NewExpression,
+) {
+    visitor.visit_expression(&node.callee);
+    walk_arguments(visitor, &node.arguments);
+}
+
+pub fn walk_meta_property<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast MetaProperty,
+) {
+    visitor.visit_identifier(&node.meta);
+    visitor.visit_identifier(&node.property);
+}
+
+pub fn walk_import_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ImportExpression,
+) {
+    visitor.visit_expression(&node.source);
+}
+
+pub fn walk_sequence_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast SequenceExpression,
+) {
+    for expression in &node.expressions {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_parenthesized_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ParenthesizedExpression,
+) {
+    visitor.visit_expression(&node.expression);
+}
+
+pub fn walk_await_expression<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast AwaitExpression,
+) {
+    visitor.visit_expression(&node.argument);
+}
+
+pub fn walk_object_pattern<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ObjectPattern,
+) {
+    for property in &node.properties {
+        match property {
+            ObjectPatternProperty::AssignmentProperty(property) => {
+                visitor.visit_assignment_property(property)
+            }
+            ObjectPatternProperty::RestElement(rest) => visitor.visit_rest_element(rest),
+        }
+    }
+}
+
+pub fn walk_array_pattern<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ArrayPattern,
+) {
+    for element in node.elements.iter().flatten() {
+        visitor.visit_pattern(element);
+    }
+}
+
+pub fn walk_rest_element<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast RestElement,
+) {
+    visitor.visit_pattern(&node.argument);
+}
+
+pub fn walk_assignment_property<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast AssignmentProperty,
+) {
+    walk_property_key(visitor, &node.key);
+    visitor.visit_pattern(&node.value);
+}
+
+pub fn walk_assignment_pattern<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast AssignmentPattern,
+) {
+    visitor.visit_pattern(&node.left);
+    visitor.visit_expression(&node.right);
+}
+
+pub fn walk_class_body<'ast, V: Visitor<'ast> + ?Sized>(visitor: &mut V, node: &'ast ClassBody) {
+    for element in &node.body {
+        match element {
+            ClassElement::MethodDefinition(method) => visitor.visit_method_definition(method),
+            ClassElement::PropertyDefinition(property) => {
+                visitor.visit_property_definition(property)
+            }
+            ClassElement::StaticBlock(block) => visitor.visit_static_block(block),
+        }
+    }
+}
+
+pub fn walk_method_definition<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast MethodDefinition,
+) {
+    visitor.visit_expression(&node.key);
+    visitor.visit_function_expression(&node.value);
+}
+
+pub fn walk_property_definition<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast PropertyDefinition,
+) {
+    visitor.visit_expression(&node.key);
+    if let Some(value) = &node.value {
+        visitor.visit_expression(value);
+    }
+}
+
+pub fn walk_static_block<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast StaticBlock,
+) {
+    for statement in &node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_class_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ClassDeclaration,
+) {
+    if let Some(id) = &node.id {
+        visitor.visit_identifier(id);
+    }
+    if let Some(super_class) = &node.super_class {
+        visitor.visit_expression(super_class);
+    }
+    visitor.visit_class_body(&node.body);
+}
+
+pub fn walk_import_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ImportDeclaration,
+) {
+    for specifier in &node.specifiers {
+        match specifier {
+            ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
+                visitor.visit_import_specifier(specifier)
+            }
+            ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier) => {
+                visitor.visit_import_default_specifier(specifier)
+            }
+            ImportDeclarationSpecifier::ImportNamespaceSpecifier(specifier) => {
+                visitor.visit_import_namespace_specifier(specifier)
+            }
+        }
+    }
+    visitor.visit_literal(&node.source);
+}
+
+pub fn walk_import_specifier<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ImportSpecifier,
+) {
+    visitor.visit_identifier(&node.imported);
+    visitor.visit_identifier(&node.local);
+}
+
+pub fn walk_import_default_specifier<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ImportDefaultSpecifier,
+) {
+    visitor.visit_identifier(&node.local);
+}
+
+pub fn walk_import_namespace_specifier<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ImportNamespaceSpecifier,
+) {
+    visitor.visit_identifier(&node.local);
+}
+
+pub fn walk_export_named_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ExportNamedDeclaration,
+) {
+    if let Some(declaration) = &node.declaration {
+        visitor.visit_statement(declaration);
+    }
+    for specifier in &node.specifiers {
+        visitor.visit_export_specifier(specifier);
+    }
+    if let Some(source) = &node.source {
+        visitor.visit_literal(source);
+    }
+}
+
+pub fn walk_export_specifier<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ExportSpecifier,
+) {
+    visitor.visit_identifier(&node.local);
+    visitor.visit_identifier(&node.exported);
+}
+
+pub fn walk_export_default_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ExportDefaultDeclaration,
+) {
+    match &node.declaration {
+        ExportDefaultDeclarationKind::FunctionDeclaration(function) => {
+            visitor.visit_function_declaration(function)
+        }
+        ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+            visitor.visit_class_declaration(class)
+        }
+        ExportDefaultDeclarationKind::Expression(expression) => {
+            visitor.visit_expression(expression)
+        }
+    }
+}
+
+// `export * as name from "source"`
+pub fn walk_export_all_declaration<'ast, V: Visitor<'ast> + ?Sized>(
+    visitor: &mut V,
+    node: &'ast ExportAllDeclaration,
+) {
+    if let Some(exported) = &node.exported {
+        visitor.visit_identifier(exported);
+    }
+    visitor.visit_literal(&node.source);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::{
+            BinaryExpression, BinaryOperator, CallExpression, Expression, Identifier, Node,
+            SourceLocation,
+        },
+        parser::parse,
+        test_support::README_SRC,
+        visit::{walk_call_expression, walk_expression, walk_program, Visitor},
+    };
+
+    #[test]
+    fn visitors_can_count_calls() {
+        struct CallCounter(usize);
+
+        impl<'ast> Visitor<'ast> for CallCounter {
+            fn visit_call_expression(&mut self, node: &'ast CallExpression) {
+                self.0 += 1;
+                walk_call_expression(self, node);
+            }
+        }
+
+        let mut counter = CallCounter(0);
+        walk_program(
+            &mut counter,
+            &parse("f(g(1), () => h()); new F(i());").unwrap(),
+        );
+        assert_eq!(counter.0, 4);
+    }
+
+    #[test]
+    fn visitors_walk_in_pre_order_and_source_order() {
+        #[derive(Default)]
+        struct Order<'ast> {
+            names: Vec<&'ast str>,
+            starts: Vec<usize>,
+        }
+
+        impl<'ast> Visitor<'ast> for Order<'ast> {
+            fn visit_expression(&mut self, node: &'ast Expression) {
+                self.starts.push(node.loc().range.0);
+                walk_expression(self, node);
+            }
+
+            fn visit_identifier(&mut self, node: &'ast Identifier) {
+                self.names.push(&node.name);
+            }
+        }
+
+        let program = parse("a ? f(b, ...c) : d[e] = `${g}${h}`; do i; while (j);").unwrap();
+        let mut order = Order::default();
+        walk_program(&mut order, &program);
+        assert_eq!(
+            order.names,
+            ["a", "f", "b", "c", "d", "e", "g", "h", "i", "j"]
+        );
+
+        let program = parse(README_SRC).unwrap();
+        let mut order = Order::default();
+        walk_program(&mut order, &program);
+        assert!(order.starts.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    #[test]
+    fn visitors_do_not_overflow_the_stack_on_deep_trees() {
+        struct Leaves(usize);
+
+        impl<'ast> Visitor<'ast> for Leaves {
+            fn visit_identifier(&mut self, _node: &'ast Identifier) {
+                self.0 += 1;
+            }
+        }
+
+        let leaf = || {
+            Expression::Identifier(Box::new(Identifier::new(
+                "a".to_string(),
+                SourceLocation::new(1, 0, 0),
+            )))
+        };
+        let mut expression = leaf();
+        for _ in 0..100_000 {
+            expression = Expression::Binary(Box::new(BinaryExpression::new(
+                BinaryOperator::Plus,
+                expression,
+                leaf(),
+                SourceLocation::new(1, 0, 0),
+            )));
+        }
+        let mut leaves = Leaves(0);
+        leaves.visit_expression(&expression);
+        assert_eq!(leaves.0, 100_001);
+        // dropping the chain would recurse as deeply as walking it did
+        std::mem::forget(expression);
+    }
+}
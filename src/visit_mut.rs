@@ -0,0 +1,1131 @@
+// Traversal that may rewrite the tree, the `&mut` counterpart of `Visitor`
+// with the same order, the same guard against deep trees, and `walk_*`
+// functions of its own for the children.
+//
+// A node can only be swapped for one of another kind where the tree allows
+// either, so replacements happen in `visit_statement`, `visit_expression` and
+// `visit_pattern`, which hold the enum rather than the node inside it:
+// folding `1 + 2` assigns an `Expression::Literal` over the
+// `Expression::Binary` it was given.
+use crate::node::{
+    ArrayExpression, ArrayExpressionElement, ArrayPattern, ArrowFunctionExpression,
+    ArrowFunctionExpressionBody, AssignmentExpression, AssignmentExpressionLeft, AssignmentPattern,
+    AssignmentProperty, AwaitExpression, BinaryExpression, BlockStatement, BreakStatement,
+    CallExpression, CallExpressionArgument, CatchClause, ChainExpression, ClassBody,
+    ClassDeclaration, ClassElement, ClassExpression, ConditionalExpression, ContinueStatement,
+    DebuggerStatement, Directive, DoWhileStatement, EmptyStatement, ErrorNode,
+    ExportAllDeclaration, ExportDefaultDeclaration, ExportDefaultDeclarationKind,
+    ExportNamedDeclaration, ExportSpecifier, Expression, ExpressionStatement, ForInStatement,
+    ForInStatementLeft, ForOfStatement, ForStatement, ForStatementInit, FunctionBody,
+    FunctionDeclaration, FunctionExpression, Identifier, IfStatement, ImportDeclaration,
+    ImportDeclarationSpecifier, ImportDefaultSpecifier, ImportExpression, ImportNamespaceSpecifier,
+    ImportSpecifier, LabeledStatement, Literal, LogicalExpression, MemberExpression, MetaProperty,
+    MethodDefinition, NewExpression, ObjectExpression, ObjectExpressionProperty, ObjectPattern,
+    ObjectPatternProperty, ParenthesizedExpression, Pattern, PrivateIdentifier, Program, Property,
+    PropertyDefinition, PropertyKey, RestElement, ReturnStatement, SequenceExpression,
+    SpreadElement, Statement, StaticBlock, Super, SwitchCase, SwitchStatement,
+    TaggedTemplateExpression, TemplateElement, TemplateLiteral, ThisExpression, ThrowStatement,
+    TryStatement, UnaryExpression, UpdateExpression, VariableDeclaration, VariableDeclarator,
+    WhileStatement, WithStatement, YieldExpression,
+};
+
+// left free on the stack before walking further, and the size of each
+// segment allocated once it's used up
+const RED_ZONE: usize = 64 * 1024;
+const STACK_SEGMENT: usize = 1024 * 1024;
+
+pub trait VisitorMut {
+    fn visit_program(&mut self, node: &mut Program) {
+        walk_program(self, node);
+    }
+
+    fn visit_statement(&mut self, node: &mut Statement) {
+        walk_statement(self, node);
+    }
+
+    fn visit_expression(&mut self, node: &mut Expression) {
+        walk_expression(self, node);
+    }
+
+    fn visit_pattern(&mut self, node: &mut Pattern) {
+        walk_pattern(self, node);
+    }
+
+    fn visit_identifier(&mut self, _node: &mut Identifier) {}
+
+    fn visit_literal(&mut self, _node: &mut Literal) {}
+
+    fn visit_expression_statement(&mut self, node: &mut ExpressionStatement) {
+        walk_expression_statement(self, node);
+    }
+
+    fn visit_directive(&mut self, node: &mut Directive) {
+        walk_directive(self, node);
+    }
+
+    fn visit_block_statement(&mut self, node: &mut BlockStatement) {
+        walk_block_statement(self, node);
+    }
+
+    fn visit_function_body(&mut self, node: &mut FunctionBody) {
+        walk_function_body(self, node);
+    }
+
+    fn visit_empty_statement(&mut self, _node: &mut EmptyStatement) {}
+
+    fn visit_debugger_statement(&mut self, _node: &mut DebuggerStatement) {}
+
+    fn visit_with_statement(&mut self, node: &mut WithStatement) {
+        walk_with_statement(self, node);
+    }
+
+    fn visit_return_statement(&mut self, node: &mut ReturnStatement) {
+        walk_return_statement(self, node);
+    }
+
+    fn visit_labeled_statement(&mut self, node: &mut LabeledStatement) {
+        walk_labeled_statement(self, node);
+    }
+
+    fn visit_break_statement(&mut self, node: &mut BreakStatement) {
+        walk_break_statement(self, node);
+    }
+
+    fn visit_continue_statement(&mut self, node: &mut ContinueStatement) {
+        walk_continue_statement(self, node);
+    }
+
+    fn visit_if_statement(&mut self, node: &mut IfStatement) {
+        walk_if_statement(self, node);
+    }
+
+    fn visit_switch_statement(&mut self, node: &mut SwitchStatement) {
+        walk_switch_statement(self, node);
+    }
+
+    fn visit_switch_case(&mut self, node: &mut SwitchCase) {
+        walk_switch_case(self, node);
+    }
+
+    fn visit_throw_statement(&mut self, node: &mut ThrowStatement) {
+        walk_throw_statement(self, node);
+    }
+
+    fn visit_try_statement(&mut self, node: &mut TryStatement) {
+        walk_try_statement(self, node);
+    }
+
+    fn visit_catch_clause(&mut self, node: &mut CatchClause) {
+        walk_catch_clause(self, node);
+    }
+
+    fn visit_while_statement(&mut self, node: &mut WhileStatement) {
+        walk_while_statement(self, node);
+    }
+
+    fn visit_do_while_statement(&mut self, node: &mut DoWhileStatement) {
+        walk_do_while_statement(self, node);
+    }
+
+    fn visit_for_statement(&mut self, node: &mut ForStatement) {
+        walk_for_statement(self, node);
+    }
+
+    fn visit_for_in_statement(&mut self, node: &mut ForInStatement) {
+        walk_for_in_statement(self, node);
+    }
+
+    fn visit_for_of_statement(&mut self, node: &mut ForOfStatement) {
+        walk_for_of_statement(self, node);
+    }
+
+    fn visit_function_declaration(&mut self, node: &mut FunctionDeclaration) {
+        walk_function_declaration(self, node);
+    }
+
+    fn visit_variable_declaration(&mut self, node: &mut VariableDeclaration) {
+        walk_variable_declaration(self, node);
+    }
+
+    fn visit_variable_declarator(&mut self, node: &mut VariableDeclarator) {
+        walk_variable_declarator(self, node);
+    }
+
+    fn visit_this_expression(&mut self, _node: &mut ThisExpression) {}
+
+    fn visit_super(&mut self, _node: &mut Super) {}
+
+    fn visit_array_expression(&mut self, node: &mut ArrayExpression) {
+        walk_array_expression(self, node);
+    }
+
+    fn visit_object_expression(&mut self, node: &mut ObjectExpression) {
+        walk_object_expression(self, node);
+    }
+
+    fn visit_property(&mut self, node: &mut Property) {
+        walk_property(self, node);
+    }
+
+    fn visit_function_expression(&mut self, node: &mut FunctionExpression) {
+        walk_function_expression(self, node);
+    }
+
+    fn visit_arrow_function_expression(&mut self, node: &mut ArrowFunctionExpression) {
+        walk_arrow_function_expression(self, node);
+    }
+
+    fn visit_class_expression(&mut self, node: &mut ClassExpression) {
+        walk_class_expression(self, node);
+    }
+
+    fn visit_unary_expression(&mut self, node: &mut UnaryExpression) {
+        walk_unary_expression(self, node);
+    }
+
+    fn visit_update_expression(&mut self, node: &mut UpdateExpression) {
+        walk_update_expression(self, node);
+    }
+
+    fn visit_binary_expression(&mut self, node: &mut BinaryExpression) {
+        walk_binary_expression(self, node);
+    }
+
+    fn visit_assignment_expression(&mut self, node: &mut AssignmentExpression) {
+        walk_assignment_expression(self, node);
+    }
+
+    fn visit_logical_expression(&mut self, node: &mut LogicalExpression) {
+        walk_logical_expression(self, node);
+    }
+
+    fn visit_member_expression(&mut self, node: &mut MemberExpression) {
+        walk_member_expression(self, node);
+    }
+
+    fn visit_conditional_expression(&mut self, node: &mut ConditionalExpression) {
+        walk_conditional_expression(self, node);
+    }
+
+    fn visit_call_expression(&mut self, node: &mut CallExpression) {
+        walk_call_expression(self, node);
+    }
+
+    fn visit_chain_expression(&mut self, node: &mut ChainExpression) {
+        walk_chain_expression(self, node);
+    }
+
+    fn visit_yield_expression(&mut self, node: &mut YieldExpression) {
+        walk_yield_expression(self, node);
+    }
+
+    fn visit_template_literal(&mut self, node: &mut TemplateLiteral) {
+        walk_template_literal(self, node);
+    }
+
+    fn visit_tagged_template_expression(&mut self, node: &mut TaggedTemplateExpression) {
+        walk_tagged_template_expression(self, node);
+    }
+
+    fn visit_template_element(&mut self, _node: &mut TemplateElement) {}
+
+    fn visit_spread_element(&mut self, node: &mut SpreadElement) {
+        walk_spread_element(self, node);
+    }
+
+    fn visit_new_expression(&mut self, node: &mut NewExpression) {
+        walk_new_expression(self, node);
+    }
+
+    fn visit_meta_property(&mut self, node: &mut MetaProperty) {
+        walk_meta_property(self, node);
+    }
+
+    fn visit_import_expression(&mut self, node: &mut ImportExpression) {
+        walk_import_expression(self, node);
+    }
+
+    fn visit_sequence_expression(&mut self, node: &mut SequenceExpression) {
+        walk_sequence_expression(self, node);
+    }
+
+    fn visit_parenthesized_expression(&mut self, node: &mut ParenthesizedExpression) {
+        walk_parenthesized_expression(self, node);
+    }
+
+    fn visit_await_expression(&mut self, node: &mut AwaitExpression) {
+        walk_await_expression(self, node);
+    }
+
+    fn visit_private_identifier(&mut self, _node: &mut PrivateIdentifier) {}
+
+    fn visit_object_pattern(&mut self, node: &mut ObjectPattern) {
+        walk_object_pattern(self, node);
+    }
+
+    fn visit_array_pattern(&mut self, node: &mut ArrayPattern) {
+        walk_array_pattern(self, node);
+    }
+
+    fn visit_rest_element(&mut self, node: &mut RestElement) {
+        walk_rest_element(self, node);
+    }
+
+    fn visit_assignment_property(&mut self, node: &mut AssignmentProperty) {
+        walk_assignment_property(self, node);
+    }
+
+    fn visit_assignment_pattern(&mut self, node: &mut AssignmentPattern) {
+        walk_assignment_pattern(self, node);
+    }
+
+    fn visit_class_body(&mut self, node: &mut ClassBody) {
+        walk_class_body(self, node);
+    }
+
+    fn visit_method_definition(&mut self, node: &mut MethodDefinition) {
+        walk_method_definition(self, node);
+    }
+
+    fn visit_property_definition(&mut self, node: &mut PropertyDefinition) {
+        walk_property_definition(self, node);
+    }
+
+    fn visit_static_block(&mut self, node: &mut StaticBlock) {
+        walk_static_block(self, node);
+    }
+
+    fn visit_class_declaration(&mut self, node: &mut ClassDeclaration) {
+        walk_class_declaration(self, node);
+    }
+
+    fn visit_import_declaration(&mut self, node: &mut ImportDeclaration) {
+        walk_import_declaration(self, node);
+    }
+
+    fn visit_import_specifier(&mut self, node: &mut ImportSpecifier) {
+        walk_import_specifier(self, node);
+    }
+
+    fn visit_import_default_specifier(&mut self, node: &mut ImportDefaultSpecifier) {
+        walk_import_default_specifier(self, node);
+    }
+
+    fn visit_import_namespace_specifier(&mut self, node: &mut ImportNamespaceSpecifier) {
+        walk_import_namespace_specifier(self, node);
+    }
+
+    fn visit_export_named_declaration(&mut self, node: &mut ExportNamedDeclaration) {
+        walk_export_named_declaration(self, node);
+    }
+
+    fn visit_export_specifier(&mut self, node: &mut ExportSpecifier) {
+        walk_export_specifier(self, node);
+    }
+
+    fn visit_export_default_declaration(&mut self, node: &mut ExportDefaultDeclaration) {
+        walk_export_default_declaration(self, node);
+    }
+
+    fn visit_export_all_declaration(&mut self, node: &mut ExportAllDeclaration) {
+        walk_export_all_declaration(self, node);
+    }
+
+    fn visit_error_node(&mut self, _node: &mut ErrorNode) {}
+}
+
+pub fn walk_program<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Program) {
+    for statement in &mut node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Statement) {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match node {
+        Statement::Expression(node) => visitor.visit_expression_statement(node),
+        Statement::Directive(node) => visitor.visit_directive(node),
+        Statement::Block(node) => visitor.visit_block_statement(node),
+        Statement::Empty(node) => visitor.visit_empty_statement(node),
+        Statement::Debugger(node) => visitor.visit_debugger_statement(node),
+        Statement::With(node) => visitor.visit_with_statement(node),
+        Statement::Return(node) => visitor.visit_return_statement(node),
+        Statement::Labeled(node) => visitor.visit_labeled_statement(node),
+        Statement::Break(node) => visitor.visit_break_statement(node),
+        Statement::Continue(node) => visitor.visit_continue_statement(node),
+        Statement::If(node) => visitor.visit_if_statement(node),
+        Statement::Switch(node) => visitor.visit_switch_statement(node),
+        Statement::Throw(node) => visitor.visit_throw_statement(node),
+        Statement::Try(node) => visitor.visit_try_statement(node),
+        Statement::While(node) => visitor.visit_while_statement(node),
+        Statement::DoWhile(node) => visitor.visit_do_while_statement(node),
+        Statement::For(node) => visitor.visit_for_statement(node),
+        Statement::ForIn(node) => visitor.visit_for_in_statement(node),
+        Statement::ForOf(node) => visitor.visit_for_of_statement(node),
+        Statement::FunctionDeclaration(node) => visitor.visit_function_declaration(node),
+        Statement::VariableDeclaration(node) => visitor.visit_variable_declaration(node),
+        Statement::ClassDeclaration(node) => visitor.visit_class_declaration(node),
+        Statement::ImportDeclaration(node) => visitor.visit_import_declaration(node),
+        Statement::ExportNamedDeclaration(node) => visitor.visit_export_named_declaration(node),
+        Statement::ExportDefaultDeclaration(node) => visitor.visit_export_default_declaration(node),
+        Statement::ExportAllDeclaration(node) => visitor.visit_export_all_declaration(node),
+        Statement::Error(node) => visitor.visit_error_node(node),
+    })
+}
+
+pub fn walk_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Expression) {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match node {
+        Expression::Identifier(node) => visitor.visit_identifier(node),
+        Expression::Literal(node) => visitor.visit_literal(node),
+        Expression::This(node) => visitor.visit_this_expression(node),
+        Expression::Super(node) => visitor.visit_super(node),
+        Expression::Array(node) => visitor.visit_array_expression(node),
+        Expression::Object(node) => visitor.visit_object_expression(node),
+        Expression::Function(node) => visitor.visit_function_expression(node),
+        Expression::ArrowFunction(node) => visitor.visit_arrow_function_expression(node),
+        Expression::Class(node) => visitor.visit_class_expression(node),
+        Expression::Unary(node) => visitor.visit_unary_expression(node),
+        Expression::Update(node) => visitor.visit_update_expression(node),
+        Expression::Binary(node) => visitor.visit_binary_expression(node),
+        Expression::Assignment(node) => visitor.visit_assignment_expression(node),
+        Expression::Logical(node) => visitor.visit_logical_expression(node),
+        Expression::Member(node) => visitor.visit_member_expression(node),
+        Expression::Conditional(node) => visitor.visit_conditional_expression(node),
+        Expression::Call(node) => visitor.visit_call_expression(node),
+        Expression::Chain(node) => visitor.visit_chain_expression(node),
+        Expression::Yield(node) => visitor.visit_yield_expression(node),
+        Expression::TemplateLiteral(node) => visitor.visit_template_literal(node),
+        Expression::TaggedTemplate(node) => visitor.visit_tagged_template_expression(node),
+        Expression::New(node) => visitor.visit_new_expression(node),
+        Expression::MetaProperty(node) => visitor.visit_meta_property(node),
+        Expression::Import(node) => visitor.visit_import_expression(node),
+        Expression::Sequence(node) => visitor.visit_sequence_expression(node),
+        Expression::Parenthesized(node) => visitor.visit_parenthesized_expression(node),
+        Expression::Await(node) => visitor.visit_await_expression(node),
+        Expression::PrivateIdentifier(node) => visitor.visit_private_identifier(node),
+        Expression::Error(node) => visitor.visit_error_node(node),
+    })
+}
+
+pub fn walk_pattern<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Pattern) {
+    stacker::maybe_grow(RED_ZONE, STACK_SEGMENT, || match node {
+        Pattern::Identifier(node) => visitor.visit_identifier(node),
+        Pattern::Member(node) => visitor.visit_member_expression(node),
+        Pattern::Object(node) => visitor.visit_object_pattern(node),
+        Pattern::Array(node) => visitor.visit_array_pattern(node),
+        Pattern::Rest(node) => visitor.visit_rest_element(node),
+        Pattern::Assignment(node) => visitor.visit_assignment_pattern(node),
+        Pattern::Error(node) => visitor.visit_error_node(node),
+    })
+}
+
+pub fn walk_expression_statement<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ExpressionStatement,
+) {
+    visitor.visit_expression(&mut node.expression);
+}
+
+pub fn walk_directive<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Directive) {
+    visitor.visit_literal(&mut node.expression);
+}
+
+pub fn walk_block_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut BlockStatement) {
+    for statement in &mut node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_function_body<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut FunctionBody) {
+    for statement in &mut node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_with_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut WithStatement) {
+    visitor.visit_expression(&mut node.object);
+    visitor.visit_statement(&mut node.body);
+}
+
+pub fn walk_return_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ReturnStatement) {
+    if let Some(argument) = &mut node.argument {
+        visitor.visit_expression(argument);
+    }
+}
+
+pub fn walk_labeled_statement<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut LabeledStatement,
+) {
+    visitor.visit_identifier(&mut node.label);
+    visitor.visit_statement(&mut node.body);
+}
+
+pub fn walk_break_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut BreakStatement) {
+    if let Some(label) = &mut node.label {
+        visitor.visit_identifier(label);
+    }
+}
+
+pub fn walk_continue_statement<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ContinueStatement,
+) {
+    if let Some(label) = &mut node.label {
+        visitor.visit_identifier(label);
+    }
+}
+
+pub fn walk_if_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut IfStatement) {
+    visitor.visit_expression(&mut node.test);
+    visitor.visit_statement(&mut node.consequent);
+    if let Some(alternate) = &mut node.alternate {
+        visitor.visit_statement(alternate);
+    }
+}
+
+pub fn walk_switch_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut SwitchStatement) {
+    visitor.visit_expression(&mut node.discriminant);
+    for case in &mut node.cases {
+        visitor.visit_switch_case(case);
+    }
+}
+
+pub fn walk_switch_case<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut SwitchCase) {
+    if let Some(test) = &mut node.test {
+        visitor.visit_expression(test);
+    }
+    for statement in &mut node.consequent {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_throw_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ThrowStatement) {
+    visitor.visit_expression(&mut node.argument);
+}
+
+pub fn walk_try_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut TryStatement) {
+    visitor.visit_block_statement(&mut node.block);
+    if let Some(handler) = &mut node.handler {
+        visitor.visit_catch_clause(handler);
+    }
+    if let Some(finalizer) = &mut node.finalizer {
+        visitor.visit_block_statement(finalizer);
+    }
+}
+
+pub fn walk_catch_clause<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut CatchClause) {
+    if let Some(param) = &mut node.param {
+        visitor.visit_pattern(param);
+    }
+    visitor.visit_block_statement(&mut node.body);
+}
+
+pub fn walk_while_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut WhileStatement) {
+    visitor.visit_expression(&mut node.test);
+    visitor.visit_statement(&mut node.body);
+}
+
+// the body of `do ... while` comes before its test
+pub fn walk_do_while_statement<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut DoWhileStatement,
+) {
+    visitor.visit_statement(&mut node.body);
+    visitor.visit_expression(&mut node.test);
+}
+
+pub fn walk_for_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ForStatement) {
+    match &mut node.init {
+        Some(ForStatementInit::VariableDeclaration(declaration)) => {
+            visitor.visit_variable_declaration(declaration)
+        }
+        Some(ForStatementInit::Expression(expression)) => visitor.visit_expression(expression),
+        None => {}
+    }
+    if let Some(test) = &mut node.test {
+        visitor.visit_expression(test);
+    }
+    if let Some(update) = &mut node.update {
+        visitor.visit_expression(update);
+    }
+    visitor.visit_statement(&mut node.body);
+}
+
+fn walk_for_in_statement_left<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ForInStatementLeft,
+) {
+    match node {
+        ForInStatementLeft::VariableDeclaration(declaration) => {
+            visitor.visit_variable_declaration(declaration)
+        }
+        ForInStatementLeft::Pattern(pattern) => visitor.visit_pattern(pattern),
+    }
+}
+
+pub fn walk_for_in_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ForInStatement) {
+    walk_for_in_statement_left(visitor, &mut node.left);
+    visitor.visit_expression(&mut node.right);
+    visitor.visit_statement(&mut node.body);
+}
+
+pub fn walk_for_of_statement<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ForOfStatement) {
+    walk_for_in_statement_left(visitor, &mut node.left);
+    visitor.visit_expression(&mut node.right);
+    visitor.visit_statement(&mut node.body);
+}
+
+pub fn walk_function_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut FunctionDeclaration,
+) {
+    if let Some(id) = &mut node.id {
+        visitor.visit_identifier(id);
+    }
+    for param in &mut node.params {
+        visitor.visit_pattern(param);
+    }
+    visitor.visit_function_body(&mut node.body);
+}
+
+pub fn walk_variable_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut VariableDeclaration,
+) {
+    for declarator in &mut node.declarations {
+        visitor.visit_variable_declarator(declarator);
+    }
+}
+
+pub fn walk_variable_declarator<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut VariableDeclarator,
+) {
+    visitor.visit_pattern(&mut node.id);
+    if let Some(init) = &mut node.init {
+        visitor.visit_expression(init);
+    }
+}
+
+pub fn walk_array_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ArrayExpression) {
+    for element in node.elements.iter_mut().flatten() {
+        match element {
+            ArrayExpressionElement::Expression(expression) => visitor.visit_expression(expression),
+            ArrayExpressionElement::SpreadElement(spread) => visitor.visit_spread_element(spread),
+        }
+    }
+}
+
+pub fn walk_object_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ObjectExpression,
+) {
+    for property in &mut node.properties {
+        match property {
+            ObjectExpressionProperty::Property(property) => visitor.visit_property(property),
+            ObjectExpressionProperty::SpreadElement(spread) => visitor.visit_spread_element(spread),
+        }
+    }
+}
+
+fn walk_property_key<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut PropertyKey) {
+    match node {
+        PropertyKey::Literal(literal) => visitor.visit_literal(literal),
+        PropertyKey::Identifier(identifier) => visitor.visit_identifier(identifier),
+        PropertyKey::Expression(expression) => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_property<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut Property) {
+    walk_property_key(visitor, &mut node.key);
+    visitor.visit_expression(&mut node.value);
+}
+
+pub fn walk_function_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut FunctionExpression,
+) {
+    if let Some(id) = &mut node.id {
+        visitor.visit_identifier(id);
+    }
+    for param in &mut node.params {
+        visitor.visit_pattern(param);
+    }
+    visitor.visit_function_body(&mut node.body);
+}
+
+pub fn walk_arrow_function_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ArrowFunctionExpression,
+) {
+    for param in &mut node.params {
+        visitor.visit_pattern(param);
+    }
+    match &mut node.body {
+        ArrowFunctionExpressionBody::FunctionBody(body) => visitor.visit_function_body(body),
+        ArrowFunctionExpressionBody::Expression(expression) => visitor.visit_expression(expression),
+    }
+}
+
+pub fn walk_class_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ClassExpression) {
+    if let Some(id) = &mut node.id {
+        visitor.visit_identifier(id);
+    }
+    if let Some(super_class) = &mut node.super_class {
+        visitor.visit_expression(super_class);
+    }
+    visitor.visit_class_body(&mut node.body);
+}
+
+pub fn walk_unary_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut UnaryExpression) {
+    visitor.visit_expression(&mut node.argument);
+}
+
+pub fn walk_update_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut UpdateExpression,
+) {
+    visitor.visit_expression(&mut node.argument);
+}
+
+pub fn walk_binary_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut BinaryExpression,
+) {
+    visitor.visit_expression(&mut node.left);
+    visitor.visit_expression(&mut node.right);
+}
+
+pub fn walk_assignment_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut AssignmentExpression,
+) {
+    match &mut node.left {
+        AssignmentExpressionLeft::Pattern(pattern) => visitor.visit_pattern(pattern),
+        AssignmentExpressionLeft::Expression(expression) => visitor.visit_expression(expression),
+    }
+    visitor.visit_expression(&mut node.right);
+}
+
+pub fn walk_logical_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut LogicalExpression,
+) {
+    visitor.visit_expression(&mut node.left);
+    visitor.visit_expression(&mut node.right);
+}
+
+pub fn walk_member_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut MemberExpression,
+) {
+    visitor.visit_expression(&mut node.object);
+    visitor.visit_expression(&mut node.property);
+}
+
+pub fn walk_conditional_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ConditionalExpression,
+) {
+    visitor.visit_expression(&mut node.test);
+    visitor.visit_expression(&mut node.consequent);
+    visitor.visit_expression(&mut node.alternate);
+}
+
+fn walk_arguments<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    arguments: &mut [CallExpressionArgument],
+) {
+    for argument in arguments {
+        match argument {
+            CallExpressionArgument::Expression(expression) => visitor.visit_expression(expression),
+            CallExpressionArgument::SpreadElement(spread) => visitor.visit_spread_element(spread),
+        }
+    }
+}
+
+pub fn walk_call_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut CallExpression) {
+    visitor.visit_expression(&mut node.callee);
+    walk_arguments(visitor, &mut node.arguments);
+}
+
+pub fn walk_chain_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ChainExpression) {
+    visitor.visit_expression(&mut node.expression);
+}
+
+pub fn walk_yield_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut YieldExpression) {
+    if let Some(argument) = &mut node.argument {
+        visitor.visit_expression(argument);
+    }
+}
+
+// each expression sits between the quasis before and after it
+pub fn walk_template_literal<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut TemplateLiteral) {
+    let mut expressions = node.expressions.iter_mut();
+    for quasi in &mut node.quasis {
+        visitor.visit_template_element(quasi);
+        if let Some(expression) = expressions.next() {
+            visitor.visit_expression(expression);
+        }
+    }
+}
+
+pub fn walk_tagged_template_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut TaggedTemplateExpression,
+) {
+    visitor.visit_expression(&mut node.tag);
+    visitor.visit_template_literal(&mut node.quasi);
+}
+
+pub fn walk_spread_element<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut SpreadElement) {
+    visitor.visit_expression(&mut node.argument);
+}
+
+pub fn walk_new_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut NewExpression) {
+    visitor.visit_expression(&mut node.callee);
+    walk_arguments(visitor, &mut node.arguments);
+}
+
+pub fn walk_meta_property<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut MetaProperty) {
+    visitor.visit_identifier(&mut node.meta);
+    visitor.visit_identifier(&mut node.property);
+}
+
+pub fn walk_import_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ImportExpression,
+) {
+    visitor.visit_expression(&mut node.source);
+}
+
+pub fn walk_sequence_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut SequenceExpression,
+) {
+    for expression in &mut node.expressions {
+        visitor.visit_expression(expression);
+    }
+}
+
+pub fn walk_parenthesized_expression<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ParenthesizedExpression,
+) {
+    visitor.visit_expression(&mut node.expression);
+}
+
+pub fn walk_await_expression<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut AwaitExpression) {
+    visitor.visit_expression(&mut node.argument);
+}
+
+pub fn walk_object_pattern<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ObjectPattern) {
+    for property in &mut node.properties {
+        match property {
+            ObjectPatternProperty::AssignmentProperty(property) => {
+                visitor.visit_assignment_property(property)
+            }
+            ObjectPatternProperty::RestElement(rest) => visitor.visit_rest_element(rest),
+        }
+    }
+}
+
+pub fn walk_array_pattern<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ArrayPattern) {
+    for element in node.elements.iter_mut().flatten() {
+        visitor.visit_pattern(element);
+    }
+}
+
+pub fn walk_rest_element<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut RestElement) {
+    visitor.visit_pattern(&mut node.argument);
+}
+
+pub fn walk_assignment_property<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut AssignmentProperty,
+) {
+    walk_property_key(visitor, &mut node.key);
+    visitor.visit_pattern(&mut node.value);
+}
+
+pub fn walk_assignment_pattern<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut AssignmentPattern,
+) {
+    visitor.visit_pattern(&mut node.left);
+    visitor.visit_expression(&mut node.right);
+}
+
+pub fn walk_class_body<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ClassBody) {
+    for element in &mut node.body {
+        match element {
+            ClassElement::MethodDefinition(method) => visitor.visit_method_definition(method),
+            ClassElement::PropertyDefinition(property) => {
+                visitor.visit_property_definition(property)
+            }
+            ClassElement::StaticBlock(block) => visitor.visit_static_block(block),
+        }
+    }
+}
+
+pub fn walk_method_definition<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut MethodDefinition,
+) {
+    visitor.visit_expression(&mut node.key);
+    visitor.visit_function_expression(&mut node.value);
+}
+
+pub fn walk_property_definition<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut PropertyDefinition,
+) {
+    visitor.visit_expression(&mut node.key);
+    if let Some(value) = &mut node.value {
+        visitor.visit_expression(value);
+    }
+}
+
+pub fn walk_static_block<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut StaticBlock) {
+    for statement in &mut node.body {
+        visitor.visit_statement(statement);
+    }
+}
+
+pub fn walk_class_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ClassDeclaration,
+) {
+    if let Some(id) = &mut node.id {
+        visitor.visit_identifier(id);
+    }
+    if let Some(super_class) = &mut node.super_class {
+        visitor.visit_expression(super_class);
+    }
+    visitor.visit_class_body(&mut node.body);
+}
+
+pub fn walk_import_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ImportDeclaration,
+) {
+    for specifier in &mut node.specifiers {
+        match specifier {
+            ImportDeclarationSpecifier::ImportSpecifier(specifier) => {
+                visitor.visit_import_specifier(specifier)
+            }
+            ImportDeclarationSpecifier::ImportDefaultSpecifier(specifier) => {
+                visitor.visit_import_default_specifier(specifier)
+            }
+            ImportDeclarationSpecifier::ImportNamespaceSpecifier(specifier) => {
+                visitor.visit_import_namespace_specifier(specifier)
+            }
+        }
+    }
+    visitor.visit_literal(&mut node.source);
+}
+
+pub fn walk_import_specifier<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ImportSpecifier) {
+    visitor.visit_identifier(&mut node.imported);
+    visitor.visit_identifier(&mut node.local);
+}
+
+pub fn walk_import_default_specifier<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ImportDefaultSpecifier,
+) {
+    visitor.visit_identifier(&mut node.local);
+}
+
+pub fn walk_import_namespace_specifier<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ImportNamespaceSpecifier,
+) {
+    visitor.visit_identifier(&mut node.local);
+}
+
+pub fn walk_export_named_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ExportNamedDeclaration,
+) {
+    if let Some(declaration) = &mut node.declaration {
+        visitor.visit_statement(declaration);
+    }
+    for specifier in &mut node.specifiers {
+        visitor.visit_export_specifier(specifier);
+    }
+    if let Some(source) = &mut node.source {
+        visitor.visit_literal(source);
+    }
+}
+
+pub fn walk_export_specifier<V: VisitorMut + ?Sized>(visitor: &mut V, node: &mut ExportSpecifier) {
+    visitor.visit_identifier(&mut node.local);
+    visitor.visit_identifier(&mut node.exported);
+}
+
+pub fn walk_export_default_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ExportDefaultDeclaration,
+) {
+    match &mut node.declaration {
+        ExportDefaultDeclarationKind::FunctionDeclaration(function) => {
+            visitor.visit_function_declaration(function)
+        }
+        ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+            visitor.visit_class_declaration(class)
+        }
+        ExportDefaultDeclarationKind::Expression(expression) => {
+            visitor.visit_expression(expression)
+        }
+    }
+}
+
+// `export * as name from "source"`
+pub fn walk_export_all_declaration<V: VisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ExportAllDeclaration,
+) {
+    if let Some(exported) = &mut node.exported {
+        visitor.visit_identifier(exported);
+    }
+    visitor.visit_literal(&mut node.source);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        node::{
+            BinaryOperator, EmptyStatement, Expression, Identifier, Literal, LiteralValue, Node,
+            Statement,
+        },
+        parser::parse,
+        visit::{walk_program, Visitor},
+        visit_mut::{self, VisitorMut},
+    };
+
+    #[derive(Default)]
+    struct NameCollector<'ast>(Vec<&'ast str>);
+
+    impl<'ast> Visitor<'ast> for NameCollector<'ast> {
+        fn visit_identifier(&mut self, node: &'ast Identifier) {
+            self.0.push(&node.name);
+        }
+    }
+
+    #[test]
+    fn mutable_visitors_can_rename_identifiers() {
+        struct Rename;
+
+        impl VisitorMut for Rename {
+            fn visit_identifier(&mut self, node: &mut Identifier) {
+                if node.name == "foo" {
+                    node.name = "bar".into();
+                }
+            }
+        }
+
+        let mut program = parse("foo(foo.foo, { foo });").unwrap();
+        Rename.visit_program(&mut program);
+        let mut names = NameCollector::default();
+        walk_program(&mut names, &program);
+        assert_eq!(names.0, ["bar", "bar", "bar", "bar", "bar"]);
+    }
+
+    #[test]
+    fn mutable_visitors_can_replace_statements() {
+        struct DropLogging;
+
+        impl VisitorMut for DropLogging {
+            fn visit_statement(&mut self, node: &mut Statement) {
+                let is_logging = match node {
+                    Statement::Expression(statement) => match &statement.expression {
+                        Expression::Call(call) => match &call.callee {
+                            Expression::Member(member) => {
+                                matches!(&member.object, Expression::Identifier(object) if object.name == "console")
+                            }
+                            _ => false,
+                        },
+                        _ => false,
+                    },
+                    _ => false,
+                };
+                if is_logging {
+                    *node = Statement::Empty(Box::new(EmptyStatement::new(node.loc().clone())));
+                } else {
+                    visit_mut::walk_statement(self, node);
+                }
+            }
+        }
+
+        let mut program = parse("if (a) { console.log(a); b(); } console.error(c);").unwrap();
+        DropLogging.visit_program(&mut program);
+        assert!(matches!(program.body[1], Statement::Empty(_)));
+        let mut names = NameCollector::default();
+        walk_program(&mut names, &program);
+        assert_eq!(names.0, ["a", "b"]);
+    }
+
+    // a node is replaced by one of another kind through the enum holding it:
+    // the children are folded first, so that `1 + 2 * 3` becomes `1 + 6` and
+    // then `7`, and the `Expression::Binary` is overwritten with an
+    // `Expression::Literal`
+    #[test]
+    fn mutable_visitors_can_fold_constants() {
+        struct Fold;
+
+        impl VisitorMut for Fold {
+            fn visit_expression(&mut self, node: &mut Expression) {
+                visit_mut::walk_expression(self, node);
+                let Expression::Binary(binary) = node else {
+                    return;
+                };
+                let value = match (&binary.operator, &binary.left, &binary.right) {
+                    (operator, Expression::Literal(left), Expression::Literal(right)) => {
+                        match (operator, &left.value, &right.value) {
+                            (
+                                BinaryOperator::Plus,
+                                LiteralValue::Number(left),
+                                LiteralValue::Number(right),
+                            ) => left + right,
+                            (
+                                BinaryOperator::Multiple,
+                                LiteralValue::Number(left),
+                                LiteralValue::Number(right),
+                            ) => left * right,
+                            _ => return,
+                        }
+                    }
+                    _ => return,
+                };
+                let literal = Literal::new(
+                    LiteralValue::Number(value),
+                    value.to_string(),
+                    binary.loc.clone(),
+                );
+                *node = Expression::Literal(Box::new(literal));
+            }
+        }
+
+        let mut program = parse("x = 1 + 2 * 3 + y;").unwrap();
+        Fold.visit_program(&mut program);
+        let assignment = match &program.body[0] {
+            Statement::Expression(statement) => match &statement.expression {
+                Expression::Assignment(assignment) => assignment,
+                _ => panic!("expected an assignment"),
+            },
+            _ => panic!("expected an expression statement"),
+        };
+        let binary = match &assignment.right {
+            Expression::Binary(binary) => binary,
+            _ => panic!("expected a binary expression"),
+        };
+        match &binary.left {
+            Expression::Literal(literal) => {
+                assert_eq!(literal.value, LiteralValue::Number(7.0));
+                assert_eq!(literal.loc.range, (4, 13));
+            }
+            _ => panic!("expected a literal"),
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// `parse` for JavaScript, built with `wasm-pack build --features wasm`:
+//
+//   import { parse } from "js_parser";
+//   const program = parse("a = 1", { sourceType: "module" });
+//
+// The program comes back as the same ESTree object the `serde` feature
+// writes as JSON, and a syntax error is thrown as the object a `ParseError`
+// serializes to, with its code, message and start and end positions. Parsing
+// reports every error as a value, so nothing can panic across the boundary.
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    node::SourceType,
+    options::{ParserOptions, DEFAULT_MAX_NESTING, LATEST_ECMA_VERSION},
+    parser::parse_with_options,
+};
+
+// the options as JavaScript spells them; any of them may be left out
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct JsOptions {
+    source_type: SourceType,
+    ecma_version: u32,
+    allow_return_outside_function: bool,
+    preserve_parens: bool,
+    max_nesting: usize,
+}
+
+impl Default for JsOptions {
+    fn default() -> Self {
+        JsOptions {
+            source_type: SourceType::Script,
+            ecma_version: LATEST_ECMA_VERSION,
+            allow_return_outside_function: false,
+            preserve_parens: false,
+            max_nesting: DEFAULT_MAX_NESTING,
+        }
+    }
+}
+
+// plain objects rather than the `Map`s serde-wasm-bindgen makes by default,
+// so that the result looks like what `JSON.parse` would give
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    let serializer = serde_wasm_bindgen::Serializer::json_compatible();
+    value
+        .serialize(&serializer)
+        .map_err(|error| JsError::new(&error.to_string()).into())
+}
+
+#[wasm_bindgen]
+pub fn parse(src: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    let options: JsOptions = if options.is_undefined() || options.is_null() {
+        JsOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)
+            .map_err(|error| JsError::new(&format!("Invalid options: {}", error)))?
+    };
+    let options = ParserOptions::new()
+        .source_type(options.source_type)
+        .ecma_version(options.ecma_version)
+        .allow_return_outside_function(options.allow_return_outside_function)
+        .preserve_parens(options.preserve_parens)
+        .max_nesting(options.max_nesting);
+    match parse_with_options(src, &options) {
+        Ok(output) => to_js(&output.program),
+        Err(error) => Err(to_js(&error)?),
+    }
+}
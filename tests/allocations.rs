@@ -0,0 +1,39 @@
+// Counts the allocations the lexer makes, with an allocator that counts them
+// for this test binary only, to check that a name repeated throughout a
+// source is allocated once rather than once a token.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use js_parser::tokenize;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static COUNTING: Counting = Counting;
+
+#[test]
+fn repeated_names_are_allocated_once() {
+    let src = "alpha(beta, gamma.delta);\n".repeat(1000);
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let lexemes = tokenize(&src).unwrap();
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    assert_eq!(lexemes.len(), 9000);
+    // the four names, the set they are kept in and the growing list of
+    // lexemes, against 4000 when every name had its own copy
+    assert!(allocations < 100, "{} allocations", allocations);
+}
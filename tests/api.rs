@@ -0,0 +1,152 @@
+// uses the crate only through what it exports, as a dependent crate would
+
+use js_parser::{
+    dependencies::{extract_dependencies, DependencyKind},
+    metrics,
+    node::VariableDeclaration,
+    parse, parse_module, parse_with_recovery, tokenize, Expression, ParseErrorKind, Pattern,
+    Program, SourceType, Statement, Token, TokenKind,
+};
+
+#[test]
+fn parses_a_script() {
+    let program: Program = parse("const answer = 6 * 7;").unwrap();
+    assert_eq!(program.source_type, SourceType::Script);
+    let Statement::VariableDeclaration(declaration) = &program.body[0] else {
+        panic!("expected a variable declaration");
+    };
+    let declaration: &VariableDeclaration = declaration;
+    assert_eq!(declaration.kind, "const");
+    let Pattern::Identifier(id) = &declaration.declarations[0].id else {
+        panic!("expected an identifier");
+    };
+    assert_eq!(id.name, "answer");
+    assert_eq!((id.loc.start.line, id.loc.start.column), (1, 6));
+    assert!(matches!(
+        declaration.declarations[0].init,
+        Some(Expression::Binary(_))
+    ));
+}
+
+#[test]
+fn parses_a_module() {
+    let program = parse_module("import a from 'a'; export default a;").unwrap();
+    assert_eq!(program.source_type, SourceType::Module);
+    assert_eq!(program.body.len(), 2);
+}
+
+#[test]
+fn lists_the_modules_a_program_depends_on() {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/dependencies.js"
+    );
+    let src = std::fs::read_to_string(path).unwrap();
+    let dependencies: Vec<(Option<String>, DependencyKind, usize, &str)> =
+        extract_dependencies(&parse_module(&src).unwrap())
+            .into_iter()
+            .map(|dependency| {
+                let (start, end) = dependency.loc.range;
+                (
+                    dependency.specifier,
+                    dependency.kind,
+                    dependency.loc.start.line,
+                    &src[start..end],
+                )
+            })
+            .collect();
+    let specifier = |specifier: &str| Some(specifier.to_string());
+    assert_eq!(
+        dependencies,
+        [
+            (specifier("./a.js"), DependencyKind::Import, 1, "'./a.js'"),
+            (specifier("./b.js"), DependencyKind::Import, 2, "'./b.js'"),
+            (specifier("./c.js"), DependencyKind::Import, 3, "\"./c.js\""),
+            (specifier("./d.js"), DependencyKind::ReExport, 4, "'./d.js'"),
+            (specifier("./e.js"), DependencyKind::ReExport, 5, "'./e.js'"),
+            (specifier("./f.js"), DependencyKind::ReExport, 6, "'./f.js'"),
+            (
+                specifier("./g.js"),
+                DependencyKind::DynamicImport,
+                7,
+                "'./g.js'"
+            ),
+            (specifier("./h.js"), DependencyKind::Require, 8, "'./h.js'"),
+            (
+                specifier("./j.js"),
+                DependencyKind::DynamicImport,
+                11,
+                "`./j.js`"
+            ),
+            (
+                None,
+                DependencyKind::DynamicImport,
+                12,
+                "'./locale/' + c.lang"
+            ),
+            (specifier("./m.js"), DependencyKind::Require, 16, "'./m.js'"),
+        ]
+    );
+}
+
+// the counts in the fixture are worked out by hand: `classify` branches at
+// both `if`s, the `for`, the `while`, the `&&` and the `?:`, and nests the
+// `while` inside the `for`
+#[test]
+fn measures_each_function() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/metrics.js");
+    let src = std::fs::read_to_string(path).unwrap();
+    let file = metrics::analyze(&parse(&src).unwrap());
+    let functions: Vec<(&str, usize, usize, usize, usize, usize)> = file
+        .functions
+        .iter()
+        .map(|function| {
+            (
+                function.name.as_str(),
+                function.loc.start.line,
+                function.params,
+                function.statements,
+                function.max_nesting,
+                function.complexity,
+            )
+        })
+        .collect();
+    assert_eq!(
+        functions,
+        [
+            ("classify", 1, 2, 8, 2, 7),
+            ("pick", 15, 1, 4, 1, 5),
+            ("click", 28, 1, 3, 1, 2),
+            ("hover", 35, 0, 0, 0, 1),
+            ("render", 39, 0, 1, 0, 1),
+            ("<anonymous>", 40, 1, 0, 0, 2),
+        ]
+    );
+    // six statements are outside any function, where the `if` at the end
+    // makes the complexity two
+    assert_eq!(
+        (file.statements, file.max_nesting, file.complexity),
+        (22, 2, 20)
+    );
+}
+
+#[test]
+fn tokenizes() {
+    let lexemes = tokenize("a = 1 // one").unwrap();
+    let kinds: Vec<TokenKind> = lexemes.iter().map(|lexeme| lexeme.token.kind()).collect();
+    assert_eq!(kinds.len(), 3);
+    assert_eq!(lexemes[0].token, Token::Name("a".into()));
+    assert_eq!(lexemes[2].column, 4);
+}
+
+#[test]
+fn reports_errors() {
+    let error = parse("a = (1").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+    assert_eq!(error.code(), "JS2002");
+    assert!(error.render("a = (1").starts_with("error[JS2002]"));
+
+    let (program, errors) = parse_with_recovery("a = ; b;", SourceType::Script);
+    assert_eq!(program.body.len(), 2);
+    assert_eq!(errors.len(), 1);
+}
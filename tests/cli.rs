@@ -0,0 +1,124 @@
+// runs the binary, which needs the JSON output of the `serde` feature
+#![cfg(feature = "serde")]
+
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn run(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_js_parser"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn prints_the_program_as_json() {
+    let output = run(&["-"], "a = 1;");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["type"], "Program");
+    assert_eq!(json["sourceType"], "script");
+    assert_eq!(
+        json["body"][0]["expression"]["type"],
+        "AssignmentExpression"
+    );
+
+    let output = run(&["--module", "-"], "import a from 'a';");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["sourceType"], "module");
+}
+
+#[test]
+fn reads_files() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/recovery.js");
+    let output = run(&[path], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("recovery.js: error["));
+}
+
+#[test]
+fn prints_tokens() {
+    let output = run(&["--tokens", "-"], "x = 1");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim_end(),
+        r#"[{"type":"identifier","value":"x","start":0,"end":1,"loc":{"start":{"line":1,"column":0},"end":{"line":1,"column":1}}},{"type":"=","value":"=","start":2,"end":3,"loc":{"start":{"line":1,"column":2},"end":{"line":1,"column":3}}},{"type":"number","value":"1","start":4,"end":5,"loc":{"start":{"line":1,"column":4},"end":{"line":1,"column":5}}}]"#
+    );
+}
+
+#[test]
+fn reports_errors_on_stderr() {
+    let output = run(&["-"], "a = ;");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "-: error[JS2001]: Unexpected token ';'\n --> line:1, column:4\n  |\n1 | a = ;\n  |     ^\n"
+    );
+
+    let output = run(&["--nope"], "");
+    assert_eq!(output.status.code(), Some(2));
+    let output = run(&["/does/not/exist.js"], "");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn checks_files_in_the_order_given() {
+    let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    let broken = format!("{}/recovery.js", fixtures);
+    let missing = format!("{}/missing.js", fixtures);
+    let output = run(&["--check", &broken, &missing, &broken], "");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), 9);
+    assert_eq!(
+        lines[0],
+        format!("{}:2:16: JS2001: Unexpected token ';'", broken)
+    );
+    assert!(lines[4].starts_with(&format!("{}: can't read the file", missing)));
+    assert_eq!(lines[5..], lines[..4]);
+
+    let output = run(&["--check", "-"], "a = 1;");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn diffs_two_files() {
+    let dir = std::env::temp_dir();
+    let old = dir.join(format!("js_parser_diff_old_{}.js", std::process::id()));
+    let new = dir.join(format!("js_parser_diff_new_{}.js", std::process::id()));
+    std::fs::write(&old, "function f() {}\na();\nb(1);\nc();\n").unwrap();
+    std::fs::write(&new, "a();\nb(2);\nc();\n\nfunction f() {}\n").unwrap();
+    let (old, new) = (old.to_str().unwrap(), new.to_str().unwrap());
+    let output = run(&["--diff", old, new], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!(
+            "{old}:3:3: changed Literal to Literal at {new}:2:3\n\
+             {old}:1:1: moved FunctionDeclaration to {new}:5:1\n"
+        )
+    );
+
+    let output = run(&["--diff", old, old], "");
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    let output = run(&["--diff", old], "");
+    assert_eq!(output.status.code(), Some(2));
+    std::fs::remove_file(old).unwrap();
+    std::fs::remove_file(new).unwrap();
+}
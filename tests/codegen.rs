@@ -0,0 +1,153 @@
+// Generates source from every fixture under tests/fixtures/ and the bench
+// sample, and checks it parses back to the tree it was generated from. The
+// trees are compared as JSON without their locations, which moving code
+// around changes.
+//
+// tests/codegen/sample.js is also generated with a few sets of options and
+// compared with the output kept beside it, which
+//
+//   UPDATE_SNAPSHOTS=1 cargo test --test codegen
+//
+// writes afresh.
+use std::{env, fs, path::Path};
+
+use js_parser::{
+    codegen::{
+        generate, generate_with_options, generate_with_source_map, CodegenOptions, Indent, Quotes,
+        Semicolons,
+    },
+    parse,
+};
+#[cfg(feature = "serde")]
+use js_parser::{parse_with_source_type, SourceType};
+#[cfg(feature = "serde")]
+use serde_json::Value;
+
+#[cfg(feature = "serde")]
+fn without_locations(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            for key in ["loc", "range", "start", "end"] {
+                object.remove(key);
+            }
+            object.values_mut().for_each(without_locations);
+        }
+        Value::Array(values) => values.iter_mut().for_each(without_locations),
+        _ => {}
+    }
+}
+
+#[cfg(feature = "serde")]
+fn round_trip(name: &str, src: &str, source_type: SourceType) -> Result<(), String> {
+    // what doesn't parse yet has nothing to generate from
+    let Ok(program) = parse_with_source_type(src, source_type) else {
+        return Ok(());
+    };
+    let mut expected = serde_json::to_value(&program).unwrap();
+    without_locations(&mut expected);
+    // with semicolons, without and minified, keeping booleans as they are
+    for options in [
+        CodegenOptions::new(),
+        CodegenOptions::new().semicolons(Semicolons::Never),
+        CodegenOptions::new().minify(true).shorten_booleans(false),
+    ] {
+        let generated = generate_with_options(&program, &options);
+        let reparsed = parse_with_source_type(&generated, source_type)
+            .map_err(|error| format!("{}:\n{}", name, error.render(&generated)))?;
+        let mut actual = serde_json::to_value(&reparsed).unwrap();
+        without_locations(&mut actual);
+        if expected != actual {
+            return Err(format!(
+                "{} parses to another tree from:\n{}",
+                name, generated
+            ));
+        }
+        // and generating again changes nothing
+        if generate_with_options(&reparsed, &options) != generated {
+            return Err(format!("{} generates differently the second time", name));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn fixtures_parse_back_from_generated_source() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut failures = vec![];
+    for group in fs::read_dir(&fixtures).unwrap() {
+        let group = group.unwrap().path();
+        if !group.is_dir() {
+            continue;
+        }
+        let source_type = if group.ends_with("modules") {
+            SourceType::Module
+        } else {
+            SourceType::Script
+        };
+        for entry in fs::read_dir(&group).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|extension| extension == "js") {
+                let src = fs::read_to_string(&path).unwrap();
+                let name = path.strip_prefix(&fixtures).unwrap().display().to_string();
+                if let Err(failure) = round_trip(&name, &src, source_type) {
+                    failures.push(failure);
+                }
+            }
+        }
+    }
+    assert!(failures.is_empty(), "\n{}\n", failures.join("\n\n"));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn the_bench_sample_parses_back_from_generated_source() {
+    let src = include_str!("../benches/fixtures/sample.js");
+    round_trip("sample.js", src, SourceType::Script).unwrap();
+}
+
+#[test]
+fn sample_matches_its_snapshots() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/codegen");
+    let src = fs::read_to_string(dir.join("sample.js")).unwrap();
+    let program = parse(&src).unwrap();
+    let presets = [
+        ("default", CodegenOptions::new()),
+        (
+            "standard",
+            CodegenOptions::new()
+                .quotes(Quotes::Single)
+                .semicolons(Semicolons::Never),
+        ),
+        (
+            "tabs",
+            CodegenOptions::new()
+                .indent(Indent::Tabs)
+                .quotes(Quotes::Auto)
+                .trailing_commas(true)
+                .max_blank_lines(0),
+        ),
+        ("minified", CodegenOptions::new().minify(true)),
+    ];
+    let update = env::var_os("UPDATE_SNAPSHOTS").is_some();
+    for (name, options) in presets {
+        let generated = generate_with_options(&program, &options);
+        // formatting what was formatted changes nothing
+        let again = generate_with_options(&parse(&generated).unwrap(), &options);
+        assert_eq!(again, generated, "{} isn't idempotent", name);
+        // and keeping a source map changes nothing either
+        let (mapped, _) = generate_with_source_map(&program, &options, "sample.js");
+        assert_eq!(mapped, generated, "{} differs with a source map", name);
+        let path = dir.join(format!("sample.{}.js", name));
+        if update {
+            fs::write(&path, &generated).unwrap();
+        } else {
+            let expected = fs::read_to_string(&path).unwrap();
+            assert_eq!(generated, expected, "{} differs from its snapshot", name);
+        }
+    }
+    assert_eq!(
+        generate(&program),
+        generate_with_options(&program, &CodegenOptions::default())
+    );
+}
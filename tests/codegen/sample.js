@@ -0,0 +1,33 @@
+'use strict';
+
+// strings with quotes of either kind
+const plain = 'plain', apostrophe = 'it\'s', quoted = "say \"hi\"";
+const both = "mixed ' and \" \\";
+
+
+// two blank lines above, one kept at most
+let counter = { count: 0, label: "counter" };
+let handlers = {
+  increment() { counter.count++; },
+  get value() { return counter.count; },
+  ...defaults
+};
+let list = [(a, b) => a + b, function () { return 1; }];
+
+class Queue extends Base {
+  items = [];
+  static created = 0;
+
+  push(item) {
+    this.items.push(item);
+  }
+  *[Symbol.iterator]() {
+    yield* this.items;
+  }
+}
+
+let total = (1 + 2) * 3
+;[counter, handlers].forEach(print)
+;(function () {})()
+if (total) print(total); else print(-total)
+do total--; while (total > 0)
@@ -0,0 +1,46 @@
+// compiles tests/ffi/parse.c against the shared library and runs it, which
+// needs a C compiler as `cc`. Cargo keeps one copy of the library whatever
+// the features, so one built without `ffi` since can be left in its place;
+// `touch src/ffi.rs` has it rebuilt.
+#![cfg(feature = "ffi")]
+
+use std::{
+    env::{self, consts},
+    path::PathBuf,
+    process::Command,
+};
+
+#[test]
+fn parses_from_c() {
+    // the test runs from target/<profile>/deps, next to the library, which
+    // is linked by its full path so that no other copy of it on the
+    // loader's search path is picked up instead
+    let lib = env::current_exe().unwrap().with_file_name(format!(
+        "{}js_parser{}",
+        consts::DLL_PREFIX,
+        consts::DLL_SUFFIX
+    ));
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("parse");
+    let status = Command::new("cc")
+        .arg(root.join("tests/ffi/parse.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg(&lib)
+        .args(["-Wall", "-Werror", "-o"])
+        .arg(&exe)
+        .status()
+        .unwrap();
+    assert!(status.success(), "can't link tests/ffi/parse.c");
+
+    let output = Command::new(&exe)
+        .env_remove("LD_LIBRARY_PATH")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let mut lines = stdout.lines();
+    let json: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+    assert_eq!(json["body"][0]["type"], "VariableDeclaration");
+    assert_eq!(lines.next(), Some("1:8: JS2001: Unexpected token ';'"));
+}
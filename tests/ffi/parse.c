@@ -0,0 +1,33 @@
+/* Parses a snippet through the C interface; tests/ffi.rs builds and runs it
+ * against the library built with `--features ffi`:
+ *
+ *   cc tests/ffi/parse.c -Iinclude -Ltarget/debug -ljs_parser -o parse
+ */
+#include <stdio.h>
+#include <string.h>
+
+#include "js_parser.h"
+
+int main(void) {
+    const char *src = "const answer = 6 * 7;";
+    JsError error;
+    char *json = js_parse_to_json(src, strlen(src), &error);
+    if (json == NULL) {
+        fprintf(stderr, "%zu:%zu: %s\n", error.line, error.column, error.message);
+        js_free_error(&error);
+        return 1;
+    }
+    printf("%s\n", json);
+    js_free_string(json);
+
+    const char *invalid = "let a = ;";
+    json = js_parse_to_json(invalid, strlen(invalid), &error);
+    if (json != NULL) {
+        fprintf(stderr, "expected a syntax error\n");
+        js_free_string(json);
+        return 1;
+    }
+    printf("%zu:%zu: %s\n", error.line, error.column, error.message);
+    js_free_error(&error);
+    return 0;
+}
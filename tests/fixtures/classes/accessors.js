@@ -0,0 +1,5 @@
+class A {
+  get a() { return 1; }
+  set a(v) {}
+  static get b() {}
+}
@@ -0,0 +1,4 @@
+class A {}
+class B { constructor() {} method() {} }
+(class {});
+(class C {});
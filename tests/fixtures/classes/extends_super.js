@@ -0,0 +1,4 @@
+class A extends B {
+  constructor() { super(); }
+  m() { return super.m(); }
+}
@@ -0,0 +1,7 @@
+class A {
+  a;
+  b = 1;
+  static c = 2;
+  [d] = 3;
+  'e' = 4;
+}
@@ -0,0 +1,6 @@
+class A {
+  #a = 1;
+  #b() { return this.#a; }
+  get #c() {}
+  static #d;
+}
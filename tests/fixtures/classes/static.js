@@ -0,0 +1,6 @@
+class A {
+  static a() {}
+  static async *b() {}
+  static() {}
+  async() {}
+}
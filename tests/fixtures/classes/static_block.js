@@ -0,0 +1,5 @@
+class A {
+  static {
+    this.x = 1;
+  }
+}
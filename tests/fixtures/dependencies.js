@@ -0,0 +1,16 @@
+import a from './a.js';
+import './b.js';
+import * as c from "./c.js";
+export { d } from './d.js';
+export * from './e.js';
+export * as f from './f.js';
+export const g = () => import('./g.js');
+const h = require('./h.js');
+require(h, './i.js');
+require(name);
+import(`./j.js`).then(a);
+import('./locale/' + c.lang);
+export { a, h };
+function k(require) { return require('./k.js'); }
+function l() { const require = k; return require('./l.js'); }
+function m() { return require('./m.js'); }
@@ -0,0 +1,3 @@
+[a, b] = [b, a];
+({a, b: c} = d);
+[a, ...b] = c;
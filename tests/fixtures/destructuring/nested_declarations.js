@@ -0,0 +1,10 @@
+const { a: { b = 1 } = {}, c: [d, ...e] = [] } = obj;
+let [, x, , ...[y, z]] = arr;
+var { p: [q = 1, { r }] = [], ...others } = source;
+let [[m, n] = [1, 2], { o = m } = {}] = pairs;
+const { [key]: { deep: [first, , third] }, "quoted": alias = "d" } = data;
+var [u = function () {}, v = (w) => w, ...[t]] = fns;
+let { 0: zero, 1.5: half, if: cond = true } = table;
+const [{ length } = "", [[[nested]]]] = strings;
+var { a: aa = 1, b: { c: cc = aa } = { c: 2 } } = {}, [dd = aa + cc] = [];
+let { x: [, ...xs] = [], y: { ...ys } = {} } = point;
@@ -0,0 +1,5 @@
+[];
+[a];
+[a, b,];
+[, a, , b];
+[...a, b];
@@ -0,0 +1,3 @@
+(a, b) => ({a, b});
+() => {};
+a => a * 2;
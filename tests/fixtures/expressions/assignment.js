@@ -0,0 +1,14 @@
+a = b;
+a += 1;
+a -= 1;
+a *= 2;
+a /= 2;
+a %= 2;
+a <<= 1;
+a >>= 1;
+a >>>= 1;
+a &= 1;
+a |= 1;
+a ^= 1;
+a **= 2;
+a = b = c;
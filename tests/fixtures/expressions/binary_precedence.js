@@ -0,0 +1,6 @@
+a + b * c - d / e % f;
+a << b + c;
+a < b == c > d;
+a & b ^ c | d;
+a in b;
+a instanceof B;
@@ -0,0 +1,4 @@
+f();
+f(a, b);
+f(...args, c);
+a.b(c)(d);
@@ -0,0 +1,2 @@
+a ? b : c;
+a ? b ? c : d : e ? f : g;
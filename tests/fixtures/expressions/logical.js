@@ -0,0 +1,4 @@
+a && b || c;
+a || b && c;
+a ?? b;
+(a || b) ?? c;
@@ -0,0 +1,5 @@
+new A;
+new A();
+new A.b(c);
+new (f())();
+new new A()();
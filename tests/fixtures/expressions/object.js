@@ -0,0 +1,4 @@
+({});
+({a: 1, 'b': 2, 3: c, [d]: e});
+({a, b});
+({...a, b: 1});
@@ -0,0 +1,9 @@
+({
+  a() {},
+  get b() { return 1; },
+  set b(v) {},
+  async c() {},
+  *d() {},
+  async *e() {},
+  [f]() {}
+});
@@ -0,0 +1,5 @@
+a?.b;
+a?.[b];
+a?.(b);
+a?.b.c?.(d)?.[e];
+(a?.b).c;
@@ -0,0 +1,3 @@
+(a);
+((a + b)) * c;
+(a, b);
@@ -0,0 +1,3 @@
+tag`a${b}c`;
+a.b`c`;
+f()`d`;
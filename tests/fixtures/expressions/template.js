@@ -0,0 +1,4 @@
+`a`;
+`a${b}c`;
+`${a}${b}`;
+`line\nbreak`;
@@ -0,0 +1,4 @@
+this;
+a.class;
+a.if.new;
+({if: 1, class: 2});
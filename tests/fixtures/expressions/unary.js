@@ -0,0 +1,8 @@
+!a;
+-a;
++a;
+~a;
+typeof a;
+void 0;
+delete a.b;
+!!a;
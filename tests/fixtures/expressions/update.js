@@ -0,0 +1,5 @@
+a++;
+a--;
+++a;
+--a;
+a.b++;
@@ -0,0 +1,4 @@
+() => 1;
+a => a;
+(a, b) => { return a; };
+(a = 1, ...b) => b;
@@ -0,0 +1,4 @@
+async function f() { await a; }
+async () => await b;
+async a => a;
+(async function () {});
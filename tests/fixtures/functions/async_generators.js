@@ -0,0 +1,3 @@
+async function* g() {
+  for await (const x of y) yield* x;
+}
@@ -0,0 +1,2 @@
+(function () {});
+(function named(a) { return; });
@@ -0,0 +1,2 @@
+function* g() { yield; yield a; yield* b; }
+(function* () {});
@@ -0,0 +1 @@
+function F() { return new.target; }
@@ -0,0 +1,40 @@
+// Replaces the snapshots of the fixtures, which the parser wrote, with the
+// JSON acorn gives for them; acorn has to be installed first:
+//
+//   npm install --no-save acorn
+//   node tests/fixtures/generate.mjs                     # every fixture
+//   node tests/fixtures/generate.mjs classes/private ... # some of them
+import { parse } from "acorn";
+import { readFileSync, readdirSync, writeFileSync } from "node:fs";
+
+const root = new URL("./", import.meta.url);
+// LATEST_ECMA_VERSION in src/options.rs
+const ecmaVersion = 2022;
+
+function fixtures() {
+  return readdirSync(root, { withFileTypes: true })
+    .filter((entry) => entry.isDirectory())
+    .flatMap((group) =>
+      readdirSync(new URL(`${group.name}/`, root))
+        .filter((file) => file.endsWith(".js"))
+        .map((file) => `${group.name}/${file.slice(0, -3)}`),
+    );
+}
+
+const names = process.argv.length > 2 ? process.argv.slice(2) : fixtures();
+for (const name of names) {
+  const src = readFileSync(new URL(`${name}.js`, root), "utf8");
+  const program = parse(src, {
+    ecmaVersion,
+    sourceType: name.startsWith("modules/") ? "module" : "script",
+    locations: true,
+    ranges: true,
+  });
+  // regular expressions and bigints have no JSON, and are null in ours
+  const json = JSON.stringify(
+    program,
+    (key, value) => (typeof value === "bigint" || value instanceof RegExp ? null : value),
+    2,
+  );
+  writeFileSync(new URL(`${name}.snapshot.json`, root), json + "\n");
+}
@@ -0,0 +1,2 @@
+10n;
+0x1Fn;
@@ -0,0 +1,8 @@
+0;
+1.5;
+.5e-3;
+0x1F;
+0o17;
+0b101;
+1_000_000;
+1e21;
@@ -0,0 +1,3 @@
+/a/;
+/a[/]b/giu;
+/(?<name>x)\1/s;
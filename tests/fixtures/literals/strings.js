@@ -0,0 +1,4 @@
+'single';
+"double";
+'\x41\u0042\u{43}';
+'\n\t\\';
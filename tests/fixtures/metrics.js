@@ -0,0 +1,46 @@
+function classify(n, options) {
+  if (n < 0) {
+    return 'negative';
+  } else if (n === 0) {
+    return 'zero';
+  }
+  for (let i = 0; i < n; i++) {
+    while (options.busy && !options.done) {
+      options.wait();
+    }
+  }
+  return n > 100 ? 'large' : 'small';
+}
+
+const pick = function (key) {
+  switch (key) {
+    case 'a':
+      return 1;
+    case 'b':
+    case 'c':
+      return 2;
+    default:
+      return key ?? 0;
+  }
+};
+
+const handlers = {
+  click(event) {
+    try {
+      event.run();
+    } catch (error) {
+      report(error || event);
+    }
+  },
+  hover: () => 1,
+};
+
+class Widget {
+  render() {
+    return items.map((item) => item.visible || item.pinned);
+  }
+}
+
+if (handlers.click) {
+  handlers.click(null);
+}
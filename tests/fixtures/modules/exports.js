@@ -0,0 +1,4 @@
+export var a = 1;
+export function b() {}
+export class C {}
+export { a as d, C as e };
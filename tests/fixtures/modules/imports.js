@@ -0,0 +1,5 @@
+import 'a';
+import b from 'b';
+import * as c from 'c';
+import { d, e as f } from 'd';
+import g, { h } from 'g';
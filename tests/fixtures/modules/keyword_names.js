@@ -0,0 +1,9 @@
+import { default as d, class as c, new as n } from "m";
+export { x as if, y as return } from "n";
+obj.default(obj.class, obj.new);
+a?.in.typeof?.delete;
+const o = { new: 1, in: 2, typeof: 3, if: 4, for: 5, void: 6 };
+const { switch: s, case: k, ...rest } = o;
+class K { static default() {} get while() {} set do(v) {} try = 1; }
+o.this.super.null.true.false;
+export { d as default, c as instanceof, n as extends };
@@ -0,0 +1,3 @@
+export * from 'a';
+export * as b from 'b';
+export { c, d as e } from 'c';
@@ -0,0 +1,3 @@
+await a;
+const b = await c;
+for await (const d of e) ;
@@ -0,0 +1,13 @@
+const total = prices.reduce((sum, price) => sum + price, 0);
+let discount = ;
+
+function apply(rate) {
+  const result = total * (1 - rate;
+  return result;
+}
+
+if (discount > 0) {
+  console.log("discount" discount);
+}
+
+export const done = apply(discount);
@@ -0,0 +1,7 @@
+a
+b
+var c = 1
+let d
+return_like: {
+  break return_like
+}
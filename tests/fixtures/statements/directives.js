@@ -0,0 +1,6 @@
+'use strict';
+"another";
+function f() {
+  'use strict';
+  return 1;
+}
@@ -0,0 +1,4 @@
+for (;;) {}
+for (var i = 0; i < n; i++) a;
+for (i = 0, j = 1; ; ) break;
+for (let i = 0; ; ) {}
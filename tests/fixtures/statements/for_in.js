@@ -0,0 +1,4 @@
+for (a in b) ;
+for (var a in b) {}
+for (let a in b) {}
+for (a.b in c) ;
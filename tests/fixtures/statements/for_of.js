@@ -0,0 +1,3 @@
+for (a of b) ;
+for (const a of b) {}
+for (let a of [1, 2]) {}
@@ -0,0 +1,3 @@
+if (a) b;
+if (a) b; else c;
+if (a) b; else if (c) d; else { e }
@@ -0,0 +1,5 @@
+outer: for (;;) {
+  inner: for (;;) { continue outer; }
+  break outer;
+}
+block: { break block; }
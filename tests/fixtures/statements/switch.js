@@ -0,0 +1,6 @@
+switch (a) {}
+switch (a) {
+  case 1:
+  case 2: b; break;
+  default: c;
+}
@@ -0,0 +1,3 @@
+try { a } catch (e) { b }
+try { a } finally { b }
+try { a } catch { b } finally { c }
@@ -0,0 +1,4 @@
+var a;
+var b = 1, c;
+let d = 2;
+const e = 3, f = 4;
@@ -0,0 +1,4 @@
+while (a) b;
+while (a) { break; }
+do a; while (b)
+do { continue; } while (a);
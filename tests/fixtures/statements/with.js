@@ -0,0 +1,2 @@
+with (a) b;
+with (a) { b }
@@ -0,0 +1,67 @@
+// inputs from tests/fuzz_regressions/ that once made the lexer or parser
+// panic, found by the fuzz target in fuzz/; each only has to give a result
+use std::{fs, panic};
+
+use js_parser::{
+    parse_module, parse_script, parse_with_recovery, tokenize, ParseErrorKind, SourceType,
+};
+
+#[test]
+fn fuzz_regressions_dont_panic() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fuzz_regressions");
+    let mut panicked = vec![];
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        // read as the fuzz target reads its input
+        let data = fs::read(&path).unwrap();
+        let src = String::from_utf8_lossy(&data);
+        let result = panic::catch_unwind(|| {
+            let _ = tokenize(&src);
+            let _ = parse_script(&src);
+            let _ = parse_module(&src);
+            let _ = parse_with_recovery(&src, SourceType::Script);
+        });
+        if result.is_err() {
+            panicked.push(path.file_name().unwrap().to_string_lossy().into_owned());
+        }
+    }
+    panicked.sort();
+    assert!(panicked.is_empty(), "panicked on {:?}", panicked);
+}
+
+// deeply nested input runs out of stack unless the parser stops at a limit,
+// and a stack overflow aborts rather than panics
+#[test]
+fn deep_nesting_is_an_error() {
+    let n = 100_000;
+    let sources = [
+        format!("{}a{}", "(".repeat(n), ")".repeat(n)),
+        format!("{}a{}", "[".repeat(n), "]".repeat(n)),
+        format!("{}{}", "{".repeat(n), "}".repeat(n)),
+        format!("{}a", "!".repeat(n)),
+        format!("{}a", "a ? b : ".repeat(n)),
+        format!("{}a", "a = ".repeat(n)),
+        format!("{}a", "a ** ".repeat(n)),
+    ];
+    for src in &sources {
+        let error = parse_script(src).unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::NestingTooDeep);
+        let (_, errors) = parse_with_recovery(src, SourceType::Script);
+        assert!(!errors.is_empty());
+    }
+}
+
+// chains of operators, members and calls are parsed in a loop, so they may
+// be far longer than anything is allowed to nest
+#[test]
+fn long_chains_parse() {
+    let n = 10_000;
+    for src in [
+        format!("{}a", "a + ".repeat(n)),
+        format!("{}a", "a, ".repeat(n)),
+        format!("a{}", ".b".repeat(n)),
+        format!("a{}", "()".repeat(n)),
+    ] {
+        assert!(parse_script(&src).is_ok());
+    }
+}
@@ -0,0 +1,339 @@
+// Property tests over generated programs. Each program is generated as a
+// small model, written out as source with only the parentheses precedence
+// needs, parsed, and read back into a model, which has to be the one it was
+// written from; so does the tree parsed from the code generator's output.
+// The grammar covered is literals, names, unary, binary and logical
+// expressions, variable declarations and function declarations; it should
+// grow as the parser does.
+use js_parser::{
+    codegen::generate,
+    node::{
+        BinaryOperator, LogicalOperator, Pattern, ReturnStatement, Statement, UnaryOperator,
+        VariableDeclaration,
+    },
+    parse, tokenize, Expression, LiteralValue,
+};
+use proptest::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(u32),
+    String(String),
+    Boolean(bool),
+    Null,
+    Name(String),
+    Unary(UnaryOperator, Box<Expr>),
+    Binary(BinaryOperator, Box<Expr>, Box<Expr>),
+    Logical(LogicalOperator, Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stmt {
+    Var(String, String, Option<Expr>),
+    Function(String, Vec<String>, Vec<Stmt>, Option<Expr>),
+    Expr(Expr),
+}
+
+const UNARY: &[(UnaryOperator, &str)] = &[
+    (UnaryOperator::Positive, "+"),
+    (UnaryOperator::Negative, "-"),
+    (UnaryOperator::LogicalInversion, "!"),
+    (UnaryOperator::BitwiseInversion, "~"),
+    (UnaryOperator::Typeof, "typeof"),
+    (UnaryOperator::Void, "void"),
+];
+
+// each operator with how tightly it binds
+const BINARY: &[(BinaryOperator, &str, u8)] = &[
+    (BinaryOperator::BitwiseOR, "|", 3),
+    (BinaryOperator::BitwiseXOR, "^", 4),
+    (BinaryOperator::BitwiseAND, "&", 5),
+    (BinaryOperator::DoubleE, "==", 6),
+    (BinaryOperator::DoubleNE, "!=", 6),
+    (BinaryOperator::TripleE, "===", 6),
+    (BinaryOperator::TripleNE, "!==", 6),
+    (BinaryOperator::LT, "<", 7),
+    (BinaryOperator::LTE, "<=", 7),
+    (BinaryOperator::GT, ">", 7),
+    (BinaryOperator::GTE, ">=", 7),
+    (BinaryOperator::In, "in", 7),
+    (BinaryOperator::Instanceof, "instanceof", 7),
+    (BinaryOperator::LeftShift, "<<", 8),
+    (BinaryOperator::RightShift, ">>", 8),
+    (BinaryOperator::URightShift, ">>>", 8),
+    (BinaryOperator::Plus, "+", 9),
+    (BinaryOperator::Minus, "-", 9),
+    (BinaryOperator::Multiple, "*", 10),
+    (BinaryOperator::Divide, "/", 10),
+    (BinaryOperator::Modulo, "%", 10),
+    (BinaryOperator::Exponent, "**", 11),
+];
+
+// `??` is left out, as it can't be mixed with the others unparenthesized
+const LOGICAL: &[(LogicalOperator, &str, u8)] = &[
+    (LogicalOperator::LogicalOR, "||", 1),
+    (LogicalOperator::LogicalAND, "&&", 2),
+];
+
+const UNARY_PRECEDENCE: u8 = 12;
+const PRIMARY_PRECEDENCE: u8 = 13;
+
+fn expr() -> impl Strategy<Value = Expr> {
+    let leaf = prop_oneof![
+        any::<u32>().prop_map(Expr::Number),
+        "[a-z ]{0,8}".prop_map(Expr::String),
+        any::<bool>().prop_map(Expr::Boolean),
+        Just(Expr::Null),
+        prop::sample::select(vec!["a", "b", "c", "x", "y"])
+            .prop_map(|name| Expr::Name(name.into())),
+    ];
+    leaf.prop_recursive(4, 24, 2, |inner| {
+        prop_oneof![
+            (0..UNARY.len(), inner.clone()).prop_map(|(index, argument)| Expr::Unary(
+                UNARY[index].0.clone(),
+                Box::new(argument)
+            )),
+            (0..BINARY.len(), inner.clone(), inner.clone()).prop_map(|(index, left, right)| {
+                Expr::Binary(BINARY[index].0.clone(), Box::new(left), Box::new(right))
+            }),
+            (0..LOGICAL.len(), inner.clone(), inner).prop_map(|(index, left, right)| {
+                Expr::Logical(LOGICAL[index].0.clone(), Box::new(left), Box::new(right))
+            }),
+        ]
+    })
+}
+
+// statements with placeholder names, which `name_declarations` fills in
+fn stmt() -> impl Strategy<Value = Stmt> {
+    let leaf = prop_oneof![
+        (
+            prop::sample::select(vec!["var", "let", "const"]),
+            prop::option::of(expr())
+        )
+            .prop_map(|(kind, init)| {
+                // a constant has to be initialized
+                let init = match kind {
+                    "const" => init.or(Some(Expr::Number(0))),
+                    _ => init,
+                };
+                Stmt::Var(kind.into(), String::new(), init)
+            }),
+        expr().prop_map(Stmt::Expr),
+    ];
+    leaf.prop_recursive(2, 16, 4, |inner| {
+        (
+            0..4usize,
+            prop::collection::vec(inner, 0..4),
+            prop::option::of(expr()),
+        )
+            .prop_map(|(params, body, result)| {
+                let params = (0..params).map(|index| format!("p{}", index)).collect();
+                Stmt::Function(String::new(), params, body, result)
+            })
+    })
+}
+
+// every declaration of a body gets a name of its own
+fn name_declarations(body: &mut [Stmt]) {
+    for (index, stmt) in body.iter_mut().enumerate() {
+        match stmt {
+            Stmt::Var(_, name, _) => *name = format!("v{}", index),
+            Stmt::Function(name, _, body, _) => {
+                *name = format!("f{}", index);
+                name_declarations(body);
+            }
+            Stmt::Expr(_) => {}
+        }
+    }
+}
+
+fn program() -> impl Strategy<Value = Vec<Stmt>> {
+    prop::collection::vec(stmt(), 0..6).prop_map(|mut body| {
+        name_declarations(&mut body);
+        body
+    })
+}
+
+// the source of an expression and how tightly it binds
+fn write_expr(expr: &Expr) -> (String, u8) {
+    let operand = |expr: &Expr, parenthesize: bool| {
+        let (source, _) = write_expr(expr);
+        if parenthesize {
+            format!("({})", source)
+        } else {
+            source
+        }
+    };
+    match expr {
+        Expr::Number(value) => (value.to_string(), PRIMARY_PRECEDENCE),
+        Expr::String(value) => (format!("'{}'", value), PRIMARY_PRECEDENCE),
+        Expr::Boolean(value) => (value.to_string(), PRIMARY_PRECEDENCE),
+        Expr::Null => ("null".to_string(), PRIMARY_PRECEDENCE),
+        Expr::Name(name) => (name.clone(), PRIMARY_PRECEDENCE),
+        Expr::Unary(operator, argument) => {
+            let (_, text) = UNARY.iter().find(|(op, _)| op == operator).unwrap();
+            let argument = operand(argument, precedence(argument) < UNARY_PRECEDENCE);
+            // the space keeps `- -a` from becoming `--a`
+            (format!("{} {}", text, argument), UNARY_PRECEDENCE)
+        }
+        Expr::Binary(operator, left, right) => {
+            let (_, text, precedence_of) = BINARY.iter().find(|(op, ..)| op == operator).unwrap();
+            let (left, right) = if *operator == BinaryOperator::Exponent {
+                // `**` is right-associative, and can't have a unary operand
+                // on its left
+                (
+                    operand(left, precedence(left) < PRIMARY_PRECEDENCE),
+                    operand(right, precedence(right) < *precedence_of),
+                )
+            } else {
+                (
+                    operand(left, precedence(left) < *precedence_of),
+                    operand(right, precedence(right) <= *precedence_of),
+                )
+            };
+            (format!("{} {} {}", left, text, right), *precedence_of)
+        }
+        Expr::Logical(operator, left, right) => {
+            let (_, text, precedence_of) = LOGICAL.iter().find(|(op, ..)| op == operator).unwrap();
+            let left = operand(left, precedence(left) < *precedence_of);
+            let right = operand(right, precedence(right) <= *precedence_of);
+            (format!("{} {} {}", left, text, right), *precedence_of)
+        }
+    }
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    write_expr(expr).1
+}
+
+fn write_body(body: &[Stmt], indent: &str) -> String {
+    body.iter()
+        .map(|stmt| match stmt {
+            Stmt::Var(kind, name, Some(init)) => {
+                format!("{}{} {} = {};\n", indent, kind, name, write_expr(init).0)
+            }
+            Stmt::Var(kind, name, None) => format!("{}{} {};\n", indent, kind, name),
+            Stmt::Function(name, params, body, result) => {
+                let inner = format!("{}  ", indent);
+                let result = match result {
+                    Some(result) => format!("{}return {};\n", inner, write_expr(result).0),
+                    None => String::new(),
+                };
+                format!(
+                    "{}function {}({}) {{\n{}{}{}}}\n",
+                    indent,
+                    name,
+                    params.join(", "),
+                    write_body(body, &inner),
+                    result,
+                    indent
+                )
+            }
+            Stmt::Expr(expr) => format!("{}{};\n", indent, write_expr(expr).0),
+        })
+        .collect()
+}
+
+fn read_expr(expression: &Expression) -> Expr {
+    match expression {
+        Expression::Literal(literal) => match &literal.value {
+            LiteralValue::Number(value) => Expr::Number(*value as u32),
+            LiteralValue::String(value) => Expr::String(value.clone()),
+            LiteralValue::Boolean(value) => Expr::Boolean(*value),
+            LiteralValue::Null(_) => Expr::Null,
+            value => panic!("unexpected literal {:?}", value),
+        },
+        Expression::Identifier(identifier) => Expr::Name(identifier.name.to_string()),
+        Expression::Unary(unary) => {
+            Expr::Unary(unary.operator.clone(), Box::new(read_expr(&unary.argument)))
+        }
+        Expression::Binary(binary) => Expr::Binary(
+            binary.operator.clone(),
+            Box::new(read_expr(&binary.left)),
+            Box::new(read_expr(&binary.right)),
+        ),
+        Expression::Logical(logical) => Expr::Logical(
+            logical.operator.clone(),
+            Box::new(read_expr(&logical.left)),
+            Box::new(read_expr(&logical.right)),
+        ),
+        expression => panic!("unexpected expression {:?}", expression),
+    }
+}
+
+fn read_name(pattern: &Pattern) -> String {
+    match pattern {
+        Pattern::Identifier(identifier) => identifier.name.to_string(),
+        pattern => panic!("unexpected pattern {:?}", pattern),
+    }
+}
+
+fn read_body(body: &[Statement]) -> Vec<Stmt> {
+    body.iter()
+        .map(|statement| match statement {
+            Statement::VariableDeclaration(declaration) => {
+                let VariableDeclaration {
+                    declarations, kind, ..
+                } = declaration.as_ref();
+                assert_eq!(declarations.len(), 1);
+                Stmt::Var(
+                    kind.clone(),
+                    read_name(&declarations[0].id),
+                    declarations[0].init.as_ref().map(read_expr),
+                )
+            }
+            Statement::FunctionDeclaration(function) => {
+                let mut body = function.body.body.as_slice();
+                let mut result = None;
+                if let Some((Statement::Return(last), rest)) = body.split_last() {
+                    let ReturnStatement { argument, .. } = last.as_ref();
+                    result = argument.as_ref().map(read_expr);
+                    body = rest;
+                }
+                Stmt::Function(
+                    function.id.as_ref().unwrap().name.to_string(),
+                    function.params.iter().map(read_name).collect(),
+                    read_body(body),
+                    result,
+                )
+            }
+            Statement::Expression(statement) => Stmt::Expr(read_expr(&statement.expression)),
+            // a string on its own at the start of a body
+            Statement::Directive(directive) => Stmt::Expr(read_expr(&Expression::Literal(
+                Box::new(directive.expression.clone()),
+            ))),
+            statement => panic!("unexpected statement {:?}", statement),
+        })
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn programs_parse_to_what_they_were_written_from(program in program()) {
+        let src = write_body(&program, "");
+        let parsed = parse(&src).map_err(|error| TestCaseError::fail(error.render(&src)))?;
+        prop_assert_eq!(read_body(&parsed.body), program.clone(), "{}", src);
+        let generated = generate(&parsed);
+        let reparsed = parse(&generated)
+            .map_err(|error| TestCaseError::fail(error.render(&generated)))?;
+        prop_assert_eq!(read_body(&reparsed.body), program, "{}", generated);
+    }
+
+    // a soup of tokens, most of which don't make a program
+    #[test]
+    fn parsing_never_panics(
+        tokens in prop::collection::vec(
+            prop::sample::select(vec![
+                "a", "1", "'s'", "`t${", "}", "`", "(", ")", "[", "]", "{", "}", ";", ",",
+                "=", "=>", "+", "++", "-", "/", "/x/", "*", "**", "?", "?.", ":", ".", "...",
+                "function", "class", "let", "var", "return", "if", "else", "for", "of", "in",
+                "new", "async", "await", "yield", "import", "export", "\n",
+            ]),
+            0..24,
+        ),
+    ) {
+        let src = tokens.join(" ");
+        let _ = tokenize(&src);
+        let _ = parse(&src);
+    }
+}
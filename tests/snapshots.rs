@@ -0,0 +1,195 @@
+// Compares the JSON of every fixture under tests/fixtures/<group>/, the `.js`
+// source, with the `.snapshot.json` beside it. Sources under `modules/` are
+// parsed as modules and the rest as scripts.
+//
+// The snapshots were written by this parser, not by acorn, so they catch
+// regressions in the tree rather than prove it is acorn's.
+// tests/fixtures/generate.mjs replaces them with acorn's output where acorn
+// can be installed, after which the fixtures the parser doesn't match go in
+// tests/fixtures/known_gaps.txt.
+//
+//   cargo test --features serde fixtures                # every group
+//   cargo test --features serde fixtures::classes       # one group
+//   FIXTURE=classes/private cargo test --features serde fixtures::classes
+//   UPDATE_SNAPSHOTS=1 cargo test --features serde fixtures   # rewrite them
+#![cfg(feature = "serde")]
+
+use std::{env, fs, path::Path};
+
+use js_parser::{parse_with_source_type, SourceType};
+use serde_json::Value;
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+
+// the fixtures listed as known gaps, without their reasons
+fn known_gaps() -> Vec<String> {
+    let list = fs::read_to_string(Path::new(FIXTURES).join("known_gaps.txt")).unwrap();
+    list.lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_whitespace().next())
+        .map(ToString::to_string)
+        .collect()
+}
+
+// Puts both sides on an equal footing, as a snapshot may come from acorn.
+// Regular expressions and bigints have no JSON value, so acorn's are written
+// as null, as ours are. Acorn's program runs on to the end of the source
+// while ours ends at its last token, so the end of the program isn't
+// compared. Offsets are the same as long as the
+// fixtures are ASCII: ours count UTF-8 bytes and acorn's UTF-16 units.
+fn normalize(program: &mut Value) {
+    for field in ["/end", "/loc/end", "/range/1"] {
+        if let Some(value) = program.pointer_mut(field) {
+            *value = Value::Null;
+        }
+    }
+    null_values(program);
+}
+
+fn null_values(value: &mut Value) {
+    match value {
+        Value::Object(object) => {
+            if object.contains_key("regex") || object.contains_key("bigint") {
+                object.insert("value".to_string(), Value::Null);
+            }
+            object.values_mut().for_each(null_values);
+        }
+        Value::Array(values) => values.iter_mut().for_each(null_values),
+        _ => {}
+    }
+}
+
+// the JSON pointer to the first place the trees differ, with both values
+fn first_difference(expected: &Value, actual: &Value, path: &str) -> Option<String> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let extra = actual.keys().filter(|key| !expected.contains_key(*key));
+            expected.keys().chain(extra).find_map(|key| {
+                let path = format!("{}/{}", path, key);
+                match (expected.get(key), actual.get(key)) {
+                    (Some(expected), Some(actual)) => first_difference(expected, actual, &path),
+                    (Some(_), None) => Some(format!("{} is missing", path)),
+                    _ => Some(format!("{} isn't expected", path)),
+                }
+            })
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() != actual.len() => {
+            Some(format!(
+                "{} has {} items, expected {}",
+                path,
+                actual.len(),
+                expected.len()
+            ))
+        }
+        (Value::Array(expected), Value::Array(actual)) => expected
+            .iter()
+            .zip(actual)
+            .enumerate()
+            .find_map(|(index, (expected, actual))| {
+                first_difference(expected, actual, &format!("{}/{}", path, index))
+            }),
+        _ if expected == actual => None,
+        _ => Some(format!("{}: expected {}, found {}", path, expected, actual)),
+    }
+}
+
+fn check(name: &str) -> Result<(), String> {
+    let path = Path::new(FIXTURES).join(name);
+    let src = fs::read_to_string(path.with_extension("js")).unwrap();
+    let snapshot = path.with_extension("snapshot.json");
+    let source_type = if name.starts_with("modules/") {
+        SourceType::Module
+    } else {
+        SourceType::Script
+    };
+    let program = parse_with_source_type(&src, source_type).map_err(|error| error.render(&src))?;
+    let mut actual = serde_json::to_value(&program).unwrap();
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        let json = serde_json::to_string_pretty(&program).unwrap();
+        fs::write(&snapshot, json + "\n").unwrap();
+    }
+    let expected = fs::read_to_string(snapshot)
+        .map_err(|_| "has no snapshot, run with UPDATE_SNAPSHOTS=1".to_string())?;
+    let mut expected: Value = serde_json::from_str(&expected).unwrap();
+    normalize(&mut expected);
+    normalize(&mut actual);
+    match first_difference(&expected, &actual, "") {
+        Some(difference) => Err(difference),
+        None => Ok(()),
+    }
+}
+
+fn check_group(group: &str) {
+    let gaps = known_gaps();
+    let only = env::var("FIXTURE").ok();
+    let mut names: Vec<String> = fs::read_dir(Path::new(FIXTURES).join(group))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "js"))
+        .map(|path| format!("{}/{}", group, path.file_stem().unwrap().to_str().unwrap()))
+        .filter(|name| {
+            only.as_ref()
+                .is_none_or(|only| name.contains(only.as_str()))
+        })
+        .collect();
+    names.sort();
+
+    let mut failures = vec![];
+    for name in &names {
+        match (check(name), gaps.contains(name)) {
+            (Err(message), false) => failures.push(format!("{}: {}", name, message)),
+            (Ok(()), true) => {
+                failures.push(format!("{}: matches now, take it off known_gaps.txt", name))
+            }
+            _ => {}
+        }
+    }
+    assert!(failures.is_empty(), "\n{}\n", failures.join("\n"));
+}
+
+macro_rules! groups {
+    ($($group:ident),* $(,)?) => {
+        const GROUPS: &[&str] = &[$(stringify!($group)),*];
+
+        $(
+            #[test]
+            fn $group() {
+                super::check_group(stringify!($group));
+            }
+        )*
+    };
+}
+
+mod fixtures {
+    use std::fs;
+
+    groups!(
+        classes,
+        destructuring,
+        expressions,
+        functions,
+        literals,
+        modules,
+        statements,
+    );
+
+    #[test]
+    fn every_group_is_checked() {
+        let mut directories: Vec<String> = fs::read_dir(super::FIXTURES)
+            .unwrap()
+            .map(|entry| entry.unwrap())
+            .filter(|entry| entry.file_type().unwrap().is_dir())
+            .map(|entry| entry.file_name().into_string().unwrap())
+            .collect();
+        directories.sort();
+        assert_eq!(directories, GROUPS);
+    }
+
+    #[test]
+    fn known_gaps_are_fixtures() {
+        for name in super::known_gaps() {
+            let path = std::path::Path::new(super::FIXTURES).join(format!("{}.js", name));
+            assert!(path.exists(), "{} isn't a fixture", name);
+        }
+    }
+}
@@ -0,0 +1,90 @@
+// Generates code with a source map from a transformed program and reads the
+// map back with the `sourcemap` crate, as a browser would, to check that the
+// identifiers in the code map to where they were in the source.
+use js_parser::{
+    codegen::{generate_with_source_map, CodegenOptions},
+    node::VariableDeclaration,
+    parse,
+    visit_mut::{walk_variable_declaration, VisitorMut},
+};
+
+const SRC: &str = "var total = 0;
+function add(count,   step) {
+    var next = count + step;
+    return next;
+}
+
+total = add(total, 2) * add(1, total);
+";
+
+// turns every `var` into `const`, so that what follows on the line moves
+struct VarToConst;
+
+impl VisitorMut for VarToConst {
+    fn visit_variable_declaration(&mut self, node: &mut VariableDeclaration) {
+        node.kind = "const".to_string();
+        walk_variable_declaration(self, node);
+    }
+}
+
+fn lines_and_columns(src: &str, name: &str) -> Vec<(u32, u32)> {
+    let mut found = vec![];
+    for (line, text) in src.lines().enumerate() {
+        for (column, _) in text.match_indices(name) {
+            let word = |char: char| char.is_alphanumeric() || char == '_';
+            let before = text[..column].chars().next_back();
+            let after = text[column + name.len()..].chars().next();
+            if !before.is_some_and(word) && !after.is_some_and(word) {
+                found.push((line as u32, column as u32));
+            }
+        }
+    }
+    found
+}
+
+#[test]
+fn identifiers_map_back_to_the_source() {
+    let mut program = parse(SRC).unwrap();
+    VarToConst.visit_program(&mut program);
+    for options in [CodegenOptions::new(), CodegenOptions::new().minify(true)] {
+        let (code, map) = generate_with_source_map(&program, &options, "add.js");
+        let map = sourcemap::SourceMap::from_slice(map.to_json().as_bytes()).unwrap();
+        assert_eq!(map.get_source(0), Some("add.js"));
+        for name in ["total", "add", "count", "step", "next"] {
+            let generated = lines_and_columns(&code, name);
+            let original = lines_and_columns(SRC, name);
+            assert_eq!(generated.len(), original.len(), "{} in\n{}", name, code);
+            for ((line, column), (src_line, src_column)) in generated.into_iter().zip(original) {
+                let token = map.lookup_token(line, column).unwrap();
+                assert_eq!(
+                    (token.get_dst_line(), token.get_dst_col()),
+                    (line, column),
+                    "{} in\n{}",
+                    name,
+                    code
+                );
+                assert_eq!(
+                    (token.get_src_line(), token.get_src_col()),
+                    (src_line, src_column),
+                    "{} in\n{}",
+                    name,
+                    code
+                );
+                assert_eq!(token.get_name(), Some(name));
+            }
+        }
+    }
+}
+
+#[test]
+fn the_map_is_json_with_vlq_mappings() {
+    let program = parse("a;\nb").unwrap();
+    let (code, map) = generate_with_source_map(&program, &CodegenOptions::new(), "ab.js");
+    assert_eq!(code, "a;\nb;\n");
+    // each line starts with a statement and then the identifier in it, at
+    // the same place
+    assert_eq!(
+        map.to_json(),
+        r#"{"version":3,"sources":["ab.js"],"names":["a","b"],"mappings":"AAAAA;AACAC"}"#
+    );
+}
@@ -0,0 +1,34 @@
+// Checks the WASM build from Node against the native parser:
+//
+//   cargo build --lib --release --target wasm32-unknown-unknown --features wasm
+//   wasm-bindgen --target nodejs --out-dir target/wasm-pkg \
+//       target/wasm32-unknown-unknown/release/js_parser.wasm
+//   node tests/wasm/smoke.mjs
+//
+// The package directory can be given as the first argument instead.
+import assert from "node:assert/strict";
+import { readFileSync } from "node:fs";
+import { createRequire } from "node:module";
+import { resolve } from "node:path";
+
+const root = new URL("../../", import.meta.url);
+const pkg = resolve(process.argv[2] ?? new URL("target/wasm-pkg", root).pathname);
+const { parse } = createRequire(import.meta.url)(`${pkg}/js_parser.js`);
+
+// the README example, starting on its second line as the library test has it
+const readme = readFileSync(new URL("README.md", root), "utf8");
+const example = "\n" + readme.split("```js\n")[1].split("```")[0].trimEnd();
+const expected = JSON.parse(readFileSync(new URL("tests/fixtures/readme.json", root), "utf8"));
+assert.deepEqual(parse(example), expected);
+
+assert.equal(parse("export default 1", { sourceType: "module" }).sourceType, "module");
+assert.equal(parse("a ?? b").body[0].expression.type, "LogicalExpression");
+assert.throws(() => parse("a ?? b", { ecmaVersion: 2019 }), (error) => error.code === "JS2015");
+
+assert.throws(
+  () => parse("let a = ;"),
+  (error) => error.code === "JS2001" && error.start.line === 1 && error.start.column === 8,
+);
+assert.throws(() => parse("a", { sourceType: "esm" }), /Invalid options/);
+
+console.log("ok");